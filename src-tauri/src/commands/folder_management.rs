@@ -1,7 +1,16 @@
-use tauri::command;
+use tauri::{command, AppHandle, State};
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::env;
+use log::{info, warn};
+
+use super::{AppState, ProjectUpdate, copy_dir_recursively, copy_folder_recursive, default_awarded_template_folders, is_linked, latest_fees_per_project, load_settings_file, normalize_base_path, rename_template_files_cross_platform, settings_json_path};
+use super::utils::safe_join;
+use crate::db::{CancellationToken, Fee, Project};
+use crate::db::utils::{normalize_thing_id, thing_matches};
+
+#[cfg(test)]
+mod tests;
 
 #[derive(Debug, serde::Serialize)]
 pub struct FolderOperationResult {
@@ -19,6 +28,33 @@ pub struct ProjectFolderInfo {
     pub exists: bool,
 }
 
+/// A var.json written before the last edit of the fee it was exported from
+#[derive(Debug, serde::Serialize)]
+pub struct StaleVarJson {
+    pub fee_id: String,
+    pub file_path: String,
+    pub file_mtime: String,
+    pub fee_updated_at: String,
+}
+
+/// Where a fee's var.json is expected after any moves of its project folder
+#[derive(Debug, serde::Serialize)]
+pub struct FeeFileStatus {
+    pub fee_id: String,
+    pub project_number: String,
+    pub folder_location: String,
+    pub file_path: Option<String>,
+    pub exists: bool,
+}
+
+/// A project whose folder is named differently from its `name_short`
+#[derive(Debug, serde::Serialize, PartialEq)]
+pub struct NameShortMismatch {
+    pub project_number: String,
+    pub db_name_short: String,
+    pub folder_name_short: String,
+}
+
 /// Get the base projects path from environment or default
 fn get_projects_base_path() -> Result<PathBuf, String> {
     match env::var("PROJECT_BASE_PATH") {
@@ -42,7 +78,7 @@ fn get_projects_base_path() -> Result<PathBuf, String> {
 }
 
 /// Get the folder name for a given status
-fn get_folder_for_status(status: &str) -> Result<&str, String> {
+pub(super) fn get_folder_for_status(status: &str) -> Result<&str, String> {
     match status.to_lowercase().as_str() {
         "draft" | "rfp" | "proposal" | "submitted" => Ok("01 RFPs"),
         "active" | "current" | "awarded" | "ongoing" => Ok("11 Current"),
@@ -55,7 +91,11 @@ fn get_folder_for_status(status: &str) -> Result<&str, String> {
 /// Find a project folder by number across all status directories
 fn find_project_folder(project_number: &str) -> Result<ProjectFolderInfo, String> {
     let base_path = get_projects_base_path()?;
-    
+    Ok(find_project_folder_in(&base_path, project_number))
+}
+
+/// Find a project folder by number under an explicit base path
+fn find_project_folder_in(base_path: &Path, project_number: &str) -> ProjectFolderInfo {
     let status_dirs = ["00 Inactive", "01 RFPs", "11 Current", "99 Completed"];
     
     for status_dir in &status_dirs {
@@ -75,12 +115,12 @@ fn find_project_folder(project_number: &str) -> Result<ProjectFolderInfo, String
                         
                         // Check if folder starts with the project number
                         if folder_str.starts_with(project_number) {
-                            return Ok(ProjectFolderInfo {
+                            return ProjectFolderInfo {
                                 project_number: project_number.to_string(),
                                 current_location: status_dir.to_string(),
                                 full_path: entry.path().to_string_lossy().to_string(),
                                 exists: true,
-                            });
+                            };
                         }
                     }
                 }
@@ -92,12 +132,12 @@ fn find_project_folder(project_number: &str) -> Result<ProjectFolderInfo, String
         }
     }
     
-    Ok(ProjectFolderInfo {
+    ProjectFolderInfo {
         project_number: project_number.to_string(),
         current_location: "not_found".to_string(),
         full_path: String::new(),
         exists: false,
-    })
+    }
 }
 
 #[command]
@@ -105,13 +145,38 @@ pub async fn get_project_folder_location(project_number: String) -> Result<Proje
     find_project_folder(&project_number)
 }
 
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct ProjectMove {
+    pub project_number: String,
+    pub new_status: String,
+}
+
 #[command]
 pub async fn move_project_folder(
     project_number: String,
     new_status: String,
+    state: State<'_, AppState>,
+    app_handle: AppHandle,
+) -> Result<FolderOperationResult, String> {
+    let base_path = get_projects_base_path()?;
+    let template_folders = awarded_template_folders(&app_handle);
+    let operation = {
+        let manager = state.lock().map_err(|e| e.to_string())?;
+        manager.start_operation(&format!("Move project {} to {}", project_number, new_status))
+    };
+    move_project_folder_in(&base_path, &project_number, &new_status, &template_folders, &operation.token)
+}
+
+/// Move a project folder to the directory for its new status under an explicit base path
+fn move_project_folder_in(
+    base_path: &Path,
+    project_number: &str,
+    new_status: &str,
+    template_folders: &[String],
+    token: &CancellationToken,
 ) -> Result<FolderOperationResult, String> {
     // Find current location
-    let current_info = find_project_folder(&project_number)?;
+    let current_info = find_project_folder_in(base_path, project_number);
     
     if !current_info.exists {
         return Ok(FolderOperationResult {
@@ -123,7 +188,7 @@ pub async fn move_project_folder(
     }
     
     // Get destination folder
-    let dest_folder = get_folder_for_status(&new_status)?;
+    let dest_folder = get_folder_for_status(new_status)?;
     
     // Check if already in correct location
     if current_info.current_location == dest_folder {
@@ -136,7 +201,6 @@ pub async fn move_project_folder(
     }
     
     // Build destination path
-    let base_path = get_projects_base_path()?;
     let dest_dir = base_path.join(dest_folder);
     
     // Ensure destination directory exists
@@ -147,9 +211,11 @@ pub async fn move_project_folder(
     // Get the folder name from current path
     let current_path = Path::new(&current_info.full_path);
     let folder_name = current_path.file_name()
-        .ok_or("Could not get folder name")?;
+        .ok_or("Could not get folder name")?
+        .to_string_lossy()
+        .to_string();
     
-    let new_path = dest_dir.join(folder_name);
+    let new_path = safe_join(base_path, &[dest_folder, &folder_name])?;
     
     // Check if destination already exists
     if new_path.exists() {
@@ -176,7 +242,7 @@ pub async fn move_project_folder(
             
             // If moving from RFP to Current, copy awarded project templates
             if current_info.current_location == "01 RFPs" && dest_folder == "11 Current" {
-                match copy_awarded_templates(&new_path) {
+                match copy_awarded_templates(base_path, &new_path, template_folders, token) {
                     Ok(_) => {
                         success_message.push_str(". Awarded project templates copied successfully.");
                     },
@@ -206,25 +272,454 @@ pub async fn move_project_folder(
 pub async fn move_project_from_rfp(
     project_number: String,
     destination: String,
+    state: State<'_, AppState>,
+    app_handle: AppHandle,
 ) -> Result<FolderOperationResult, String> {
     // Validate destination
     match destination.as_str() {
-        "current" => move_project_folder(project_number, "active".to_string()).await,
-        "archive" => move_project_folder(project_number, "completed".to_string()).await,
-        "inactive" => move_project_folder(project_number, "cancelled".to_string()).await,
+        "current" => move_project_folder(project_number, "active".to_string(), state, app_handle).await,
+        "archive" => move_project_folder(project_number, "completed".to_string(), state, app_handle).await,
+        "inactive" => move_project_folder(project_number, "cancelled".to_string(), state, app_handle).await,
         _ => Err(format!("Invalid destination: {}. Use 'current', 'archive', or 'inactive'", destination))
     }
 }
 
 #[command]
-pub async fn move_project_to_archive(project_number: String) -> Result<FolderOperationResult, String> {
-    move_project_folder(project_number, "completed".to_string()).await
+pub async fn move_project_to_archive(
+    project_number: String,
+    state: State<'_, AppState>,
+    app_handle: AppHandle,
+) -> Result<FolderOperationResult, String> {
+    move_project_folder(project_number, "completed".to_string(), state, app_handle).await
+}
+
+/// Compress a project folder into `<dest_dir>/<folder name>.zip`
+/// 
+/// The archive holds the folder itself, so extracting it recreates
+/// `<number> <short name>`. The original is removed only when
+/// `delete_original` is set and the archive has been read back successfully.
+fn archive_project_folder_in(
+    base_path: &Path,
+    project_number: &str,
+    dest_dir: &Path,
+    delete_original: bool,
+) -> Result<PathBuf, String> {
+    let info = find_project_folder_in(base_path, project_number);
+    if !info.exists {
+        return Err(format!("Project folder {} not found", project_number));
+    }
+    
+    let source = PathBuf::from(&info.full_path);
+    let folder_name = source.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .ok_or_else(|| format!("Invalid project folder path: {}", info.full_path))?;
+    let archive_path = dest_dir.join(format!("{}.zip", folder_name));
+    if archive_path.exists() {
+        return Err(format!("Archive already exists: {}", archive_path.display()));
+    }
+    
+    let file = fs::File::create(&archive_path)
+        .map_err(|e| format!("Failed to create archive {}: {}", archive_path.display(), e))?;
+    let mut writer = zip::ZipWriter::new(file);
+    let written = add_folder_to_zip(&mut writer, &source, &folder_name)
+        .and_then(|count| writer.finish().map(|_| count).map_err(|e| format!("Failed to finish archive: {}", e)));
+    let written = match written {
+        Ok(count) => count,
+        Err(e) => {
+            let _ = fs::remove_file(&archive_path);
+            return Err(e);
+        }
+    };
+    
+    if delete_original {
+        let readable = fs::File::open(&archive_path)
+            .map_err(|e| e.to_string())
+            .and_then(|file| zip::ZipArchive::new(file).map_err(|e| e.to_string()))
+            .map(|archive| archive.len() == written)
+            .unwrap_or(false);
+        if !readable {
+            return Err(format!("Archive {} could not be verified; original folder kept", archive_path.display()));
+        }
+        fs::remove_dir_all(&source)
+            .map_err(|e| format!("Archive created but failed to remove {}: {}", source.display(), e))?;
+    }
+    
+    Ok(archive_path)
+}
+
+/// Add `dir` and everything below it to `writer` under `prefix`, returning the entry count
+fn add_folder_to_zip(writer: &mut zip::ZipWriter<fs::File>, dir: &Path, prefix: &str) -> Result<usize, String> {
+    let options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated);
+    writer.add_directory(format!("{}/", prefix), options)
+        .map_err(|e| format!("Failed to add {} to archive: {}", prefix, e))?;
+    
+    let mut entries: Vec<fs::DirEntry> = fs::read_dir(dir)
+        .map_err(|e| format!("Failed to read directory {}: {}", dir.display(), e))?
+        .collect::<Result<_, _>>()
+        .map_err(|e| format!("Failed to read entry in {}: {}", dir.display(), e))?;
+    entries.sort_by_key(|entry| entry.file_name());
+    
+    let mut count = 1;
+    for entry in entries {
+        let path = entry.path();
+        let name = format!("{}/{}", prefix, entry.file_name().to_string_lossy());
+        
+        if path.is_dir() {
+            count += add_folder_to_zip(writer, &path, &name)?;
+        } else {
+            writer.start_file(name.as_str(), options)
+                .map_err(|e| format!("Failed to add {} to archive: {}", name, e))?;
+            let mut file = fs::File::open(&path)
+                .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+            std::io::copy(&mut file, writer)
+                .map_err(|e| format!("Failed to compress {}: {}", path.display(), e))?;
+            count += 1;
+        }
+    }
+    
+    Ok(count)
+}
+
+/// Compress a completed project's folder into a zip archive in `dest_path`
+/// 
+/// Returns the archive path. The project folder is kept unless
+/// `delete_original` is true. Projects in the database must be Completed or
+/// Cancelled (`Project::is_terminal`); folders without a project are allowed
+/// so orphans can be cleaned up.
+#[command]
+pub async fn archive_project_folder(
+    project_number: String,
+    dest_path: String,
+    delete_original: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<String, String> {
+    let manager_clone = {
+        let manager = state.lock().map_err(|e| e.to_string())?;
+        manager.clone()
+    };
+    
+    let project = manager_clone.get_project_by_number(&project_number).await
+        .map_err(|e| format!("Failed to fetch project {}: {}", project_number, e))?;
+    if let Some(project) = project.filter(|project| !project.is_terminal()) {
+        return Err(format!(
+            "Project {} is '{}'; only Completed or Cancelled projects can be archived",
+            project_number, project.status
+        ));
+    }
+    
+    let base_path = get_projects_base_path()?;
+    let dest_dir = PathBuf::from(&dest_path);
+    if !dest_dir.is_dir() {
+        return Err(format!("Destination folder does not exist: {}", dest_path));
+    }
+    
+    let archive_path = archive_project_folder_in(&base_path, &project_number, &dest_dir, delete_original.unwrap_or(false))?;
+    Ok(archive_path.to_string_lossy().to_string())
+}
+
+/// Rename a project folder in place to `<number> <short name>`
+/// 
+/// Folders keep the short name they were created with during the RFP stage.
+/// Once a project is awarded the short name is often changed, so this brings
+/// the folder back in line with the database.
+fn repair_project_folder_name_in(
+    base_path: &Path,
+    project_number: &str,
+    project_short_name: &str,
+) -> Result<FolderOperationResult, String> {
+    let current_info = find_project_folder_in(base_path, project_number);
+    
+    if !current_info.exists {
+        return Ok(FolderOperationResult {
+            success: false,
+            message: format!("Project folder {} not found", project_number),
+            old_path: None,
+            new_path: None,
+        });
+    }
+    
+    let expected_name = format!("{} {}", project_number, project_short_name);
+    let expected_path = safe_join(base_path, &[&current_info.current_location, &expected_name])?;
+    
+    if Path::new(&current_info.full_path) == expected_path {
+        return Ok(FolderOperationResult {
+            success: true,
+            message: format!("Folder for {} is already named {}", project_number, expected_name),
+            old_path: Some(current_info.full_path.clone()),
+            new_path: Some(current_info.full_path),
+        });
+    }
+    
+    if expected_path.exists() {
+        return Ok(FolderOperationResult {
+            success: false,
+            message: format!("Destination folder already exists: {}", expected_path.display()),
+            old_path: Some(current_info.full_path),
+            new_path: Some(expected_path.to_string_lossy().to_string()),
+        });
+    }
+    
+    match fs::rename(&current_info.full_path, &expected_path) {
+        Ok(_) => Ok(FolderOperationResult {
+            success: true,
+            message: format!("Renamed folder for {} to {}", project_number, expected_name),
+            old_path: Some(current_info.full_path),
+            new_path: Some(expected_path.to_string_lossy().to_string()),
+        }),
+        Err(e) => Ok(FolderOperationResult {
+            success: false,
+            message: format!("Failed to rename folder: {}", e),
+            old_path: Some(current_info.full_path),
+            new_path: Some(expected_path.to_string_lossy().to_string()),
+        }),
+    }
+}
+
+/// Locate a project's `02 Proposal/<number>-var.json`, wherever its folder lives
+fn find_var_json_in(base_path: &Path, project_number: &str) -> Option<PathBuf> {
+    let info = find_project_folder_in(base_path, project_number);
+    if !info.exists {
+        return None;
+    }
+    
+    let path = PathBuf::from(info.full_path)
+        .join("02 Proposal")
+        .join(format!("{}-var.json", project_number));
+    path.exists().then_some(path)
+}
+
+#[command]
+pub async fn repair_project_folder_name(
+    project_number: String,
+    project_short_name: String,
+) -> Result<FolderOperationResult, String> {
+    let base_path = get_projects_base_path()?;
+    repair_project_folder_name_in(&base_path, &project_number, &project_short_name)
+}
+
+/// The canonical name for a folder whose leading project number is misformatted
+/// 
+/// Older tooling wrote `25_97105 Name` or kept SurrealDB brackets, as in
+/// `⟨25-97105⟩ Name`. Returns `None` for folders that are already canonical or
+/// don't start with a project number.
+fn canonical_project_folder_name(folder_name: &str) -> Option<String> {
+    let (number, rest) = match folder_name.split_once(' ') {
+        Some((number, rest)) => (number, Some(rest)),
+        None => (folder_name, None),
+    };
+    
+    let canonical: String = number
+        .chars()
+        .filter(|c| !matches!(c, '⟨' | '⟩' | '[' | ']'))
+        .map(|c| if c == '_' { '-' } else { c })
+        .collect();
+    
+    let is_number = canonical.len() == 8
+        && canonical.char_indices().all(|(i, c)| if i == 2 { c == '-' } else { c.is_ascii_digit() });
+    if !is_number || canonical == number {
+        return None;
+    }
+    
+    Some(match rest {
+        Some(rest) => format!("{} {}", canonical, rest),
+        None => canonical,
+    })
+}
+
+/// Project folders in the status directories whose number needs fixing,
+/// paired with their canonical name
+fn misformatted_project_folders_in(base_path: &Path) -> Vec<(PathBuf, String)> {
+    let mut misformatted = Vec::new();
+    
+    for status_dir in ["00 Inactive", "01 RFPs", "11 Current", "99 Completed"] {
+        let Ok(entries) = fs::read_dir(base_path.join(status_dir)) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            if !entry.path().is_dir() {
+                continue;
+            }
+            let folder_name = entry.file_name().to_string_lossy().to_string();
+            if let Some(canonical) = canonical_project_folder_name(&folder_name) {
+                misformatted.push((entry.path(), canonical));
+            }
+        }
+    }
+    
+    misformatted.sort();
+    misformatted
+}
+
+#[command]
+pub async fn find_misformatted_project_folders() -> Result<Vec<String>, String> {
+    let base_path = get_projects_base_path()?;
+    Ok(misformatted_project_folders_in(&base_path)
+        .into_iter()
+        .map(|(path, _)| path.to_string_lossy().to_string())
+        .collect())
+}
+
+/// Rename misformatted project folders to `YY-CCCNN Name`, skipping any whose
+/// canonical name is already taken
+fn repair_misformatted_project_folders_in(base_path: &Path) -> Vec<FolderOperationResult> {
+    misformatted_project_folders_in(base_path)
+        .into_iter()
+        .map(|(path, canonical)| {
+            let old_path = Some(path.to_string_lossy().to_string());
+            let new_path = path.with_file_name(&canonical);
+            
+            if new_path.exists() {
+                return FolderOperationResult {
+                    success: false,
+                    message: format!("Destination folder already exists: {}", new_path.display()),
+                    old_path,
+                    new_path: Some(new_path.to_string_lossy().to_string()),
+                };
+            }
+            
+            match fs::rename(&path, &new_path) {
+                Ok(_) => FolderOperationResult {
+                    success: true,
+                    message: format!("Renamed folder to {}", canonical),
+                    old_path,
+                    new_path: Some(new_path.to_string_lossy().to_string()),
+                },
+                Err(e) => FolderOperationResult {
+                    success: false,
+                    message: format!("Failed to rename folder: {}", e),
+                    old_path,
+                    new_path: Some(new_path.to_string_lossy().to_string()),
+                },
+            }
+        })
+        .collect()
+}
+
+#[command]
+pub async fn repair_misformatted_project_folders() -> Result<Vec<FolderOperationResult>, String> {
+    let base_path = get_projects_base_path()?;
+    Ok(repair_misformatted_project_folders_in(&base_path))
+}
+
+/// Move each project folder in turn, continuing past individual failures.
+/// Once cancelled, the remaining moves are reported as not attempted.
+fn bulk_move_project_folders(
+    base_path: &Path,
+    moves: &[ProjectMove],
+    template_folders: &[String],
+    token: &CancellationToken,
+) -> Vec<FolderOperationResult> {
+    moves
+        .iter()
+        .map(|m| {
+            if token.is_cancelled() {
+                return FolderOperationResult {
+                    success: false,
+                    message: format!("Cancelled before moving {}", m.project_number),
+                    old_path: None,
+                    new_path: None,
+                };
+            }
+            move_project_folder_in(base_path, &m.project_number, &m.new_status, template_folders, token).unwrap_or_else(|e| {
+                FolderOperationResult {
+                    success: false,
+                    message: format!("Failed to move {}: {}", m.project_number, e),
+                    old_path: None,
+                    new_path: None,
+                }
+            })
+        })
+        .collect()
+}
+
+#[command]
+pub async fn bulk_move_projects(
+    moves: Vec<ProjectMove>,
+    state: State<'_, AppState>,
+    app_handle: AppHandle,
+) -> Result<Vec<FolderOperationResult>, String> {
+    let base_path = get_projects_base_path()?;
+    let template_folders = awarded_template_folders(&app_handle);
+    
+    let (manager_clone, operation) = {
+        let manager = state.lock().map_err(|e| e.to_string())?;
+        (manager.clone(), manager.start_operation(&format!("Move {} project folders", moves.len())))
+    };
+    
+    let projects = manager_clone.get_projects(true).await
+        .map_err(|e| format!("Failed to fetch projects: {}", e))?;
+    
+    let mut results = bulk_move_project_folders(&base_path, &moves, &template_folders, &operation.token);
+    
+    // Update the database status for every folder that moved
+    for (m, result) in moves.iter().zip(results.iter_mut()) {
+        if !result.success {
+            continue;
+        }
+        
+        let project_id = projects
+            .iter()
+            .find(|p| p.number.id == m.project_number)
+            .and_then(|p| p.id.as_ref())
+            .map(normalize_thing_id);
+        
+        let project_id = match project_id {
+            Some(id) => id,
+            None => {
+                result.success = false;
+                result.message.push_str(&format!(". Warning: Project {} not found in database", m.project_number));
+                continue;
+            }
+        };
+        
+        let update = ProjectUpdate {
+            name: None,
+            name_short: None,
+            status: Some(m.new_status.clone()),
+            area: None,
+            city: None,
+            country: None,
+            folder: None,
+        };
+        
+        if let Err(e) = manager_clone.update_project(&project_id, update).await {
+            result.success = false;
+            result.message.push_str(&format!(". Warning: Failed to update database status: {}", e));
+        }
+        
+        // Awarded projects keep their RFP-era folder name; bring it in line with the database
+        if get_folder_for_status(&m.new_status) == Ok("11 Current") {
+            let name_short = projects
+                .iter()
+                .find(|p| p.number.id == m.project_number)
+                .map(|p| p.name_short.clone())
+                .unwrap_or_default();
+            
+            match repair_project_folder_name_in(&base_path, &m.project_number, &name_short) {
+                Ok(repair) if repair.success => {
+                    if repair.old_path != repair.new_path {
+                        result.new_path = repair.new_path;
+                        result.message.push_str(&format!(". {}", repair.message));
+                    }
+                }
+                Ok(repair) => {
+                    result.message.push_str(&format!(". Warning: {}", repair.message));
+                }
+                Err(e) => {
+                    result.message.push_str(&format!(". Warning: Failed to check folder name: {}", e));
+                }
+            }
+        }
+    }
+    
+    Ok(results)
 }
 
 #[command]
 pub async fn list_projects_in_folder(folder_path: String) -> Result<Vec<String>, String> {
     let base_path = get_projects_base_path()?;
-    let full_path = base_path.join(&folder_path);
+    let full_path = safe_join(&base_path, &[&folder_path])?;
     
     if !full_path.exists() {
         return Err(format!("Folder does not exist: {}", full_path.display()));
@@ -253,54 +748,462 @@ pub async fn list_projects_in_folder(folder_path: String) -> Result<Vec<String>,
     Ok(projects)
 }
 
-/// Copy awarded project template folders when moving from RFP to Current
-fn copy_awarded_templates(project_path: &Path) -> Result<(), String> {
-    let base_path = get_projects_base_path()?;
+/// Template subfolders of `11 Current/00 Additional Folders` added to awarded
+/// projects, from the `awarded_template_folders` setting
+fn awarded_template_folders(app_handle: &AppHandle) -> Vec<String> {
+    match load_settings_file(&settings_json_path(app_handle), &[]) {
+        Ok(settings) => settings.awarded_template_folders,
+        Err(e) => {
+            warn!("Using default awarded template folders: {}", e);
+            default_awarded_template_folders()
+        }
+    }
+}
+
+/// Copy the given awarded project template folders when moving from RFP to
+/// Current, returning the folders that were added
+/// 
+/// Folders already in the project are left alone. A folder that fails to
+/// copy, or is being copied on cancellation, is removed again, so each
+/// template folder is either fully present or absent and a retry picks it up.
+fn copy_awarded_templates(
+    base_path: &Path,
+    project_path: &Path,
+    template_folders: &[String],
+    token: &CancellationToken,
+) -> Result<Vec<String>, String> {
     let template_path = base_path.join("11 Current").join("00 Additional Folders");
     
     if !template_path.exists() {
         return Err("Awarded project template folder not found".to_string());
     }
     
-    // Copy each template folder to the project
-    let template_folders = ["03 Contract", "04 Deliverables", "05 Submittals", "11 SubContractors", "98 Outgoing", "99 Temp"];
-    
-    for folder in &template_folders {
-        let src = template_path.join(folder);
-        let dest = project_path.join(folder);
+    let mut added = Vec::new();
+    for folder in template_folders {
+        let src = safe_join(&template_path, &[folder])?;
+        let dest = safe_join(project_path, &[folder])?;
         
         if src.exists() && !dest.exists() {
-            if let Err(e) = copy_dir_recursively(&src, &dest) {
-                eprintln!("Failed to copy template folder {}: {}", folder, e);
-                // Continue with other folders even if one fails
+            match copy_dir_recursively(&src, &dest, token) {
+                Ok(_) => added.push(folder.clone()),
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {
+                    let _ = fs::remove_dir_all(&dest);
+                    return Err(format!("Cancelled while copying {}", folder));
+                }
+                Err(e) => {
+                    warn!("Failed to copy template folder {}: {}", folder, e);
+                    let _ = fs::remove_dir_all(&dest);
+                    // Continue with other folders even if one fails
+                }
             }
         }
     }
     
-    Ok(())
+    Ok(added)
 }
 
-/// Recursively copy a directory and its contents
-fn copy_dir_recursively(src: &Path, dest: &Path) -> Result<(), std::io::Error> {
-    if !src.exists() {
-        return Ok(());
+/// Copy any awarded template folders missing from a Current project
+fn ensure_awarded_templates_in(base_path: &Path, project_number: &str, template_folders: &[String]) -> Result<Vec<String>, String> {
+    let info = find_project_folder_in(base_path, project_number);
+    if !info.exists {
+        return Err(format!("Project folder not found for {}", project_number));
+    }
+    if info.current_location != "11 Current" {
+        return Err(format!(
+            "Project {} is in {}, awarded templates only apply to 11 Current",
+            project_number, info.current_location
+        ));
     }
     
-    fs::create_dir_all(dest)?;
+    copy_awarded_templates(base_path, Path::new(&info.full_path), template_folders, &CancellationToken::default())
+}
+
+/// Retry the awarded template copy for a Current project, e.g. after a
+/// folder failed to copy during the move from RFP, returning the folders added
+#[command]
+pub async fn ensure_awarded_templates(project_number: String, app_handle: AppHandle) -> Result<Vec<String>, String> {
+    let base_path = get_projects_base_path()?;
+    let added = ensure_awarded_templates_in(&base_path, &project_number, &awarded_template_folders(&app_handle))?;
+    if !added.is_empty() {
+        info!("Added awarded template folders to {}: {:?}", project_number, added);
+    }
+    Ok(added)
+}
+
+/// Filter projects down to those with no folder in any status directory
+fn projects_missing_folders(base_path: &Path, projects: Vec<Project>) -> Vec<Project> {
+    projects
+        .into_iter()
+        .filter(|project| !find_project_folder_in(base_path, &project.number.id).exists)
+        .collect()
+}
+
+#[command]
+pub async fn find_projects_without_folders(state: State<'_, AppState>) -> Result<Vec<Project>, String> {
+    let base_path = get_projects_base_path()?;
+    
+    let manager_clone = {
+        let manager = state.lock().map_err(|e| e.to_string())?;
+        manager.clone()
+    };
+    
+    let projects = manager_clone.get_projects(true).await
+        .map_err(|e| format!("Failed to fetch projects: {}", e))?;
+    
+    Ok(projects_missing_folders(&base_path, projects))
+}
+
+/// Whether two names differ only in Unicode normalization (e.g. NFD vs NFC)
+fn differs_only_in_normalization(a: &str, b: &str) -> bool {
+    use unicode_normalization::UnicodeNormalization;
+    
+    a != b && a.nfc().eq(b.nfc())
+}
+
+/// Folders named `<number> <name_short>` that only match the database once
+/// both are NFC-normalized
+fn folder_name_encoding_issues(base_path: &Path, projects: &[Project]) -> Vec<String> {
+    projects
+        .iter()
+        .filter_map(|project| {
+            let info = find_project_folder_in(base_path, &project.number.id);
+            if !info.exists {
+                return None;
+            }
+            
+            let folder_name = Path::new(&info.full_path).file_name()?.to_string_lossy().to_string();
+            let expected = format!("{} {}", project.number.id, project.name_short);
+            differs_only_in_normalization(&folder_name, &expected).then_some(info.full_path)
+        })
+        .collect()
+}
+
+/// Find project folders whose names use a different Unicode normalization
+/// than the database, which network shares (notably macOS/SMB) can introduce
+/// and which breaks plain string comparisons
+#[command]
+pub async fn find_folder_name_encoding_issues(state: State<'_, AppState>) -> Result<Vec<String>, String> {
+    let base_path = get_projects_base_path()?;
+    
+    let manager_clone = {
+        let manager = state.lock().map_err(|e| e.to_string())?;
+        manager.clone()
+    };
+    
+    let projects = manager_clone.get_projects(true).await
+        .map_err(|e| format!("Failed to fetch projects: {}", e))?;
+    
+    let issues = folder_name_encoding_issues(&base_path, &projects);
+    if !issues.is_empty() {
+        warn!("{} project folders differ from the database only in Unicode normalization", issues.len());
+    }
+    Ok(issues)
+}
+
+/// Projects whose folder name, with the leading number stripped, differs from
+/// `name_short` in the database
+/// 
+/// Folders that only differ in Unicode normalization are left to
+/// `folder_name_encoding_issues`.
+fn name_short_mismatches(base_path: &Path, projects: &[Project]) -> Vec<NameShortMismatch> {
+    projects
+        .iter()
+        .filter_map(|project| {
+            let info = find_project_folder_in(base_path, &project.number.id);
+            if !info.exists {
+                return None;
+            }
+            
+            let folder_name = Path::new(&info.full_path).file_name()?.to_string_lossy().to_string();
+            let folder_name_short = folder_name.split_once(' ').map_or("", |(_, rest)| rest);
+            if folder_name_short == project.name_short
+                || differs_only_in_normalization(folder_name_short, &project.name_short)
+            {
+                return None;
+            }
+            
+            Some(NameShortMismatch {
+                project_number: project.number.id.clone(),
+                db_name_short: project.name_short.clone(),
+                folder_name_short: folder_name_short.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// List projects whose folder and database short names have diverged, e.g.
+/// after a folder was renamed by hand
+#[command]
+pub async fn find_name_short_mismatches(state: State<'_, AppState>) -> Result<Vec<NameShortMismatch>, String> {
+    let base_path = get_projects_base_path()?;
+    
+    let manager_clone = {
+        let manager = state.lock().map_err(|e| e.to_string())?;
+        manager.clone()
+    };
+    
+    let projects = manager_clone.get_projects(true).await
+        .map_err(|e| format!("Failed to fetch projects: {}", e))?;
+    
+    let mismatches = name_short_mismatches(&base_path, &projects);
+    if !mismatches.is_empty() {
+        warn!("{} project folders have a different short name than the database", mismatches.len());
+    }
+    Ok(mismatches)
+}
+
+/// The folder a project points at, as a path that can be compared with others
+/// 
+/// Absolute `folder` values are used as is; bare folder names are resolved
+/// against the status directory of the project. Returns `None` for projects
+/// with no folder set.
+fn resolved_project_folder(base_path: &Path, project: &Project) -> Option<PathBuf> {
+    let folder = normalize_base_path(&project.folder);
+    if folder.is_empty() {
+        return None;
+    }
+    
+    let folder_path = Path::new(&folder);
+    if folder_path.is_absolute() {
+        return Some(folder_path.to_path_buf());
+    }
     
-    for entry in fs::read_dir(src)? {
-        let entry = entry?;
-        let src_path = entry.path();
-        let dest_path = dest.join(entry.file_name());
+    let status_dir = get_folder_for_status(&project.status).unwrap_or_default();
+    Some(base_path.join(status_dir).join(folder_path))
+}
+
+/// Group projects by resolved folder, keeping only groups of two or more
+fn project_folder_collisions(base_path: &Path, projects: Vec<Project>) -> Vec<Vec<Project>> {
+    let mut groups: std::collections::BTreeMap<PathBuf, Vec<Project>> = std::collections::BTreeMap::new();
+    for project in projects {
+        if let Some(folder) = resolved_project_folder(base_path, &project) {
+            groups.entry(folder).or_default().push(project);
+        }
+    }
+    
+    groups.into_values().filter(|group| group.len() > 1).collect()
+}
+
+/// Find projects that point at the same physical folder, e.g. after a
+/// copy-paste error in `folder`
+#[command]
+pub async fn find_project_folder_collisions(state: State<'_, AppState>) -> Result<Vec<Vec<Project>>, String> {
+    let base_path = get_projects_base_path()?;
+    
+    let manager_clone = {
+        let manager = state.lock().map_err(|e| e.to_string())?;
+        manager.clone()
+    };
+    
+    let projects = manager_clone.get_projects(true).await
+        .map_err(|e| format!("Failed to fetch projects: {}", e))?;
+    
+    Ok(project_folder_collisions(&base_path, projects))
+}
+
+/// Create a project folder from the RFP template in its status directory.
+/// A copy that fails or is cancelled removes the partial folder again.
+fn create_project_folder_from_template(base_path: &Path, project: &Project, token: &CancellationToken) -> FolderOperationResult {
+    let project_number = &project.number.id;
+    
+    let dest_folder = match get_folder_for_status(&project.status) {
+        Ok(folder) => folder,
+        Err(e) => {
+            return FolderOperationResult {
+                success: false,
+                message: format!("Skipped {}: {}", project_number, e),
+                old_path: None,
+                new_path: None,
+            };
+        }
+    };
+    
+    let template_path = base_path.join("01 RFPs").join("_yy-cccnn Project Name");
+    let new_path = match safe_join(base_path, &[dest_folder, &format!("{} {}", project_number, project.name_short)]) {
+        Ok(path) => path,
+        Err(e) => {
+            return FolderOperationResult {
+                success: false,
+                message: format!("Skipped {}: {}", project_number, e),
+                old_path: None,
+                new_path: None,
+            };
+        }
+    };
+    let new_path_str = new_path.to_string_lossy().to_string();
+    
+    if !template_path.exists() {
+        return FolderOperationResult {
+            success: false,
+            message: format!("Template folder not found: {}", template_path.display()),
+            old_path: None,
+            new_path: Some(new_path_str),
+        };
+    }
+    
+    if new_path.exists() {
+        return FolderOperationResult {
+            success: false,
+            message: format!("Destination folder already exists: {}", new_path.display()),
+            old_path: None,
+            new_path: Some(new_path_str),
+        };
+    }
+    
+    if let Err(e) = copy_folder_recursive(&template_path, &new_path, token) {
+        let _ = fs::remove_dir_all(&new_path);
+        return FolderOperationResult {
+            success: false,
+            message: format!("Failed to create folder for {}: {}", project_number, e),
+            old_path: None,
+            new_path: Some(new_path_str),
+        };
+    }
+    
+    let message = match rename_template_files_cross_platform(&new_path, "yy-cccnn", project_number) {
+        Ok(_) => format!("Created folder for {} in {}", project_number, dest_folder),
+        Err(e) => format!(
+            "Created folder for {} in {}. Warning: Failed to rename template files: {}",
+            project_number, dest_folder, e
+        ),
+    };
+    
+    FolderOperationResult {
+        success: true,
+        message,
+        old_path: None,
+        new_path: Some(new_path_str),
+    }
+}
+
+#[command]
+pub async fn create_missing_project_folders(state: State<'_, AppState>) -> Result<Vec<FolderOperationResult>, String> {
+    let base_path = get_projects_base_path()?;
+    
+    let (manager_clone, operation) = {
+        let manager = state.lock().map_err(|e| e.to_string())?;
+        (manager.clone(), manager.start_operation("Create missing project folders"))
+    };
+    
+    let projects = manager_clone.get_projects(true).await
+        .map_err(|e| format!("Failed to fetch projects: {}", e))?;
+    
+    Ok(create_project_folders(&base_path, &projects_missing_folders(&base_path, projects), &operation.token))
+}
+
+/// Create each project's folder in turn, continuing past individual failures.
+/// Once cancelled, the remaining projects are reported as not attempted.
+fn create_project_folders(base_path: &Path, projects: &[Project], token: &CancellationToken) -> Vec<FolderOperationResult> {
+    projects
+        .iter()
+        .map(|project| {
+            if token.is_cancelled() {
+                return FolderOperationResult {
+                    success: false,
+                    message: format!("Cancelled before creating folder for {}", project.number.id),
+                    old_path: None,
+                    new_path: None,
+                };
+            }
+            create_project_folder_from_template(base_path, project, token)
+        })
+        .collect()
+}
+
+/// Compare each project's var.json against the latest fee of that project
+fn stale_var_jsons_in(base_path: &Path, fees: Vec<Fee>, projects: &[Project]) -> Vec<StaleVarJson> {
+    let mut stale = Vec::new();
+    
+    for fee in latest_fees_per_project(fees) {
+        let Some(project) = projects.iter().find(|p| is_linked(&p.id, &fee.project_id)) else {
+            continue;
+        };
+        let Some(var_json) = find_var_json_in(base_path, &project.number.id) else {
+            continue;
+        };
+        let Ok(updated_at) = chrono::DateTime::parse_from_rfc3339(&fee.time.updated_at) else {
+            warn!("Skipping fee {} with unparseable updated_at '{}'", fee.number, fee.time.updated_at);
+            continue;
+        };
+        let Ok(modified) = fs::metadata(&var_json).and_then(|m| m.modified()) else {
+            continue;
+        };
         
-        if src_path.is_dir() {
-            copy_dir_recursively(&src_path, &dest_path)?;
-        } else {
-            fs::copy(&src_path, &dest_path)?;
+        let file_mtime = chrono::DateTime::<chrono::Utc>::from(modified);
+        if file_mtime < updated_at {
+            stale.push(StaleVarJson {
+                fee_id: fee.id.as_ref().map(normalize_thing_id).unwrap_or_default(),
+                file_path: var_json.to_string_lossy().to_string(),
+                file_mtime: file_mtime.to_rfc3339(),
+                fee_updated_at: fee.time.updated_at.clone(),
+            });
         }
     }
     
-    Ok(())
+    stale
+}
+
+/// Resolve a fee's var.json path from the current location of its project folder
+fn fee_file_status_in(base_path: &Path, fee: &Fee, projects: &[Project]) -> Result<FeeFileStatus, String> {
+    let fee_id = fee.id.as_ref().map(normalize_thing_id).unwrap_or_default();
+    let project = projects.iter()
+        .find(|p| is_linked(&p.id, &fee.project_id))
+        .ok_or_else(|| format!("Project not found for fee {}", fee_id))?;
+    
+    let info = find_project_folder_in(base_path, &project.number.id);
+    let file_path = info.exists.then(|| {
+        PathBuf::from(&info.full_path)
+            .join("02 Proposal")
+            .join(format!("{}-var.json", project.number.id))
+    });
+    
+    Ok(FeeFileStatus {
+        fee_id,
+        project_number: project.number.id.clone(),
+        folder_location: info.current_location,
+        exists: file_path.as_ref().is_some_and(|path| path.exists()),
+        file_path: file_path.map(|path| path.to_string_lossy().to_string()),
+    })
+}
+
+/// Report whether a fee's var.json exists where its project folder is now
+#[command]
+pub async fn get_fee_file_status(fee_id: String, state: State<'_, AppState>) -> Result<FeeFileStatus, String> {
+    let base_path = get_projects_base_path()?;
+    
+    let manager_clone = {
+        let manager = state.lock().map_err(|e| e.to_string())?;
+        manager.clone()
+    };
+    
+    let fees = manager_clone.get_fees().await
+        .map_err(|e| format!("Failed to fetch fees: {}", e))?;
+    let projects = manager_clone.get_projects(true).await
+        .map_err(|e| format!("Failed to fetch projects: {}", e))?;
+    
+    let fee = fees.iter()
+        .find(|f| f.id.as_ref().is_some_and(|id| thing_matches(id, &fee_id)))
+        .ok_or_else(|| format!("Fee {} not found", fee_id))?;
+    
+    fee_file_status_in(&base_path, fee, &projects)
+}
+
+/// Find exported var.json files that predate the latest edit of their fee
+#[command]
+pub async fn find_stale_var_jsons(state: State<'_, AppState>) -> Result<Vec<StaleVarJson>, String> {
+    let base_path = get_projects_base_path()?;
+    
+    let manager_clone = {
+        let manager = state.lock().map_err(|e| e.to_string())?;
+        manager.clone()
+    };
+    
+    let fees = manager_clone.get_fees().await
+        .map_err(|e| format!("Failed to fetch fees: {}", e))?;
+    let projects = manager_clone.get_projects(true).await
+        .map_err(|e| format!("Failed to fetch projects: {}", e))?;
+    
+    Ok(stale_var_jsons_in(&base_path, fees, &projects))
 }
 
 #[command]
@@ -315,4 +1218,4 @@ pub async fn validate_project_base_path() -> Result<String, String> {
         }
         Err(e) => Err(e)
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,517 @@
+//! # Folder Management Unit Tests
+//!
+//! Tests for the folder helpers behind the folder management commands. They
+//! run against temporary project base folders instead of the configured one.
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::folder_management::*;
+    use crate::db::{ProjectNumber, TimeStamps};
+    use surrealdb::sql::Thing;
+
+    fn temp_base() -> PathBuf {
+        let base = env::temp_dir().join(format!("efees-folders-{}", uuid::Uuid::new_v4()));
+        for status_dir in ["00 Inactive", "01 RFPs", "11 Current", "99 Completed"] {
+            fs::create_dir_all(base.join(status_dir)).unwrap();
+        }
+        base
+    }
+
+    fn project(number: &str, name_short: &str, status: &str) -> Project {
+        Project {
+            id: Some(Thing::from(("projects", number.replace('-', "_").as_str()))),
+            name: format!("{} Project", name_short),
+            name_short: name_short.to_string(),
+            status: status.to_string(),
+            area: "Downtown".to_string(),
+            city: "Dubai".to_string(),
+            country: "United Arab Emirates".to_string(),
+            folder: format!("{} {}", number, name_short),
+            number: ProjectNumber {
+                year: 25,
+                country: 971,
+                seq: number[6..].parse().unwrap(),
+                id: number.to_string(),
+            },
+            time: TimeStamps {
+                created_at: "2025-01-01T00:00:00Z".to_string(),
+                updated_at: "2025-01-01T00:00:00Z".to_string(),
+            },
+            activity: None,
+            package: None,
+            template_override: None,
+            deleted_at: None,
+        }
+    }
+
+    fn fee(key: &str, project_key: &str, updated_at: &str) -> Fee {
+        Fee {
+            id: Some(Thing::from(("fee", key))),
+            name: "Lighting Design".to_string(),
+            number: format!("{}-FP", project_key),
+            rev: 1,
+            status: "Sent".to_string(),
+            issue_date: "250110".to_string(),
+            activity: "Design and Consultancy".to_string(),
+            package: "Lighting".to_string(),
+            project_id: Thing::from(("projects", project_key)),
+            company_id: Thing::from(("company", "CHE")),
+            contact_id: Thing::from(("contacts", "john_smith")),
+            staff_name: "Staff".to_string(),
+            staff_email: "staff@example.com".to_string(),
+            staff_phone: "+971 50 000 0000".to_string(),
+            staff_position: "Director".to_string(),
+            strap_line: "sensory design studio".to_string(),
+            revisions: Vec::new(),
+            time: TimeStamps {
+                created_at: "2020-01-01T00:00:00Z".to_string(),
+                updated_at: updated_at.to_string(),
+            },
+            currency: Some("AED".to_string()),
+            amount: None,
+        }
+    }
+
+    /// Café (folder name decomposed, as some file servers return it) and Villa
+    /// in 11 Current, and Tower whose RFP folder is still named "Tower Old".
+    /// Returns the decomposed Café folder and the three projects.
+    fn cafe_villa_tower(base: &Path) -> (PathBuf, Vec<Project>) {
+        let decomposed = base.join("11 Current").join("25-97105 Cafe\u{0301}");
+        fs::create_dir_all(&decomposed).unwrap();
+        fs::create_dir_all(base.join("11 Current").join("25-97106 Villa")).unwrap();
+        fs::create_dir_all(base.join("01 RFPs").join("25-97107 Tower Old")).unwrap();
+
+        let projects = vec![
+            project("25-97105", "Caf\u{00E9}", "Active"),
+            project("25-97106", "Villa", "Active"),
+            project("25-97107", "Tower", "RFP"),
+        ];
+        (decomposed, projects)
+    }
+
+    #[test]
+    fn test_projects_missing_folders() {
+        let base = temp_base();
+        fs::create_dir_all(base.join("11 Current").join("25-97105 Hotel")).unwrap();
+
+        let projects = vec![project("25-97105", "Hotel", "Active"), project("25-97106", "Villa", "RFP")];
+        let missing = projects_missing_folders(&base, projects);
+
+        assert_eq!(missing.len(), 1);
+        assert_eq!(missing[0].number.id, "25-97106");
+
+        fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn test_archive_project_folder_round_trips() {
+        use std::io::Read;
+        
+        let base = temp_base();
+        let project_dir = base.join("99 Completed").join("25-97105 Hotel");
+        fs::create_dir_all(project_dir.join("02 Proposal")).unwrap();
+        fs::create_dir_all(project_dir.join("03 Empty")).unwrap();
+        fs::write(project_dir.join("02 Proposal").join("25-97105-var Default Values.json"), "{\"a\": 1}").unwrap();
+        fs::write(project_dir.join("notes.txt"), "handover notes").unwrap();
+        let dest = base.join("archives");
+        fs::create_dir_all(&dest).unwrap();
+
+        let archive_path = archive_project_folder_in(&base, "25-97105", &dest, false).unwrap();
+        assert_eq!(archive_path, dest.join("25-97105 Hotel.zip"));
+        assert!(archive_path.exists());
+        assert!(project_dir.exists());
+        assert!(archive_project_folder_in(&base, "25-97105", &dest, false).is_err());
+
+        let mut archive = zip::ZipArchive::new(fs::File::open(&archive_path).unwrap()).unwrap();
+        let mut json = String::new();
+        archive.by_name("25-97105 Hotel/02 Proposal/25-97105-var Default Values.json").unwrap().read_to_string(&mut json).unwrap();
+        assert_eq!(json, "{\"a\": 1}");
+        assert!(archive.by_name("25-97105 Hotel/03 Empty/").unwrap().is_dir());
+
+        let extracted = base.join("extracted");
+        archive.extract(&extracted).unwrap();
+        assert_eq!(fs::read_to_string(extracted.join("25-97105 Hotel").join("notes.txt")).unwrap(), "handover notes");
+
+        fs::remove_file(&archive_path).unwrap();
+        archive_project_folder_in(&base, "25-97105", &dest, true).unwrap();
+        assert!(!project_dir.exists());
+        assert!(archive_project_folder_in(&base, "25-97106", &dest, false).is_err());
+
+        fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn test_decomposed_folder_name_flagged() {
+        let base = temp_base();
+        let (decomposed, projects) = cafe_villa_tower(&base);
+        let issues = folder_name_encoding_issues(&base, &projects);
+
+        assert_eq!(issues, vec![decomposed.to_string_lossy().to_string()]);
+
+        fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn test_name_short_mismatch_reported() {
+        let base = temp_base();
+        let (_, mut projects) = cafe_villa_tower(&base);
+        projects.push(project("25-97108", "Mall", "RFP"));
+        let mismatches = name_short_mismatches(&base, &projects);
+
+        assert_eq!(
+            mismatches,
+            vec![NameShortMismatch {
+                project_number: "25-97107".to_string(),
+                db_name_short: "Tower".to_string(),
+                folder_name_short: "Tower Old".to_string(),
+            }]
+        );
+
+        fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn test_project_folder_collisions() {
+        let base = temp_base();
+        let hotel = project("25-97105", "Hotel", "Active");
+        let mut copy = project("25-97106", "Villa", "Active");
+        copy.folder = hotel.folder.clone();
+        let other = project("25-97107", "Tower", "Active");
+        let mut unset = project("25-97108", "Mall", "Active");
+        unset.folder = String::new();
+
+        let collisions = project_folder_collisions(&base, vec![hotel, copy, other, unset.clone(), unset]);
+
+        assert_eq!(collisions.len(), 1);
+        let numbers: Vec<&str> = collisions[0].iter().map(|project| project.number.id.as_str()).collect();
+        assert_eq!(numbers, ["25-97105", "25-97106"]);
+
+        fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn test_create_project_folder_from_template() {
+        let base = temp_base();
+        let template = base.join("01 RFPs").join("_yy-cccnn Project Name");
+        fs::create_dir_all(template.join("02 Proposal")).unwrap();
+        fs::write(template.join("02 Proposal").join("yy-cccnn-var Default Values.json"), "{}").unwrap();
+
+        let missing = project("25-97106", "Villa", "Active");
+        assert_eq!(projects_missing_folders(&base, vec![missing.clone()]).len(), 1);
+
+        let result = create_project_folder_from_template(&base, &missing, &CancellationToken::default());
+        assert!(result.success, "{}", result.message);
+
+        let created = base.join("11 Current").join("25-97106 Villa");
+        assert!(created.join("02 Proposal").join("25-97106-var Default Values.json").exists());
+        assert!(projects_missing_folders(&base, vec![missing]).is_empty());
+
+        fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn test_create_project_folder_unknown_status() {
+        let base = temp_base();
+        let result = create_project_folder_from_template(&base, &project("25-97107", "Mall", "Mystery"), &CancellationToken::default());
+        assert!(!result.success);
+        assert!(result.message.contains("Unknown status"));
+        fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn test_bulk_move_project_folders_continues_past_failure() {
+        let base = temp_base();
+        fs::create_dir_all(base.join("01 RFPs").join("25-97105 Hotel")).unwrap();
+        fs::create_dir_all(base.join("01 RFPs").join("25-97106 Villa")).unwrap();
+        fs::create_dir_all(base.join("11 Current").join("25-97107 Mall")).unwrap();
+        // Destination already exists for the Mall move
+        fs::create_dir_all(base.join("99 Completed").join("25-97107 Mall")).unwrap();
+
+        let moves = vec![
+            ProjectMove { project_number: "25-97105".to_string(), new_status: "Lost".to_string() },
+            ProjectMove { project_number: "25-97107".to_string(), new_status: "Completed".to_string() },
+            ProjectMove { project_number: "25-97106".to_string(), new_status: "Lost".to_string() },
+        ];
+        let results = bulk_move_project_folders(&base, &moves, &default_awarded_template_folders(), &CancellationToken::default());
+
+        assert_eq!(results.len(), 3);
+        assert!(results[0].success);
+        assert!(!results[1].success);
+        assert!(results[1].message.contains("already exists"));
+        assert!(results[2].success);
+        assert!(base.join("00 Inactive").join("25-97105 Hotel").exists());
+        assert!(base.join("00 Inactive").join("25-97106 Villa").exists());
+        assert!(base.join("11 Current").join("25-97107 Mall").exists());
+
+        fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn test_repair_folder_name_after_move_to_current() {
+        let base = temp_base();
+        let proposal = base.join("01 RFPs").join("25-97105 Hotel RFP").join("02 Proposal");
+        fs::create_dir_all(&proposal).unwrap();
+        fs::write(proposal.join("25-97105-var.json"), "{}").unwrap();
+
+        let moved = move_project_folder_in(&base, "25-97105", "Awarded", &default_awarded_template_folders(), &CancellationToken::default()).unwrap();
+        assert!(moved.success, "{}", moved.message);
+
+        let repaired = repair_project_folder_name_in(&base, "25-97105", "Hotel").unwrap();
+        assert!(repaired.success, "{}", repaired.message);
+
+        let current = base.join("11 Current").join("25-97105 Hotel");
+        assert_eq!(find_var_json_in(&base, "25-97105"), Some(current.join("02 Proposal").join("25-97105-var.json")));
+        assert!(!base.join("11 Current").join("25-97105 Hotel RFP").exists());
+
+        // Already correct names are left alone
+        let again = repair_project_folder_name_in(&base, "25-97105", "Hotel").unwrap();
+        assert!(again.success);
+        assert_eq!(again.old_path, again.new_path);
+
+        fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn test_cancelled_template_copy_halts_and_cleans_up() {
+        let base = temp_base();
+        let templates = base.join("11 Current").join("00 Additional Folders");
+        for folder in ["03 Contract", "04 Deliverables"] {
+            fs::create_dir_all(templates.join(folder)).unwrap();
+            for i in 0..20 {
+                fs::write(templates.join(folder).join(format!("sheet-{:02}.pdf", i)), "x").unwrap();
+            }
+        }
+        let project = base.join("11 Current").join("25-97105 Hotel");
+        fs::create_dir_all(&project).unwrap();
+
+        let token = CancellationToken::default();
+        token.cancel();
+        let err = copy_awarded_templates(&base, &project, &default_awarded_template_folders(), &token).unwrap_err();
+        assert!(err.contains("Cancelled"));
+        assert!(!project.join("03 Contract").exists());
+        assert!(!project.join("04 Deliverables").exists());
+
+        // Cancellation between files stops the copy with an Interrupted error
+        let partial = base.join("partial");
+        let err = copy_dir_recursively(&templates.join("03 Contract"), &partial, &token).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::Interrupted);
+        assert_eq!(fs::read_dir(&partial).unwrap().count(), 0);
+
+        copy_awarded_templates(&base, &project, &default_awarded_template_folders(), &CancellationToken::default()).unwrap();
+        assert_eq!(fs::read_dir(project.join("04 Deliverables")).unwrap().count(), 20);
+
+        fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn test_template_copy_cancelled_mid_copy() {
+        let base = temp_base();
+        let template = base.join("01 RFPs").join("_yy-cccnn Project Name");
+        for folder in ["01 Admin", "02 Proposal", "03 Drawings"] {
+            fs::create_dir_all(template.join(folder)).unwrap();
+            for i in 0..400 {
+                fs::write(template.join(folder).join(format!("sheet-{:03}.pdf", i)), "x").unwrap();
+            }
+        }
+        let dest = base.join("partial");
+
+        // Cancel from another thread as soon as the first entry has been copied
+        let token = CancellationToken::default();
+        let copy = {
+            let (template, dest, token) = (template.clone(), dest.clone(), token.clone());
+            std::thread::spawn(move || copy_folder_recursive(&template, &dest, &token))
+        };
+        while fs::read_dir(&dest).map(|entries| entries.count()).unwrap_or(0) == 0 {
+            std::thread::yield_now();
+        }
+        token.cancel();
+
+        let err = copy.join().unwrap().unwrap_err();
+        assert!(err.contains("Cancelled"), "{}", err);
+        let copied = walk_files(&dest);
+        assert!(copied > 0 && copied < 1200, "copied {} files", copied);
+
+        // A cancelled folder creation removes its partial folder
+        let result = create_project_folder_from_template(&base, &project("25-97105", "Hotel", "RFP"), &token);
+        assert!(!result.success);
+        assert!(result.message.contains("Cancelled"), "{}", result.message);
+        assert!(!base.join("01 RFPs").join("25-97105 Hotel").exists());
+
+        // and a cancelled batch doesn't attempt the remaining projects
+        let results = create_project_folders(&base, &[project("25-97106", "Villa", "RFP")], &token);
+        assert!(results[0].message.starts_with("Cancelled before"));
+        assert!(!base.join("01 RFPs").join("25-97106 Villa").exists());
+
+        fs::remove_dir_all(&base).unwrap();
+    }
+
+    fn walk_files(dir: &Path) -> usize {
+        fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| {
+                let path = entry.unwrap().path();
+                if path.is_dir() { walk_files(&path) } else { 1 }
+            })
+            .sum()
+    }
+
+    #[test]
+    fn test_ensure_awarded_templates_adds_missing_folders() {
+        let base = temp_base();
+        let templates = base.join("11 Current").join("00 Additional Folders");
+        for folder in ["03 Contract", "04 Deliverables", "05 Submittals"] {
+            fs::create_dir_all(templates.join(folder)).unwrap();
+            fs::write(templates.join(folder).join("template.docx"), "template").unwrap();
+        }
+        let project = base.join("11 Current").join("25-97105 Hotel");
+        fs::create_dir_all(project.join("03 Contract")).unwrap();
+        fs::write(project.join("03 Contract").join("signed.pdf"), "signed").unwrap();
+        fs::create_dir_all(project.join("05 Submittals")).unwrap();
+
+        let added = ensure_awarded_templates_in(&base, "25-97105", &default_awarded_template_folders()).unwrap();
+        assert_eq!(added, vec!["04 Deliverables".to_string()]);
+        assert!(project.join("04 Deliverables").join("template.docx").exists());
+
+        // Existing folders are left as they were
+        assert!(project.join("03 Contract").join("signed.pdf").exists());
+        assert!(!project.join("03 Contract").join("template.docx").exists());
+        assert!(!project.join("05 Submittals").join("template.docx").exists());
+
+        assert!(ensure_awarded_templates_in(&base, "25-97105", &default_awarded_template_folders()).unwrap().is_empty());
+
+        fs::create_dir_all(base.join("01 RFPs").join("25-97106 Villa")).unwrap();
+        assert!(ensure_awarded_templates_in(&base, "25-97106", &default_awarded_template_folders()).unwrap_err().contains("01 RFPs"));
+
+        fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn test_awarded_templates_use_configured_folders() {
+        let base = temp_base();
+        let templates = base.join("11 Current").join("00 Additional Folders");
+        for folder in ["03 Contract", "04 Deliverables", "07 Drawings"] {
+            fs::create_dir_all(templates.join(folder)).unwrap();
+        }
+        fs::create_dir_all(base.join("01 RFPs").join("25-97105 Hotel")).unwrap();
+
+        let folders = vec!["07 Drawings".to_string(), "03 Contract".to_string()];
+        let moved = move_project_folder_in(&base, "25-97105", "Awarded", &folders, &CancellationToken::default()).unwrap();
+        assert!(moved.success, "{}", moved.message);
+
+        let project = base.join("11 Current").join("25-97105 Hotel");
+        assert!(project.join("07 Drawings").is_dir());
+        assert!(project.join("03 Contract").is_dir());
+        assert!(!project.join("04 Deliverables").exists());
+
+        let added = ensure_awarded_templates_in(&base, "25-97105", &["04 Deliverables".to_string()]).unwrap();
+        assert_eq!(added, vec!["04 Deliverables".to_string()]);
+
+        fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn test_misformatted_project_folders() {
+        let base = temp_base();
+        fs::create_dir_all(base.join("01 RFPs").join("25_97105 Hotel")).unwrap();
+        fs::create_dir_all(base.join("11 Current").join("⟨25-97106⟩ Villa")).unwrap();
+        fs::create_dir_all(base.join("11 Current").join("25-97107 Mall")).unwrap();
+        fs::create_dir_all(base.join("01 RFPs").join("_yy-cccnn Project Name")).unwrap();
+        fs::create_dir_all(base.join("11 Current").join("00 Additional Folders")).unwrap();
+
+        let flagged: Vec<String> = misformatted_project_folders_in(&base)
+            .into_iter()
+            .map(|(_, canonical)| canonical)
+            .collect();
+        assert_eq!(flagged, vec!["25-97105 Hotel", "25-97106 Villa"]);
+
+        let results = repair_misformatted_project_folders_in(&base);
+        assert!(results.iter().all(|r| r.success));
+        assert!(base.join("01 RFPs").join("25-97105 Hotel").exists());
+        assert!(base.join("11 Current").join("25-97106 Villa").exists());
+        assert!(misformatted_project_folders_in(&base).is_empty());
+
+        fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn test_stale_var_json_after_fee_edit() {
+        let base = temp_base();
+        for folder in ["25-97105 Hotel", "25-97106 Villa"] {
+            let proposal = base.join("01 RFPs").join(folder).join("02 Proposal");
+            fs::create_dir_all(&proposal).unwrap();
+            fs::write(proposal.join(format!("{}-var.json", &folder[..8])), "{}").unwrap();
+        }
+
+        let hotel = project("25-97105", "Hotel", "RFP");
+        let villa = project("25-97106", "Villa", "RFP");
+
+        // Hotel's fee was edited after its var.json was written; Villa's before
+        let edited_later = (chrono::Utc::now() + chrono::Duration::hours(1)).to_rfc3339();
+        let fees = vec![
+            fee("25_97105_1", "25_97105", &edited_later),
+            fee("25_97106_1", "25_97106", "2020-01-01T00:00:00Z"),
+        ];
+
+        let stale = stale_var_jsons_in(&base, fees, &[hotel, villa]);
+        assert_eq!(stale.len(), 1);
+        assert_eq!(stale[0].fee_id, "25_97105_1");
+        assert_eq!(stale[0].fee_updated_at, edited_later);
+        assert!(stale[0].file_path.ends_with("25-97105-var.json"));
+
+        fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn test_fee_file_found_after_project_move() {
+        let base = temp_base();
+        let proposal = base.join("01 RFPs").join("25-97105 Hotel").join("02 Proposal");
+        fs::create_dir_all(&proposal).unwrap();
+        fs::write(proposal.join("25-97105-var.json"), "{}").unwrap();
+
+        let projects = [project("25-97105", "Hotel", "RFP")];
+        let fee = fee("25_97105_1", "25_97105", "2025-01-10T00:00:00Z");
+
+        let before = fee_file_status_in(&base, &fee, &projects).unwrap();
+        assert!(before.exists);
+        assert_eq!(before.folder_location, "01 RFPs");
+
+        let moved = move_project_folder_in(&base, "25-97105", "Lost", &default_awarded_template_folders(), &CancellationToken::default()).unwrap();
+        assert!(moved.success, "{}", moved.message);
+
+        let after = fee_file_status_in(&base, &fee, &projects).unwrap();
+        assert!(after.exists);
+        assert_eq!(after.fee_id, "25_97105_1");
+        assert_eq!(after.folder_location, "00 Inactive");
+        assert_eq!(
+            after.file_path.map(PathBuf::from),
+            Some(base.join("00 Inactive").join("25-97105 Hotel").join("02 Proposal").join("25-97105-var.json"))
+        );
+
+        fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn test_fee_file_found_for_escaped_project_link() {
+        use surrealdb::sql::Id;
+
+        let base = temp_base();
+        let proposal = base.join("01 RFPs").join("25-97105 Hotel").join("02 Proposal");
+        fs::create_dir_all(&proposal).unwrap();
+        fs::write(proposal.join("25-97105-var.json"), "{}").unwrap();
+
+        // The project's ID displays as projects:⟨2597105⟩, the fee links projects:2597105
+        let mut hotel = project("25-97105", "Hotel", "RFP");
+        hotel.id = Some(Thing::from(("projects", Id::String("2597105".to_string()))));
+        let projects = [hotel];
+        let mut fee = fee("2597105_1", "2597105", "2099-01-01T00:00:00Z");
+        fee.project_id = Thing::from(("projects", Id::Number(2597105)));
+
+        let status = fee_file_status_in(&base, &fee, &projects).unwrap();
+        assert!(status.exists);
+        assert_eq!(status.folder_location, "01 RFPs");
+        // Updated after the file was written, so flagged once the link matches
+        assert_eq!(stale_var_jsons_in(&base, vec![fee], &projects).len(), 1);
+
+        fs::remove_dir_all(&base).unwrap();
+    }
+}
@@ -21,6 +21,8 @@
 
 pub mod utils;
 pub mod folder_management;
+#[cfg(test)]
+mod tests;
 
 // Re-export folder management commands
 pub use folder_management::{
@@ -28,15 +30,29 @@ pub use folder_management::{
     move_project_folder,
     move_project_from_rfp,
     move_project_to_archive,
+    archive_project_folder,
     list_projects_in_folder,
     validate_project_base_path,
+    find_projects_without_folders,
+    find_project_folder_collisions,
+    find_folder_name_encoding_issues,
+    find_name_short_mismatches,
+    ensure_awarded_templates,
+    create_missing_project_folders,
+    bulk_move_projects,
+    repair_project_folder_name,
+    find_stale_var_jsons,
+    get_fee_file_status,
+    find_misformatted_project_folders,
+    repair_misformatted_project_folders,
 };
 
 // Import the utility functions and macros
-use utils::execute_with_manager;
+use utils::{execute_with_manager, safe_join};
+use crate::db::utils::{normalize_thing_id, record_key, thing_matches};
 use crate::crud_command;
 
-use crate::db::{DatabaseManager, ConnectionStatus, Project, NewProject, Company, CompanyCreate, Contact, ContactCreate, Fee, FeeCreate, FeeUpdate};
+use crate::db::{AdjacentProjects, CancellationToken, CapacityWarning, CountryProjectCount, YearProjectCount, CountryCurrency, Currency, DatabaseConfig, DatabaseManager, ConnectionStatus, DatabaseInfo, IncompleteContact, OperationInfo, Page, Project, ProjectCreateResult, ProjectNumber, NewProject, StatsCache, StatsCounts, Company, CompanyCreate, Contact, ContactCreate, Fee, FeeCreate, FeeLoad, FeeUpdate};
 // use crate::db::entities::FeeUpdate; // Temporarily disabled for testing
 use std::sync::{Arc, Mutex};
 use std::fs;
@@ -123,14 +139,17 @@ pub struct ProjectUpdate {
 /// 
 /// This struct represents all configurable settings that can be modified
 /// through the application's settings interface. Settings are persisted
-/// to the `.env` file in the project root.
+/// to `settings.json` in the app data directory.
 /// 
 /// # Database Configuration
 /// - `surrealdb_url`: WebSocket URL for SurrealDB connection
 /// - `surrealdb_ns`: Database namespace (typically "emittiv")
 /// - `surrealdb_db`: Database name (typically "projects")
 /// - `surrealdb_user`: Authentication username
-/// - `surrealdb_pass`: Authentication password
+/// - `surrealdb_pass`: Authentication password, stored in the OS keychain
+///   when one is available
+/// - `surrealdb_pass_error`: Set instead of the password when the keychain
+///   could not be read
 /// 
 /// # Staff Information
 /// - `staff_name`: Default staff member name for proposals
@@ -140,7 +159,17 @@ pub struct ProjectUpdate {
 /// 
 /// # File System
 /// - `project_folder_path`: Base path for project template folders
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// - `awarded_template_folders`: Subfolders of `11 Current/00 Additional Folders`
+///   copied into a project when it is awarded (defaults to the standard set)
+/// 
+/// # New Project Defaults
+/// - `default_country`: Country to prefill in the New Project form
+/// - `default_activity`: Activity to prefill in the New Project form
+/// 
+/// # Connection Monitoring
+/// - `heartbeat_failure_threshold`: Consecutive failed health checks before the
+///   connection is reported as lost (default 2)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct AppSettings {
     pub surrealdb_url: Option<String>,
     pub surrealdb_ns: Option<String>,
@@ -152,6 +181,204 @@ pub struct AppSettings {
     pub staff_phone: Option<String>,
     pub staff_position: Option<String>,
     pub project_folder_path: Option<String>,
+    pub default_country: Option<String>,
+    pub default_activity: Option<String>,
+    pub heartbeat_failure_threshold: Option<String>,
+    #[serde(default = "default_awarded_template_folders")]
+    pub awarded_template_folders: Vec<String>,
+    /// Why the keychain password could not be read (`surrealdb_pass` is then
+    /// `None`). Reported to the frontend only; never read from or written to
+    /// the settings file.
+    #[serde(default, skip_deserializing, skip_serializing_if = "Option::is_none")]
+    pub surrealdb_pass_error: Option<String>,
+}
+
+/// Environment variable names backing each `AppSettings` field, in file order.
+const SETTINGS_KEYS: &[&str] = &[
+    "SURREALDB_URL",
+    "SURREALDB_NS",
+    "SURREALDB_DB",
+    "SURREALDB_USER",
+    "SURREALDB_PASS",
+    "STAFF_NAME",
+    "STAFF_EMAIL",
+    "STAFF_PHONE",
+    "STAFF_POSITION",
+    "PROJECT_FOLDER_PATH",
+    "DEFAULT_COUNTRY",
+    "DEFAULT_ACTIVITY",
+    "HEARTBEAT_FAILURE_THRESHOLD",
+];
+
+/// Template subfolders added to awarded projects when none are configured
+const DEFAULT_AWARDED_TEMPLATE_FOLDERS: [&str; 6] = ["03 Contract", "04 Deliverables", "05 Submittals", "11 SubContractors", "98 Outgoing", "99 Temp"];
+
+fn default_awarded_template_folders() -> Vec<String> {
+    DEFAULT_AWARDED_TEMPLATE_FOLDERS.iter().map(|f| f.to_string()).collect()
+}
+
+/// A single differing field between two fee proposals.
+/// 
+/// Returned by `compare_fees` to drive the side-by-side comparison view.
+/// Record links are rendered as `table:id` strings so they can be shown as-is.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FeeFieldDiff {
+    pub field: String,
+    pub a_value: Value,
+    pub b_value: Value,
+}
+
+/// A project with its fee proposals, used for the projects→fees tree view.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectFeeNode {
+    pub project: Project,
+    pub fees: Vec<Fee>,
+}
+
+/// A field whose value on a fee differs from the value on its project.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FeeProjectMismatch {
+    pub field: String,
+    pub fee_value: String,
+    pub project_value: String,
+}
+
+/// One line of a project's proposal index, as printed on a cover page.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ProposalIndexEntry {
+    pub number: String,
+    pub rev: i32,
+    pub status: String,
+    pub issue_date: String,
+}
+
+/// The configured staff member, used to prefill the "from" fields of a fee.
+/// 
+/// Unset settings are returned as empty strings.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct StaffInfo {
+    pub name: String,
+    pub email: String,
+    pub phone: String,
+    pub position: String,
+}
+
+/// Where the app keeps its data, logs and settings file on this machine.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AppPaths {
+    pub data_dir: String,
+    pub log_dir: String,
+    pub settings_path: String,
+}
+
+/// Window position and size for `position_window`, in logical or physical pixels.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WindowPlacement {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+    pub use_logical: bool,
+}
+
+/// A connected monitor; position and size are in physical pixels.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MonitorInfo {
+    pub name: Option<String>,
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub scale_factor: f64,
+    pub is_primary: bool,
+}
+
+/// Which of the expected template entries exist; `valid` only if none are missing.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TemplateStructureReport {
+    pub template_path: String,
+    pub template_found: bool,
+    pub present: Vec<String>,
+    pub missing: Vec<String>,
+    pub valid: bool,
+}
+
+/// Result of checking one number in `validate_project_numbers`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ProjectNumberCheck {
+    pub number: String,
+    pub available: bool,
+    pub format_ok: bool,
+}
+
+/// Outcome of one row of `bulk_create_contacts`, by position in the input.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContactImportRow {
+    pub index: usize,
+    pub result: Result<Contact, String>,
+}
+
+/// Summary of a `bulk_create_contacts` run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BulkContactResult {
+    pub created: usize,
+    pub failed: usize,
+    pub details: Vec<ContactImportRow>,
+}
+
+/// Outcome of one `run_startup_checks` step, with a hint for fixing it.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct StartupCheck {
+    pub name: String,
+    pub passed: bool,
+    pub message: String,
+    pub remediation: Option<String>,
+}
+
+/// Result of `run_startup_checks`; `passed` is true only if every check passed.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct StartupReport {
+    pub passed: bool,
+    pub checks: Vec<StartupCheck>,
+}
+
+/// One key of the InDesign `-var.json` export and the field that feeds it.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FeeJsonField {
+    pub key: String,
+    pub source: String,
+}
+
+/// Outcome of exporting one fee during `export_all_active_fees`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FeeExportResult {
+    pub fee_id: String,
+    pub fee_number: String,
+    pub success: bool,
+    pub skipped: bool,
+    pub message: String,
+}
+
+/// Summary of a batch var.json export.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BatchResult {
+    pub exported: usize,
+    pub skipped: usize,
+    pub failed: usize,
+    /// Whether the run was stopped through `cancel_operation`
+    pub cancelled: bool,
+    pub results: Vec<FeeExportResult>,
+}
+
+impl From<&AppSettings> for StaffInfo {
+    fn from(settings: &AppSettings) -> Self {
+        StaffInfo {
+            name: settings.staff_name.clone().unwrap_or_default(),
+            email: settings.staff_email.clone().unwrap_or_default(),
+            phone: settings.staff_phone.clone().unwrap_or_default(),
+            position: settings.staff_position.clone().unwrap_or_default(),
+        }
+    }
 }
 
 // ============================================================================
@@ -228,7 +455,8 @@ pub async fn get_connection_status(state: State<'_, AppState>) -> Result<Connect
 /// 
 /// This command fetches the complete list of projects with all associated
 /// metadata including project numbers, status, location, and timestamps.
-/// The results are sorted by creation date (newest first) at the database level.
+/// The results are sorted by creation date (newest first) at the database level,
+/// with projects lacking a timestamp listed last by project number.
 /// 
 /// # Returns
 /// - `Ok(Vec<Project>)`: List of all projects
@@ -241,23 +469,72 @@ pub async fn get_connection_status(state: State<'_, AppState>) -> Result<Connect
 /// - File system: folder path for project templates
 /// - Timestamps: created_at and updated_at (auto-managed)
 /// 
+/// # Parameters
+/// - `include_deleted`: Also return archived projects (default false)
+/// 
 /// # Frontend Usage
 /// ```typescript
 /// const projects = await invoke('get_projects');
 /// projects.forEach(project => console.log(project.name, project.number.id));
+/// const everything = await invoke('get_projects', { includeDeleted: true });
 /// ```
 /// 
 /// # Performance Considerations
 /// - Database query is optimized with proper indexing
 /// - Large result sets (1000+ projects) may take 2-3 seconds
 /// - Results are cached at the frontend level for better UX
-crud_command!(
-    get_projects,
-    Vec<Project>,
-    get_projects,
-    "fetch",
-    "projects"
-);
+#[tauri::command]
+pub async fn get_projects(include_deleted: Option<bool>, state: State<'_, AppState>) -> Result<Vec<Project>, String> {
+    let include_deleted = include_deleted.unwrap_or(false);
+    execute_with_manager(
+        &state,
+        |manager| Box::pin(async move { manager.get_projects(include_deleted).await }),
+        "fetch",
+        "projects"
+    ).await
+}
+
+/// Get one page of projects, sorted like `get_projects`.
+/// 
+/// Lets the project list load incrementally instead of fetching every
+/// record at once.
+/// 
+/// # Parameters
+/// - `limit`: Page size, capped at 500
+/// - `offset`: Number of projects to skip
+/// - `include_deleted`: Also return archived projects (default false)
+/// 
+/// # Returns
+/// - `Ok(Page<Project>)`: `{ items, total }` where `total` counts all matches
+/// - `Err(String)`: Zero page size or database error
+/// 
+/// # Frontend Usage
+/// ```typescript
+/// const page = await invoke('get_projects_paginated', { limit: 100, offset: 0 });
+/// console.log(`Showing ${page.items.length} of ${page.total}`);
+/// ```
+#[tauri::command]
+pub async fn get_projects_paginated(limit: u32, offset: u32, include_deleted: Option<bool>, state: State<'_, AppState>) -> Result<Page<Project>, String> {
+    let limit = page_size(limit)?;
+    let include_deleted = include_deleted.unwrap_or(false);
+    execute_with_manager(
+        &state,
+        |manager| Box::pin(async move { manager.get_projects_paged(offset.into(), limit, include_deleted).await }),
+        "fetch",
+        "projects"
+    ).await
+}
+
+/// Largest page the paginated list commands return.
+const MAX_PAGE_SIZE: u64 = 500;
+
+/// Cap a requested page size at `MAX_PAGE_SIZE`, rejecting empty pages.
+fn page_size(limit: u32) -> Result<u64, String> {
+    if limit == 0 {
+        return Err("Invalid page size 0: expected 1-500".to_string());
+    }
+    Ok(u64::from(limit).min(MAX_PAGE_SIZE))
+}
 
 /// Search projects using fuzzy matching across multiple fields.
 /// 
@@ -313,6 +590,102 @@ pub async fn search_projects(query: String, state: State<'_, AppState>) -> Resul
     }
 }
 
+/// Get projects modified after a given timestamp.
+/// 
+/// Lets the frontend poll for changed projects instead of refetching the
+/// whole table. Pass the `time.updated_at` of the newest project already
+/// loaded to receive only later changes.
+/// 
+/// # Parameters
+/// - `timestamp`: RFC 3339 timestamp (e.g., "2025-01-10T08:00:00Z")
+/// 
+/// # Returns
+/// - `Ok(Vec<Project>)`: Projects updated strictly after `timestamp`, oldest first
+/// - `Err(String)`: Invalid timestamp or database error
+/// 
+/// # Frontend Usage
+/// ```typescript
+/// const changed = await invoke('get_projects_modified_since', {
+///   timestamp: lastSync
+/// });
+/// ```
+#[tauri::command]
+pub async fn get_projects_modified_since(timestamp: String, state: State<'_, AppState>) -> Result<Vec<Project>, String> {
+    chrono::DateTime::parse_from_rfc3339(&timestamp)
+        .map_err(|e| format!("Invalid timestamp '{}': {}", timestamp, e))?;
+    
+    execute_with_manager(
+        &state,
+        |manager| Box::pin(async move {
+            manager.get_projects_modified_since(&timestamp).await
+        }),
+        "fetch",
+        "modified projects"
+    ).await
+}
+
+/// Get a single project by number.
+/// 
+/// Selects the record directly instead of loading every project and
+/// filtering.
+/// 
+/// # Parameters
+/// - `project_number`: "25-97105", "25_97105", "projects:25_97105" or "projects:⟨25_97105⟩"
+/// 
+/// # Returns
+/// - `Ok(Some(Project))`: The project
+/// - `Ok(None)`: No project with that number
+/// - `Err(String)`: Database error
+/// 
+/// # Frontend Usage
+/// ```typescript
+/// const project = await invoke('get_project', { projectNumber: '25-97105' });
+/// if (project === null) console.warn('Project not found');
+/// ```
+#[tauri::command]
+pub async fn get_project(project_number: String, state: State<'_, AppState>) -> Result<Option<Project>, String> {
+    let key = project_key(&project_number);
+    let entity = format!("project '{}'", key);
+    execute_with_manager(
+        &state,
+        |manager| Box::pin(async move { manager.get_project(&key).await }),
+        "fetch",
+        &entity
+    ).await
+}
+
+/// Check whether a project is in a terminal status (Completed or Cancelled).
+/// 
+/// Uses `Project::is_terminal`, so callers guarding deletes or folder moves
+/// agree on which statuses count as closed out.
+/// 
+/// # Parameters
+/// - `id`: "25-97105", "25_97105", "projects:25_97105" or "projects:⟨25_97105⟩"
+/// 
+/// # Returns
+/// - `Ok(bool)`: Whether the project is Completed or Cancelled
+/// - `Err(String)`: Project not found or database error
+/// 
+/// # Frontend Usage
+/// ```typescript
+/// const closed = await invoke('is_project_terminal', { id: '25-97105' });
+/// ```
+#[tauri::command]
+pub async fn is_project_terminal(id: String, state: State<'_, AppState>) -> Result<bool, String> {
+    let key = project_key(&id);
+    let entity = format!("project '{}'", key);
+    execute_with_manager(
+        &state,
+        |manager| Box::pin(async move {
+            manager.get_project(&key).await?
+                .map(|project| project.is_terminal())
+                .ok_or_else(|| surrealdb::Error::Api(surrealdb::error::Api::InvalidRequest("Project not found".to_string())))
+        }),
+        "check",
+        &entity
+    ).await
+}
+
 /// Create a new project in the database.
 /// 
 /// This command creates a standard project record without template folder
@@ -323,7 +696,8 @@ pub async fn search_projects(query: String, state: State<'_, AppState>) -> Resul
 /// - `project`: Complete project object with all required fields
 /// 
 /// # Returns
-/// - `Ok(Project)`: Created project with database-assigned ID
+/// - `Ok(ProjectCreateResult)`: `{ project, warnings }`, the created project with
+///   its database-assigned ID and any soft warnings (e.g. an unusual number year)
 /// - `Err(String)`: Validation error or database failure
 /// 
 /// # Validation Rules
@@ -344,11 +718,11 @@ pub async fn search_projects(query: String, state: State<'_, AppState>) -> Resul
 ///   number: { year: 25, country: 971, seq: 6, id: "25-97106" },
 ///   folder: "25-97106 Hotel XYZ"
 /// };
-/// const created = await invoke('create_project', { project: newProject });
+/// const { project, warnings } = await invoke('create_project', { project: newProject });
 /// ```
 crud_command!(
     create_project,
-    Project,
+    ProjectCreateResult,
     Project,
     create_project,
     "create",
@@ -398,6 +772,40 @@ crud_command!(
     "companies"
 );
 
+/// Get one page of companies, optionally filtered by country.
+/// 
+/// Companies are sorted by name like `get_companies`, so the directory can
+/// be paged through without loading every record.
+/// 
+/// # Parameters
+/// - `offset`: Number of companies to skip
+/// - `limit`: Page size (1-500)
+/// - `country`: Country to filter by, case-insensitive (optional)
+/// 
+/// # Returns
+/// - `Ok(Page<Company>)`: `{ items, total }` where `total` counts all matches
+/// - `Err(String)`: Invalid page size or database error
+/// 
+/// # Frontend Usage
+/// ```typescript
+/// const page = await invoke('get_companies_paged', { offset: 0, limit: 50, country: 'United Arab Emirates' });
+/// console.log(`Showing ${page.items.length} of ${page.total}`);
+/// ```
+#[tauri::command]
+pub async fn get_companies_paged(offset: u64, limit: u64, country: Option<String>, state: State<'_, AppState>) -> Result<Page<Company>, String> {
+    if !(1..=500).contains(&limit) {
+        return Err(format!("Invalid page size {}: expected 1-500", limit));
+    }
+    let country = country.map(|c| c.trim().to_string()).filter(|c| !c.is_empty());
+    
+    execute_with_manager(
+        &state,
+        |manager| Box::pin(async move { manager.get_companies_paged(offset, limit, country).await }),
+        "fetch",
+        "companies"
+    ).await
+}
+
 /// Create a new company in the database.
 /// 
 /// This command creates a new company record with automatic ID generation
@@ -414,6 +822,8 @@ crud_command!(
 /// - `name`: Must be non-empty and unique
 /// - `abbreviation`: Must be unique and alphanumeric (becomes ID)
 /// - `city` and `country`: Must be non-empty
+/// - `country`: Must match a name or code in the `country` table; aliases
+///   such as "UAE" are stored as the canonical name
 /// - Optional fields can be empty but not invalid
 /// 
 /// # Frontend Usage
@@ -459,6 +869,7 @@ crud_command!(
 /// - Unspecified fields remain unchanged
 /// - Timestamps are automatically updated
 /// - Validation is applied to new values
+/// - A new `country` is checked and canonicalized as in `create_company`
 /// 
 /// # Frontend Usage
 /// ```typescript
@@ -595,15 +1006,104 @@ crud_command!(
 /// };
 /// const created = await invoke('create_contact', { contact: newContact });
 /// ```
-crud_command!(
-    create_contact,
-    Contact,
-    ContactCreate,
-    create_contact,
-    "create",
-    "contact",
-    data: contact
-);
+#[tauri::command]
+pub async fn create_contact(contact: ContactCreate, state: State<'_, AppState>) -> Result<Contact, String> {
+    let manager_clone = {
+        let manager = state.lock().map_err(|e| e.to_string())?;
+        manager.clone()
+    };
+    ensure_contact_email_available(&manager_clone, &contact.email, "").await?;
+    
+    execute_with_manager(
+        &state,
+        |manager| Box::pin(async move { manager.create_contact(contact).await }),
+        "create",
+        "contact"
+    ).await
+}
+
+/// Create many contacts at once, e.g. from a pasted CSV.
+/// 
+/// Rows are validated and created one after another with the same rules as
+/// `create_contact`. A bad row (invalid field, unknown company, email already
+/// in use) is reported in the summary and does not stop the batch. Emails
+/// repeated within the batch fail after their first row.
+/// 
+/// # Parameters
+/// - `contacts`: Contacts to create, in input order
+/// 
+/// # Returns
+/// - `Ok(BulkContactResult)`: Created/failed counts and a result per row
+/// - `Err(String)`: State unavailable
+/// 
+/// # Frontend Usage
+/// ```typescript
+/// const summary = await invoke('bulk_create_contacts', { contacts: rows });
+/// summary.details.filter(d => 'Err' in d.result).forEach(d => console.warn(d.index, d.result.Err));
+/// ```
+#[tauri::command]
+pub async fn bulk_create_contacts(contacts: Vec<ContactCreate>, state: State<'_, AppState>) -> Result<BulkContactResult, String> {
+    info!("Bulk creating {} contacts", contacts.len());
+    
+    let manager_clone = {
+        let manager = state.lock().map_err(|e| e.to_string())?;
+        manager.clone()
+    };
+    
+    let mut details = Vec::with_capacity(contacts.len());
+    for (index, contact) in contacts.into_iter().enumerate() {
+        let result = create_contact_row(&manager_clone, contact).await;
+        if let Err(e) = &result {
+            warn!("Contact row {} not created: {}", index, e);
+        }
+        details.push(ContactImportRow { index, result });
+    }
+    
+    let created = details.iter().filter(|row| row.result.is_ok()).count();
+    let failed = details.len() - created;
+    info!("Bulk contact creation finished: {} created, {} failed", created, failed);
+    Ok(BulkContactResult { created, failed, details })
+}
+
+/// Validate and create a single `bulk_create_contacts` row.
+async fn create_contact_row(manager: &DatabaseManager, contact: ContactCreate) -> Result<Contact, String> {
+    validate_contact_create(&contact)?;
+    
+    let company = manager.get_record("company", contact.company.trim()).await
+        .map_err(|e| format!("Failed to check company: {}", e))?;
+    if company.is_none() {
+        return Err(format!("Company '{}' not found", contact.company.trim()));
+    }
+    ensure_contact_email_available(manager, &contact.email, "").await?;
+    
+    manager.create_contact(contact).await
+        .map_err(|e| format!("Failed to create contact: {}", e))
+}
+
+/// Check the `create_contact` field rules: names and company present, email
+/// containing `@` and phone in international `+` format.
+fn validate_contact_create(contact: &ContactCreate) -> Result<(), String> {
+    let mut errors = Vec::new();
+    
+    if contact.first_name.trim().is_empty() || contact.last_name.trim().is_empty() {
+        errors.push("First and last name are required".to_string());
+    }
+    if !contact.email.contains('@') {
+        errors.push(format!("Invalid email '{}'", contact.email.trim()));
+    }
+    if !contact.phone.contains('+') {
+        errors.push("Phone must contain '+' (international format)".to_string());
+    }
+    if contact.company.trim().is_empty() {
+        errors.push("Company is required".to_string());
+    }
+    
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors.join("; "))
+    }
+}
 
 /// Update an existing contact in the database.
 /// 
@@ -621,9 +1121,18 @@ crud_command!(
 /// * `contactUpdate` - Partial contact data with only fields to update
 /// 
 /// # Returns
-/// * `Result<Contact, String>` - Updated contact or error message
+/// * `Result<Contact, String>` - Updated contact or error message, including
+///   "Email already in use by another contact" when the new email is taken
 #[tauri::command]
 pub async fn update_contact(id: String, contactUpdate: ContactUpdate, state: State<'_, AppState>) -> Result<Contact, String> {
+    if let Some(email) = &contactUpdate.email {
+        let manager_clone = {
+            let manager = state.lock().map_err(|e| e.to_string())?;
+            manager.clone()
+        };
+        ensure_contact_email_available(&manager_clone, email, contact_key(&id)).await?;
+    }
+    
     let contact_name = format!("contact '{}'", id);
     execute_with_manager(
         &state,
@@ -667,20 +1176,20 @@ pub async fn update_contact(id: String, contactUpdate: ContactUpdate, state: Sta
 /// ```
 /// Delete a contact from the database.
 /// 
-/// This command permanently removes a contact record. Note that this
-/// operation will fail if the contact has associated RFPs or other
-/// relationships that prevent deletion.
+/// This command permanently removes a contact record. Contacts that are still
+/// the `contact_id` of any fee proposal are not deleted, so fees never point
+/// at a missing contact.
 /// 
 /// # Arguments
-/// * `id` - The contact ID (extracted from SurrealDB Thing object)
+/// * `id` - The contact ID, either `contacts:john_smith` or bare `john_smith`
 /// 
 /// # Returns
-/// * `Result<Contact, String>` - The deleted contact or error message
+/// * `Result<Contact, String>` - The deleted contact (for undo) or error message
 /// 
 /// # Errors
 /// Returns error if:
+/// - Contact is referenced by fee proposals ("Cannot delete contact: referenced by 3 fee proposals")
 /// - Contact doesn't exist
-/// - Contact has dependent records (RFPs, etc.)
 /// - Database connection failure
 /// 
 /// # Frontend Usage
@@ -694,13 +1203,29 @@ pub async fn update_contact(id: String, contactUpdate: ContactUpdate, state: Sta
 /// ```
 #[tauri::command]
 pub async fn delete_contact(id: String, state: State<'_, AppState>) -> Result<Contact, String> {
+    let key = contact_key(&id).to_string();
+    
+    let manager_clone = {
+        let manager = state.lock().map_err(|e| e.to_string())?;
+        manager.clone()
+    };
+    let references = manager_clone.count_fees_for_contact(&key).await
+        .map_err(|e| format!("Failed to check fee proposals for contact '{}': {}", id, e))?;
+    if references > 0 {
+        warn!("Refusing to delete contact '{}' referenced by {} fee proposals", id, references);
+        return Err(format!(
+            "Cannot delete contact: referenced by {} fee proposal{}",
+            references,
+            if references == 1 { "" } else { "s" }
+        ));
+    }
+    
     let contact_name = format!("contact '{}'", id);
     execute_with_manager(
         &state,
         |manager| {
-            let id_clone = id.clone();
             Box::pin(async move { 
-                manager.delete_contact(&id_clone).await 
+                manager.delete_contact(&key).await 
             })
         },
         "delete",
@@ -708,6 +1233,130 @@ pub async fn delete_contact(id: String, state: State<'_, AppState>) -> Result<Co
     ).await
 }
 
+/// Reject an email that another contact already uses, ignoring case, so the
+/// user sees a clear message instead of the unique index error.
+async fn ensure_contact_email_available(manager: &DatabaseManager, email: &str, excluding_id: &str) -> Result<(), String> {
+    let in_use = manager.email_in_use(email, excluding_id).await
+        .map_err(|e| format!("Failed to check contact email: {}", e))?;
+    if in_use {
+        warn!("Contact email '{}' is already in use", email);
+        return Err("Email already in use by another contact".to_string());
+    }
+    Ok(())
+}
+
+/// Record key of a contact ID given as `contacts:john_smith`,
+/// `contacts:⟨john smith⟩` or bare `john_smith`.
+fn contact_key(id: &str) -> &str {
+    record_key(id, "contacts")
+}
+
+/// List contacts that `get_contacts` hides because required fields are missing.
+/// 
+/// # Returns
+/// - `Ok(Vec<IncompleteContact>)`: Contact ID and the names of its missing fields
+/// - `Err(String)`: Database error
+/// 
+/// # Frontend Usage
+/// ```typescript
+/// const incomplete = await invoke('find_incomplete_contacts');
+/// // [{ id: "contacts:john_smith", missing_fields: ["phone", "position"] }]
+/// ```
+crud_command!(
+    find_incomplete_contacts,
+    Vec<IncompleteContact>,
+    find_incomplete_contacts,
+    "check",
+    "contacts for missing fields"
+);
+
+/// Repair legacy contacts whose company is stored as a plain string.
+/// 
+/// Such contacts deserialize without a company and are filtered out of
+/// `get_contacts`. The string is rewritten as a `company:` record link.
+/// 
+/// # Returns
+/// - `Ok(u64)`: Number of contacts repaired
+/// - `Err(String)`: Database error
+/// 
+/// # Frontend Usage
+/// ```typescript
+/// const repaired = await invoke('repair_contact_company_links');
+/// ```
+crud_command!(
+    repair_contact_company_links,
+    u64,
+    repair_contact_company_links,
+    "repair",
+    "contact company links"
+);
+
+/// Check a contact's email domain against the other contacts at its company.
+/// 
+/// A domain that differs from the one most colleagues use is likely a typo
+/// (e.g. `@exmaple.com`). A suggestion is only made when at least two other
+/// contacts, and a majority of them, share the common domain.
+/// 
+/// # Arguments
+/// * `contact_id` - The contact ID, either `contacts:john_smith` or bare `john_smith`
+/// 
+/// # Returns
+/// - `Ok(Some(String))`: The company's common domain when the contact's differs
+/// - `Ok(None)`: Domain is consistent, or there is too little data to tell
+/// - `Err(String)`: Contact not found or database error
+/// 
+/// # Frontend Usage
+/// ```typescript
+/// const domain = await invoke('check_contact_email_domain', { contactId: 'contacts:john_smith' });
+/// if (domain) console.warn(`Other contacts at this company use @${domain}`);
+/// ```
+#[tauri::command]
+pub async fn check_contact_email_domain(contact_id: String, state: State<'_, AppState>) -> Result<Option<String>, String> {
+    let contacts = execute_with_manager(
+        &state,
+        |manager| Box::pin(async move { manager.get_contacts().await }),
+        "fetch",
+        "contacts"
+    ).await?;
+    
+    let key = contact_key(&contact_id);
+    let contact = contacts.iter()
+        .find(|c| c.id.as_ref().is_some_and(|id| contact_key(&id.to_string()) == key))
+        .ok_or_else(|| format!("Contact '{}' not found", contact_id))?;
+    
+    let suggestion = suggested_email_domain(contact, &contacts);
+    if let Some(domain) = &suggestion {
+        warn!("Contact '{}' email domain differs from company domain '{}'", contact_id, domain);
+    }
+    Ok(suggestion)
+}
+
+/// Lowercased domain part of an email address.
+fn email_domain(email: &str) -> Option<String> {
+    let (_, domain) = email.trim().rsplit_once('@')?;
+    let domain = domain.trim().to_lowercase();
+    (!domain.is_empty()).then_some(domain)
+}
+
+/// Most common email domain among the contact's colleagues, when it differs
+/// from the contact's own and is shared by at least two and a majority of them.
+fn suggested_email_domain(contact: &Contact, contacts: &[Contact]) -> Option<String> {
+    let domain = email_domain(contact.email.as_deref()?)?;
+    let company = contact.company.as_ref()?;
+    
+    let mut counts: std::collections::BTreeMap<String, usize> = std::collections::BTreeMap::new();
+    let mut colleagues = 0;
+    for other in contacts.iter().filter(|c| c.id != contact.id && c.company.as_ref() == Some(company)) {
+        if let Some(other_domain) = other.email.as_deref().and_then(email_domain) {
+            *counts.entry(other_domain).or_default() += 1;
+            colleagues += 1;
+        }
+    }
+    
+    let (common, count) = counts.into_iter().max_by_key(|(_, count)| *count)?;
+    (common != domain && count >= 2 && count * 2 > colleagues).then_some(common)
+}
+
 // ============================================================================
 // FEE PROPOSAL MANAGEMENT COMMANDS
 // ============================================================================
@@ -749,7 +1398,102 @@ crud_command!(
     "fee proposals"
 );
 
-/// Create a new fee proposal in the database.
+/// Get one page of fee proposals, newest first.
+/// 
+/// Rows that don't deserialize into `Fee` are skipped like in `get_fees`,
+/// but still count towards `total`, so a page may hold fewer items than
+/// `limit`.
+/// 
+/// # Parameters
+/// - `limit`: Page size, capped at 500
+/// - `offset`: Number of fee rows to skip
+/// 
+/// # Returns
+/// - `Ok(Page<Fee>)`: `{ items, total }` where `total` counts all fee rows
+/// - `Err(String)`: Zero page size or database error
+/// 
+/// # Frontend Usage
+/// ```typescript
+/// const page = await invoke('get_fees_paginated', { limit: 100, offset: 200 });
+/// ```
+#[tauri::command]
+pub async fn get_fees_paginated(limit: u32, offset: u32, state: State<'_, AppState>) -> Result<Page<Fee>, String> {
+    let limit = page_size(limit)?;
+    execute_with_manager(
+        &state,
+        |manager| Box::pin(async move { manager.get_fees_paged(offset.into(), limit).await }),
+        "fetch",
+        "fee proposals"
+    ).await
+}
+
+/// List the raw fee rows that don't deserialize into `Fee`.
+/// 
+/// These are the rows `get_fees` skips. The raw JSON shows an admin what
+/// doesn't match the schema (e.g. a `rev` stored as a string).
+/// 
+/// # Returns
+/// - `Ok(Vec<Value>)`: Raw records of the unreadable fees
+/// - `Err(String)`: Database error
+/// 
+/// # Frontend Usage
+/// ```typescript
+/// const broken = await invoke('find_unparseable_fees');
+/// ```
+crud_command!(
+    find_unparseable_fees,
+    Vec<Value>,
+    find_unparseable_fees,
+    "check",
+    "fees for schema mismatches"
+);
+
+/// Repair the common schema mismatches that make fee rows unreadable.
+/// 
+/// Converts numeric string `rev` values to integers and fills missing text
+/// fields and `revisions` with empty values. Rows with other problems are
+/// left for manual fixing; check `find_unparseable_fees` afterwards.
+/// 
+/// # Returns
+/// - `Ok(u64)`: Number of fees repaired
+/// - `Err(String)`: Database error
+/// 
+/// # Frontend Usage
+/// ```typescript
+/// const repaired = await invoke('repair_fee_rows');
+/// ```
+crud_command!(
+    repair_fee_rows,
+    u64,
+    repair_fee_rows,
+    "repair",
+    "fee rows"
+);
+
+/// Retrieve all fees plus the number of fee records that could not be read.
+/// 
+/// `get_fees` skips fee rows that don't match the current schema so the rest
+/// still load. This variant also reports how many were skipped, so the UI can
+/// tell the user some proposals are hidden.
+/// 
+/// # Returns
+/// - `Ok(FeeLoad)`: `{ fees, skipped }`
+/// - `Err(String)`: Database error or connection failure
+/// 
+/// # Frontend Usage
+/// ```typescript
+/// const { fees, skipped } = await invoke('get_fees_checked');
+/// if (skipped > 0) showWarning(`${skipped} fee records could not be loaded`);
+/// ```
+crud_command!(
+    get_fees_checked,
+    FeeLoad,
+    load_fees,
+    "fetch",
+    "fee proposals"
+);
+
+/// Create a new fee proposal in the database.
 /// 
 /// This command creates a new fee proposal with automatic number generation
 /// and revision tracking initialization.
@@ -870,346 +1614,894 @@ crud_command!(
     id: String
 );
 
-/// Write RFP (fee proposal) data to JSON file in project folder
+/// Search fee proposals by free text.
 /// 
-/// This command fetches complete RFP data including all linked records
-/// and writes it to the project's JSON template file for InDesign integration.
+/// Performs a case-insensitive `CONTAINS` match across the fee name, number,
+/// activity, package and strap line. The search term is passed as a bound
+/// parameter rather than interpolated into the query.
 /// 
 /// # Parameters
-/// - `rfp_id`: The string ID of the RFP to export (e.g., "24_96606_1")
+/// - `query`: Search text (e.g., "97105" or "lighting")
 /// 
 /// # Returns
-/// - `Ok(String)`: Success message with file path
-/// - `Err(String)`: Error message if operation fails
+/// - `Ok(Vec<Fee>)`: Matching fees ordered by number and revision
+/// - `Err(String)`: Database error
+/// 
+/// # Frontend Usage
+/// ```typescript
+/// const results = await invoke('search_fees', { query: 'lighting' });
+/// ```
 #[tauri::command]
-pub async fn write_fee_to_json(rfp_id: String, state: State<'_, AppState>, app_handle: AppHandle) -> Result<String, String> {
-    use std::fs;
-    use std::path::Path;
-    use serde_json::json;
-    use chrono::Utc;
-    
-    info!("Writing fee {} to JSON file", rfp_id);
+pub async fn search_fees(query: String, state: State<'_, AppState>) -> Result<Vec<Fee>, String> {
+    execute_with_manager(
+        &state,
+        |manager| Box::pin(async move { manager.search_fees(&query).await }),
+        "search",
+        "fee proposals"
+    ).await
+}
+
+/// Retrieve the fee proposals of one project.
+/// 
+/// Filters in the database instead of loading every fee. The project ID is
+/// accepted in any of the forms the frontend holds.
+/// 
+/// # Parameters
+/// - `project_id`: "projects:25_97105", "projects:⟨25_97105⟩", "25_97105" or "25-97105"
+/// 
+/// # Returns
+/// - `Ok(Vec<Fee>)`: The project's fees, newest first
+/// - `Err(String)`: Database error
+/// 
+/// # Frontend Usage
+/// ```typescript
+/// const fees = await invoke('get_fees_for_project', { projectId: 'projects:25_97105' });
+/// ```
+#[tauri::command]
+pub async fn get_fees_for_project(project_id: String, state: State<'_, AppState>) -> Result<Vec<Fee>, String> {
+    let key = project_key(&project_id);
+    let entity = format!("fee proposals for project '{}'", key);
+    execute_with_manager(
+        &state,
+        |manager| Box::pin(async move { manager.get_fees_for_project(&key).await }),
+        "fetch",
+        &entity
+    ).await
+}
+
+/// List a project's fee proposals as a compact, revision-ordered index.
+/// 
+/// Returns only the fields a client-facing cover page needs, oldest
+/// revision first.
+/// 
+/// # Parameters
+/// - `project_id`: "projects:25_97105", "projects:⟨25_97105⟩", "25_97105" or "25-97105"
+/// 
+/// # Returns
+/// - `Ok(Vec<ProposalIndexEntry>)`: `{ number, rev, status, issue_date }` per proposal
+/// - `Err(String)`: Database error
+/// 
+/// # Frontend Usage
+/// ```typescript
+/// const index = await invoke('get_project_proposal_index', { projectId: 'projects:25_97105' });
+/// index.forEach(p => console.log(`${p.number} rev ${p.rev} (${p.status})`));
+/// ```
+#[tauri::command]
+pub async fn get_project_proposal_index(project_id: String, state: State<'_, AppState>) -> Result<Vec<ProposalIndexEntry>, String> {
+    let key = project_key(&project_id);
+    let entity = format!("proposal index for project '{}'", key);
+    execute_with_manager(
+        &state,
+        |manager| Box::pin(async move { manager.get_fees_for_project(&key).await }),
+        "fetch",
+        &entity
+    ).await.map(proposal_index)
+}
+
+/// Get the proposed fee value of a project.
+/// 
+/// Takes the amount of the project's latest revision (highest `rev`, then
+/// latest `issue_date`), so superseded proposals never count.
+/// 
+/// # Parameters
+/// - `project_id`: "projects:25_97105", "projects:⟨25_97105⟩", "25_97105" or "25-97105"
+/// 
+/// # Returns
+/// - `Ok(Some(f64))`: Amount of the latest revision
+/// - `Ok(None)`: No fees, or the latest revision has no amount
+/// - `Err(String)`: Database error
+/// 
+/// # Frontend Usage
+/// ```typescript
+/// const value = await invoke('get_project_fee_value', { projectId: 'projects:25_97105' });
+/// ```
+#[tauri::command]
+pub async fn get_project_fee_value(project_id: String, state: State<'_, AppState>) -> Result<Option<f64>, String> {
+    let key = project_key(&project_id);
+    let entity = format!("fee value for project '{}'", key);
+    execute_with_manager(
+        &state,
+        |manager| Box::pin(async move { manager.get_fees_for_project(&key).await }),
+        "fetch",
+        &entity
+    ).await.map(latest_fee_amount)
+}
+
+/// Amount of the newest fee by `rev`, then `issue_date`.
+fn latest_fee_amount(fees: Vec<Fee>) -> Option<f64> {
+    fees.into_iter()
+        .max_by(|a, b| (a.rev, &a.issue_date).cmp(&(b.rev, &b.issue_date)))
+        .and_then(|fee| fee.amount)
+}
+
+/// Reduce fees to index entries ordered by `rev`, then `issue_date` and `number`.
+fn proposal_index(fees: Vec<Fee>) -> Vec<ProposalIndexEntry> {
+    let mut index: Vec<ProposalIndexEntry> = fees
+        .into_iter()
+        .map(|fee| ProposalIndexEntry {
+            number: fee.number,
+            rev: fee.rev,
+            status: fee.status,
+            issue_date: fee.issue_date,
+        })
+        .collect();
+    index.sort_by(|a, b| (a.rev, &a.issue_date, &a.number).cmp(&(b.rev, &b.issue_date, &b.number)));
+    index
+}
+
+/// Record key of a project ID given as `projects:25_97105`,
+/// `projects:⟨25_97105⟩`, bare `25_97105` or project number `25-97105`.
+fn project_key(id: &str) -> String {
+    record_key(id, "projects").replace('-', "_")
+}
+
+/// Compare two fee proposals field by field.
+/// 
+/// Used when negotiating to compare revision N against N-1, or two alternative
+/// proposals for the same project. Only fields whose values differ are returned;
+/// the record ID and timestamps are ignored.
+/// 
+/// # Parameters
+/// - `a_id`: ID of the first fee (e.g., "fee:25_97105_1" or "25_97105_1")
+/// - `b_id`: ID of the second fee
+/// 
+/// # Returns
+/// - `Ok(Vec<FeeFieldDiff>)`: Differing fields in declaration order (empty if identical)
+/// - `Err(String)`: Database error or either fee not found
+/// 
+/// # Frontend Usage
+/// ```typescript
+/// const diffs = await invoke('compare_fees', { aId: 'fee:25_97105_1', bId: 'fee:25_97105_2' });
+/// diffs.forEach(d => console.log(`${d.field}: ${d.a_value} -> ${d.b_value}`));
+/// ```
+#[tauri::command]
+pub async fn compare_fees(a_id: String, b_id: String, state: State<'_, AppState>) -> Result<Vec<FeeFieldDiff>, String> {
+    info!("Comparing fees {} and {}", a_id, b_id);
     
-    // This is the exact same approach used in populate_project_data that works
     let manager_clone = {
         let manager = state.lock().map_err(|e| e.to_string())?;
         manager.clone()
     };
-
-    // Get all fee records - this works in the UI
+    
     let fees = manager_clone.get_fees().await
         .map_err(|e| format!("Failed to fetch fee records: {}", e))?;
     
-    info!("Got {} fee records", fees.len());
+    let find_fee = |input_id: &str| {
+        fees.iter()
+            .find(|f| f.id.as_ref().map_or(false, |id| thing_matches(id, input_id)))
+            .ok_or_else(|| format!("Fee record not found with ID: {}", input_id))
+    };
     
-    // Debug: Log the first few fee record IDs to understand the format
-    for (i, fee) in fees.iter().take(3).enumerate() {
-        if let Some(id) = &fee.id {
-            info!("Fee {}: Full Thing = {:?}", i, id);
-            info!("Fee {}: Thing.to_string() = '{}'", i, id.to_string());
-            info!("Fee {}: Thing.tb = '{}'", i, id.tb);
-            info!("Fee {}: Thing.id = {:?}", i, id.id);
-            info!("Fee {}: Thing.id.to_string() = '{}'", i, id.id.to_string());
-        }
-    }
+    let fee_a = find_fee(&a_id)?;
+    let fee_b = find_fee(&b_id)?;
     
-    // Find the specific fee record - simplified and robust ID matching
-    let fee = fees.iter()
-        .find(|f| {
-            if let Some(id) = &f.id {
-                // Extract clean ID parts from both database format and input
-                let db_id_clean = id.id.to_string().trim_start_matches('⟨').trim_end_matches('⟩').to_string();
-                let input_id_clean = rfp_id.trim_start_matches("fee:").to_string();
-                
-                info!("Comparing fee IDs: db='{}' vs input='{}'", db_id_clean, input_id_clean);
-                
-                let matches = db_id_clean == input_id_clean;
-                if matches {
-                    info!("✓ Found matching fee: {}", f.number);
-                }
-                
-                matches
-            } else {
-                false
-            }
+    let diffs = diff_fees(fee_a, fee_b);
+    info!("Fees {} and {} differ in {} fields", a_id, b_id, diffs.len());
+    Ok(diffs)
+}
+
+/// Build the list of differing fields between two fees.
+fn diff_fees(a: &Fee, b: &Fee) -> Vec<FeeFieldDiff> {
+    use serde_json::json;
+    
+    let fields: Vec<(&str, Value, Value)> = vec![
+        ("name", json!(a.name), json!(b.name)),
+        ("number", json!(a.number), json!(b.number)),
+        ("rev", json!(a.rev), json!(b.rev)),
+        ("status", json!(a.status), json!(b.status)),
+        ("issue_date", json!(a.issue_date), json!(b.issue_date)),
+        ("activity", json!(a.activity), json!(b.activity)),
+        ("package", json!(a.package), json!(b.package)),
+        ("project_id", json!(a.project_id.to_string()), json!(b.project_id.to_string())),
+        ("company_id", json!(a.company_id.to_string()), json!(b.company_id.to_string())),
+        ("contact_id", json!(a.contact_id.to_string()), json!(b.contact_id.to_string())),
+        ("staff_name", json!(a.staff_name), json!(b.staff_name)),
+        ("staff_email", json!(a.staff_email), json!(b.staff_email)),
+        ("staff_phone", json!(a.staff_phone), json!(b.staff_phone)),
+        ("staff_position", json!(a.staff_position), json!(b.staff_position)),
+        ("strap_line", json!(a.strap_line), json!(b.strap_line)),
+        ("currency", json!(a.currency), json!(b.currency)),
+        ("amount", json!(a.amount), json!(b.amount)),
+        ("revisions", json!(a.revisions), json!(b.revisions)),
+    ];
+    
+    fields.into_iter()
+        .filter(|(_, a_value, b_value)| a_value != b_value)
+        .map(|(field, a_value, b_value)| FeeFieldDiff {
+            field: field.to_string(),
+            a_value,
+            b_value,
         })
-        .ok_or_else(|| format!("Fee record not found with ID: {}", rfp_id))?;
+        .collect()
+}
+
+/// Get all projects with their fee proposals nested underneath.
+/// 
+/// Projects and fees are each fetched once and grouped in memory, so the
+/// cost doesn't grow with the number of projects. Projects are sorted by
+/// project number and each project's fees by revision. Projects without
+/// fees are included with an empty list.
+/// 
+/// # Returns
+/// - `Ok(Vec<ProjectFeeNode>)`: One node per project
+/// - `Err(String)`: Database error
+/// 
+/// # Frontend Usage
+/// ```typescript
+/// const tree = await invoke('get_fee_tree');
+/// tree.forEach(node => console.log(node.project.number.id, node.fees.length));
+/// ```
+#[tauri::command]
+pub async fn get_fee_tree(state: State<'_, AppState>) -> Result<Vec<ProjectFeeNode>, String> {
+    info!("Building project fee tree");
     
-    info!("Found fee record: {} - {}", fee.number, fee.name);
-    info!("Fee has project_id: {}", fee.project_id);
-    info!("Fee has company_id: {}", fee.company_id);
-    info!("Fee has contact_id: {}", fee.contact_id);
+    let manager_clone = {
+        let manager = state.lock().map_err(|e| e.to_string())?;
+        manager.clone()
+    };
     
-    // Get all related data
-    let projects = manager_clone.get_projects().await
+    let projects = manager_clone.get_projects(true).await
         .map_err(|e| format!("Failed to fetch projects: {}", e))?;
+    let fees = manager_clone.get_fees().await
+        .map_err(|e| format!("Failed to fetch fee records: {}", e))?;
     
-    info!("Got {} projects", projects.len());
-    
-    let project = projects.iter()
-        .find(|p| {
-            if let Some(p_id) = &p.id {
-                let matches = fee.project_id.id.to_string() == p_id.id.to_string();
-                if matches {
-                    info!("Found matching project: {}", p.name);
-                }
-                matches
-            } else {
-                false
-            }
-        })
-        .ok_or_else(|| {
-            error!("Project not found. Fee project_id: {}, Available project IDs:", fee.project_id);
-            for p in projects.iter().take(5) {
-                if let Some(id) = &p.id {
-                    error!("  - {}", id);
-                }
-            }
-            format!("Project not found for fee")
-        })?;
+    Ok(build_fee_tree(projects, fees))
+}
+
+/// Group fees under their projects, sorted by project number and fee revision.
+fn build_fee_tree(mut projects: Vec<Project>, fees: Vec<Fee>) -> Vec<ProjectFeeNode> {
+    use std::collections::HashMap;
+    
+    let mut fees_by_project: HashMap<String, Vec<Fee>> = HashMap::new();
+    for fee in fees {
+        fees_by_project
+            .entry(normalize_thing_id(&fee.project_id))
+            .or_default()
+            .push(fee);
+    }
     
-    let companies = manager_clone.get_companies().await
-        .map_err(|e| format!("Failed to fetch companies: {}", e))?;
+    projects.sort_by(|a, b| a.number.id.cmp(&b.number.id));
     
-    let company = companies.iter()
-        .find(|c| {
-            if let Some(c_id) = &c.id {
-                fee.company_id.id.to_string() == c_id.id.to_string()
-            } else {
-                false
-            }
+    let tree: Vec<ProjectFeeNode> = projects
+        .into_iter()
+        .map(|project| {
+            let mut fees = project.id.as_ref()
+                .and_then(|id| fees_by_project.remove(&normalize_thing_id(id)))
+                .unwrap_or_default();
+            fees.sort_by_key(|fee| fee.rev);
+            ProjectFeeNode { project, fees }
         })
-        .ok_or_else(|| format!("Company not found for fee"))?;
+        .collect();
     
-    let contacts = manager_clone.get_contacts().await
-        .map_err(|e| format!("Failed to fetch contacts: {}", e))?;
+    let orphaned: usize = fees_by_project.values().map(|fees| fees.len()).sum();
+    if orphaned > 0 {
+        warn!("{} fees reference projects that were not found", orphaned);
+    }
     
-    let contact = contacts.iter()
-        .find(|c| {
-            if let Some(c_id) = &c.id {
-                fee.contact_id.id.to_string() == c_id.id.to_string()
-            } else {
-                false
-            }
-        })
-        .ok_or_else(|| format!("Contact not found for fee"))?;
+    tree
+}
 
-    // Get project folder path from settings
-    let settings = get_settings(app_handle).await.map_err(|e| format!("Failed to get settings: {}", e))?;
-    let project_folder_path = settings.project_folder_path
-        .ok_or_else(|| "PROJECT_FOLDER_PATH not configured in settings".to_string())?;
+/// Get the most recent fee proposal for each project.
+/// 
+/// Fetches all fees in a single query and keeps the one with the highest
+/// `rev` per project, falling back to the latest `issue_date` when revisions
+/// are equal. Used by dashboards that only show the current proposal.
+/// 
+/// # Returns
+/// - `Ok(Vec<Fee>)`: One fee per project, ordered by fee number
+/// - `Err(String)`: Database error
+/// 
+/// # Frontend Usage
+/// ```typescript
+/// const latest = await invoke('get_latest_fee_per_project');
+/// ```
+#[tauri::command]
+pub async fn get_latest_fee_per_project(state: State<'_, AppState>) -> Result<Vec<Fee>, String> {
+    execute_with_manager(
+        &state,
+        |manager| Box::pin(async move { manager.get_fees().await }),
+        "fetch",
+        "fee records"
+    ).await.map(latest_fees_per_project)
+}
 
-    // Build project directory path - match the format used by copyProjectTemplate
-    // Use the project.number.id field which contains the formatted project number
-    // Strip angle brackets if present (same as frontend does)
-    let project_number = project.number.id.replace("⟨", "").replace("⟩", "");
+/// Keep the newest fee per project by `rev`, then `issue_date`.
+/// 
+/// Fees are grouped by `normalize_thing_id` of their project link, so
+/// `projects:⟨2597105⟩` and `projects:2597105` count as the same project.
+fn latest_fees_per_project(fees: Vec<Fee>) -> Vec<Fee> {
+    use std::collections::HashMap;
+    
+    let mut latest: HashMap<String, Fee> = HashMap::new();
+    for fee in fees {
+        let key = normalize_thing_id(&fee.project_id);
+        match latest.get(&key) {
+            Some(current) if (current.rev, &current.issue_date) >= (fee.rev, &fee.issue_date) => {}
+            _ => {
+                latest.insert(key, fee);
+            }
+        }
+    }
     
-    let project_name = &project.name_short;  // Use short name for folder path
+    let mut result: Vec<Fee> = latest.into_values().collect();
+    result.sort_by(|a, b| a.number.cmp(&b.number));
+    result
+}
 
-    let project_dir = format!("{}/01 RFPs/{} {}", project_folder_path, project_number, project_name);
-    let old_json_file_path = format!("{}/02 Proposal/{}-var Default Values.json", project_dir, project_number);
-    let new_json_file_path = format!("{}/02 Proposal/{}-var.json", project_dir, project_number);
-    
-    info!("DEBUG: Project folder path: {}", project_folder_path);
-    info!("DEBUG: Project number: {}", project_number);
-    info!("DEBUG: Project name: {}", project_name);
-    info!("DEBUG: Project dir: {}", project_dir);
-    info!("DEBUG: Old JSON path: {}", old_json_file_path);
-    info!("DEBUG: New JSON path: {}", new_json_file_path);
-    
-    // Check if we need to rename the template file first
-    if Path::new(&old_json_file_path).exists() && !Path::new(&new_json_file_path).exists() {
-        info!("Renaming template file from '{}' to '{}'", old_json_file_path, new_json_file_path);
-        
-        // Check if file might be syncing (basic check for .sync extension or recent modification)
-        let metadata = fs::metadata(&old_json_file_path).map_err(|e| {
-            format!("Failed to read file metadata: {}", e)
-        })?;
-        
-        // Check if file was modified very recently (within last 30 seconds) - might indicate sync activity
-        if let Ok(modified) = metadata.modified() {
-            if let Ok(elapsed) = modified.elapsed() {
-                if elapsed.as_secs() < 30 {
-                    info!("File was recently modified, waiting for potential sync to complete...");
-                    std::thread::sleep(std::time::Duration::from_secs(2));
-                }
-            }
-        }
-        
-        // Rename the file to remove "Default Values"
-        fs::rename(&old_json_file_path, &new_json_file_path).map_err(|e| {
-            error!("Failed to rename file: {}", e);
-            format!("Failed to rename file from '{}' to '{}': {}", old_json_file_path, new_json_file_path, e)
-        })?;
+/// Find fees whose issue date lies in the future.
+/// 
+/// A data-entry QA check: a future YYMMDD `issue_date` usually means
+/// transposed digits. Fees with unparseable dates are not included.
+/// 
+/// # Returns
+/// - `Ok(Vec<Fee>)`: Fees dated after today
+/// - `Err(String)`: Database error
+/// 
+/// # Frontend Usage
+/// ```typescript
+/// const suspicious = await invoke('find_fees_with_future_issue_date');
+/// ```
+#[tauri::command]
+pub async fn find_fees_with_future_issue_date(state: State<'_, AppState>) -> Result<Vec<Fee>, String> {
+    let today = Utc::now().date_naive();
+    execute_with_manager(
+        &state,
+        |manager| Box::pin(async move { manager.get_fees().await }),
+        "fetch",
+        "fee records"
+    ).await.map(|fees| fees_issued_after(fees, today))
+}
+
+/// Keep fees whose parsed `issue_date` is after `today`.
+fn fees_issued_after(fees: Vec<Fee>, today: chrono::NaiveDate) -> Vec<Fee> {
+    fees.into_iter()
+        .filter(|fee| matches!(parse_issue_date(&fee.issue_date), Some(date) if date > today))
+        .collect()
+}
+
+/// Summarize proposals issued in one month, by status.
+/// 
+/// Fees are matched on their YYMMDD `issue_date`; fees with unparseable
+/// dates are left out.
+/// 
+/// # Parameters
+/// - `year`: Two-digit year as used in issue dates (e.g. 25)
+/// - `month`: Month number, 1-12
+/// 
+/// # Returns
+/// - `Ok(Value)`: `{ year, month, total, byStatus: { status: count }, fees }`
+/// - `Err(String)`: Invalid year/month or database error
+/// 
+/// # Frontend Usage
+/// ```typescript
+/// const report = await invoke('get_monthly_report', { year: 25, month: 6 });
+/// console.log(`${report.total} proposals issued, ${report.byStatus.Sent ?? 0} sent`);
+/// ```
+#[tauri::command]
+pub async fn get_monthly_report(year: u8, month: u8, state: State<'_, AppState>) -> Result<Value, String> {
+    if year > 99 {
+        return Err(format!("Invalid year {}: expected a two-digit year (0-99)", year));
+    }
+    if !(1..=12).contains(&month) {
+        return Err(format!("Invalid month {}: expected 1-12", month));
     }
     
-    let json_file_path = new_json_file_path;
+    execute_with_manager(
+        &state,
+        |manager| Box::pin(async move { manager.get_fees().await }),
+        "fetch",
+        "fee records"
+    ).await.map(|fees| monthly_report(fees, year, month))
+}
 
-    // Format issue date
-    let issue_date = {
-        let d = &fee.issue_date;
-        // Parse YYMMDD format and convert to "dd MMM yyyy"
-        if d.len() == 6 {
-            if let (Ok(year), Ok(month), Ok(day)) = (
-                d[0..2].parse::<i32>(),
-                d[2..4].parse::<u32>(),
-                d[4..6].parse::<u32>()
-            ) {
-                let full_year = if year >= 50 { 1900 + year } else { 2000 + year };
-                let date = chrono::NaiveDate::from_ymd_opt(full_year, month, day);
-                date.map(|d| d.format("%d %b %Y").to_string())
-                    .unwrap_or_else(|| Utc::now().format("%d %b %Y").to_string())
-            } else {
-                Utc::now().format("%d %b %Y").to_string()
-            }
-        } else {
-            Utc::now().format("%d %b %Y").to_string()
-        }
-    };
+/// Build the monthly report for fees issued in `year`/`month` (two-digit year).
+fn monthly_report(fees: Vec<Fee>, year: u8, month: u8) -> Value {
+    use chrono::Datelike;
+    
+    // Same century mapping as parse_issue_date
+    let full_year = if year >= 50 { 1900 + year as i32 } else { 2000 + year as i32 };
+    let issued: Vec<Fee> = fees.into_iter()
+        .filter(|fee| matches!(
+            parse_issue_date(&fee.issue_date),
+            Some(date) if date.year() == full_year && date.month() == month as u32
+        ))
+        .collect();
+    
+    let mut by_status: std::collections::BTreeMap<String, u64> = std::collections::BTreeMap::new();
+    for fee in &issued {
+        *by_status.entry(fee.status.clone()).or_insert(0) += 1;
+    }
+    
+    serde_json::json!({
+        "year": full_year,
+        "month": month,
+        "total": issued.len(),
+        "byStatus": by_status,
+        "fees": issued
+    })
+}
 
-    // Create JSON data mapping from the actual structs
-    let json_data = json!({
-        "01 Document Name": fee.name.clone(),
-        "02 Document Number": fee.number.clone(),
-        "03 Document Release": fee.rev.to_string(),
-        "04 Document Issue Date": issue_date,
-        "06 Project Name": project.name.clone(),
-        "07 Project Activity": fee.activity.clone(),
-        "08 Project Package": fee.package.clone(),
-        "09 Project Stage": project.status.clone(),
-        "11 Project Area": project.area.clone(),
-        "12 Project City": project.city.clone(),
-        "13 Project Country": project.country.clone(),
-        "21 Client Company": company.name.clone(),
-        "22 Client City": company.city.clone(),
-        "23 Client Country": company.country.clone(),
-        "26 Contact Name": contact.full_name.clone().unwrap_or_else(|| {
-            let first = contact.first_name.clone().unwrap_or_default();
-            let last = contact.last_name.clone().unwrap_or_default();
-            format!("{} {}", first, last)
-        }),
-        "27 Contact Position": contact.position.clone().unwrap_or_default(),
-        "28 Contact Phone": contact.phone.clone().unwrap_or_default(),
-        "29 Contact Email": contact.email.clone().unwrap_or_default(),
-        "99 Strap Line": fee.strap_line.clone()
-    });
+/// Find fee records that share the same proposal `number`.
+/// 
+/// Distinct records can end up with the same number (e.g. after a fee is
+/// reassigned to another project), which makes proposal numbering ambiguous.
+/// Numbers are compared after trimming whitespace.
+/// 
+/// # Returns
+/// - `Ok(Vec<Vec<Fee>>)`: One group per duplicated number, sorted by number
+/// - `Err(String)`: Database error
+/// 
+/// # Frontend Usage
+/// ```typescript
+/// const groups = await invoke('find_duplicate_fee_numbers');
+/// groups.forEach(g => console.warn(`${g[0].number} used by ${g.length} fees`));
+/// ```
+#[tauri::command]
+pub async fn find_duplicate_fee_numbers(state: State<'_, AppState>) -> Result<Vec<Vec<Fee>>, String> {
+    execute_with_manager(
+        &state,
+        |manager| Box::pin(async move { manager.get_fees().await }),
+        "fetch",
+        "fee records"
+    ).await.map(duplicate_fee_numbers)
+}
 
-    // Ensure directory exists
-    if let Some(parent) = Path::new(&json_file_path).parent() {
-        fs::create_dir_all(parent).map_err(|e| {
-            error!("Failed to create directory: {}", e);
-            format!("Failed to create directory: {}", e)
-        })?;
+/// Group fees by trimmed `number`, keeping only groups with more than one fee.
+fn duplicate_fee_numbers(fees: Vec<Fee>) -> Vec<Vec<Fee>> {
+    let mut by_number: std::collections::BTreeMap<String, Vec<Fee>> = std::collections::BTreeMap::new();
+    for fee in fees {
+        let number = fee.number.trim();
+        if number.is_empty() {
+            continue;
+        }
+        by_number.entry(number.to_string()).or_default().push(fee);
     }
+    
+    by_number.into_values()
+        .filter(|group| group.len() > 1)
+        .collect()
+}
 
-    // Write JSON file
-    fs::write(&json_file_path, serde_json::to_string_pretty(&json_data).map_err(|e| {
-        error!("Failed to serialize JSON: {}", e);
-        format!("Failed to serialize JSON: {}", e)
-    })?).map_err(|e| {
-        error!("Failed to write file: {}", e);
-        format!("Failed to write file: {}", e)
-    })?;
+/// Recompute each fee's `rev` from its revisions and repair mismatches.
+/// 
+/// `create_fee` accepts a caller-supplied `rev` while starting with an empty
+/// `revisions` array, so the two can disagree. The expected value is the
+/// number of revisions, or the record ID suffix for fees without revisions.
+/// 
+/// # Returns
+/// - `Ok(u64)`: Number of fees repaired
+/// - `Err(String)`: Database error
+/// 
+/// # Frontend Usage
+/// ```typescript
+/// const repaired = await invoke('reconcile_fee_revisions');
+/// console.log(`Repaired ${repaired} fees`);
+/// ```
+crud_command!(
+    reconcile_fee_revisions,
+    u64,
+    reconcile_fee_revisions,
+    "reconcile",
+    "fee revisions"
+);
 
-    info!("Successfully wrote RFP data to: {}", json_file_path);
-    Ok(format!("Successfully wrote RFP data to: {}", json_file_path))
+/// Record a new revision of a fee proposal.
+/// 
+/// Appends a `Revision` dated now to the fee's `revisions` and sets `rev`
+/// to its number, leaving all other fee fields untouched. Use this instead
+/// of `update_fee` when the proposal history should be kept.
+/// 
+/// # Parameters
+/// - `fee_id`: Fee ID (e.g., "fee:25_97105_1" or "25_97105_1")
+/// - `notes`: What changed in this revision
+/// - `author_name`: Name of the staff member making the revision
+/// - `author_email`: Their email address (must contain '@')
+/// 
+/// # Returns
+/// - `Ok(Fee)`: The fee with the new revision appended
+/// - `Err(String)`: Invalid email, fee not found or database error
+/// 
+/// # Frontend Usage
+/// ```typescript
+/// const fee = await invoke('create_fee_revision', {
+///   feeId: 'fee:25_97105_1',
+///   notes: 'Reduced lighting scope',
+///   authorName: 'Jane Doe',
+///   authorEmail: 'jane@example.com'
+/// });
+/// ```
+#[tauri::command]
+pub async fn create_fee_revision(fee_id: String, notes: String, author_name: String, author_email: String, state: State<'_, AppState>) -> Result<Fee, String> {
+    if !author_email.contains('@') {
+        return Err(format!("Invalid author email '{}': must contain '@'", author_email));
+    }
+    
+    let key = fee_key(&fee_id).to_string();
+    let fee_name = format!("revision of fee '{}'", key);
+    execute_with_manager(
+        &state,
+        |manager| Box::pin(async move {
+            manager.create_fee_revision(&key, &notes, &author_name, &author_email).await
+        }),
+        "create",
+        &fee_name
+    ).await
 }
 
-/// Write fee proposal data to JSON file with enhanced safety checks.
-/// 
-/// This enhanced version of `write_fee_to_json` includes safety mechanisms to detect
-/// and protect existing project data while allowing safe overwriting of placeholder content.
+/// Record key of a fee ID given as `fee:25_97105_1`, `fee:⟨25_97105_1⟩`
+/// or bare `25_97105_1`.
+fn fee_key(id: &str) -> &str {
+    record_key(id, "fee")
+}
+
+/// Compare a fee's activity and package with those of its project.
 /// 
-/// # Safety Features
-/// - Detects placeholder content vs. real project data
-/// - Creates timestamped backup copies of files containing real data
-/// - Alerts user when existing data is found
-/// - Safely renames template files when appropriate
+/// Advisory only: nothing is changed or enforced. Projects that don't
+/// record an activity or package are not compared on that field.
 /// 
 /// # Parameters
-/// - `fee_id`: The string ID of the fee proposal to export
+/// - `fee_id`: Fee record ID (e.g., "fee:25_97105_1" or "25_97105_1")
 /// 
 /// # Returns
-/// - `Ok(String)`: Success message with file path and safety actions taken
-/// - `Err(String)`: Error message if operation fails or user confirmation needed
+/// - `Ok(Vec<FeeProjectMismatch>)`: `{ field, fee_value, project_value }` per mismatch (empty when aligned)
+/// - `Err(String)`: Fee not found or database error
 /// 
-/// # Placeholder Detection
-/// Files are considered to contain placeholder data if they contain:
-/// - Default template values like "Project Name" or "Client Name"
-/// - All field values match expected template patterns
-/// - File was recently created from template
+/// # Frontend Usage
+/// ```typescript
+/// const mismatches = await invoke('check_fee_project_consistency', { feeId: 'fee:25_97105_1' });
+/// mismatches.forEach(m => console.warn(`${m.field}: ${m.fee_value} vs ${m.project_value}`));
+/// ```
+#[tauri::command]
+pub async fn check_fee_project_consistency(fee_id: String, state: State<'_, AppState>) -> Result<Vec<FeeProjectMismatch>, String> {
+    let key = fee_key(&fee_id).to_string();
+    let entity = format!("fee '{}'", key);
+    execute_with_manager(
+        &state,
+        |manager| Box::pin(async move { manager.get_fee_project_fields(&key).await }),
+        "check",
+        &entity
+    ).await.map(|row| fee_project_mismatches(&row))
+}
+
+/// Mismatched fields in a `FEE_PROJECT_FIELDS_QUERY` row, ignoring case and
+/// surrounding whitespace.
+fn fee_project_mismatches(row: &Value) -> Vec<FeeProjectMismatch> {
+    ["activity", "package"]
+        .into_iter()
+        .filter_map(|field| {
+            let fee_value = row[format!("fee_{}", field)].as_str().unwrap_or_default().trim();
+            let project_value = row[format!("project_{}", field)].as_str()?.trim();
+            (fee_value.to_lowercase() != project_value.to_lowercase()).then(|| FeeProjectMismatch {
+                field: field.to_string(),
+                fee_value: fee_value.to_string(),
+                project_value: project_value.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Check a fee for blank fields that the exported proposal depends on.
+/// 
+/// `write_fee_to_json` fills the proposal's signature block from the fee's
+/// staff fields and its addressee from the linked contact. Fees created
+/// without staff details export with empty values, so this lets the UI ask
+/// the user to fix them first.
+/// 
+/// # Parameters
+/// - `fee_id`: Fee record ID (e.g., "fee:25_97105_1" or "25_97105_1")
+/// 
+/// # Returns
+/// - `Ok(Vec<String>)`: Names of the empty required fields (empty when ready)
+/// - `Err(String)`: Fee not found or database error
 /// 
 /// # Frontend Usage
 /// ```typescript
-/// const result = await invoke('write_fee_to_json_safe', { fee_id: 'fee:some_id' });
+/// const missing = await invoke('validate_fee_for_export', { feeId: 'fee:25_97105_1' });
+/// if (missing.length) showWarning(`Please fill in: ${missing.join(', ')}`);
 /// ```
 #[tauri::command]
-pub async fn write_fee_to_json_safe(fee_id: String, state: State<'_, AppState>, app_handle: AppHandle) -> Result<String, String> {
+pub async fn validate_fee_for_export(fee_id: String, state: State<'_, AppState>) -> Result<Vec<String>, String> {
+    info!("Validating fee {} for export", fee_id);
+    
+    let manager_clone = {
+        let manager = state.lock().map_err(|e| e.to_string())?;
+        manager.clone()
+    };
+    
+    let fees = manager_clone.get_fees().await
+        .map_err(|e| format!("Failed to fetch fee records: {}", e))?;
+    
+    let fee = fees.iter()
+        .find(|f| f.id.as_ref().map_or(false, |id| thing_matches(id, &fee_id)))
+        .ok_or_else(|| format!("Fee record not found with ID: {}", fee_id))?;
+    
+    let projects = manager_clone.get_projects(true).await
+        .map_err(|e| format!("Failed to fetch projects: {}", e))?;
+    let contacts = manager_clone.get_contacts().await
+        .map_err(|e| format!("Failed to fetch contacts: {}", e))?;
+    
+    let project = projects.iter().find(|p| is_linked(&p.id, &fee.project_id));
+    let contact = contacts.iter().find(|c| is_linked(&c.id, &fee.contact_id));
+    
+    let missing = missing_export_fields(fee, project, contact);
+    if !missing.is_empty() {
+        warn!("Fee {} is missing export fields: {:?}", fee_id, missing);
+    }
+    Ok(missing)
+}
+
+/// List the required export fields that are blank on a fee or its links.
+fn missing_export_fields(fee: &Fee, project: Option<&Project>, contact: Option<&Contact>) -> Vec<String> {
+    let is_blank = |value: &str| value.trim().is_empty();
+    let mut missing = Vec::new();
+    
+    if is_blank(&fee.staff_name) {
+        missing.push("staff_name".to_string());
+    }
+    if is_blank(&fee.staff_email) {
+        missing.push("staff_email".to_string());
+    }
+    if project.map_or(true, |p| is_blank(&p.name)) {
+        missing.push("project_name".to_string());
+    }
+    let contact_name = contact.map(|c| {
+        format!("{} {}", c.first_name.as_deref().unwrap_or(""), c.last_name.as_deref().unwrap_or(""))
+    });
+    if contact_name.map_or(true, |name| is_blank(&name)) {
+        missing.push("contact_name".to_string());
+    }
+    
+    missing
+}
+
+/// Write RFP (fee proposal) data to JSON file in project folder
+/// 
+/// This command fetches complete RFP data including all linked records
+/// and writes it to the project's JSON template file for InDesign integration.
+/// 
+/// # Parameters
+/// - `rfp_id`: The string ID of the RFP to export (e.g., "24_96606_1")
+/// 
+/// # Returns
+/// - `Ok(String)`: Success message with file path
+/// - `Err(String)`: Error message if operation fails
+#[tauri::command]
+pub async fn write_fee_to_json(rfp_id: String, state: State<'_, AppState>, app_handle: AppHandle) -> Result<String, String> {
+    use std::fs;
     use std::path::Path;
-    use serde_json::json;
     
-    info!("Writing fee {} to JSON file with safety checks", fee_id);
+    info!("Writing fee {} to JSON file", rfp_id);
     
-    // Get fee data using the same logic as the original function
+    // This is the exact same approach used in populate_project_data that works
     let manager_clone = {
         let manager = state.lock().map_err(|e| e.to_string())?;
         manager.clone()
     };
 
-    // Get fee record and related data (reusing existing logic)
+    // Get all fee records - this works in the UI
     let fees = manager_clone.get_fees().await
         .map_err(|e| format!("Failed to fetch fee records: {}", e))?;
     
+    info!("Got {} fee records", fees.len());
+    
     let fee = fees.iter()
-        .find(|f| {
-            if let Some(id) = &f.id {
-                let db_id_clean = id.id.to_string().trim_start_matches('⟨').trim_end_matches('⟩').to_string();
-                let input_id_clean = fee_id.trim_start_matches("fee:").to_string();
-                db_id_clean == input_id_clean
-            } else {
-                false
-            }
-        })
-        .ok_or_else(|| format!("Fee record not found with ID: {}", fee_id))?;
-
-    // Get related data
-    let projects = manager_clone.get_projects().await
+        .find(|f| f.id.as_ref().is_some_and(|id| thing_matches(id, &rfp_id)))
+        .ok_or_else(|| format!("Fee record not found with ID: {}", rfp_id))?;
+    
+    info!("Found fee record: {} - {}", fee.number, fee.name);
+    info!("Fee has project_id: {}", fee.project_id);
+    info!("Fee has company_id: {}", fee.company_id);
+    info!("Fee has contact_id: {}", fee.contact_id);
+    
+    // Get all related data
+    let projects = manager_clone.get_projects(true).await
         .map_err(|e| format!("Failed to fetch projects: {}", e))?;
     
+    info!("Got {} projects", projects.len());
+    
     let project = projects.iter()
         .find(|p| {
-            if let Some(p_id) = &p.id {
-                fee.project_id.id.to_string() == p_id.id.to_string()
-            } else {
-                false
+            let matches = is_linked(&p.id, &fee.project_id);
+            if matches {
+                info!("Found matching project: {}", p.name);
             }
+            matches
         })
-        .ok_or_else(|| format!("Project not found for fee"))?;
-
+        .ok_or_else(|| {
+            error!("Project not found. Fee project_id: {}, Available project IDs:", fee.project_id);
+            for p in projects.iter().take(5) {
+                if let Some(id) = &p.id {
+                    error!("  - {}", id);
+                }
+            }
+            format!("Project not found for fee")
+        })?;
+    
     let companies = manager_clone.get_companies().await
         .map_err(|e| format!("Failed to fetch companies: {}", e))?;
     
     let company = companies.iter()
-        .find(|c| {
-            if let Some(c_id) = &c.id {
-                fee.company_id.id.to_string() == c_id.id.to_string()
-            } else {
-                false
-            }
-        })
+        .find(|c| is_linked(&c.id, &fee.company_id))
         .ok_or_else(|| format!("Company not found for fee"))?;
-
+    
     let contacts = manager_clone.get_contacts().await
         .map_err(|e| format!("Failed to fetch contacts: {}", e))?;
     
     let contact = contacts.iter()
-        .find(|c| {
-            if let Some(c_id) = &c.id {
-                fee.contact_id.id.to_string() == c_id.id.to_string()
+        .find(|c| is_linked(&c.id, &fee.contact_id))
+        .ok_or_else(|| format!("Contact not found for fee"))?;
+
+    // Get project folder path from settings
+    let settings = get_settings(app_handle).await.map_err(|e| format!("Failed to get settings: {}", e))?;
+    let project_folder_path = settings.project_folder_path
+        .ok_or_else(|| "PROJECT_FOLDER_PATH not configured in settings".to_string())?;
+
+    // Build project directory path - match the format used by copyProjectTemplate
+    // Use the project.number.id field which contains the formatted project number
+    // Strip angle brackets if present (same as frontend does)
+    let project_number = project.number.id.replace("⟨", "").replace("⟩", "");
+    
+    let project_name = &project.name_short;  // Use short name for folder path
+
+    let project_dir = safe_join(
+        Path::new(&project_folder_path),
+        &["01 RFPs", &format!("{} {}", project_number, project_name)],
+    )?.to_string_lossy().to_string();
+    let proposal_dir = Path::new(&project_dir).join("02 Proposal");
+    let old_json_file_path = proposal_dir.join(format!("{}-var Default Values.json", project_number)).to_string_lossy().to_string();
+    let new_json_file_path = proposal_dir.join(format!("{}-var.json", project_number)).to_string_lossy().to_string();
+    
+    info!("DEBUG: Project folder path: {}", project_folder_path);
+    info!("DEBUG: Project number: {}", project_number);
+    info!("DEBUG: Project name: {}", project_name);
+    info!("DEBUG: Project dir: {}", project_dir);
+    info!("DEBUG: Old JSON path: {}", old_json_file_path);
+    info!("DEBUG: New JSON path: {}", new_json_file_path);
+    
+    // Check if we need to rename the template file first
+    if Path::new(&old_json_file_path).exists() && !Path::new(&new_json_file_path).exists() {
+        info!("Renaming template file from '{}' to '{}'", old_json_file_path, new_json_file_path);
+        
+        // Check if file might be syncing (basic check for .sync extension or recent modification)
+        let metadata = fs::metadata(&old_json_file_path).map_err(|e| {
+            format!("Failed to read file metadata: {}", e)
+        })?;
+        
+        // Check if file was modified very recently (within last 30 seconds) - might indicate sync activity
+        if let Ok(modified) = metadata.modified() {
+            if let Ok(elapsed) = modified.elapsed() {
+                if elapsed.as_secs() < 30 {
+                    info!("File was recently modified, waiting for potential sync to complete...");
+                    std::thread::sleep(std::time::Duration::from_secs(2));
+                }
+            }
+        }
+        
+        // Rename the file to remove "Default Values"
+        fs::rename(&old_json_file_path, &new_json_file_path).map_err(|e| {
+            error!("Failed to rename file: {}", e);
+            format!("Failed to rename file from '{}' to '{}': {}", old_json_file_path, new_json_file_path, e)
+        })?;
+    }
+    
+    let json_file_path = new_json_file_path;
+
+    // Create JSON data mapping from the actual structs
+    let json_data = build_fee_json(fee, project, company, contact);
+
+    // Ensure directory exists
+    if let Some(parent) = Path::new(&json_file_path).parent() {
+        fs::create_dir_all(parent).map_err(|e| {
+            error!("Failed to create directory: {}", e);
+            format!("Failed to create directory: {}", e)
+        })?;
+    }
+
+    // Write JSON file
+    fs::write(&json_file_path, serde_json::to_string_pretty(&json_data).map_err(|e| {
+        error!("Failed to serialize JSON: {}", e);
+        format!("Failed to serialize JSON: {}", e)
+    })?).map_err(|e| {
+        error!("Failed to write file: {}", e);
+        format!("Failed to write file: {}", e)
+    })?;
+
+    info!("Successfully wrote RFP data to: {}", json_file_path);
+    Ok(format!("Successfully wrote RFP data to: {}", json_file_path))
+}
+
+/// Write fee proposal data to JSON file with enhanced safety checks.
+/// 
+/// This enhanced version of `write_fee_to_json` includes safety mechanisms to detect
+/// and protect existing project data while allowing safe overwriting of placeholder content.
+/// 
+/// # Safety Features
+/// - Detects placeholder content vs. real project data
+/// - Creates timestamped backup copies of files containing real data
+/// - Alerts user when existing data is found
+/// - Safely renames template files when appropriate
+/// 
+/// # Parameters
+/// - `fee_id`: The string ID of the fee proposal to export
+/// 
+/// # Returns
+/// - `Ok(String)`: Success message with file path and safety actions taken
+/// - `Err(String)`: Error message if operation fails or user confirmation needed
+/// 
+/// # Placeholder Detection
+/// Files are considered to contain placeholder data if they contain:
+/// - Default template values like "Project Name" or "Client Name"
+/// - All field values match expected template patterns
+/// - File was recently created from template
+/// 
+/// # Frontend Usage
+/// ```typescript
+/// const result = await invoke('write_fee_to_json_safe', { fee_id: 'fee:some_id' });
+/// ```
+#[tauri::command]
+pub async fn write_fee_to_json_safe(fee_id: String, state: State<'_, AppState>, app_handle: AppHandle) -> Result<String, String> {
+    info!("Writing fee {} to JSON file with safety checks", fee_id);
+    
+    // Get fee data using the same logic as the original function
+    let manager_clone = {
+        let manager = state.lock().map_err(|e| e.to_string())?;
+        manager.clone()
+    };
+
+    // Get fee record and related data (reusing existing logic)
+    let fees = manager_clone.get_fees().await
+        .map_err(|e| format!("Failed to fetch fee records: {}", e))?;
+    
+    let fee = fees.iter()
+        .find(|f| {
+            if let Some(id) = &f.id {
+                thing_matches(id, &fee_id)
             } else {
                 false
             }
         })
+        .ok_or_else(|| format!("Fee record not found with ID: {}", fee_id))?;
+
+    // Get related data
+    let projects = manager_clone.get_projects(true).await
+        .map_err(|e| format!("Failed to fetch projects: {}", e))?;
+    
+    let project = projects.iter()
+        .find(|p| is_linked(&p.id, &fee.project_id))
+        .ok_or_else(|| format!("Project not found for fee"))?;
+
+    let companies = manager_clone.get_companies().await
+        .map_err(|e| format!("Failed to fetch companies: {}", e))?;
+    
+    let company = companies.iter()
+        .find(|c| is_linked(&c.id, &fee.company_id))
+        .ok_or_else(|| format!("Company not found for fee"))?;
+
+    let contacts = manager_clone.get_contacts().await
+        .map_err(|e| format!("Failed to fetch contacts: {}", e))?;
+    
+    let contact = contacts.iter()
+        .find(|c| is_linked(&c.id, &fee.contact_id))
         .ok_or_else(|| format!("Contact not found for fee"))?;
 
     // Get settings and build file paths
@@ -1217,20 +2509,38 @@ pub async fn write_fee_to_json_safe(fee_id: String, state: State<'_, AppState>,
     let project_folder_path = settings.project_folder_path
         .ok_or_else(|| "PROJECT_FOLDER_PATH not configured in settings".to_string())?;
 
+    write_fee_json_file(&project_folder_path, fee, project, company, contact)
+}
+
+/// Write a fee's var.json into its RFP project folder.
+/// 
+/// Renames a leftover template file into place, overwrites placeholder
+/// content, and backs up a file holding real data before replacing it.
+/// 
+/// # Returns
+/// - `Ok(String)`: Success message listing the safety actions taken
+/// - `Err(String)`: Invalid path or file system error
+fn write_fee_json_file(project_folder_path: &str, fee: &Fee, project: &Project, company: &Company, contact: &Contact) -> Result<String, String> {
     let project_number = project.number.id.replace("⟨", "").replace("⟩", "");
     let project_name = &project.name_short;
 
-    let project_dir = format!("{}/01 RFPs/{} {}", project_folder_path, project_number, project_name);
+    let proposal_dir = safe_join(
+        Path::new(project_folder_path),
+        &["01 RFPs", &format!("{} {}", project_number, project_name), "02 Proposal"],
+    )?;
     
     // Check all possible file name variations
-    let possible_template_paths = vec![
-        format!("{}/02 Proposal/{}-var Default Values.json", project_dir, project_number),
-        format!("{}/02 Proposal/{}-var default values.json", project_dir, project_number),
-        format!("{}/02 Proposal/{}-var - Default Values.json", project_dir, project_number),
-        format!("{}/02 Proposal/{}-var - default values.json", project_dir, project_number),
-    ];
-    
-    let final_file_path = format!("{}/02 Proposal/{}-var.json", project_dir, project_number);
+    let possible_template_paths: Vec<String> = [
+        "-var Default Values.json",
+        "-var default values.json",
+        "-var - Default Values.json",
+        "-var - default values.json",
+    ]
+    .iter()
+    .map(|suffix| proposal_dir.join(format!("{}{}", project_number, suffix)).to_string_lossy().to_string())
+    .collect();
+    
+    let final_file_path = proposal_dir.join(format!("{}-var.json", project_number)).to_string_lossy().to_string();
     
     info!("Checking template file variations:");
     for template_path in &possible_template_paths {
@@ -1256,14 +2566,14 @@ pub async fn write_fee_to_json_safe(fee_id: String, state: State<'_, AppState>,
         } else {
             // File contains real project data - create backup
             let timestamp = Utc::now().format("%Y%m%d_%H%M%S");
-            let backup_path = format!("{}/02 Proposal/{}-var_backup_{}.json", project_dir, project_number, timestamp);
+            let backup_path = proposal_dir.join(format!("{}-var_backup_{}.json", project_number, timestamp));
             
             fs::copy(&final_file_path, &backup_path)
                 .map_err(|e| format!("Failed to create backup: {}", e))?;
             
-            info!("Created backup of existing data: {}", backup_path);
+            info!("Created backup of existing data: {}", backup_path.display());
             target_file_path = final_file_path;
-            safety_actions.push(format!("⚠️  EXISTING DATA DETECTED - Created backup: {}", backup_path));
+            safety_actions.push(format!("⚠️  EXISTING DATA DETECTED - Created backup: {}", backup_path.display()));
             safety_actions.push("Previous file contained real project data and has been preserved".to_string());
         }
     } else {
@@ -1305,35 +2615,8 @@ pub async fn write_fee_to_json_safe(fee_id: String, state: State<'_, AppState>,
         }
     }
 
-    // Format issue date (same as original function)
-    let issue_date = format_issue_date(&fee.issue_date);
-
     // Create JSON data (same as original function)
-    let json_data = json!({
-        "01 Document Name": fee.name.clone(),
-        "02 Document Number": fee.number.clone(),
-        "03 Document Release": fee.rev.to_string(),
-        "04 Document Issue Date": issue_date,
-        "06 Project Name": project.name.clone(),
-        "07 Project Activity": fee.activity.clone(),
-        "08 Project Package": fee.package.clone(),
-        "09 Project Stage": project.status.clone(),
-        "11 Project Area": project.area.clone(),
-        "12 Project City": project.city.clone(),
-        "13 Project Country": project.country.clone(),
-        "21 Client Company": company.name.clone(),
-        "22 Client City": company.city.clone(),
-        "23 Client Country": company.country.clone(),
-        "26 Contact Name": contact.full_name.clone().unwrap_or_else(|| {
-            let first = contact.first_name.clone().unwrap_or_default();
-            let last = contact.last_name.clone().unwrap_or_default();
-            format!("{} {}", first, last)
-        }),
-        "27 Contact Position": contact.position.clone().unwrap_or_default(),
-        "28 Contact Phone": contact.phone.clone().unwrap_or_default(),
-        "29 Contact Email": contact.email.clone().unwrap_or_default(),
-        "99 Strap Line": fee.strap_line.clone()
-    });
+    let json_data = build_fee_json(fee, project, company, contact);
 
     // Ensure directory exists
     if let Some(parent) = Path::new(&target_file_path).parent() {
@@ -1365,103 +2648,478 @@ pub async fn write_fee_to_json_safe(fee_id: String, state: State<'_, AppState>,
     Ok(result_message)
 }
 
-/// Check if JSON content contains placeholder data or real project data.
+/// Write var.json files for every active fee in one run.
+/// 
+/// Exports each fee that is not Lost or Cancelled using the same safety
+/// checks as `write_fee_to_json_safe`. One failing fee does not stop the run.
 /// 
 /// # Parameters
-/// - `content`: The JSON file content as a string
+/// - `overwrite`: Replace existing var.json files (real data is backed up
+///   first). When `false`, fees that already have a var.json are skipped.
 /// 
 /// # Returns
-/// - `Ok(true)`: Content appears to be placeholder data (safe to overwrite)
-/// - `Ok(false)`: Content appears to be real project data (needs backup)
-/// - `Err(String)`: Error parsing or analyzing content
-fn check_if_placeholder_content(content: &str) -> Result<bool, String> {
-    // Parse JSON content
-    let json_value: Value = serde_json::from_str(content)
-        .map_err(|e| format!("Failed to parse JSON content: {}", e))?;
+/// - `Ok(BatchResult)`: Counts plus a per-fee result
+/// - `Err(String)`: Database or settings error before any export started
+/// 
+/// # Skipped Fees
+/// - Project folder not found under `01 RFPs`
+/// - var.json already exists and `overwrite` is `false`
+/// 
+/// # Frontend Usage
+/// ```typescript
+/// const batch = await invoke('export_all_active_fees', { overwrite: false });
+/// console.log(`${batch.exported} exported, ${batch.skipped} skipped`);
+/// ```
+#[tauri::command]
+pub async fn export_all_active_fees(overwrite: bool, state: State<'_, AppState>, app_handle: AppHandle) -> Result<BatchResult, String> {
+    info!("Exporting var.json for all active fees (overwrite: {})", overwrite);
     
-    // Check for common placeholder indicators
-    let placeholder_indicators = [
-        "Project Name",
-        "Client Name", 
-        "Contact Name",
-        "Default Values",
-        "Template",
-        "PLACEHOLDER",
-        "XXX",
-        "TBD",
-        "yy-cccnn"
-    ];
+    let (manager_clone, operation) = {
+        let manager = state.lock().map_err(|e| e.to_string())?;
+        (manager.clone(), manager.start_operation("Export var.json for all active fees"))
+    };
     
-    // Convert JSON to string for pattern matching
-    let content_str = content.to_lowercase();
+    let fees = manager_clone.get_fees().await
+        .map_err(|e| format!("Failed to fetch fee records: {}", e))?;
+    let projects = manager_clone.get_projects(true).await
+        .map_err(|e| format!("Failed to fetch projects: {}", e))?;
+    let companies = manager_clone.get_companies().await
+        .map_err(|e| format!("Failed to fetch companies: {}", e))?;
+    let contacts = manager_clone.get_contacts().await
+        .map_err(|e| format!("Failed to fetch contacts: {}", e))?;
     
-    // Count placeholder indicators found
-    let placeholder_count = placeholder_indicators.iter()
-        .filter(|&&indicator| content_str.contains(&indicator.to_lowercase()))
-        .count();
+    let settings = get_settings(app_handle).await.map_err(|e| format!("Failed to get settings: {}", e))?;
+    let project_folder_path = settings.project_folder_path
+        .ok_or_else(|| "PROJECT_FOLDER_PATH not configured in settings".to_string())?;
     
-    // Check specific fields that indicate real data
-    if let Some(obj) = json_value.as_object() {
-        // Check if document name looks real (not placeholder)
-        if let Some(doc_name) = obj.get("01 Document Name") {
-            if let Some(name_str) = doc_name.as_str() {
-                if !name_str.trim().is_empty() && 
-                   !placeholder_indicators.iter().any(|&ind| 
-                       name_str.to_lowercase().contains(&ind.to_lowercase())) {
-                    // Document has a real name
-                    if placeholder_count < 2 {
-                        return Ok(false); // Likely real data
-                    }
-                }
-            }
-        }
-        
-        // Check if project name looks real
-        if let Some(project_name) = obj.get("06 Project Name") {
-            if let Some(name_str) = project_name.as_str() {
-                if !name_str.trim().is_empty() && 
-                   !placeholder_indicators.iter().any(|&ind| 
-                       name_str.to_lowercase().contains(&ind.to_lowercase())) {
-                    // Project has a real name
-                    if placeholder_count < 2 {
-                        return Ok(false); // Likely real data
-                    }
-                }
-            }
+    let fees = without_archived_project_fees(fees, &projects);
+    let batch = export_active_fees(&project_folder_path, &fees, &projects, &companies, &contacts, overwrite, &operation.token);
+    info!("Batch export finished: {} exported, {} skipped, {} failed", batch.exported, batch.skipped, batch.failed);
+    Ok(batch)
+}
+
+/// Export each active fee's var.json, recording a result per fee.
+/// 
+/// Stops between fees once `token` is cancelled; fees already written stay.
+fn export_active_fees(
+    project_folder_path: &str,
+    fees: &[Fee],
+    projects: &[Project],
+    companies: &[Company],
+    contacts: &[Contact],
+    overwrite: bool,
+    token: &CancellationToken,
+) -> BatchResult {
+    let mut results = Vec::new();
+    for fee in fees.iter().filter(|f| f.status != "Lost" && f.status != "Cancelled") {
+        if token.is_cancelled() {
+            info!("Batch export cancelled after {} fees", results.len());
+            break;
         }
+        results.push(export_fee_in_batch(project_folder_path, fee, projects, companies, contacts, overwrite));
     }
     
-    // If we found multiple placeholder indicators, it's likely template data
-    Ok(placeholder_count >= 2)
+    BatchResult {
+        exported: results.iter().filter(|r| r.success).count(),
+        skipped: results.iter().filter(|r| r.skipped).count(),
+        failed: results.iter().filter(|r| !r.success && !r.skipped).count(),
+        cancelled: token.is_cancelled(),
+        results,
+    }
 }
 
-/// Format issue date from YYMMDD to "dd MMM yyyy" format.
+/// List long-running operations that can be cancelled.
 /// 
-/// # Parameters
-/// - `date_str`: Date string in YYMMDD format
+/// Bulk var.json exports and project folder moves register themselves here
+/// while they run.
 /// 
 /// # Returns
-/// - Formatted date string or current date if parsing fails
-fn format_issue_date(date_str: &str) -> String {
-    if date_str.len() == 6 {
-        if let (Ok(year), Ok(month), Ok(day)) = (
-            date_str[0..2].parse::<i32>(),
-            date_str[2..4].parse::<u32>(),
-            date_str[4..6].parse::<u32>()
-        ) {
-            let full_year = if year >= 50 { 1900 + year } else { 2000 + year };
-            let date = chrono::NaiveDate::from_ymd_opt(full_year, month, day);
-            return date.map(|d| d.format("%d %b %Y").to_string())
-                .unwrap_or_else(|| Utc::now().format("%d %b %Y").to_string());
-        }
-    }
-    Utc::now().format("%d %b %Y").to_string()
-}
-
-/// Update an existing project in the database.
+/// - `Ok(Vec<OperationInfo>)`: Running operations, oldest first
 /// 
-/// This command updates a project record with new data. Uses partial updates
-/// to only modify the specified fields while preserving others.
+/// # Frontend Usage
+/// ```typescript
+/// const ops = await invoke('list_operations');
+/// ```
+#[tauri::command]
+pub async fn list_operations(state: State<'_, AppState>) -> Result<Vec<OperationInfo>, String> {
+    let manager = state.lock().map_err(|e| e.to_string())?;
+    let registry = manager.operations.lock().map_err(|e| e.to_string())?;
+    Ok(registry.list())
+}
+
+/// Cancel a running operation.
+/// 
+/// The operation stops at its next file boundary, leaving everything it has
+/// already written in place.
+/// 
+/// # Parameters
+/// - `id`: Operation ID from `list_operations`
+/// 
+/// # Returns
+/// - `Ok(true)`: Cancellation requested
+/// - `Ok(false)`: No running operation with that ID
+/// 
+/// # Frontend Usage
+/// ```typescript
+/// await invoke('cancel_operation', { id: ops[0].id });
+/// ```
+#[tauri::command]
+pub async fn cancel_operation(id: String, state: State<'_, AppState>) -> Result<bool, String> {
+    let manager = state.lock().map_err(|e| e.to_string())?;
+    let registry = manager.operations.lock().map_err(|e| e.to_string())?;
+    let cancelled = registry.cancel(&id);
+    if cancelled {
+        info!("Cancellation requested for operation {}", id);
+    }
+    Ok(cancelled)
+}
+
+/// Export a single fee for `export_active_fees`.
+fn export_fee_in_batch(
+    project_folder_path: &str,
+    fee: &Fee,
+    projects: &[Project],
+    companies: &[Company],
+    contacts: &[Contact],
+    overwrite: bool,
+) -> FeeExportResult {
+    let fee_id = fee.id.as_ref()
+        .map(normalize_thing_id)
+        .unwrap_or_default();
+    let result = |success: bool, skipped: bool, message: String| FeeExportResult {
+        fee_id: fee_id.clone(),
+        fee_number: fee.number.clone(),
+        success,
+        skipped,
+        message,
+    };
+    
+    let Some(project) = projects.iter().find(|p| is_linked(&p.id, &fee.project_id)) else {
+        return result(false, false, "Project not found for fee".to_string());
+    };
+    let Some(company) = companies.iter().find(|c| is_linked(&c.id, &fee.company_id)) else {
+        return result(false, false, "Company not found for fee".to_string());
+    };
+    let Some(contact) = contacts.iter().find(|c| is_linked(&c.id, &fee.contact_id)) else {
+        return result(false, false, "Contact not found for fee".to_string());
+    };
+    
+    let project_number = record_key(&project.number.id, "projects");
+    let project_dir = match rfp_project_folder(project_folder_path, project_number, &project.name_short) {
+        Ok(dir) => dir,
+        Err(e) => return result(false, false, e),
+    };
+    if !project_dir.is_dir() {
+        return result(false, true, format!("Project folder not found: {}", project_dir.display()));
+    }
+    
+    let existing = project_dir.join("02 Proposal").join(format!("{}-var.json", project_number));
+    if !overwrite && existing.exists() {
+        return result(false, true, format!("var.json already exists: {}", existing.display()));
+    }
+    
+    match write_fee_json_file(project_folder_path, fee, project, company, contact) {
+        Ok(message) => result(true, false, message),
+        Err(e) => result(false, false, e),
+    }
+}
+
+/// Whether a record's ID is the one a fee links to.
+fn is_linked(id: &Option<surrealdb::sql::Thing>, target: &surrealdb::sql::Thing) -> bool {
+    id.as_ref().is_some_and(|id| normalize_thing_id(id) == normalize_thing_id(target))
+}
+
+/// A contact's full name, or first and last name for older records.
+fn contact_display_name(contact: &Contact) -> String {
+    contact.full_name.clone().unwrap_or_else(|| {
+        let first = contact.first_name.clone().unwrap_or_default();
+        let last = contact.last_name.clone().unwrap_or_default();
+        format!("{} {}", first, last)
+    })
+}
+
+/// Export every fee proposal to a CSV spreadsheet.
+/// 
+/// Project, company and contact links are resolved to names the same way
+/// the var.json export does. Fees whose links no longer resolve are still
+/// written, with those name columns left blank.
+/// 
+/// # Parameters
+/// - `output_path`: CSV file to create or overwrite
+/// 
+/// # Returns
+/// - `Ok(usize)`: Number of fee rows written (excluding the header)
+/// - `Err(String)`: Database error or the file could not be written
+/// 
+/// # CSV Columns
+/// number, name, status, issue_date, project_name, company_name,
+/// contact_name, activity, package
+/// 
+/// # Frontend Usage
+/// ```typescript
+/// const rows = await invoke('export_fees_csv', { outputPath: '/Users/me/Desktop/fees.csv' });
+/// ```
+#[tauri::command]
+pub async fn export_fees_csv(output_path: String, state: State<'_, AppState>) -> Result<usize, String> {
+    info!("Exporting fees to CSV: {}", output_path);
+    
+    let manager_clone = {
+        let manager = state.lock().map_err(|e| e.to_string())?;
+        manager.clone()
+    };
+    
+    let fees = manager_clone.get_fees().await
+        .map_err(|e| format!("Failed to fetch fee records: {}", e))?;
+    let projects = manager_clone.get_projects(true).await
+        .map_err(|e| format!("Failed to fetch projects: {}", e))?;
+    let companies = manager_clone.get_companies().await
+        .map_err(|e| format!("Failed to fetch companies: {}", e))?;
+    let contacts = manager_clone.get_contacts().await
+        .map_err(|e| format!("Failed to fetch contacts: {}", e))?;
+    
+    let file = fs::File::create(&output_path)
+        .map_err(|e| format!("Failed to create {}: {}", output_path, e))?;
+    let fees = without_archived_project_fees(fees, &projects);
+    let rows = write_fees_csv(file, fees, &projects, &companies, &contacts)?;
+    
+    info!("Exported {} fees to {}", rows, output_path);
+    Ok(rows)
+}
+
+/// Drop fees whose project is archived, so exports only cover live projects.
+/// Fees whose project can't be found are kept.
+fn without_archived_project_fees(fees: Vec<Fee>, projects: &[Project]) -> Vec<Fee> {
+    fees.into_iter()
+        .filter(|fee| !projects.iter().any(|p| p.deleted_at.is_some() && is_linked(&p.id, &fee.project_id)))
+        .collect()
+}
+
+/// Write fees as CSV rows sorted by number, returning the number of rows.
+fn write_fees_csv<W: std::io::Write>(
+    writer: W,
+    mut fees: Vec<Fee>,
+    projects: &[Project],
+    companies: &[Company],
+    contacts: &[Contact],
+) -> Result<usize, String> {
+    let mut csv_writer = csv::Writer::from_writer(writer);
+    let to_error = |e: csv::Error| format!("Failed to write CSV: {}", e);
+    
+    csv_writer.write_record([
+        "number", "name", "status", "issue_date", "project_name", "company_name", "contact_name", "activity", "package",
+    ]).map_err(to_error)?;
+    
+    fees.sort_by(|a, b| a.number.cmp(&b.number));
+    for fee in &fees {
+        let project_name = projects.iter().find(|p| is_linked(&p.id, &fee.project_id))
+            .map(|p| p.name.clone()).unwrap_or_default();
+        let company_name = companies.iter().find(|c| is_linked(&c.id, &fee.company_id))
+            .map(|c| c.name.clone()).unwrap_or_default();
+        let contact_name = contacts.iter().find(|c| is_linked(&c.id, &fee.contact_id))
+            .map(contact_display_name).unwrap_or_default();
+        
+        csv_writer.write_record([
+            &fee.number, &fee.name, &fee.status, &fee.issue_date, &project_name, &company_name, &contact_name, &fee.activity, &fee.package,
+        ]).map_err(to_error)?;
+    }
+    
+    csv_writer.flush().map_err(|e| format!("Failed to write CSV: {}", e))?;
+    Ok(fees.len())
+}
+
+/// Check if JSON content contains placeholder data or real project data.
+/// 
+/// # Parameters
+/// - `content`: The JSON file content as a string
+/// 
+/// # Returns
+/// - `Ok(true)`: Content appears to be placeholder data (safe to overwrite)
+/// - `Ok(false)`: Content appears to be real project data (needs backup)
+/// - `Err(String)`: Error parsing or analyzing content
+fn check_if_placeholder_content(content: &str) -> Result<bool, String> {
+    // Parse JSON content
+    let json_value: Value = serde_json::from_str(content)
+        .map_err(|e| format!("Failed to parse JSON content: {}", e))?;
+    
+    // Check for common placeholder indicators
+    let placeholder_indicators = [
+        "Project Name",
+        "Client Name", 
+        "Contact Name",
+        "Default Values",
+        "Template",
+        "PLACEHOLDER",
+        "XXX",
+        "TBD",
+        "yy-cccnn"
+    ];
+    
+    // Convert JSON to string for pattern matching
+    let content_str = content.to_lowercase();
+    
+    // Count placeholder indicators found
+    let placeholder_count = placeholder_indicators.iter()
+        .filter(|&&indicator| content_str.contains(&indicator.to_lowercase()))
+        .count();
+    
+    // Check specific fields that indicate real data
+    if let Some(obj) = json_value.as_object() {
+        // Check if document name looks real (not placeholder)
+        if let Some(doc_name) = obj.get("01 Document Name") {
+            if let Some(name_str) = doc_name.as_str() {
+                if !name_str.trim().is_empty() && 
+                   !placeholder_indicators.iter().any(|&ind| 
+                       name_str.to_lowercase().contains(&ind.to_lowercase())) {
+                    // Document has a real name
+                    if placeholder_count < 2 {
+                        return Ok(false); // Likely real data
+                    }
+                }
+            }
+        }
+        
+        // Check if project name looks real
+        if let Some(project_name) = obj.get("06 Project Name") {
+            if let Some(name_str) = project_name.as_str() {
+                if !name_str.trim().is_empty() && 
+                   !placeholder_indicators.iter().any(|&ind| 
+                       name_str.to_lowercase().contains(&ind.to_lowercase())) {
+                    // Project has a real name
+                    if placeholder_count < 2 {
+                        return Ok(false); // Likely real data
+                    }
+                }
+            }
+        }
+    }
+    
+    // If we found multiple placeholder indicators, it's likely template data
+    Ok(placeholder_count >= 2)
+}
+
+/// InDesign keys written by `build_fee_json`, with the field that feeds each.
+/// 
+/// Keep this in step with `build_fee_json`; it is what `get_fee_json_mapping`
+/// reports as the integration contract.
+const FEE_JSON_MAPPING: &[(&str, &str)] = &[
+    ("01 Document Name", "fee.name"),
+    ("02 Document Number", "fee.number"),
+    ("03 Document Release", "fee.rev"),
+    ("04 Document Issue Date", "fee.issue_date (formatted as dd MMM yyyy)"),
+    ("06 Project Name", "project.name"),
+    ("07 Project Activity", "fee.activity"),
+    ("08 Project Package", "fee.package"),
+    ("09 Project Stage", "project.status"),
+    ("11 Project Area", "project.area"),
+    ("12 Project City", "project.city"),
+    ("13 Project Country", "project.country"),
+    ("21 Client Company", "company.name"),
+    ("22 Client City", "company.city"),
+    ("23 Client Country", "company.country"),
+    ("26 Contact Name", "contact.full_name (or first_name + last_name)"),
+    ("27 Contact Position", "contact.position"),
+    ("28 Contact Phone", "contact.phone"),
+    ("29 Contact Email", "contact.email"),
+    ("31 Fee Amount", "fee.amount (two decimals, blank if unpriced)"),
+    ("32 Fee Currency", "fee.currency"),
+    ("99 Strap Line", "fee.strap_line"),
+];
+
+/// Build the InDesign `-var.json` content for a fee and its linked records.
+fn build_fee_json(fee: &Fee, project: &Project, company: &Company, contact: &Contact) -> Value {
+    serde_json::json!({
+        "01 Document Name": fee.name.clone(),
+        "02 Document Number": fee.number.clone(),
+        "03 Document Release": fee.rev.to_string(),
+        "04 Document Issue Date": format_issue_date(&fee.issue_date),
+        "06 Project Name": project.name.clone(),
+        "07 Project Activity": fee.activity.clone(),
+        "08 Project Package": fee.package.clone(),
+        "09 Project Stage": project.status.clone(),
+        "11 Project Area": project.area.clone(),
+        "12 Project City": project.city.clone(),
+        "13 Project Country": project.country.clone(),
+        "21 Client Company": company.name.clone(),
+        "22 Client City": company.city.clone(),
+        "23 Client Country": company.country.clone(),
+        "26 Contact Name": contact_display_name(contact),
+        "27 Contact Position": contact.position.clone().unwrap_or_default(),
+        "28 Contact Phone": contact.phone.clone().unwrap_or_default(),
+        "29 Contact Email": contact.email.clone().unwrap_or_default(),
+        "31 Fee Amount": fee.amount.map(|amount| format!("{:.2}", amount)).unwrap_or_default(),
+        "32 Fee Currency": fee.currency.clone().unwrap_or_default(),
+        "99 Strap Line": fee.strap_line.clone()
+    })
+}
+
+/// Get the mapping between InDesign JSON keys and database fields.
+/// 
+/// Documents which field feeds each key of the `-var.json` file written by
+/// `write_fee_to_json` and `write_fee_to_json_safe`.
+/// 
+/// # Returns
+/// - `Ok(Vec<FeeJsonField>)`: One entry per exported key, in file order
+/// 
+/// # Frontend Usage
+/// ```typescript
+/// const mapping = await invoke('get_fee_json_mapping');
+/// // [{ key: "26 Contact Name", source: "contact.full_name (or first_name + last_name)" }, ...]
+/// ```
+#[tauri::command]
+pub async fn get_fee_json_mapping() -> Result<Vec<FeeJsonField>, String> {
+    Ok(fee_json_mapping())
+}
+
+fn fee_json_mapping() -> Vec<FeeJsonField> {
+    FEE_JSON_MAPPING
+        .iter()
+        .map(|(key, source)| FeeJsonField {
+            key: key.to_string(),
+            source: source.to_string(),
+        })
+        .collect()
+}
+
+/// Format issue date from YYMMDD to "dd MMM yyyy" format.
+/// 
+/// # Parameters
+/// - `date_str`: Date string in YYMMDD format
+/// 
+/// # Returns
+/// - Formatted date string or current date if parsing fails
+fn format_issue_date(date_str: &str) -> String {
+    parse_issue_date(date_str)
+        .map(|d| d.format("%d %b %Y").to_string())
+        .unwrap_or_else(|| Utc::now().format("%d %b %Y").to_string())
+}
+
+/// Parse a YYMMDD issue date. Years 50-99 map to 19xx, 00-49 to 20xx.
+/// 
+/// # Returns
+/// - `Some(NaiveDate)`: Valid calendar date
+/// - `None`: Wrong length, non-numeric or impossible date
+fn parse_issue_date(date_str: &str) -> Option<chrono::NaiveDate> {
+    if date_str.len() != 6 {
+        return None;
+    }
+    
+    match (
+        date_str[0..2].parse::<i32>(),
+        date_str[2..4].parse::<u32>(),
+        date_str[4..6].parse::<u32>()
+    ) {
+        (Ok(year), Ok(month), Ok(day)) => {
+            let full_year = if year >= 50 { 1900 + year } else { 2000 + year };
+            chrono::NaiveDate::from_ymd_opt(full_year, month, day)
+        }
+        _ => None,
+    }
+}
+
+/// Update an existing project in the database.
+/// 
+/// This command updates a project record with new data. Uses partial updates
+/// to only modify the specified fields while preserving others.
 /// 
 /// # Parameters
 /// - `id`: The string ID of the project to update
@@ -1498,620 +3156,1710 @@ pub async fn update_project(id: String, project_update: ProjectUpdate, state: St
     ).await
 }
 
-/// Delete a project from the database.
-/// 
-/// This command permanently removes a project record from the database.
-/// This operation cannot be undone.
+/// Delete a project from the database.
+/// 
+/// This command permanently removes a project record from the database.
+/// This operation cannot be undone, so the project must be archived with
+/// `archive_project` first and be Completed or Cancelled (`Project::is_terminal`).
+/// 
+/// # Parameters
+/// - `id`: The string ID of the project to delete
+/// 
+/// # Returns
+/// - `Ok(Project)`: The deleted project data for confirmation
+/// - `Err(String)`: Project not found, not archived, not terminal, or deletion failed
+/// 
+/// # Frontend Usage
+/// ```typescript
+/// const deletedProject = await invoke('delete_project', { 
+///   id: "25_97105" 
+/// });
+/// ```
+crud_command!(
+    delete_project,
+    Project,
+    delete_project,
+    "delete",
+    "project",
+    id: String
+);
+
+/// Archive (soft-delete) a project.
+/// 
+/// Sets `deleted_at` instead of removing the row, so fees that reference
+/// the project keep working. Archived projects are hidden from
+/// `get_projects` unless `includeDeleted` is set.
+/// 
+/// # Parameters
+/// - `id`: The string ID of the project (e.g., "25_97105")
+/// 
+/// # Returns
+/// - `Ok(Project)`: The archived project
+/// - `Err(String)`: Project not found or database error
+/// 
+/// # Frontend Usage
+/// ```typescript
+/// const archived = await invoke('archive_project', { id: "25_97105" });
+/// ```
+#[tauri::command]
+pub async fn archive_project(id: String, state: State<'_, AppState>) -> Result<Project, String> {
+    let project_name = format!("project '{}'", id);
+    execute_with_manager(
+        &state,
+        |manager| Box::pin(async move { manager.set_project_archived(&id, true).await }),
+        "archive",
+        &project_name
+    ).await
+}
+
+/// Restore an archived project by clearing `deleted_at`.
+/// 
+/// # Parameters
+/// - `id`: The string ID of the project (e.g., "25_97105")
+/// 
+/// # Returns
+/// - `Ok(Project)`: The restored project
+/// - `Err(String)`: Project not found or database error
+/// 
+/// # Frontend Usage
+/// ```typescript
+/// const restored = await invoke('restore_project', { id: "25_97105" });
+/// ```
+#[tauri::command]
+pub async fn restore_project(id: String, state: State<'_, AppState>) -> Result<Project, String> {
+    let project_name = format!("project '{}'", id);
+    execute_with_manager(
+        &state,
+        |manager| Box::pin(async move { manager.set_project_archived(&id, false).await }),
+        "restore",
+        &project_name
+    ).await
+}
+
+/// Export everything known about a project as a single JSON document.
+/// 
+/// Bundles the project record, all of its fees (including revisions), the
+/// companies and contacts those fees reference, and the project folder's
+/// location on disk. Intended for handover and archival.
+/// 
+/// # Parameters
+/// - `project_id`: Project ID (e.g., "25_97105" or "projects:25_97105")
+/// 
+/// # Returns
+/// - `Ok(Value)`: Dossier with `project`, `fees`, `companies`, `contacts`, `folder` and `exported_at`
+/// - `Err(String)`: Database error or project not found
+/// 
+/// # Frontend Usage
+/// ```typescript
+/// const dossier = await invoke('export_project_dossier', { projectId: '25_97105' });
+/// console.log(`${dossier.fees.length} fees`);
+/// ```
+#[tauri::command]
+pub async fn export_project_dossier(project_id: String, state: State<'_, AppState>) -> Result<Value, String> {
+    info!("Exporting dossier for project {}", project_id);
+    
+    let manager_clone = {
+        let manager = state.lock().map_err(|e| e.to_string())?;
+        manager.clone()
+    };
+    
+    let projects = manager_clone.get_projects(true).await
+        .map_err(|e| format!("Failed to fetch projects: {}", e))?;
+    
+    let key = project_key(&project_id);
+    let project = projects.into_iter()
+        .find(|p| p.id.as_ref().map_or(false, |id| thing_matches(id, &key)))
+        .ok_or_else(|| format!("Project not found with ID: {}", project_id))?;
+    
+    let fees = manager_clone.get_fees().await
+        .map_err(|e| format!("Failed to fetch fee records: {}", e))?;
+    let companies = manager_clone.get_companies().await
+        .map_err(|e| format!("Failed to fetch companies: {}", e))?;
+    let contacts = manager_clone.get_contacts().await
+        .map_err(|e| format!("Failed to fetch contacts: {}", e))?;
+    
+    let folder = match get_project_folder_location(project.number.id.clone()).await {
+        Ok(info) => serde_json::to_value(info).unwrap_or(Value::Null),
+        Err(e) => {
+            warn!("Could not locate folder for project {}: {}", project.number.id, e);
+            Value::Null
+        }
+    };
+    
+    Ok(build_project_dossier(&project, &fees, &companies, &contacts, folder))
+}
+
+/// Assemble the dossier JSON from already-fetched records.
+fn build_project_dossier(project: &Project, fees: &[Fee], companies: &[Company], contacts: &[Contact], folder: Value) -> Value {
+    use serde_json::json;
+    
+    let mut project_fees: Vec<&Fee> = fees.iter()
+        .filter(|fee| is_linked(&project.id, &fee.project_id))
+        .collect();
+    project_fees.sort_by_key(|fee| fee.rev);
+    
+    let linked_companies: Vec<&Company> = companies.iter()
+        .filter(|company| company.id.as_ref().map_or(false, |id| {
+            project_fees.iter().any(|fee| normalize_thing_id(&fee.company_id) == normalize_thing_id(id))
+        }))
+        .collect();
+    
+    let linked_contacts: Vec<&Contact> = contacts.iter()
+        .filter(|contact| contact.id.as_ref().map_or(false, |id| {
+            project_fees.iter().any(|fee| normalize_thing_id(&fee.contact_id) == normalize_thing_id(id))
+        }))
+        .collect();
+    
+    json!({
+        "project": project,
+        "fees": project_fees,
+        "companies": linked_companies,
+        "contacts": linked_contacts,
+        "folder": folder,
+        "exported_at": Utc::now().to_rfc3339(),
+    })
+}
+
+// ============================================================================
+// SYSTEM AND UTILITY COMMANDS
+// ============================================================================
+
+/// Application health check command.
+/// 
+/// This command provides a simple way to verify that the Tauri backend
+/// is running and responsive. It's used for system monitoring and debugging.
+/// 
+/// # Returns
+/// - `Ok(String)`: Always returns "Application is running"
+/// - `Err(String)`: Should never occur unless system is critically failing
+/// 
+/// # Frontend Usage
+/// ```typescript
+/// const status = await invoke('health_check');
+/// console.log(status); // "Application is running"
+/// ```
+#[tauri::command]
+pub async fn health_check() -> Result<String, String> {
+    info!("Application health check");
+    Ok("Application is running".to_string())
+}
+
+/// Get comprehensive database connection information for debugging.
+/// 
+/// This command provides detailed diagnostic information about the database
+/// connection, configuration, and troubleshooting guidance. It's primarily
+/// used for development and support purposes.
+/// 
+/// # Returns
+/// - `Ok(serde_json::Value)`: Detailed connection information object
+/// - `Err(String)`: Error accessing connection information
+/// 
+/// # Information Included
+/// - Database configuration (URL, namespace, database, username)
+/// - Current connection status with timestamp
+/// - Troubleshooting guidance for common issues
+/// - Environment configuration source information
+/// 
+/// # Security Note
+/// Passwords are never included in the response for security reasons.
+/// 
+/// # Frontend Usage
+/// ```typescript
+/// const info = await invoke('get_db_info');
+/// console.log(`Connected to: ${info.url}/${info.namespace}/${info.database}`);
+/// console.log(`Status: ${info.connection_status.is_connected}`);
+/// ```
+#[tauri::command]
+pub async fn get_db_info(state: State<'_, AppState>) -> Result<serde_json::Value, String> {
+    info!("Getting database connection information");
+    
+    let (connection_status, config) = {
+        let manager = state.lock().map_err(|e| e.to_string())?;
+        (manager.get_status(), manager.config.clone())
+    };
+    
+    let info = serde_json::json!({
+        "url": config.url,
+        "namespace": config.namespace,
+        "database": config.database,
+        "username": config.username,
+        "connection_status": connection_status,
+        "timestamp": chrono::Utc::now().to_rfc3339(),
+        "troubleshooting": {
+            "dns_issue": format!("If you see 'No such host is known', the server {} cannot be resolved", config.url),
+            "network_check": format!("Try pinging the server: ping {}", config.url.replace("ws://", "").replace(":8000", "")),
+            "surrealdb_check": format!("Verify SurrealDB is running: telnet {} 8000", config.url.replace("ws://", "").replace(":8000", "")),
+            "config_source": "Configuration loaded from settings file or environment variables"
+        }
+    });
+    
+    Ok(info)
+}
+
+/// Get application statistics and metrics.
+/// 
+/// This command calculates and returns key application metrics for
+/// dashboard displays and monitoring purposes. Statistics are calculated
+/// in real-time from the database.
+/// 
+/// # Returns
+/// - `Ok(serde_json::Value)`: Statistics object with counts and metrics
+/// - `Err(String)`: Error calculating statistics
+/// 
+/// # Statistics Included
+/// - `totalProjects`: Total count of all projects
+/// - `activeRfps`: Count of RFPs not in 'Lost' or 'Cancelled' status
+/// - `totalCompanies`: Total count of all companies
+/// - `totalContacts`: Total count of all contacts
+/// - `totalRfps`: Total count of all RFPs
+/// 
+/// # Performance
+/// Counts are computed in the database, so only five numbers cross the
+/// wire. If the count queries fail, records are fetched and counted instead.
+/// 
+/// # Frontend Usage
+/// ```typescript
+/// const stats = await invoke('get_stats');
+/// console.log(`Active RFPs: ${stats.activeRfps}/${stats.totalRfps}`);
+/// console.log(`Total Projects: ${stats.totalProjects}`);
+/// ```
+#[tauri::command]
+pub async fn get_stats(state: State<'_, AppState>) -> Result<serde_json::Value, String> {
+    info!("Fetching application statistics");
+    
+    let manager_clone = {
+        let manager = state.lock().map_err(|e| e.to_string())?;
+        manager.clone()
+    }; // Lock is automatically dropped here when manager goes out of scope
+    
+    let stats = compute_stats(&manager_clone).await;
+    
+    info!("Successfully calculated statistics");
+    Ok(stats)
+}
+
+/// Count projects, companies, contacts and fees for the dashboard.
+async fn compute_stats(manager_clone: &DatabaseManager) -> serde_json::Value {
+    let counts = match manager_clone.get_stats_counts().await {
+        Ok(counts) => counts,
+        Err(e) => {
+            warn!("Count queries failed, counting fetched records instead: {}", e);
+            count_fetched_records(manager_clone).await
+        }
+    };
+    
+    stats_json(&counts)
+}
+
+/// Fallback for `compute_stats`: fetch every record and count it locally.
+async fn count_fetched_records(manager_clone: &DatabaseManager) -> StatsCounts {
+    let projects = manager_clone.get_projects(false).await.unwrap_or_default();
+    let companies = manager_clone.get_companies().await.unwrap_or_default();
+    let contacts = manager_clone.get_contacts().await.unwrap_or_default();
+    let fees = manager_clone.get_fees().await.unwrap_or_default();
+    
+    // Calculate active fees (exclude Lost and Cancelled)
+    let active_fees = fees.iter()
+        .filter(|f| f.status != "Lost" && f.status != "Cancelled")
+        .count();
+    
+    StatsCounts {
+        projects: projects.len() as u64,
+        companies: companies.len() as u64,
+        contacts: contacts.len() as u64,
+        fees: fees.len() as u64,
+        active_fees: active_fees as u64,
+    }
+}
+
+/// Break down the project total by country for the dashboard.
+/// 
+/// Countries without projects are simply absent. Projects with no country
+/// are counted under "Unknown", so the counts add up to `totalProjects`
+/// from `get_stats`.
+/// 
+/// # Returns
+/// - `Ok(Vec<CountryProjectCount>)`: `{ country, count }`, largest count first
+/// - `Err(String)`: Database error
+/// 
+/// # Frontend Usage
+/// ```typescript
+/// const byCountry = await invoke('get_project_stats_by_country');
+/// // [{ country: "United Arab Emirates", count: 42 }, { country: "Saudi Arabia", count: 17 }]
+/// ```
+#[tauri::command]
+pub async fn get_project_stats_by_country(state: State<'_, AppState>) -> Result<Vec<CountryProjectCount>, String> {
+    execute_with_manager(
+        &state,
+        |manager| Box::pin(async move { manager.get_project_counts_by_country().await }),
+        "fetch",
+        "project counts by country"
+    ).await
+}
+
+/// Break down the project total by project number year for the dashboard.
+/// 
+/// Years come from `number.year` (two digits, e.g. 25). Projects without a
+/// number have a `null` year, so the counts add up to `totalProjects`.
+/// 
+/// # Returns
+/// - `Ok(Vec<YearProjectCount>)`: `{ year, count }`, largest count first
+/// - `Err(String)`: Database error
+/// 
+/// # Frontend Usage
+/// ```typescript
+/// const byYear = await invoke('get_project_stats_by_year');
+/// // [{ year: 25, count: 31 }, { year: 24, count: 28 }]
+/// ```
+#[tauri::command]
+pub async fn get_project_stats_by_year(state: State<'_, AppState>) -> Result<Vec<YearProjectCount>, String> {
+    execute_with_manager(
+        &state,
+        |manager| Box::pin(async move { manager.get_project_counts_by_year().await }),
+        "fetch",
+        "project counts by year"
+    ).await
+}
+
+/// The `get_stats` JSON shape for a set of counts.
+fn stats_json(counts: &StatsCounts) -> serde_json::Value {
+    serde_json::json!({
+        "totalProjects": counts.projects,
+        "activeFees": counts.active_fees,
+        "totalCompanies": counts.companies,
+        "totalContacts": counts.contacts,
+        "totalFees": counts.fees
+    })
+}
+
+/// Get application statistics, reusing a recent result.
+/// 
+/// Same output as `get_stats`, but the result is cached on the database
+/// manager for a TTL (default 30 seconds) so dashboards can poll without
+/// scanning every table each time.
+/// 
+/// # Parameters
+/// - `force_refresh`: Recompute even if the cached value is still fresh
+/// - `ttl_seconds`: Optional new cache TTL, kept for subsequent calls
+/// 
+/// # Returns
+/// - `Ok(serde_json::Value)`: Statistics object (see `get_stats`)
+/// - `Err(String)`: Application state could not be locked
+/// 
+/// # Frontend Usage
+/// ```typescript
+/// const stats = await invoke('get_cached_stats', { forceRefresh: false });
+/// ```
+#[tauri::command]
+pub async fn get_cached_stats(force_refresh: Option<bool>, ttl_seconds: Option<u64>, state: State<'_, AppState>) -> Result<serde_json::Value, String> {
+    let manager_clone = {
+        let manager = state.lock().map_err(|e| e.to_string())?;
+        manager.clone()
+    };
+    
+    if let Some(seconds) = ttl_seconds {
+        let mut cache = manager_clone.stats_cache.lock().map_err(|e| e.to_string())?;
+        cache.ttl = std::time::Duration::from_secs(seconds);
+    }
+    
+    let cache = manager_clone.stats_cache.clone();
+    let generation = manager_clone.generation();
+    cached_stats(&cache, generation, force_refresh.unwrap_or(false), || async move {
+        compute_stats(&manager_clone).await
+    }).await
+}
+
+/// Return the cached statistics for `generation`, or compute and store them.
+async fn cached_stats<F, Fut>(
+    cache: &Mutex<StatsCache>,
+    generation: u64,
+    force_refresh: bool,
+    compute: F,
+) -> Result<serde_json::Value, String>
+where
+    F: FnOnce() -> Fut,
+    Fut: std::future::Future<Output = serde_json::Value>,
+{
+    if !force_refresh {
+        let cached = cache.lock().map_err(|e| e.to_string())?.get(generation);
+        if let Some(stats) = cached {
+            info!("Returning cached statistics");
+            return Ok(stats);
+        }
+    }
+    
+    info!("Computing statistics (force_refresh: {})", force_refresh);
+    let stats = compute().await;
+    cache.lock().map_err(|e| e.to_string())?.store(generation, stats.clone());
+    Ok(stats)
+}
+
+/// Get database table schema information for development.
+/// 
+/// This command retrieves the schema definition for a specified table,
+/// including field definitions, constraints, and indexes. It's used for
+/// development, debugging, and documentation purposes.
+/// 
+/// # Parameters
+/// - `table_name`: Name of the table to inspect (e.g., "projects", "company")
+/// 
+/// # Returns
+/// - `Ok(serde_json::Value)`: Table schema information
+/// - `Err(String)`: Table not found or access error
+/// 
+/// # Schema Information
+/// - Field definitions with types and constraints
+/// - Index information for performance optimization
+/// - Foreign key relationships
+/// - Validation rules and default values
+/// 
+/// # Frontend Usage
+/// ```typescript
+/// const schema = await invoke('get_table_schema', { table_name: 'projects' });
+/// console.log('Project fields:', schema.fields);
+/// ```
+#[tauri::command]
+pub async fn get_table_schema(table_name: String, state: State<'_, AppState>) -> Result<serde_json::Value, String> {
+    info!("Getting schema for table: {}", table_name);
+    
+    let manager_clone = {
+        let manager = state.lock().map_err(|e| e.to_string())?;
+        manager.clone()
+    };
+    
+    match manager_clone.get_table_schema(&table_name).await {
+        Ok(schema) => {
+            info!("Successfully retrieved schema for table: {}", table_name);
+            Ok(schema)
+        }
+        Err(e) => {
+            error!("Failed to get schema for table {}: {}", table_name, e);
+            Err(format!("Failed to get schema for table {}: {}", table_name, e))
+        }
+    }
+}
+
+/// Report differences between a stored project row and the `Project` structs.
+/// 
+/// A developer aid for consolidating `db::Project` with `entities::Project`
+/// and for diagnosing deserialization failures. One sample row from
+/// `projects` is compared against both structs.
+/// 
+/// # Returns
+/// - `Ok(Vec<String>)`: One line per gap, e.g.
+///   "entities::Project: missing `stage` (optional)"
+/// - `Err(String)`: No project rows or database error
+/// 
+/// # Frontend Usage
+/// ```typescript
+/// const gaps = await invoke('describe_project_schema_gaps');
+/// gaps.forEach(g => console.log(g));
+/// ```
+crud_command!(
+    describe_project_schema_gaps,
+    Vec<String>,
+    describe_project_schema_gaps,
+    "inspect",
+    "project schema"
+);
+
+/// Verify that the configured namespace and database exist.
+/// 
+/// Authentication can succeed at root level while the namespace or database
+/// was never defined; every query then returns empty results that look like
+/// "no data". This runs `INFO FOR ROOT` and `INFO FOR NS` to rule that out.
+/// 
+/// # Returns
+/// - `Ok(true)`: Both the namespace and the database exist
+/// - `Err(String)`: Names the missing namespace or database, or a connection error
+/// 
+/// # Frontend Usage
+/// ```typescript
+/// await invoke('verify_namespace_database');
+/// ```
+crud_command!(
+    verify_namespace_database,
+    bool,
+    verify_namespace_database,
+    "verify",
+    "namespace and database"
+);
+
+/// Check that the configured database user can write.
+/// 
+/// A user with read-only access connects and lists records fine, but every
+/// create then fails. This creates and immediately deletes a throwaway record
+/// in the `write_probe` table to find out up front.
+/// 
+/// # Returns
+/// - `Ok(true)`: Writes are permitted
+/// - `Ok(false)`: The write was refused for lack of permissions
+/// - `Err(String)`: Connection or other database error
+/// 
+/// # Frontend Usage
+/// ```typescript
+/// const canWrite = await invoke('check_write_permission');
+/// ```
+crud_command!(
+    check_write_permission,
+    bool,
+    check_write_permission,
+    "check",
+    "database write permission"
+);
+
+/// Get the structured output of `INFO FOR DB` for the schema explorer.
+/// 
+/// Unlike `get_db_info`, which reports connection settings, this queries the
+/// database itself and lists the tables, functions, params, analyzers and
+/// users it defines. Use `get_table_schema` to drill into a single table.
+/// 
+/// # Returns
+/// - `Ok(DatabaseInfo)`: Sorted names for each schema section
+/// - `Err(String)`: Database connection or query error
+/// 
+/// # Frontend Usage
+/// ```typescript
+/// const info = await invoke('get_database_info');
+/// console.log('Tables:', info.tables);
+/// ```
+#[tauri::command]
+pub async fn get_database_info(state: State<'_, AppState>) -> Result<DatabaseInfo, String> {
+    info!("Getting database schema information");
+    
+    let manager_clone = {
+        let manager = state.lock().map_err(|e| e.to_string())?;
+        manager.clone()
+    };
+    
+    match manager_clone.get_database_info().await {
+        Ok(info) => {
+            info!("Database defines {} tables", info.tables.len());
+            Ok(info)
+        }
+        Err(e) => {
+            error!("Failed to get database info: {}", e);
+            Err(format!("Failed to get database info: {}", e))
+        }
+    }
+}
+
+// ============================================================================
+// WINDOW AND DESKTOP INTEGRATION COMMANDS
+// ============================================================================
+
+/// Position application window on right half of 4K monitor.
+/// 
+/// Kept for existing callers; equivalent to `position_window` with the
+/// physical placement below. Prefer `position_window` with values derived
+/// from `get_monitor_info` on other monitor layouts.
+/// 
+/// # Parameters
+/// - `window`: Tauri window handle (automatically provided)
+/// 
+/// # Returns
+/// - `Ok(String)`: Success message
+/// - `Err(String)`: Window positioning error
+/// 
+/// # Window Configuration
+/// - **Position**: (1920, 0) - Right half of 4K screen
+/// - **Size**: 1920x2160 - Half width, full height
+/// - **Use Case**: Dual-monitor setup or large 4K display
+/// 
+/// # Frontend Usage
+/// ```typescript
+/// await invoke('position_window_4k');
+/// // Window now positioned on right half of 4K monitor
+/// ```
+#[tauri::command]
+pub async fn position_window_4k(window: tauri::Window) -> Result<String, String> {
+    info!("Positioning window for 4K monitor");
+    position_window(window, WindowPlacement {
+        x: 1920.0,
+        y: 0.0,
+        width: 1920.0,
+        height: 2160.0,
+        use_logical: false,
+    }).await
+}
+
+/// Position and size the application window.
+/// 
+/// Logical coordinates are scaled by the monitor's scale factor, physical
+/// ones are raw device pixels. Physical values are rounded to whole pixels.
+/// 
+/// # Parameters
+/// - `placement`: `{ x, y, width, height, use_logical }`
+/// - `window`: Tauri window handle (automatically provided)
+/// 
+/// # Returns
+/// - `Ok(String)`: Success message
+/// - `Err(String)`: Non-positive size or window positioning error
+/// 
+/// # Frontend Usage
+/// ```typescript
+/// await invoke('position_window', {
+///   placement: { x: 1280, y: 0, width: 1280, height: 1400, use_logical: true }
+/// });
+/// ```
+#[tauri::command]
+pub async fn position_window(window: tauri::Window, placement: WindowPlacement) -> Result<String, String> {
+    let (position, size) = window_geometry(&placement)?;
+    
+    window.set_position(position)
+        .map_err(|e| format!("Failed to set position: {}", e))?;
+    window.set_size(size)
+        .map_err(|e| format!("Failed to set size: {}", e))?;
+    
+    info!("Window positioned at ({}, {}) with size {}x{} ({})",
+          placement.x, placement.y, placement.width, placement.height,
+          if placement.use_logical { "logical" } else { "physical" });
+    Ok("Window positioned successfully".to_string())
+}
+
+/// Convert a placement into Tauri position and size values.
+fn window_geometry(placement: &WindowPlacement) -> Result<(tauri::Position, tauri::Size), String> {
+    if !(placement.width > 0.0 && placement.height > 0.0) {
+        return Err(format!("Window size must be positive, got {}x{}", placement.width, placement.height));
+    }
+    
+    if placement.use_logical {
+        Ok((
+            tauri::Position::Logical(tauri::LogicalPosition { x: placement.x, y: placement.y }),
+            tauri::Size::Logical(tauri::LogicalSize { width: placement.width, height: placement.height }),
+        ))
+    } else {
+        Ok((
+            tauri::Position::Physical(tauri::PhysicalPosition { x: placement.x.round() as i32, y: placement.y.round() as i32 }),
+            tauri::Size::Physical(tauri::PhysicalSize { width: placement.width.round() as u32, height: placement.height.round() as u32 }),
+        ))
+    }
+}
+
+/// List the monitors available to the application window.
+/// 
+/// Lets the frontend work out a sensible `position_window` placement for
+/// the user's actual monitor arrangement.
+/// 
+/// # Parameters
+/// - `window`: Tauri window handle (automatically provided)
+/// 
+/// # Returns
+/// - `Ok(Vec<MonitorInfo>)`: Name, physical position and size, scale factor and primary flag
+/// - `Err(String)`: Monitors could not be queried
+/// 
+/// # Frontend Usage
+/// ```typescript
+/// const monitors = await invoke('get_monitor_info');
+/// const primary = monitors.find(m => m.is_primary);
+/// ```
+#[tauri::command]
+pub async fn get_monitor_info(window: tauri::Window) -> Result<Vec<MonitorInfo>, String> {
+    let monitors = window.available_monitors()
+        .map_err(|e| format!("Failed to list monitors: {}", e))?;
+    let primary = window.primary_monitor()
+        .map_err(|e| format!("Failed to get primary monitor: {}", e))?;
+    
+    Ok(monitors
+        .iter()
+        .map(|monitor| MonitorInfo {
+            name: monitor.name().cloned(),
+            x: monitor.position().x,
+            y: monitor.position().y,
+            width: monitor.size().width,
+            height: monitor.size().height,
+            scale_factor: monitor.scale_factor(),
+            is_primary: primary.as_ref().map_or(false, |p| {
+                p.name() == monitor.name() && p.position() == monitor.position()
+            }),
+        })
+        .collect())
+}
+
+// ============================================================================
+// SETTINGS AND CONFIGURATION COMMANDS
+// ============================================================================
+
+/// Read current application settings from the settings file.
+/// 
+/// This command loads all configurable application settings from
+/// `settings.json` in the app data directory. Settings include database
+/// configuration, staff information, and file system paths.
+/// 
+/// # Returns
+/// - `Ok(AppSettings)`: Current settings with all configured values
+/// - `Err(String)`: File read error or parsing failure
+/// 
+/// # Configuration Source
+/// Settings are read from `settings.json` (`settings.dev.json` in debug
+/// builds). On first run without that file, a legacy `.env` in the app data
+/// directory or the current directory is read instead and migrated into
+/// `settings.json`. Missing settings are returned as `None` values, allowing
+/// for partial configuration and gradual setup.
+/// 
+/// # Security Considerations
+/// Database passwords and sensitive information are included in the response
+/// but should be handled securely on the frontend (e.g., masked in UI).
+/// 
+/// # Frontend Usage
+/// ```typescript
+/// const settings = await invoke('get_settings');
+/// if (settings.surrealdb_url) {
+///   console.log(`Database: ${settings.surrealdb_url}`);
+/// }
+/// ```
+#[tauri::command]
+pub async fn get_settings(app_handle: AppHandle) -> Result<AppSettings, String> {
+    let json_path = settings_json_path(&app_handle);
+    info!("Loading settings from: {:?}", json_path);
+    
+    let settings = load_settings_file(&json_path, &legacy_settings_env_paths(&app_handle))?;
+    let settings = resolve_keychain_password(settings, keychain_load_password);
+    if let Some(ref url) = settings.surrealdb_url {
+        info!("Database URL loaded: {}", url);
+    }
+    
+    info!("Returning settings: staff_name={:?}, staff_email={:?}, staff_phone={:?}, staff_position={:?}", 
+          settings.staff_name, settings.staff_email, settings.staff_phone, settings.staff_position);
+    
+    Ok(settings)
+}
+
+/// Resolve the settings file read by `get_settings`.
+/// 
+/// Uses the app data directory when running from the app bundle, falling back
+/// to the current directory. In debug mode `settings.dev.json` is used to keep
+/// dev and production configs separate.
+fn settings_json_path(app_handle: &AppHandle) -> PathBuf {
+    let json_filename = settings_json_filename();
+
+    if let Ok(app_data_dir) = app_handle.path().app_data_dir() {
+        app_data_dir.join(json_filename)
+    } else {
+        // Fallback to current directory for development
+        PathBuf::from(json_filename)
+    }
+}
+
+/// Settings file name: `settings.dev.json` in debug builds, `settings.json` otherwise.
+fn settings_json_filename() -> &'static str {
+    if cfg!(debug_assertions) {
+        "settings.dev.json"
+    } else {
+        "settings.json"
+    }
+}
+
+/// Legacy `.env` files migrated on first run, in lookup order: the app data
+/// directory, then the current directory.
+fn legacy_settings_env_paths(app_handle: &AppHandle) -> Vec<PathBuf> {
+    let env_filename = settings_env_filename();
+    let mut paths = Vec::new();
+    if let Ok(app_data_dir) = app_handle.path().app_data_dir() {
+        paths.push(app_data_dir.join(env_filename));
+    }
+    paths.push(PathBuf::from(env_filename));
+    paths
+}
+
+/// Legacy settings file name: `.env.dev` in debug builds, `.env` otherwise.
+fn settings_env_filename() -> &'static str {
+    if cfg!(debug_assertions) {
+        ".env.dev"
+    } else {
+        ".env"
+    }
+}
+
+/// Load settings from `json_path`, migrating a legacy `.env` on first run.
+/// 
+/// When `json_path` does not exist yet, the first existing file in
+/// `legacy_paths` is parsed and written to `json_path`, so later reads no
+/// longer touch the `.env`. A failed migration write is logged and the parsed
+/// settings are still returned. With no file at all, empty settings are returned.
+fn load_settings_file(json_path: &Path, legacy_paths: &[PathBuf]) -> Result<AppSettings, String> {
+    if json_path.exists() {
+        let content = fs::read_to_string(json_path)
+            .map_err(|e| format!("Failed to read settings file: {}", e))?;
+        return serde_json::from_str(&content)
+            .map_err(|e| format!("Invalid settings file {}: {}", json_path.display(), e));
+    }
+    
+    let env_path = match legacy_paths.iter().find(|path| path.exists()) {
+        Some(path) => path,
+        None => {
+            info!("No settings file found, returning empty settings");
+            return Ok(parse_settings_env(""));
+        }
+    };
+    
+    info!("Migrating legacy settings from {:?} to {:?}", env_path, json_path);
+    let content = fs::read_to_string(env_path)
+        .map_err(|e| format!("Failed to read .env file: {}", e))?;
+    let settings = parse_settings_env(&content);
+    
+    if let Err(e) = write_settings_file(json_path, &settings) {
+        warn!("Failed to migrate settings to {:?}: {}", json_path, e);
+    }
+    Ok(settings)
+}
+
+/// Write settings to `path` as pretty-printed JSON, creating its directory.
+fn write_settings_file(path: &Path, settings: &AppSettings) -> Result<(), String> {
+    if let Some(parent_dir) = path.parent() {
+        fs::create_dir_all(parent_dir)
+            .map_err(|e| format!("Failed to create app data directory: {}", e))?;
+    }
+    
+    let content = serde_json::to_string_pretty(settings)
+        .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+    fs::write(path, content).map_err(|e| format!("Failed to write settings file: {}", e))
+}
+
+/// Settings file value standing in for a password kept in the OS keychain.
+const KEYCHAIN_PASSWORD_REF: &str = "keychain:surrealdb_pass";
+
+/// OS keychain entry holding the SurrealDB password.
+fn keychain_entry() -> Result<keyring::Entry, String> {
+    keyring::Entry::new("com.emittiv.e-fees", "surrealdb_pass")
+        .map_err(|e| format!("Keychain unavailable: {}", e))
+}
+
+/// Store the SurrealDB password in the OS keychain.
+fn keychain_store_password(password: &str) -> Result<(), String> {
+    keychain_entry()?
+        .set_password(password)
+        .map_err(|e| format!("Failed to store password in keychain: {}", e))
+}
+
+/// Read the SurrealDB password from the OS keychain.
+fn keychain_load_password() -> Result<String, String> {
+    keychain_entry()?
+        .get_password()
+        .map_err(|e| format!("Failed to read password from keychain: {}", e))
+}
+
+/// Move a plaintext password into the keychain, leaving only a reference.
+/// 
+/// Returns the settings to write to the settings file. When the keychain
+/// can't be used (e.g. headless CI) the password stays in plaintext and a
+/// warning is logged. A missing password keeps `existing_pass`, the value
+/// already in the settings file, if that is a keychain reference.
+fn stash_keychain_password<F>(mut settings: AppSettings, existing_pass: Option<&str>, store: F) -> AppSettings
+where
+    F: FnOnce(&str) -> Result<(), String>,
+{
+    settings.surrealdb_pass_error = None;
+    let missing = matches!(settings.surrealdb_pass.as_deref(), None | Some(""));
+    if missing && existing_pass == Some(KEYCHAIN_PASSWORD_REF) {
+        settings.surrealdb_pass = Some(KEYCHAIN_PASSWORD_REF.to_string());
+        return settings;
+    }
+    
+    let password = match settings.surrealdb_pass.as_deref() {
+        Some(password) if !password.is_empty() && password != KEYCHAIN_PASSWORD_REF => password,
+        _ => return settings,
+    };
+    
+    match store(password) {
+        Ok(()) => settings.surrealdb_pass = Some(KEYCHAIN_PASSWORD_REF.to_string()),
+        Err(e) => warn!("{}; keeping the database password in plaintext", e),
+    }
+    settings
+}
+
+/// Replace a keychain reference read from the settings file with the password.
+/// 
+/// When the keychain can't be read the password is left unset and the error
+/// is reported in `surrealdb_pass_error`; the reference itself is never
+/// passed on as a credential. Saving the settings without a password keeps
+/// the reference in the file (see `stash_keychain_password`).
+fn resolve_keychain_password<F>(mut settings: AppSettings, load: F) -> AppSettings
+where
+    F: FnOnce() -> Result<String, String>,
+{
+    if settings.surrealdb_pass.as_deref() == Some(KEYCHAIN_PASSWORD_REF) {
+        match load() {
+            Ok(password) => settings.surrealdb_pass = Some(password),
+            Err(e) => {
+                warn!("{}; the database password must be re-entered", e);
+                settings.surrealdb_pass = None;
+                settings.surrealdb_pass_error = Some(e);
+            }
+        }
+    }
+    settings
+}
+
+/// Move a plaintext database password from the settings file into the OS keychain.
 /// 
-/// # Parameters
-/// - `id`: The string ID of the project to delete
+/// The password in the settings file is replaced by a keychain reference that
+/// `get_settings` resolves transparently. Unlike `save_settings`, which falls
+/// back to plaintext, this fails if the keychain is unavailable.
 /// 
 /// # Returns
-/// - `Ok(Project)`: The deleted project data for confirmation
-/// - `Err(String)`: Project not found or deletion failed
+/// - `Ok(true)`: Password moved into the keychain
+/// - `Ok(false)`: No plaintext password to migrate
+/// - `Err(String)`: Keychain unavailable or settings file unreadable/unwritable
 /// 
 /// # Frontend Usage
 /// ```typescript
-/// const deletedProject = await invoke('delete_project', { 
-///   id: "25_97105" 
-/// });
+/// const migrated = await invoke('migrate_plaintext_password');
 /// ```
-crud_command!(
-    delete_project,
-    Project,
-    delete_project,
-    "delete",
-    "project",
-    id: String
-);
-
-// ============================================================================
-// SYSTEM AND UTILITY COMMANDS
-// ============================================================================
+#[tauri::command]
+pub async fn migrate_plaintext_password(app_handle: AppHandle) -> Result<bool, String> {
+    let json_path = settings_json_path(&app_handle);
+    let mut settings = load_settings_file(&json_path, &legacy_settings_env_paths(&app_handle))?;
+    
+    let password = match settings.surrealdb_pass.as_deref() {
+        Some(password) if !password.is_empty() && password != KEYCHAIN_PASSWORD_REF => password.to_string(),
+        _ => return Ok(false),
+    };
+    
+    keychain_store_password(&password)?;
+    settings.surrealdb_pass = Some(KEYCHAIN_PASSWORD_REF.to_string());
+    write_settings_file(&json_path, &settings)?;
+    
+    info!("Moved database password from {:?} into the keychain", json_path);
+    Ok(true)
+}
 
-/// Application health check command.
+/// Save application settings to the settings file.
 /// 
-/// This command provides a simple way to verify that the Tauri backend
-/// is running and responsive. It's used for system monitoring and debugging.
+/// This command writes application settings to `settings.json` in the app
+/// data directory, the same file `get_settings` reads. The legacy `.env` is
+/// no longer written. The database password is stored in the OS keychain and
+/// only a reference is written to the file; without a keychain it is written
+/// in plaintext and a warning is logged. An empty password keeps an existing
+/// keychain reference rather than clearing it.
+/// 
+/// # Parameters
+/// - `settings`: Complete settings object with values to save
+/// - `skip_path_validation`: Save even if the project folder can't be found,
+///   e.g. while its network drive is offline (default false)
 /// 
 /// # Returns
-/// - `Ok(String)`: Always returns "Application is running"
-/// - `Err(String)`: Should never occur unless system is critically failing
+/// - `Ok(String)`: Success message
+/// - `Err(String)`: Project folder missing, file write error or permission issue
+/// 
+/// # File Structure
+/// ```json
+/// {
+///   "surrealdb_url": "ws://10.0.1.17:8000",
+///   "surrealdb_ns": "emittiv",
+///   "staff_name": "John Smith",
+///   "project_folder_path": "E:\\Projects"
+/// }
+/// ```
 /// 
 /// # Frontend Usage
 /// ```typescript
-/// const status = await invoke('health_check');
-/// console.log(status); // "Application is running"
+/// const settings = {
+///   surrealdb_url: "ws://10.0.1.17:8000",
+///   surrealdb_ns: "emittiv",
+///   staff_name: "John Smith"
+/// };
+/// await invoke('save_settings', { settings });
+/// await invoke('save_settings', { settings, skipPathValidation: true });
 /// ```
 #[tauri::command]
-pub async fn health_check() -> Result<String, String> {
-    info!("Application health check");
-    Ok("Application is running".to_string())
+pub async fn save_settings(mut settings: AppSettings, skip_path_validation: Option<bool>, app_handle: AppHandle) -> Result<String, String> {
+    info!("Saving settings to settings file");
+    
+    if !skip_path_validation.unwrap_or(false) {
+        validate_settings(&settings)?;
+    }
+    settings.project_folder_path = settings.project_folder_path.map(|path| normalize_base_path(&path));
+    settings.awarded_template_folders = clean_awarded_template_folders(&settings.awarded_template_folders)?;
+    
+    // Write to the same file get_settings reads from
+    let json_path = settings_json_path(&app_handle);
+    info!("Using settings file path: {:?}", json_path);
+    
+    let existing_pass = load_settings_file(&json_path, &[]).ok().and_then(|current| current.surrealdb_pass);
+    let settings = stash_keychain_password(settings, existing_pass.as_deref(), keychain_store_password);
+    
+    match write_settings_file(&json_path, &settings) {
+        Ok(_) => {
+            info!("Successfully saved settings to {:?}", json_path);
+            Ok("Settings saved successfully".to_string())
+        }
+        Err(e) => {
+            error!("{}", e);
+            Err(e)
+        }
+    }
 }
 
-/// Get comprehensive database connection information for debugging.
+/// Normalize a project folder base path entered in the settings.
 /// 
-/// This command provides detailed diagnostic information about the database
-/// connection, configuration, and troubleshooting guidance. It's primarily
-/// used for development and support purposes.
+/// Trims whitespace and trailing `/` or `\` separators, so `E:\Projects\`
+/// becomes `E:\Projects`, while keeping the user's native separators for
+/// display. Drive and filesystem roots (`E:\`, `/`) keep their separator.
 /// 
 /// # Returns
-/// - `Ok(serde_json::Value)`: Detailed connection information object
-/// - `Err(String)`: Error accessing connection information
+/// - `Ok(String)`: The normalized path
+/// - `Err(String)`: The path is empty
 /// 
-/// # Information Included
-/// - Database configuration (URL, namespace, database, username)
-/// - Current connection status with timestamp
-/// - Troubleshooting guidance for common issues
-/// - Environment configuration source information
+/// # Frontend Usage
+/// ```typescript
+/// const path = await invoke('normalize_project_folder_path', { path: 'E:\\Projects\\' });
+/// // "E:\\Projects"
+/// ```
+#[tauri::command]
+pub async fn normalize_project_folder_path(path: String) -> Result<String, String> {
+    let normalized = normalize_base_path(&path);
+    if normalized.is_empty() {
+        return Err("Project folder path is empty".to_string());
+    }
+    Ok(normalized)
+}
+
+/// Check an awarded template folder list before it is saved.
 /// 
-/// # Security Note
-/// Passwords are never included in the response for security reasons.
+/// Names are trimmed and must be single folder names, without separators or
+/// `..`, and not repeated. An empty list is allowed and copies nothing.
+/// 
+/// # Returns
+/// - `Ok(Vec<String>)`: The trimmed folder names, in the given order
+/// - `Err(String)`: Every name that is blank, invalid or duplicated
 /// 
 /// # Frontend Usage
 /// ```typescript
-/// const info = await invoke('get_db_info');
-/// console.log(`Connected to: ${info.url}/${info.namespace}/${info.database}`);
-/// console.log(`Status: ${info.connection_status.is_connected}`);
+/// const folders = await invoke('validate_awarded_template_folders', {
+///   folders: ['03 Contract', ' 04 Deliverables ']
+/// });
+/// // ["03 Contract", "04 Deliverables"]
 /// ```
 #[tauri::command]
-pub async fn get_db_info(state: State<'_, AppState>) -> Result<serde_json::Value, String> {
-    info!("Getting database connection information");
+pub async fn validate_awarded_template_folders(folders: Vec<String>) -> Result<Vec<String>, String> {
+    clean_awarded_template_folders(&folders)
+}
+
+/// Trim awarded template folder names, rejecting invalid and repeated ones.
+fn clean_awarded_template_folders(folders: &[String]) -> Result<Vec<String>, String> {
+    let mut cleaned: Vec<String> = Vec::new();
+    let mut problems = Vec::new();
+    
+    for folder in folders {
+        let name = folder.trim();
+        let is_plain_name = matches!(
+            Path::new(name).components().collect::<Vec<_>>().as_slice(),
+            [std::path::Component::Normal(_)]
+        );
+        if name.is_empty() {
+            problems.push("empty folder name".to_string());
+        } else if !is_plain_name || name.contains("..") || name.contains(['/', '\\', '\0']) {
+            problems.push(format!("'{}' is not a single folder name", name));
+        } else if cleaned.iter().any(|c| c == name) {
+            problems.push(format!("'{}' is listed more than once", name));
+        } else {
+            cleaned.push(name.to_string());
+        }
+    }
     
-    let (connection_status, config) = {
-        let manager = state.lock().map_err(|e| e.to_string())?;
-        (manager.get_status(), manager.config.clone())
+    if problems.is_empty() {
+        Ok(cleaned)
+    } else {
+        Err(format!("Invalid awarded template folders: {}", problems.join(", ")))
+    }
+}
+
+/// Check that a configured project folder exists and contains `01 RFPs`.
+/// 
+/// An unset or blank `project_folder_path` passes; the error lists everything
+/// that is missing.
+fn validate_settings(settings: &AppSettings) -> Result<(), String> {
+    let Some(base) = settings.project_folder_path.as_deref().map(normalize_base_path).filter(|p| !p.is_empty()) else {
+        return Ok(());
     };
     
-    let info = serde_json::json!({
-        "url": config.url,
-        "namespace": config.namespace,
-        "database": config.database,
-        "username": config.username,
-        "connection_status": connection_status,
-        "timestamp": chrono::Utc::now().to_rfc3339(),
-        "troubleshooting": {
-            "dns_issue": format!("If you see 'No such host is known', the server {} cannot be resolved", config.url),
-            "network_check": format!("Try pinging the server: ping {}", config.url.replace("ws://", "").replace(":8000", "")),
-            "surrealdb_check": format!("Verify SurrealDB is running: telnet {} 8000", config.url.replace("ws://", "").replace(":8000", "")),
-            "config_source": "Configuration loaded from .env file or environment variables"
+    let base_path = Path::new(&base);
+    let mut missing = Vec::new();
+    if !base_path.is_dir() {
+        missing.push(format!("project folder '{}'", base));
+    }
+    if !base_path.join("01 RFPs").is_dir() {
+        missing.push(format!("'01 RFPs' subfolder in '{}'", base));
+    }
+    
+    if missing.is_empty() {
+        Ok(())
+    } else {
+        Err(format!("Project folder path is invalid, missing: {}", missing.join(", ")))
+    }
+}
+
+/// Trim whitespace and trailing separators, keeping a root's own separator.
+fn normalize_base_path(path: &str) -> String {
+    let path = path.trim();
+    let trimmed = path.trim_end_matches(['/', '\\']);
+    let is_drive = trimmed.len() == 2 && trimmed.ends_with(':');
+    if (trimmed.is_empty() || is_drive) && trimmed.len() < path.len() {
+        // Keep the root separator: "/" or "E:\"
+        return path[..trimmed.len() + 1].to_string();
+    }
+    trimmed.to_string()
+}
+
+/// The `AppSettings` value backing a settings key, or `None` if it is unset.
+fn settings_value<'a>(settings: &'a AppSettings, key: &str) -> Option<&'a String> {
+    match key {
+        "SURREALDB_URL" => settings.surrealdb_url.as_ref(),
+        "SURREALDB_NS" => settings.surrealdb_ns.as_ref(),
+        "SURREALDB_DB" => settings.surrealdb_db.as_ref(),
+        "SURREALDB_USER" => settings.surrealdb_user.as_ref(),
+        "SURREALDB_PASS" => settings.surrealdb_pass.as_ref(),
+        "STAFF_NAME" => settings.staff_name.as_ref(),
+        "STAFF_EMAIL" => settings.staff_email.as_ref(),
+        "STAFF_PHONE" => settings.staff_phone.as_ref(),
+        "STAFF_POSITION" => settings.staff_position.as_ref(),
+        "PROJECT_FOLDER_PATH" => settings.project_folder_path.as_ref(),
+        "DEFAULT_COUNTRY" => settings.default_country.as_ref(),
+        "DEFAULT_ACTIVITY" => settings.default_activity.as_ref(),
+        "HEARTBEAT_FAILURE_THRESHOLD" => settings.heartbeat_failure_threshold.as_ref(),
+        _ => None,
+    }
+}
+
+/// Value of the part after `KEY=`, without quotes or a trailing inline comment.
+/// 
+/// Quoted values end at the closing quote; unquoted values end at ` #`.
+fn split_env_value(rest: &str) -> String {
+    let trimmed = rest.trim_start();
+    if let Some(quoted) = trimmed.strip_prefix('"') {
+        if let Some(end) = quoted.find('"') {
+            return quoted[..end].to_string();
         }
-    });
+    }
+    let value = match trimmed.find(" #") {
+        Some(pos) => &trimmed[..pos],
+        None => trimmed,
+    };
+    value.trim().trim_matches('"').to_string()
+}
+
+/// Parse a legacy `.env` settings file into `AppSettings`, ignoring unmanaged variables.
+fn parse_settings_env(content: &str) -> AppSettings {
+    let mut settings = AppSettings {
+        surrealdb_url: None,
+        surrealdb_ns: None,
+        surrealdb_db: None,
+        surrealdb_user: None,
+        surrealdb_pass: None,
+        staff_name: None,
+        staff_email: None,
+        staff_phone: None,
+        staff_position: None,
+        project_folder_path: None,
+        default_country: None,
+        default_activity: None,
+        heartbeat_failure_threshold: None,
+        awarded_template_folders: default_awarded_template_folders(),
+        surrealdb_pass_error: None,
+    };
     
-    Ok(info)
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue; // Skip empty lines and comments
+        }
+        
+        if let Some((key, rest)) = line.split_once('=') {
+            let value = Some(split_env_value(rest));
+            
+            // Map environment variables to settings fields
+            match key.trim() {
+                "SURREALDB_URL" => settings.surrealdb_url = value,
+                "SURREALDB_NS" => settings.surrealdb_ns = value,
+                "SURREALDB_DB" => settings.surrealdb_db = value,
+                "SURREALDB_USER" => settings.surrealdb_user = value,
+                "SURREALDB_PASS" => settings.surrealdb_pass = value,
+                "STAFF_NAME" => settings.staff_name = value,
+                "STAFF_EMAIL" => settings.staff_email = value,
+                "STAFF_PHONE" => settings.staff_phone = value,
+                "STAFF_POSITION" => settings.staff_position = value,
+                "PROJECT_FOLDER_PATH" => settings.project_folder_path = value.map(|v| normalize_base_path(&v)),
+                "DEFAULT_COUNTRY" => settings.default_country = value,
+                "DEFAULT_ACTIVITY" => settings.default_activity = value,
+                "HEARTBEAT_FAILURE_THRESHOLD" => settings.heartbeat_failure_threshold = value,
+                _ => {} // Ignore unknown variables
+            }
+        }
+    }
+    
+    settings
 }
 
-/// Get application statistics and metrics.
+/// Check that saving the current settings and reading them back is lossless.
 /// 
-/// This command calculates and returns key application metrics for
-/// dashboard displays and monitoring purposes. Statistics are calculated
-/// in real-time from the database.
+/// Reads the settings, saves them unchanged, re-reads them and compares.
+/// The settings file is rewritten in the process.
 /// 
 /// # Returns
-/// - `Ok(serde_json::Value)`: Statistics object with counts and metrics
-/// - `Err(String)`: Error calculating statistics
+/// - `Ok(true)`: Settings survived the round trip unchanged
+/// - `Ok(false)`: Values differ after saving
+/// - `Err(String)`: Settings file could not be read or written
 /// 
-/// # Statistics Included
-/// - `totalProjects`: Total count of all projects
-/// - `activeRfps`: Count of RFPs not in 'Lost' or 'Cancelled' status
-/// - `totalCompanies`: Total count of all companies
-/// - `totalContacts`: Total count of all contacts
-/// - `totalRfps`: Total count of all RFPs
+/// # Frontend Usage
+/// ```typescript
+/// const ok = await invoke('verify_settings_roundtrip');
+/// ```
+#[tauri::command]
+pub async fn verify_settings_roundtrip(app_handle: AppHandle) -> Result<bool, String> {
+    let before = get_settings(app_handle.clone()).await?;
+    save_settings(before.clone(), Some(true), app_handle.clone()).await?;
+    let after = get_settings(app_handle).await?;
+    
+    let matches = before == after;
+    if !matches {
+        warn!("Settings changed after round trip: {:?} -> {:?}", before, after);
+    }
+    Ok(matches)
+}
+
+/// Reload database configuration from the settings file and reinitialize connection.
 /// 
-/// # Performance
-/// This command queries multiple tables and may take 1-2 seconds for
-/// large datasets. Results should be cached on the frontend.
+/// This command allows the application to pick up new database settings without
+/// requiring a restart. It reloads settings from the settings file, rebuilds the
+/// database configuration, re-initializes the connection and makes sure the
+/// heartbeat monitor is running against the new manager.
+/// 
+/// The new manager is prepared on a clone and swapped into state only once the
+/// connection attempt has finished. Reconfiguring bumps the manager generation,
+/// so a heartbeat check that was in flight against the old connection will not
+/// overwrite the fresh status.
+/// 
+/// If the new settings fail to connect, the manager reverts to the last
+/// configuration that connected successfully and reconnects with it.
+/// 
+/// # Returns
+/// - `Ok(ConnectionStatus)`: Connection status after re-initialization
+/// - `Err(String)`: Settings are incomplete, state is unavailable, or the new
+///   settings failed and the previous configuration was restored
 /// 
 /// # Frontend Usage
 /// ```typescript
-/// const stats = await invoke('get_stats');
-/// console.log(`Active RFPs: ${stats.activeRfps}/${stats.totalRfps}`);
-/// console.log(`Total Projects: ${stats.totalProjects}`);
+/// try {
+///   const status = await invoke<ConnectionStatus>('reload_database_config');
+///   if (status.is_connected) {
+///     console.log('Database configuration reloaded and connected');
+///   } else {
+///     console.warn('Reloaded but not connected:', status.error_message);
+///   }
+/// } catch (error) {
+///   console.error('Failed to reload database config:', error);
+/// }
 /// ```
 #[tauri::command]
-pub async fn get_stats(state: State<'_, AppState>) -> Result<serde_json::Value, String> {
-    info!("Fetching application statistics");
+pub async fn reload_database_config(state: State<'_, AppState>, app_handle: AppHandle) -> Result<ConnectionStatus, String> {
+    info!("Reloading database configuration from settings file");
     
-    let manager_clone = {
+    // Load fresh settings from the settings file
+    let settings = get_settings(app_handle).await?;
+    
+    // Extract database configuration
+    let url = settings.surrealdb_url.ok_or("Missing SurrealDB URL in settings")?;
+    let namespace = settings.surrealdb_ns.ok_or("Missing SurrealDB namespace in settings")?;
+    let database = settings.surrealdb_db.ok_or("Missing SurrealDB database in settings")?;
+    let username = settings.surrealdb_user.ok_or("Missing SurrealDB username in settings")?;
+    if let Some(error) = settings.surrealdb_pass_error {
+        return Err(format!("SurrealDB password unavailable: {}", error));
+    }
+    let password = settings.surrealdb_pass.ok_or("Missing SurrealDB password in settings")?;
+    let failure_threshold = DatabaseConfig::parse_failure_threshold(settings.heartbeat_failure_threshold.as_deref());
+    
+    // Reconfigure a clone so the lock isn't held while connecting
+    let mut manager_clone = {
         let manager = state.lock().map_err(|e| e.to_string())?;
         manager.clone()
-    }; // Lock is automatically dropped here when manager goes out of scope
-    
-    // Fetch all data in parallel for better performance
-    let projects = manager_clone.get_projects().await.unwrap_or_default();
-    let companies = manager_clone.get_companies().await.unwrap_or_default();
-    let contacts = manager_clone.get_contacts().await.unwrap_or_default();
-    let fees = manager_clone.get_fees().await.unwrap_or_default();
+    };
+    manager_clone.config.heartbeat_failure_threshold = failure_threshold;
+    
+    // Re-initialize the connection with the new settings, rolling back to the
+    // last working configuration if they don't connect
+    let outcome = manager_clone.reconfigure_or_rollback(url, namespace, database, username, password).await;
+    match &outcome {
+        Ok(_) if manager_clone.get_status().is_connected => info!("Database reconfiguration successful - connection established"),
+        Ok(_) => warn!("Database reconfiguration completed but connection failed"),
+        Err(e) => warn!("Database reconfiguration rolled back: {}", e),
+    }
     
-    // Calculate active fees (exclude Lost and Cancelled)
-    let active_fees = fees.iter()
-        .filter(|f| f.status != "Lost" && f.status != "Cancelled")
-        .count();
+    // Swap the re-initialized manager into shared state
+    {
+        let mut manager = state.lock().map_err(|e| e.to_string())?;
+        *manager = manager_clone.clone();
+    }
     
-    let stats = serde_json::json!({
-        "totalProjects": projects.len(),
-        "activeFees": active_fees,
-        "totalCompanies": companies.len(),
-        "totalContacts": contacts.len(),
-        "totalFees": fees.len()
-    });
+    // Start heartbeat monitoring if it wasn't started at launch
+    DatabaseManager::start_heartbeat(state.inner().clone()).await;
     
-    info!("Successfully calculated statistics");
-    Ok(stats)
+    outcome.map(|_| manager_clone.get_status())
 }
 
-/// Get database table schema information for development.
-/// 
-/// This command retrieves the schema definition for a specified table,
-/// including field definitions, constraints, and indexes. It's used for
-/// development, debugging, and documentation purposes.
+/// Reconnect to the database with the current configuration.
 /// 
-/// # Parameters
-/// - `table_name`: Name of the table to inspect (e.g., "projects", "company")
+/// Use after a VPN drop or server restart instead of restarting the app. The
+/// heartbeat also reconnects on its own with exponential backoff once the
+/// connection is reported lost; `reconnect_attempts` in the connection
+/// status counts those attempts.
 /// 
 /// # Returns
-/// - `Ok(serde_json::Value)`: Table schema information
-/// - `Err(String)`: Table not found or access error
-/// 
-/// # Schema Information
-/// - Field definitions with types and constraints
-/// - Index information for performance optimization
-/// - Foreign key relationships
-/// - Validation rules and default values
+/// - `Ok(ConnectionStatus)`: Status after a successful reconnect
+/// - `Err(String)`: Connection or health check failed
 /// 
 /// # Frontend Usage
 /// ```typescript
-/// const schema = await invoke('get_table_schema', { table_name: 'projects' });
-/// console.log('Project fields:', schema.fields);
+/// const status = await invoke<ConnectionStatus>('reconnect_database');
 /// ```
 #[tauri::command]
-pub async fn get_table_schema(table_name: String, state: State<'_, AppState>) -> Result<serde_json::Value, String> {
-    info!("Getting schema for table: {}", table_name);
+pub async fn reconnect_database(state: State<'_, AppState>) -> Result<ConnectionStatus, String> {
+    info!("Manual database reconnect requested");
     
-    let manager_clone = {
-        let manager = state.lock().map_err(|e| e.to_string())?;
-        manager.clone()
-    };
+    let status = DatabaseManager::reconnect(state.inner()).await
+        .map_err(|e| format!("Failed to reconnect to database: {}", e))?;
     
-    match manager_clone.get_table_schema(&table_name).await {
-        Ok(schema) => {
-            info!("Successfully retrieved schema for table: {}", table_name);
-            Ok(schema)
-        }
-        Err(e) => {
-            error!("Failed to get schema for table {}: {}", table_name, e);
-            Err(format!("Failed to get schema for table {}: {}", table_name, e))
-        }
-    }
+    // Start heartbeat monitoring if it wasn't started at launch
+    DatabaseManager::start_heartbeat(state.inner().clone()).await;
+    
+    Ok(status)
 }
 
-// ============================================================================
-// WINDOW AND DESKTOP INTEGRATION COMMANDS
-// ============================================================================
-
-/// Position application window on right half of 4K monitor.
+/// Set how many consecutive failed health checks mark the database as
+/// disconnected.
 /// 
-/// This command provides a convenient way to position the application window
-/// for optimal use with 4K monitors. It places the window on the right half
-/// of the screen at full height.
+/// A single failed heartbeat is often just network jitter; requiring several
+/// in a row stops the connection status from flapping. The value is saved to
+/// settings and applied to the running heartbeat from its next check.
 /// 
 /// # Parameters
-/// - `window`: Tauri window handle (automatically provided)
+/// - `threshold`: Consecutive failures before reporting disconnected (1-20)
 /// 
 /// # Returns
-/// - `Ok(String)`: Success message
-/// - `Err(String)`: Window positioning error
-/// 
-/// # Window Configuration
-/// - **Position**: (1920, 0) - Right half of 4K screen
-/// - **Size**: 1920x2160 - Half width, full height
-/// - **Use Case**: Dual-monitor setup or large 4K display
+/// - `Ok(u32)`: The applied threshold
+/// - `Err(String)`: Out-of-range value or settings could not be saved
 /// 
 /// # Frontend Usage
 /// ```typescript
-/// await invoke('position_window_4k');
-/// // Window now positioned on right half of 4K monitor
+/// await invoke('set_heartbeat_failure_threshold', { threshold: 3 });
 /// ```
 #[tauri::command]
-pub async fn position_window_4k(window: tauri::Window) -> Result<String, String> {
-    info!("Positioning window for 4K monitor");
+pub async fn set_heartbeat_failure_threshold(threshold: u32, state: State<'_, AppState>, app_handle: AppHandle) -> Result<u32, String> {
+    if !(1..=20).contains(&threshold) {
+        return Err(format!("Invalid heartbeat failure threshold {}: expected 1-20", threshold));
+    }
     
-    // Set position to right half of 4K screen
-    window.set_position(tauri::Position::Physical(tauri::PhysicalPosition { x: 1920, y: 0 }))
-        .map_err(|e| format!("Failed to set position: {}", e))?;
+    let mut settings = get_settings(app_handle.clone()).await?;
+    settings.heartbeat_failure_threshold = Some(threshold.to_string());
+    save_settings(settings, Some(true), app_handle).await?;
     
-    // Set size to half width, full height
-    window.set_size(tauri::Size::Physical(tauri::PhysicalSize { width: 1920, height: 2160 }))
-        .map_err(|e| format!("Failed to set size: {}", e))?;
+    // The heartbeat reads the config from state on every tick
+    let mut manager = state.lock().map_err(|e| e.to_string())?;
+    manager.config.heartbeat_failure_threshold = threshold;
+    info!("Heartbeat failure threshold set to {}", threshold);
     
-    info!("Window positioned successfully on right half of 4K monitor");
-    Ok("Window positioned successfully".to_string())
+    Ok(threshold)
 }
 
-// ============================================================================
-// SETTINGS AND CONFIGURATION COMMANDS
-// ============================================================================
+/// Get the configured staff member for fee form defaults.
+/// 
+/// # Returns
+/// - `Ok(StaffInfo)`: Staff name, email, phone and position from settings
+/// - `Err(String)`: Settings could not be loaded
+/// 
+/// # Frontend Usage
+/// ```typescript
+/// const staff = await invoke('get_default_staff');
+/// form.staff_name = staff.name;
+/// ```
+#[tauri::command]
+pub async fn get_default_staff(app_handle: AppHandle) -> Result<StaffInfo, String> {
+    let settings = get_settings(app_handle).await?;
+    Ok(StaffInfo::from(&settings))
+}
 
-/// Read current application settings from .env file.
+/// Get the settings-backed defaults for the New Project form.
 /// 
-/// This command loads all configurable application settings from the
-/// `.env` file located in the project root directory. Settings include
-/// database configuration, staff information, and file system paths.
+/// Set them with `save_settings` (`default_country`, `default_activity`).
 /// 
 /// # Returns
-/// - `Ok(AppSettings)`: Current settings with all configured values
-/// - `Err(String)`: File read error or parsing failure
+/// - `Ok(Value)`: `{ country, activity }`, with unset defaults as empty strings
+/// - `Err(String)`: Settings could not be loaded
 /// 
-/// # Configuration Source
-/// Settings are read from `../.env` relative to the Tauri binary location.
-/// Missing settings are returned as `None` values, allowing for partial
-/// configuration and gradual setup.
+/// # Frontend Usage
+/// ```typescript
+/// const defaults = await invoke('get_new_project_defaults');
+/// form.country = form.country || defaults.country;
+/// ```
+#[tauri::command]
+pub async fn get_new_project_defaults(app_handle: AppHandle) -> Result<Value, String> {
+    let settings = get_settings(app_handle).await?;
+    Ok(new_project_defaults(&settings))
+}
+
+/// The New Project form defaults held in settings.
+fn new_project_defaults(settings: &AppSettings) -> Value {
+    serde_json::json!({
+        "country": settings.default_country.clone().unwrap_or_default(),
+        "activity": settings.default_activity.clone().unwrap_or_default(),
+    })
+}
+
+/// Report where each setting's effective value comes from.
 /// 
-/// # Security Considerations
-/// Database passwords and sensitive information are included in the response
-/// but should be handled securely on the frontend (e.g., masked in UI).
+/// In development `dotenvy` loads `.env` into the process environment, and
+/// environment variables take precedence over the settings file when the
+/// database configuration falls back to `DatabaseConfig::from_env`. This
+/// command makes the source of each value explicit.
+/// 
+/// # Returns
+/// - `Ok(HashMap<String, String>)`: Setting key → `"env"`, `"file"` or `"default"`
+/// - `Err(String)`: Settings file exists but could not be read
 /// 
 /// # Frontend Usage
 /// ```typescript
-/// const settings = await invoke('get_settings');
-/// if (settings.surrealdb_url) {
-///   console.log(`Database: ${settings.surrealdb_url}`);
-/// }
+/// const sources = await invoke('get_settings_sources');
+/// console.log(sources.SURREALDB_URL); // "env" | "file" | "default"
 /// ```
 #[tauri::command]
-pub async fn get_settings(app_handle: AppHandle) -> Result<AppSettings, String> {
-    info!("Reading settings from .env file");
-    
-    // Log the current working directory
-    if let Ok(cwd) = std::env::current_dir() {
-        info!("Current working directory: {:?}", cwd);
-    }
+pub async fn get_settings_sources(app_handle: AppHandle) -> Result<std::collections::HashMap<String, String>, String> {
+    let json_path = settings_json_path(&app_handle);
+    info!("Resolving settings sources using {:?}", json_path);
     
-    // Use app data directory for .env file when running from app bundle
-    // In debug mode, use .env.dev to separate dev and production configs
-    let env_filename = if cfg!(debug_assertions) {
-        ".env.dev"
+    let file_settings = if json_path.exists() {
+        Some(load_settings_file(&json_path, &[])?)
     } else {
-        ".env"
+        None
     };
+    
+    Ok(resolve_settings_sources(file_settings.as_ref(), |key| std::env::var(key).ok()))
+}
 
-    let env_path = if let Ok(app_data_dir) = app_handle.path().app_data_dir() {
-        app_data_dir.join(env_filename)
-    } else {
-        // Fallback to current directory for development
-        PathBuf::from(env_filename)
-    };
+/// Classify each settings key as coming from the environment, the file or a default.
+/// 
+/// Environment variables win over the file, matching the precedence used when
+/// the database configuration is loaded from the process environment.
+fn resolve_settings_sources<F>(file_settings: Option<&AppSettings>, env_lookup: F) -> std::collections::HashMap<String, String>
+where
+    F: Fn(&str) -> Option<String>,
+{
+    SETTINGS_KEYS
+        .iter()
+        .map(|key| {
+            let source = if env_lookup(key).map_or(false, |value| !value.is_empty()) {
+                "env"
+            } else if file_settings.is_some_and(|settings| settings_value(settings, key).is_some()) {
+                "file"
+            } else {
+                "default"
+            };
+            (key.to_string(), source.to_string())
+        })
+        .collect()
+}
 
-    // Log which .env file is being loaded
-    info!("Loading environment configuration from: {:?}", env_path);
-    if cfg!(debug_assertions) {
-        info!("Running in DEBUG mode - using .env.dev");
-    } else {
-        info!("Running in RELEASE mode - using .env");
-    }
+/// Placeholder written instead of the database password when exporting masked settings.
+const MASKED_PASSWORD: &str = "********";
 
-    let mut settings = AppSettings {
-        surrealdb_url: None,
-        surrealdb_ns: None,
-        surrealdb_db: None,
-        surrealdb_user: None,
-        surrealdb_pass: None,
-        staff_name: None,
-        staff_email: None,
-        staff_phone: None,
-        staff_position: None,
-        project_folder_path: None,
-    };
+/// Export all settings to a JSON file for setting up another machine.
+/// 
+/// # Parameters
+/// - `path`: Destination file path (e.g., "/Users/me/Desktop/e-fees-settings.json")
+/// - `mask_password`: Replace the database password with a placeholder (default: true)
+/// 
+/// # Returns
+/// - `Ok(String)`: Success message with the written path
+/// - `Err(String)`: Settings could not be read or the file could not be written
+/// 
+/// # Frontend Usage
+/// ```typescript
+/// await invoke('export_settings', { path: '/Users/me/Desktop/e-fees-settings.json', maskPassword: true });
+/// ```
+#[tauri::command]
+pub async fn export_settings(path: String, mask_password: Option<bool>, app_handle: AppHandle) -> Result<String, String> {
+    info!("Exporting settings to {}", path);
     
-    info!("Looking for .env file at: {:?}", env_path);
-    
-    if env_path.exists() {
-        info!(".env file found, reading contents");
-        match fs::read_to_string(&env_path) {
-            Ok(content) => {
-                info!("Successfully read .env file, parsing {} lines", content.lines().count());
-                // Parse .env file line by line
-                for line in content.lines() {
-                    let line = line.trim();
-                    if line.is_empty() || line.starts_with('#') {
-                        continue; // Skip empty lines and comments
-                    }
-                    
-                    if let Some((key, value)) = line.split_once('=') {
-                        let key = key.trim();
-                        let value = value.trim().trim_matches('"'); // Remove quotes
-                        
-                        // Map environment variables to settings fields
-                        match key {
-                            "SURREALDB_URL" => settings.surrealdb_url = Some(value.to_string()),
-                            "SURREALDB_NS" => settings.surrealdb_ns = Some(value.to_string()),
-                            "SURREALDB_DB" => settings.surrealdb_db = Some(value.to_string()),
-                            "SURREALDB_USER" => settings.surrealdb_user = Some(value.to_string()),
-                            "SURREALDB_PASS" => settings.surrealdb_pass = Some(value.to_string()),
-                            "STAFF_NAME" => settings.staff_name = Some(value.to_string()),
-                            "STAFF_EMAIL" => settings.staff_email = Some(value.to_string()),
-                            "STAFF_PHONE" => settings.staff_phone = Some(value.to_string()),
-                            "STAFF_POSITION" => settings.staff_position = Some(value.to_string()),
-                            "PROJECT_FOLDER_PATH" => settings.project_folder_path = Some(value.to_string()),
-                            _ => {} // Ignore unknown variables
-                        }
-                    }
-                }
-                info!("Successfully loaded settings from .env file");
-                if let Some(ref url) = settings.surrealdb_url {
-                    info!("Database URL loaded: {}", url);
-                }
-            }
-            Err(e) => {
-                error!("Failed to read .env file: {}", e);
-                return Err(format!("Failed to read .env file: {}", e));
-            }
-        }
-    } else {
-        info!("No .env file found, returning empty settings");
+    let mut settings = get_settings(app_handle).await?;
+    if mask_password.unwrap_or(true) && settings.surrealdb_pass.is_some() {
+        settings.surrealdb_pass = Some(MASKED_PASSWORD.to_string());
     }
     
-    info!("Returning settings: staff_name={:?}, staff_email={:?}, staff_phone={:?}, staff_position={:?}", 
-          settings.staff_name, settings.staff_email, settings.staff_phone, settings.staff_position);
+    let content = serde_json::to_string_pretty(&settings)
+        .map_err(|e| format!("Failed to serialize settings: {}", e))?;
     
-    Ok(settings)
+    fs::write(&path, content).map_err(|e| {
+        error!("Failed to write settings export {}: {}", path, e);
+        format!("Failed to write settings file: {}", e)
+    })?;
+    
+    Ok(format!("Settings exported to: {}", path))
 }
 
-/// Save application settings to .env file.
+/// Import settings from a JSON file created by `export_settings`.
 /// 
-/// This command writes application settings to the `.env` file, preserving
-/// existing environment variables that are not managed by the application.
-/// The settings are organized into logical sections with comments.
+/// The file is parsed and validated before anything is written. A masked
+/// password keeps the password currently configured on this machine. Valid
+/// settings are applied through `save_settings`; call `reload_database_config`
+/// afterwards to connect with them.
 /// 
 /// # Parameters
-/// - `settings`: Complete settings object with values to save
+/// - `path`: Path to the exported settings JSON file
 /// 
 /// # Returns
-/// - `Ok(String)`: Success message
-/// - `Err(String)`: File write error or permission issue
-/// 
-/// # File Management Strategy
-/// 1. Read existing .env file to preserve other variables
-/// 2. Remove old application-managed variables
-/// 3. Add new settings in organized sections
-/// 4. Write complete file atomically
-/// 
-/// # File Structure
-/// ```env
-/// # Existing variables preserved
-/// 
-/// # SurrealDB Configuration  
-/// SURREALDB_URL="ws://10.0.1.17:8000"
-/// SURREALDB_NS="emittiv"
-/// # ... other DB settings
-/// 
-/// # Staff Information
-/// STAFF_NAME="John Smith"
-/// # ... other staff settings
-/// 
-/// # Project Configuration
-/// PROJECT_FOLDER_PATH="E:\\Projects"
-/// ```
+/// - `Ok(String)`: Success message from `save_settings`
+/// - `Err(String)`: File unreadable, invalid JSON, or invalid settings values
 /// 
 /// # Frontend Usage
 /// ```typescript
-/// const settings = {
-///   surrealdb_url: "ws://10.0.1.17:8000",
-///   surrealdb_ns: "emittiv",
-///   staff_name: "John Smith"
-/// };
-/// await invoke('save_settings', { settings });
+/// await invoke('import_settings', { path: selectedFile });
+/// await invoke('reload_database_config');
 /// ```
 #[tauri::command]
-pub async fn save_settings(settings: AppSettings, app_handle: AppHandle) -> Result<String, String> {
-    info!("Saving settings to .env file");
+pub async fn import_settings(path: String, app_handle: AppHandle) -> Result<String, String> {
+    info!("Importing settings from {}", path);
     
-    // Use app data directory for .env file when running from app bundle
-    let env_path = if let Ok(app_data_dir) = app_handle.path().app_data_dir() {
-        let env_file_path = app_data_dir.join(".env");
-        
-        // Create app data directory if it doesn't exist
-        if let Some(parent_dir) = env_file_path.parent() {
-            if let Err(e) = fs::create_dir_all(parent_dir) {
-                return Err(format!("Failed to create app data directory: {}", e));
-            }
-        }
-        
-        env_file_path
-    } else {
-        // Fallback to current directory for development
-        PathBuf::from(".env")
-    };
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read settings file: {}", e))?;
     
-    info!("Using .env file path: {:?}", env_path);
-    let mut lines = Vec::new();
-    
-    // Read existing .env file to preserve other variables
-    if env_path.exists() {
-        match fs::read_to_string(&env_path) {
-            Ok(content) => {
-                for line in content.lines() {
-                    let line = line.trim();
-                    if line.is_empty() {
-                        lines.push(line.to_string());
-                        continue;
-                    }
-                    
-                    // Skip our managed section headers to avoid duplicates
-                    if line.starts_with('#') {
-                        match line {
-                            "# SurrealDB Configuration" | 
-                            "# TLS Configuration" |
-                            "# Staff Information" | 
-                            "# Project Configuration" => continue,
-                            _ => {
-                                lines.push(line.to_string());
-                                continue;
-                            }
-                        }
-                    }
-                    
-                    if let Some((key, _)) = line.split_once('=') {
-                        let key = key.trim();
-                        
-                        // Skip our managed settings - we'll add them back
-                        match key {
-                            "SURREALDB_URL" | "SURREALDB_NS" | "SURREALDB_DB" | 
-                            "SURREALDB_USER" | "SURREALDB_PASS" |
-                            "SURREALDB_VERIFY_CERTS" | "SURREALDB_ACCEPT_INVALID_HOSTNAMES" |
-                            "STAFF_NAME" | "STAFF_EMAIL" | "STAFF_PHONE" | "STAFF_POSITION" |
-                            "PROJECT_FOLDER_PATH" => continue,
-                            _ => lines.push(line.to_string()),
-                        }
-                    } else {
-                        lines.push(line.to_string());
-                    }
-                }
-            }
-            Err(e) => {
-                error!("Failed to read existing .env file: {}", e);
-                return Err(format!("Failed to read existing .env file: {}", e));
-            }
-        }
+    let mut settings = parse_settings_import(&content)?;
+    
+    if settings.surrealdb_pass.as_deref() == Some(MASKED_PASSWORD) {
+        let current = get_settings(app_handle.clone()).await?;
+        settings.surrealdb_pass = current.surrealdb_pass;
     }
     
-    // Add our settings in organized sections
-    lines.push("".to_string()); // Empty line for separation
-    lines.push("# SurrealDB Configuration".to_string());
+    // The imported folder may belong to another machine's drive layout
+    save_settings(settings, Some(true), app_handle).await
+}
+
+/// Parse and validate an exported settings file.
+fn parse_settings_import(content: &str) -> Result<AppSettings, String> {
+    let settings: AppSettings = serde_json::from_str(content)
+        .map_err(|e| format!("Invalid settings file: {}", e))?;
     
     if let Some(url) = &settings.surrealdb_url {
-        lines.push(format!("SURREALDB_URL=\"{}\"", url));
-    }
-    if let Some(ns) = &settings.surrealdb_ns {
-        lines.push(format!("SURREALDB_NS=\"{}\"", ns));
-    }
-    if let Some(db) = &settings.surrealdb_db {
-        lines.push(format!("SURREALDB_DB=\"{}\"", db));
-    }
-    if let Some(user) = &settings.surrealdb_user {
-        lines.push(format!("SURREALDB_USER=\"{}\"", user));
-    }
-    if let Some(pass) = &settings.surrealdb_pass {
-        lines.push(format!("SURREALDB_PASS=\"{}\"", pass));
+        let valid_scheme = ["ws://", "wss://", "http://", "https://"]
+            .iter()
+            .any(|scheme| url.starts_with(scheme));
+        if !valid_scheme {
+            return Err(format!("Invalid SurrealDB URL in settings file: {}", url));
+        }
     }
     
-    // Add TLS configuration
-    lines.push("".to_string());
-    lines.push("# TLS Configuration".to_string());
-    lines.push("SURREALDB_VERIFY_CERTS=true".to_string());
-    lines.push("SURREALDB_ACCEPT_INVALID_HOSTNAMES=false".to_string());
-    
-    lines.push("".to_string());
-    lines.push("# Staff Information".to_string());
-    
-    if let Some(name) = &settings.staff_name {
-        lines.push(format!("STAFF_NAME=\"{}\"", name));
-    }
     if let Some(email) = &settings.staff_email {
-        lines.push(format!("STAFF_EMAIL=\"{}\"", email));
-    }
-    if let Some(phone) = &settings.staff_phone {
-        lines.push(format!("STAFF_PHONE=\"{}\"", phone));
-    }
-    if let Some(position) = &settings.staff_position {
-        lines.push(format!("STAFF_POSITION=\"{}\"", position));
+        if !email.is_empty() && !email.contains('@') {
+            return Err(format!("Invalid staff email in settings file: {}", email));
+        }
     }
     
-    lines.push("".to_string());
-    lines.push("# Project Configuration".to_string());
-    
-    if let Some(folder_path) = &settings.project_folder_path {
-        lines.push(format!("PROJECT_FOLDER_PATH=\"{}\"", folder_path));
+    let has_db_settings = settings.surrealdb_url.is_some()
+        || settings.surrealdb_ns.is_some()
+        || settings.surrealdb_db.is_some();
+    if !has_db_settings && settings.project_folder_path.is_none() && settings.staff_name.is_none() {
+        return Err("Settings file does not contain any known settings".to_string());
     }
     
-    // Write to file atomically
-    let content = lines.join("\n");
-    match fs::write(&env_path, content) {
-        Ok(_) => {
-            info!("Successfully saved settings to .env file");
-            Ok("Settings saved successfully".to_string())
-        }
-        Err(e) => {
-            error!("Failed to write .env file: {}", e);
-            Err(format!("Failed to write .env file: {}", e))
-        }
-    }
+    Ok(settings)
 }
 
-/// Reload database configuration from the .env file and reinitialize connection.
+/// Check the whole settings → database → project folder chain in one call.
 /// 
-/// This command allows the application to pick up new database settings without
-/// requiring a restart. It reloads settings from the .env file and reconfigures
-/// the database manager with the new connection parameters.
+/// Used by FirstRunSetup and the "diagnose" button. Checks, in order:
+/// settings file present, database config complete, database reachable,
+/// database user allowed to write, project folder set and writable, and
+/// RFP template folder present. The
+/// database is contacted with a separate connection built from the saved
+/// settings, so the check reflects what the next launch would do.
 /// 
 /// # Returns
-/// - `Ok(String)`: Success message indicating the database was reconfigured
-/// - `Err(String)`: Error message if reconfiguration failed
+/// - `Ok(StartupReport)`: Per-check pass/fail with remediation hints
+/// - `Err(String)`: Settings file could not be read
 /// 
 /// # Frontend Usage
 /// ```typescript
-/// try {
-///   await invoke('reload_database_config');
-///   console.log('Database configuration reloaded successfully');
-/// } catch (error) {
-///   console.error('Failed to reload database config:', error);
-/// }
+/// const report = await invoke('run_startup_checks');
+/// report.checks.filter(c => !c.passed).forEach(c => console.warn(c.name, c.remediation));
 /// ```
 #[tauri::command]
-pub async fn reload_database_config(state: State<'_, AppState>, app_handle: AppHandle) -> Result<String, String> {
-    info!("Reloading database configuration from .env file");
-    
-    // Load fresh settings from the .env file
-    let settings = get_settings(app_handle).await?;
-    
-    // Extract database configuration
-    let url = settings.surrealdb_url.ok_or("Missing SurrealDB URL in settings")?;
-    let namespace = settings.surrealdb_ns.ok_or("Missing SurrealDB namespace in settings")?;
-    let database = settings.surrealdb_db.ok_or("Missing SurrealDB database in settings")?;
-    let username = settings.surrealdb_user.ok_or("Missing SurrealDB username in settings")?;
-    let password = settings.surrealdb_pass.ok_or("Missing SurrealDB password in settings")?;
-    
-    // Reconfigure the database manager
-    {
-        let mut manager = state.lock().map_err(|e| e.to_string())?;
-        manager.reconfigure(url, namespace, database, username, password)?;
-    }
+pub async fn run_startup_checks(app_handle: AppHandle) -> Result<StartupReport, String> {
+    // get_settings migrates a legacy .env, so check for the file afterwards
+    let settings = get_settings(app_handle.clone()).await?;
+    let settings_found = settings_json_path(&app_handle).exists();
+    
+    let db_result = match DatabaseConfig::from_settings(&settings) {
+        Ok(config) => {
+            let mut manager = DatabaseManager::from_config(config);
+            Some(match manager.initialize().await {
+                Ok(_) if manager.get_status().is_connected => Ok(manager.check_write_permission().await.unwrap_or_else(|e| {
+                    warn!("Write permission check failed: {}", e);
+                    false
+                })),
+                Ok(_) => Err(manager.get_status().error_message.unwrap_or_else(|| "Health check failed".to_string())),
+                Err(e) => Err(e.to_string()),
+            })
+        }
+        Err(_) => None,
+    };
     
-    // Test the new connection
-    let manager_clone = {
-        let manager = state.lock().map_err(|e| e.to_string())?;
-        manager.clone()
+    let report = startup_report(settings_found, &settings, db_result);
+    info!("Startup checks: {}/{} passed", report.checks.iter().filter(|c| c.passed).count(), report.checks.len());
+    Ok(report)
+}
+
+/// Build the startup report from the settings and the database connection
+/// attempt (`None` when the config was incomplete and nothing was tried).
+/// A successful connection carries whether the write probe succeeded.
+fn startup_report(settings_found: bool, settings: &AppSettings, db_result: Option<Result<bool, String>>) -> StartupReport {
+    let check = |name: &str, result: Result<String, (String, &str)>| match result {
+        Ok(message) => StartupCheck { name: name.to_string(), passed: true, message, remediation: None },
+        Err((message, hint)) => StartupCheck { name: name.to_string(), passed: false, message, remediation: Some(hint.to_string()) },
     };
+    let mut checks = Vec::new();
     
-    // Attempt to connect with new settings
-    let is_connected = manager_clone.check_connection().await;
-    if is_connected {
-        info!("Database reconfiguration successful - connection established");
-        Ok("Database configuration reloaded and connected successfully".to_string())
+    checks.push(check("Settings", if settings_found {
+        Ok("Settings file found".to_string())
     } else {
-        warn!("Database reconfiguration completed but connection test failed");
-        Ok("Database configuration reloaded but connection test failed - check settings".to_string())
-    }
+        Err(("No settings file found".to_string(), "Complete the first-run setup or import a settings file"))
+    }));
+    
+    checks.push(check("Database configuration", match DatabaseConfig::from_settings(settings) {
+        Ok(config) => Ok(format!("Configured for {}", config.url)),
+        Err(e) => Err((e, "Enter the SurrealDB URL, namespace, database, username and password in Settings")),
+    }));
+    
+    checks.push(check("Database connection", match &db_result {
+        Some(Ok(_)) => Ok("Connected and healthy".to_string()),
+        Some(Err(e)) => Err((format!("Could not connect: {}", e), "Check the database server is running and the URL and credentials are correct")),
+        None => Err(("Skipped: database configuration is incomplete".to_string(), "Fix the database configuration first")),
+    }));
+    
+    checks.push(check("Database write access", match db_result {
+        Some(Ok(true)) => Ok("Database user can write".to_string()),
+        Some(Ok(false)) => Err(("Database user cannot write".to_string(), "Give the database user the Editor or Owner role, or use another user")),
+        _ => Err(("Skipped: not connected to the database".to_string(), "Fix the database connection first")),
+    }));
+    
+    let base_path = settings.project_folder_path.as_deref().filter(|p| !p.trim().is_empty());
+    checks.push(check("Project folder", match base_path {
+        Some(base) => match is_dir_writable(Path::new(base)) {
+            Ok(true) => Ok(format!("{} is writable", base)),
+            Ok(false) => Err((format!("{} is not writable", base), "Check the folder permissions or that the network drive is mounted read-write")),
+            Err(e) => Err((e, "Check the network drive is mounted or choose the project folder again in Settings")),
+        },
+        None => Err(("Project folder path is not set".to_string(), "Choose the project folder in Settings")),
+    }));
+    
+    checks.push(check("Project template", match base_path.map(|base| project_template_path(base, None)) {
+        Some(Ok(template)) if template.is_dir() => Ok(format!("Template found at {}", template.display())),
+        Some(Ok(template)) => Err((
+            format!("Template folder not found: {}", template.display()),
+            "Restore the '_yy-cccnn Project Name' template folder in '01 RFPs'",
+        )),
+        Some(Err(e)) => Err((e, "Choose the project folder again in Settings")),
+        None => Err(("Skipped: project folder path is not set".to_string(), "Choose the project folder in Settings")),
+    }));
+    
+    StartupReport { passed: checks.iter().all(|c| c.passed), checks }
 }
 
 // ============================================================================
@@ -2199,49 +4947,180 @@ pub async fn select_folder(app_handle: tauri::AppHandle) -> Result<Option<String
 /// - **macOS**: Uses `open` command  
 /// - **Linux**: Uses `xdg-open` command
 /// 
-/// # Security Considerations
-/// Path validation is performed to prevent command injection, but callers
-/// should ensure paths are trusted before calling this command.
+/// # Security Considerations
+/// Path validation is performed to prevent command injection, but callers
+/// should ensure paths are trusted before calling this command.
+/// 
+/// # Frontend Usage
+/// ```typescript
+/// await invoke('open_folder_in_explorer', { 
+///   folder_path: 'E:\\Projects\\25-97105 Hotel Project' 
+/// });
+/// // Opens folder in system file manager
+/// ```
+#[tauri::command]
+pub async fn open_folder_in_explorer(folder_path: String) -> Result<String, String> {
+    info!("Opening folder in explorer: {}", folder_path);
+    
+    use std::process::Command;
+    
+    // Use platform-specific command to open folder
+    let result = if cfg!(target_os = "windows") {
+        Command::new("explorer")
+            .arg(&folder_path)
+            .spawn()
+    } else if cfg!(target_os = "macos") {
+        Command::new("open")
+            .arg(&folder_path)
+            .spawn()
+    } else {
+        // Linux and other Unix-like systems
+        Command::new("xdg-open")
+            .arg(&folder_path)
+            .spawn()
+    };
+    
+    match result {
+        Ok(_) => {
+            info!("Successfully opened folder: {}", folder_path);
+            Ok("Folder opened successfully".to_string())
+        }
+        Err(e) => {
+            error!("Failed to open folder {}: {}", folder_path, e);
+            Err(format!("Failed to open folder: {}", e))
+        }
+    }
+}
+
+/// Get the app's data, log and settings file locations.
+/// 
+/// Paths are resolved through the Tauri path API, so they include the app
+/// identifier (e.g. `~/Library/Application Support/com.emittiv.e-fees`).
+/// Useful for support requests and backups.
+/// 
+/// # Returns
+/// - `Ok(AppPaths)`: Resolved directories and settings file path
+/// - `Err(String)`: Platform directories could not be resolved
+/// 
+/// # Frontend Usage
+/// ```typescript
+/// const paths = await invoke('get_app_paths');
+/// console.log(paths.settings_path);
+/// ```
+#[tauri::command]
+pub async fn get_app_paths(app_handle: AppHandle) -> Result<AppPaths, String> {
+    let data_dir = app_handle.path().app_data_dir()
+        .map_err(|e| format!("Failed to resolve app data directory: {}", e))?;
+    let log_dir = app_handle.path().app_log_dir()
+        .map_err(|e| format!("Failed to resolve app log directory: {}", e))?;
+    
+    Ok(app_paths_from_dirs(&data_dir, &log_dir))
+}
+
+/// Build `AppPaths` from resolved data and log directories.
+fn app_paths_from_dirs(data_dir: &Path, log_dir: &Path) -> AppPaths {
+    AppPaths {
+        data_dir: data_dir.to_string_lossy().to_string(),
+        log_dir: log_dir.to_string_lossy().to_string(),
+        settings_path: data_dir.join(settings_json_filename()).to_string_lossy().to_string(),
+    }
+}
+
+/// Open one of the app's directories in the system file manager.
+/// 
+/// # Parameters
+/// - `target`: `"data"`, `"log"` or `"settings"` (opens the folder containing the settings file)
+/// 
+/// # Frontend Usage
+/// ```typescript
+/// await invoke('open_app_path', { target: 'log' });
+/// ```
+#[tauri::command]
+pub async fn open_app_path(target: String, app_handle: AppHandle) -> Result<String, String> {
+    let paths = get_app_paths(app_handle).await?;
+    
+    let folder = match target.as_str() {
+        "data" => PathBuf::from(&paths.data_dir),
+        "log" => PathBuf::from(&paths.log_dir),
+        "settings" => PathBuf::from(&paths.settings_path)
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_else(|| PathBuf::from(&paths.data_dir)),
+        _ => return Err(format!("Unknown app path '{}'. Expected data, log or settings", target)),
+    };
+    
+    // The log or data directory may not exist yet on a fresh install
+    fs::create_dir_all(&folder)
+        .map_err(|e| format!("Failed to create folder {}: {}", folder.display(), e))?;
+    
+    open_folder_in_explorer(folder.to_string_lossy().to_string()).await
+}
+
+/// Return the last lines of the app's log for an in-app support panel.
+/// 
+/// Reads the newest `.log` file in the app log directory (resolved through
+/// the Tauri path API). When the log was just rotated and holds fewer lines
+/// than requested, the remainder comes from the previous file. A missing log
+/// directory or file yields an empty list.
+/// 
+/// # Parameters
+/// - `lines`: Number of lines to return (1-5000)
+/// 
+/// # Returns
+/// - `Ok(Vec<String>)`: Log lines, oldest first
+/// - `Err(String)`: Invalid line count or unreadable log directory
 /// 
 /// # Frontend Usage
 /// ```typescript
-/// await invoke('open_folder_in_explorer', { 
-///   folder_path: 'E:\\Projects\\25-97105 Hotel Project' 
-/// });
-/// // Opens folder in system file manager
+/// const lines = await invoke('get_recent_logs', { lines: 200 });
 /// ```
 #[tauri::command]
-pub async fn open_folder_in_explorer(folder_path: String) -> Result<String, String> {
-    info!("Opening folder in explorer: {}", folder_path);
+pub async fn get_recent_logs(lines: u32, app_handle: AppHandle) -> Result<Vec<String>, String> {
+    if !(1..=5000).contains(&lines) {
+        return Err(format!("Invalid line count {}: expected 1-5000", lines));
+    }
     
-    use std::process::Command;
+    let log_dir = app_handle.path().app_log_dir()
+        .map_err(|e| format!("Failed to resolve app log directory: {}", e))?;
     
-    // Use platform-specific command to open folder
-    let result = if cfg!(target_os = "windows") {
-        Command::new("explorer")
-            .arg(&folder_path)
-            .spawn()
-    } else if cfg!(target_os = "macos") {
-        Command::new("open")
-            .arg(&folder_path)
-            .spawn()
-    } else {
-        // Linux and other Unix-like systems
-        Command::new("xdg-open")
-            .arg(&folder_path)
-            .spawn()
+    tail_log_lines(&log_dir, lines as usize)
+}
+
+/// Last `count` lines across the `.log` files in `log_dir`, newest file last.
+fn tail_log_lines(log_dir: &Path, count: usize) -> Result<Vec<String>, String> {
+    let entries = match fs::read_dir(log_dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(format!("Failed to read log directory {}: {}", log_dir.display(), e)),
     };
     
-    match result {
-        Ok(_) => {
-            info!("Successfully opened folder: {}", folder_path);
-            Ok("Folder opened successfully".to_string())
+    let mut log_files: Vec<(std::time::SystemTime, PathBuf)> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "log"))
+        .filter_map(|path| Some((fs::metadata(&path).ok()?.modified().ok()?, path)))
+        .collect();
+    log_files.sort();
+    
+    // Walk back from the newest file until enough lines are collected
+    let mut tail: Vec<String> = Vec::new();
+    for (_, path) in log_files.iter().rev() {
+        // The file may be rotated away between listing and reading
+        let Ok(bytes) = fs::read(path) else { continue };
+        let content = String::from_utf8_lossy(&bytes);
+        let mut file_lines: Vec<String> = content.lines().map(str::to_string).collect();
+        let needed = count - tail.len();
+        if file_lines.len() > needed {
+            file_lines.drain(..file_lines.len() - needed);
         }
-        Err(e) => {
-            error!("Failed to open folder {}: {}", folder_path, e);
-            Err(format!("Failed to open folder: {}", e))
+        file_lines.append(&mut tail);
+        tail = file_lines;
+        if tail.len() >= count {
+            break;
         }
     }
+    
+    Ok(tail)
 }
 
 // ============================================================================
@@ -2295,6 +5174,73 @@ pub async fn investigate_record(record_id: String, state: State<'_, AppState>) -
     }
 }
 
+/// Resolve a record ID to a label people recognise.
+/// 
+/// Used for tooltips and audit displays that would otherwise show raw
+/// record IDs. Projects and companies are labelled by name, contacts by full
+/// name and fees by number. A record without that field is labelled with
+/// its ID.
+/// 
+/// # Parameters
+/// - `record_id`: A `projects:`, `company:`, `contacts:` or `fee:` record ID
+/// 
+/// # Returns
+/// - `Ok(String)`: The record's label
+/// - `Err(String)`: Unsupported table, record not found or database error
+/// 
+/// # Frontend Usage
+/// ```typescript
+/// const label = await invoke('resolve_record_label', { recordId: 'contacts:john_smith' });
+/// // "John Smith"
+/// ```
+#[tauri::command]
+pub async fn resolve_record_label(record_id: String, state: State<'_, AppState>) -> Result<String, String> {
+    let (table, key) = labelled_record(&record_id)?;
+    let entity = format!("record '{}'", record_id);
+    let record = execute_with_manager(
+        &state,
+        |manager| Box::pin(async move { manager.get_record(table, &key).await }),
+        "fetch",
+        &entity
+    ).await?;
+    
+    let record = record.ok_or_else(|| format!("Record {} not found", record_id))?;
+    Ok(record_label(table, &record).unwrap_or(record_id))
+}
+
+/// Split a record ID into one of the labelled tables and its bare key.
+fn labelled_record(record_id: &str) -> Result<(&'static str, String), String> {
+    let (table, _) = record_id.trim().split_once(':')
+        .ok_or_else(|| format!("Not a record ID: {}", record_id))?;
+    let table = ["projects", "company", "contacts", "fee"]
+        .into_iter()
+        .find(|labelled| *labelled == table)
+        .ok_or_else(|| format!("Cannot label records of table '{}'", table))?;
+    
+    Ok((table, record_key(record_id, table).to_string()))
+}
+
+/// Label field of a record from `table`; `None` when it is missing or blank.
+fn record_label(table: &str, record: &Value) -> Option<String> {
+    let text = |field: &str| {
+        record.get(field)
+            .and_then(Value::as_str)
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(str::to_string)
+    };
+    
+    match table {
+        "projects" | "company" => text("name"),
+        "contacts" => text("full_name").or_else(|| {
+            let name = [text("first_name"), text("last_name")].into_iter().flatten().collect::<Vec<_>>().join(" ");
+            Some(name).filter(|name| !name.is_empty())
+        }),
+        "fee" => text("number"),
+        _ => None,
+    }
+}
+
 // ============================================================================
 // PROJECT NUMBER GENERATION COMMANDS
 // ============================================================================
@@ -2353,6 +5299,154 @@ pub async fn search_countries(query: String, state: State<'_, AppState>) -> Resu
     }
 }
 
+/// Look up the currency of a country.
+/// 
+/// Follows the country's `currency_code` link to the `currency` table. New
+/// fees use this as their default currency when none is given, and the fee
+/// currency picker preselects it.
+/// 
+/// # Parameters
+/// - `country_name`: Country display name (e.g., "United Arab Emirates")
+/// 
+/// # Returns
+/// - `Ok(CountryCurrency)`: `{ code, name }`, e.g. `{ code: "AED", name: "UAE Dirham" }`
+/// - `Err(String)`: Unknown country, no currency linked or database error
+/// 
+/// # Frontend Usage
+/// ```typescript
+/// const currency = await invoke('get_currency_for_country', { countryName: 'United Arab Emirates' });
+/// ```
+#[tauri::command]
+pub async fn get_currency_for_country(country_name: String, state: State<'_, AppState>) -> Result<CountryCurrency, String> {
+    let entity = format!("currency for country '{}'", country_name);
+    execute_with_manager(
+        &state,
+        |manager| Box::pin(async move { manager.get_currency_for_country(&country_name).await }),
+        "fetch",
+        &entity
+    ).await
+}
+
+/// List all currencies for the fee currency picker.
+/// 
+/// # Returns
+/// - `Ok(Vec<Currency>)`: Currency records ordered by code
+/// - `Err(String)`: Database error or connection failure
+/// 
+/// # Frontend Usage
+/// ```typescript
+/// const currencies = await invoke('get_currencies');
+/// // [{ id: "currency:AED", code: "AED", name: "UAE Dirham" }, ...]
+/// ```
+crud_command!(
+    get_currencies,
+    Vec<Currency>,
+    get_currencies,
+    "fetch",
+    "currencies"
+);
+
+/// Import the country reference table from a JSON file.
+/// 
+/// Needed on a fresh database, where project number generation depends on
+/// each country's `dial_code`. Countries are matched by name and keep the
+/// `id` given in the file (or their existing ID), and `currency_code` is
+/// linked to the `currency` table. The file is validated before anything is
+/// written.
+/// 
+/// # Parameters
+/// - `path`: JSON file containing an array of country objects
+/// - `replace`: Overwrite countries that already exist; otherwise only new
+///   countries are created
+/// 
+/// # Returns
+/// - `Ok(u64)`: Number of countries created or replaced
+/// - `Err(String)`: File unreadable, a record without `name` or `dial_code`,
+///   or database error
+/// 
+/// # Frontend Usage
+/// ```typescript
+/// const count = await invoke('import_countries', { path: selectedFile, replace: false });
+/// ```
+#[tauri::command]
+pub async fn import_countries(path: String, replace: bool, state: State<'_, AppState>) -> Result<u64, String> {
+    info!("Importing countries from {} (replace: {})", path, replace);
+    
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read countries file: {}", e))?;
+    let countries = parse_country_import(&content)?;
+    
+    execute_with_manager(
+        &state,
+        |manager| Box::pin(async move { manager.import_countries(countries, replace).await }),
+        "import",
+        "countries"
+    ).await
+}
+
+/// Parse and validate a countries file for `import_countries`.
+/// 
+/// Every record must be an object with a non-empty `name` and a numeric
+/// `dial_code`, and names must be unique. Each record is returned as
+/// `{ id, name_key, currency_code, record }` for `COUNTRY_IMPORT_QUERY`: the
+/// record key from `id` (or null), a key derived from the name for countries
+/// without one, the bare currency key (or null), and the remaining fields.
+fn parse_country_import(content: &str) -> Result<Vec<Value>, String> {
+    let records: Vec<Value> = serde_json::from_str(content)
+        .map_err(|e| format!("Invalid countries file: {}", e))?;
+    
+    let mut names = std::collections::HashSet::new();
+    let mut countries = Vec::with_capacity(records.len());
+    for (index, record) in records.into_iter().enumerate() {
+        let Value::Object(mut country) = record else {
+            return Err(format!("Country #{} is not an object", index + 1));
+        };
+        let name = country
+            .get("name")
+            .and_then(Value::as_str)
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .ok_or_else(|| format!("Country #{} has no name", index + 1))?
+            .to_string();
+        if country.get("dial_code").and_then(Value::as_u64).is_none() {
+            return Err(format!("Country '{}' has no numeric dial_code", name));
+        }
+        if !names.insert(name.clone()) {
+            return Err(format!("Country '{}' appears more than once", name));
+        }
+        
+        let key = |field: Option<Value>, table: &str| match field {
+            Some(Value::String(id)) if !record_key(&id, table).is_empty() => Ok(Value::String(record_key(&id, table).to_string())),
+            None | Some(Value::Null) => Ok(Value::Null),
+            Some(other) => Err(format!("Country '{}' has an invalid {} ID: {}", name, table, other)),
+        };
+        let id = key(country.remove("id"), "country")?;
+        let currency_code = key(country.remove("currency_code"), "currency")?;
+        let name_key = country_name_key(&name);
+        
+        country.insert("name".to_string(), Value::String(name));
+        countries.push(serde_json::json!({
+            "id": id,
+            "name_key": name_key,
+            "currency_code": currency_code,
+            "record": country,
+        }));
+    }
+    
+    Ok(countries)
+}
+
+/// Record key for a country imported without an `id`: the name lowercased,
+/// with runs of other characters replaced by `_` (`"Côte d'Ivoire"` →
+/// `côte_d_ivoire`).
+fn country_name_key(name: &str) -> String {
+    name.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
 /// Generate next sequential project number for given country and year.
 /// 
 /// This command implements the core project numbering algorithm, generating
@@ -2412,6 +5506,117 @@ pub async fn generate_next_project_number(country_name: String, year: Option<u8>
     }
 }
 
+/// Reserve a block of consecutive project numbers for a program of works.
+/// 
+/// Each number gets a `project_reservation` placeholder, so
+/// `generate_next_project_number` continues after the block. Reserved
+/// numbers can still be used to create the actual projects.
+/// 
+/// # Parameters
+/// - `country_name`: Country name used to look up the dial code
+/// - `year`: Two-digit year (0-99)
+/// - `count`: Number of project numbers to reserve (1-99)
+/// 
+/// # Returns
+/// - `Ok(Vec<String>)`: Reserved numbers in order (e.g., ["25-97105", "25-97106"])
+/// - `Err(String)`: Block would exceed 99 projects for the country-year, or database error
+/// 
+/// # Frontend Usage
+/// ```typescript
+/// const numbers = await invoke('allocate_project_number_block', {
+///   countryName: 'United Arab Emirates',
+///   year: 25,
+///   count: 5
+/// });
+/// ```
+#[tauri::command]
+pub async fn allocate_project_number_block(country_name: String, year: u8, count: u32, state: State<'_, AppState>) -> Result<Vec<String>, String> {
+    if year > 99 {
+        return Err(format!("Invalid year {}: expected a two-digit year (0-99)", year));
+    }
+    if !(1..=99).contains(&count) {
+        return Err(format!("Invalid count {}: expected 1-99 project numbers", count));
+    }
+    
+    let manager_clone = {
+        let manager = state.lock().map_err(|e| e.to_string())?;
+        manager.clone()
+    };
+    
+    manager_clone.allocate_project_number_block(&country_name, year, count).await
+        .map_err(|e| {
+            error!("Failed to allocate project numbers: {}", e);
+            format!("Failed to allocate project numbers: {}", e)
+        })
+}
+
+/// Release the unused project number reservations for a country-year.
+/// 
+/// Removes `project_reservation` placeholders whose number has no project,
+/// so abandoned allocations stop consuming numbers. Projects are never
+/// deleted, and reservations that were used for a project are kept.
+/// 
+/// # Parameters
+/// - `country_name`: Country name used to look up the dial code
+/// - `year`: Two-digit year (0-99)
+/// 
+/// # Returns
+/// - `Ok(u64)`: Number of reservations cleared
+/// - `Err(String)`: Unknown country or database error
+/// 
+/// # Frontend Usage
+/// ```typescript
+/// const cleared = await invoke('clear_project_number_reservations', {
+///   countryName: 'United Arab Emirates',
+///   year: 25
+/// });
+/// ```
+#[tauri::command]
+pub async fn clear_project_number_reservations(country_name: String, year: u8, state: State<'_, AppState>) -> Result<u64, String> {
+    if year > 99 {
+        return Err(format!("Invalid year {}: expected a two-digit year (0-99)", year));
+    }
+    
+    let entity = format!("project number reservations for {} {:02}", country_name, year);
+    execute_with_manager(
+        &state,
+        |manager| Box::pin(async move {
+            manager.clear_project_number_reservations(&country_name, year).await
+        }),
+        "clear",
+        &entity
+    ).await
+}
+
+/// List country-years that are running out of project numbers.
+/// 
+/// Each country-year allows sequences 01-99. Capacity is measured from the
+/// highest sequence used by a project or reservation, since new numbers are
+/// always allocated above it. Lets the firm plan renumbering before
+/// `generate_next_project_number` starts failing.
+/// 
+/// # Parameters
+/// - `threshold`: Report country-years with fewer than this many numbers left
+/// 
+/// # Returns
+/// - `Ok(Vec<CapacityWarning>)`: `{ year, country, used, remaining }`, fullest first
+/// - `Err(String)`: Database error
+/// 
+/// # Frontend Usage
+/// ```typescript
+/// const warnings = await invoke('get_capacity_warnings', { threshold: 10 });
+/// // [{ year: 25, country: 971, used: 95, remaining: 4 }]
+/// ```
+#[tauri::command]
+pub async fn get_capacity_warnings(threshold: u32, state: State<'_, AppState>) -> Result<Vec<CapacityWarning>, String> {
+    execute_with_manager(
+        &state,
+        |manager| Box::pin(async move { manager.get_capacity_warnings(threshold).await }),
+        "fetch",
+        "capacity warnings"
+    ).await
+}
+
 /// Validate that a project number doesn't already exist.
 /// 
 /// This command checks the database to ensure a generated project number
@@ -2461,6 +5666,139 @@ pub async fn validate_project_number(project_number: String, state: State<'_, Ap
     }
 }
 
+/// Validate a batch of project numbers for an import.
+/// 
+/// Checks the `YY-CCCNN` format of every number and looks up all of them
+/// with a single query, instead of one `validate_project_number` call each.
+/// A number repeated within the batch is only available the first time.
+/// 
+/// # Parameters
+/// - `numbers`: Project numbers to check (e.g., ["25-97105", "25-97106"])
+/// 
+/// # Returns
+/// - `Ok(Vec<ProjectNumberCheck>)`: `{ number, available, format_ok }` per input, in order
+/// - `Err(String)`: Database error
+/// 
+/// # Frontend Usage
+/// ```typescript
+/// const checks = await invoke('validate_project_numbers', { numbers: rows.map(r => r.number) });
+/// const rejected = checks.filter(c => !c.available);
+/// ```
+#[tauri::command]
+pub async fn validate_project_numbers(numbers: Vec<String>, state: State<'_, AppState>) -> Result<Vec<ProjectNumberCheck>, String> {
+    let wellformed: Vec<String> = numbers.iter()
+        .filter_map(|number| ProjectNumber::parse(number))
+        .map(|number| number.id)
+        .collect();
+    
+    let taken = if wellformed.is_empty() {
+        Vec::new()
+    } else {
+        execute_with_manager(
+            &state,
+            |manager| Box::pin(async move { manager.get_taken_project_numbers(wellformed).await }),
+            "check",
+            "project numbers"
+        ).await?
+    };
+    
+    Ok(project_number_checks(numbers, &taken))
+}
+
+/// Check each number's format and availability against the `taken` numbers.
+fn project_number_checks(numbers: Vec<String>, taken: &[String]) -> Vec<ProjectNumberCheck> {
+    use std::collections::HashSet;
+    
+    let mut used: HashSet<String> = taken.iter().cloned().collect();
+    numbers
+        .into_iter()
+        .map(|number| {
+            let parsed = ProjectNumber::parse(&number);
+            ProjectNumberCheck {
+                format_ok: parsed.is_some(),
+                available: parsed.is_some_and(|parsed| used.insert(parsed.id)),
+                number,
+            }
+        })
+        .collect()
+}
+
+/// Get the projects numbered immediately before and after a project number.
+/// 
+/// Gives context when assigning numbers: the neighbours are the projects with
+/// the nearest lower and higher sequence in the same country and year, so
+/// gaps in the sequence are skipped. The number itself need not exist.
+/// 
+/// # Parameters
+/// - `number_id`: Project number (e.g., "25-97105")
+/// 
+/// # Returns
+/// - `Ok(AdjacentProjects)`: `{ prev, next }`, either may be `null`
+/// - `Err(String)`: Invalid number format or database error
+/// 
+/// # Frontend Usage
+/// ```typescript
+/// const { prev, next } = await invoke('get_adjacent_projects', { numberId: '25-97105' });
+/// ```
+#[tauri::command]
+pub async fn get_adjacent_projects(number_id: String, state: State<'_, AppState>) -> Result<AdjacentProjects, String> {
+    let project_name = format!("projects adjacent to {}", number_id);
+    execute_with_manager(
+        &state,
+        |manager| Box::pin(async move {
+            manager.get_adjacent_projects(&number_id).await
+        }),
+        "fetch",
+        &project_name
+    ).await
+}
+
+/// Check that a project number's `id` matches its year/country/seq components.
+/// 
+/// # Parameters
+/// - `number`: Project number object as stored on a project
+/// 
+/// # Returns
+/// - `Ok(true)`: `id` equals the formatted components
+/// - `Ok(false)`: The two have diverged
+/// 
+/// # Frontend Usage
+/// ```typescript
+/// const ok = await invoke('validate_project_number_consistency', {
+///   number: { year: 25, country: 971, seq: 5, id: '25-97105' }
+/// });
+/// ```
+#[tauri::command]
+pub async fn validate_project_number_consistency(number: ProjectNumber) -> Result<bool, String> {
+    let consistent = number.is_consistent();
+    if !consistent {
+        warn!("Project number '{}' does not match components {}/{}/{}", number.id, number.year, number.country, number.seq);
+    }
+    Ok(consistent)
+}
+
+/// Get soft warnings for a project number before creating the project.
+/// 
+/// Unlike validation errors these do not block creation; the UI shows them
+/// and asks the user to confirm. `create_project` and
+/// `create_project_with_template` return the same warnings with the project. Currently warns when the number's year is
+/// more than two years away from the current year (e.g. `15-97105` in 2025).
+/// 
+/// # Returns
+/// - `Ok(Vec<String>)`: Warning messages, empty when the number looks fine
+/// 
+/// # Frontend Usage
+/// ```typescript
+/// const warnings = await invoke('get_project_number_warnings', { number });
+/// if (warnings.length && !confirm(`${warnings.join('\n')}\n\nAre you sure?`)) return;
+/// ```
+#[tauri::command]
+pub async fn get_project_number_warnings(number: ProjectNumber) -> Result<Vec<String>, String> {
+    use chrono::Datelike;
+    
+    Ok(number.year_warning(Utc::now().year()).into_iter().collect())
+}
+
 /// Create project with automatic template folder copying.
 /// 
 /// This command provides the complete project creation workflow including:
@@ -2471,24 +5809,30 @@ pub async fn validate_project_number(project_number: String, state: State<'_, Ap
 /// 
 /// # Parameters
 /// - `project`: NewProject object with all required fields
+/// - `force`: Keep the project record even if the template copy fails (default false)
 /// 
 /// # Returns
-/// - `Ok(Project)`: Created project with database metadata
-/// - `Err(String)`: Creation error or file system failure
+/// - `Ok(ProjectCreateResult)`: `{ project, warnings }`, the created project with
+///   database metadata and any soft warnings, including a template copy
+///   failure kept because of `force`
+/// - `Err(String)`: Creation error, or template copy failure after rollback
 /// 
 /// # Template Folder Operations
-/// 1. **Source**: `{PROJECT_FOLDER_PATH}\\01 RFPs\\_yy-cccnn Project Name`
+/// 1. **Source**: `{PROJECT_FOLDER_PATH}\\01 RFPs\\_yy-cccnn Project Name`, or the
+///    project's `template_override` folder in `01 RFPs` when set
 /// 2. **Destination**: `{PROJECT_FOLDER_PATH}\\01 RFPs\\{project_number} {name_short}`
 /// 3. **File Renaming**: All files containing "yy-cccnn" are renamed with actual number
 /// 
-/// # Cross-Platform Support
-/// - **Windows**: Uses `xcopy` command for robust folder copying
-/// - **Other Platforms**: Could be extended with platform-specific commands
+/// # Cancellation
+/// The template copy is registered as a cancellable operation and checks for
+/// cancellation between files; a cancelled copy is rolled back like a failed one.
 /// 
 /// # Error Handling
 /// - Database creation is atomic - if it fails, no files are created
-/// - File operations are best-effort - project is still created if they fail
-/// - Detailed logging for all operations for debugging
+/// - If the template copy fails, the partial folder and the new project record
+///   are removed again and the error says what was rolled back
+/// - With `force`, the project record is kept even when the copy fails
+/// - Renaming template files is best-effort and never fails the operation
 /// 
 /// # Frontend Usage
 /// ```typescript
@@ -2503,7 +5847,7 @@ pub async fn validate_project_number(project_number: String, state: State<'_, Ap
 ///   folder: "25-97106 Hotel ABC"
 /// };
 /// 
-/// const created = await invoke('create_project_with_template', {
+/// const { project, warnings } = await invoke('create_project_with_template', {
 ///   project: projectData
 /// });
 /// ```
@@ -2512,12 +5856,41 @@ pub async fn validate_project_number(project_number: String, state: State<'_, Ap
 /// - `PROJECT_FOLDER_PATH` must be configured in settings
 /// - Template folder `_yy-cccnn Project Name` must exist
 /// - Write permissions for destination directory
-/// - Windows: `xcopy` command must be available (standard on Windows)
 #[tauri::command]
-pub async fn create_project_with_template(project: NewProject, state: State<'_, AppState>, app_handle: AppHandle) -> Result<Project, String> {
+pub async fn create_project_with_template(project: NewProject, force: Option<bool>, state: State<'_, AppState>, app_handle: AppHandle) -> Result<ProjectCreateResult, String> {
     info!("Creating project with template: {}", project.name);
     info!("Project data: {:?}", project);
     
+    /// Completed steps, undone in reverse if the template copy fails.
+    enum CreationStep {
+        Record(String),
+        Folder(PathBuf),
+    }
+    
+    /// Copy the template (project override or the default) into the new RFP folder.
+    fn copy_template(base_path: &str, project: &Project, completed: &mut Vec<CreationStep>, token: &CancellationToken) -> Result<(), String> {
+        let project_number = project.number.id.clone();
+        let template_path = project_template_path(base_path, project.template_override.as_deref())?;
+        let dest_path = rfp_project_folder(base_path, &project_number, &project.name_short)?;
+        if dest_path.exists() {
+            return Err(format!("Destination folder already exists: {}", dest_path.display()));
+        }
+        
+        info!("Copying template from {} to {}", template_path.display(), dest_path.display());
+        let copied = copy_folder_recursive(&template_path, &dest_path, token);
+        if dest_path.exists() {
+            completed.push(CreationStep::Folder(dest_path.clone()));
+        }
+        copied?;
+        
+        // Rename files within the copied folder
+        if let Err(e) = rename_template_files(&dest_path.to_string_lossy(), "yy-cccnn", &project_number) {
+            error!("Failed to rename template files: {}", e);
+            // Don't fail the entire operation just because rename failed
+        }
+        Ok(())
+    }
+    
     let manager_clone = {
         let manager = state.lock().map_err(|e| e.to_string())?;
         manager.clone()
@@ -2525,60 +5898,66 @@ pub async fn create_project_with_template(project: NewProject, state: State<'_,
     
     // First create the project in database
     info!("About to create project in database...");
-    match manager_clone.create_new_project(project.clone()).await {
-        Ok(created_project) => {
-            info!("Successfully created project in database: {:?}", created_project.id);
-            
-            // Get project folder path from settings
-            info!("Getting settings for project folder path...");
-            let settings = get_settings(app_handle).await.map_err(|e| format!("Failed to get settings: {}", e))?;
-            
-            info!("Settings loaded - project_folder_path: {:?}", settings.project_folder_path);
-            
-            if let Some(base_path) = settings.project_folder_path {
-                // Copy template folder
-                let template_path = format!("{}\\01 RFPs\\_yy-cccnn Project Name", base_path);
-                let project_number = created_project.number.id.clone();
-                let dest_folder_name = format!("{} {}", project_number, created_project.name_short);
-                let dest_path = format!("{}\\01 RFPs\\{}", base_path, dest_folder_name);
-                
-                info!("Copying template from {} to {}", template_path, dest_path);
-                
-                // Use Windows xcopy for robust folder copying
-                use std::process::Command;
-                let result = Command::new("xcopy")
-                    .args(&[&template_path, &dest_path, "/E", "/I", "/Q"])
-                    .output();
-                
-                match result {
-                    Ok(output) => {
-                        if output.status.success() {
-                            info!("Successfully copied template folder");
-                            
-                            // Rename files within the copied folder
-                            if let Err(e) = rename_template_files(&dest_path, "yy-cccnn", &project_number) {
-                                error!("Failed to rename template files: {}", e);
-                                // Don't fail the entire operation just because rename failed
-                            }
-                        } else {
-                            error!("Failed to copy template folder: {}", String::from_utf8_lossy(&output.stderr));
-                        }
-                    }
-                    Err(e) => {
-                        error!("Failed to execute xcopy: {}", e);
-                    }
+    let mut created = manager_clone.create_new_project(project.clone()).await
+        .map_err(|e| {
+            error!("Failed to create project: {}", e);
+            format!("Failed to create project: {}", e)
+        })?;
+    let created_project = created.project.clone();
+    info!("Successfully created project in database: {:?}", created_project.id);
+    
+    let mut completed = Vec::new();
+    if let Some(id) = &created_project.id {
+        completed.push(CreationStep::Record(project_key(&id.to_string())));
+    }
+    
+    // Get project folder path from settings
+    info!("Getting settings for project folder path...");
+    let copy_result = match get_settings(app_handle).await {
+        Ok(settings) => {
+            info!("Settings loaded - project_folder_path: {:?}", settings.project_folder_path);
+            match settings.project_folder_path {
+                Some(base_path) => {
+                    let operation = manager_clone.start_operation(&format!("Copy template for {}", created_project.number.id));
+                    copy_template(&base_path, &created_project, &mut completed, &operation.token)
+                }
+                None => {
+                    info!("No project_folder_path configured in settings - skipping template folder creation");
+                    Ok(())
                 }
-            } else {
-                info!("No project_folder_path configured in settings - skipping template folder creation");
             }
-            
-            Ok(created_project)
         }
-        Err(e) => {
-            error!("Failed to create project: {}", e);
-            Err(format!("Failed to create project: {}", e))
+        Err(e) => Err(format!("Failed to get settings: {}", e)),
+    };
+    
+    let copy_error = match copy_result {
+        Ok(()) => return Ok(created),
+        Err(e) => e,
+    };
+    
+    if force.unwrap_or(false) {
+        warn!("Template folder creation failed, keeping project {} because force was set: {}", created_project.number.id, copy_error);
+        created.warnings.push(format!("Template folder creation failed: {}", copy_error));
+        return Ok(created);
+    }
+    
+    // Unwind in reverse so the folder goes before the record it belongs to
+    let mut outcome = Vec::new();
+    for step in completed.into_iter().rev() {
+        match step {
+            CreationStep::Folder(path) => match fs::remove_dir_all(&path) {
+                Ok(()) => outcome.push(format!("partial folder {} was removed", path.display())),
+                Err(e) => outcome.push(format!("partial folder {} could not be removed ({})", path.display(), e)),
+            },
+            CreationStep::Record(key) => match manager_clone.discard_project(&key).await {
+                Ok(_) => outcome.push(format!("project record {} was removed", created_project.number.id)),
+                Err(e) => outcome.push(format!("project record {} could not be removed ({})", created_project.number.id, e)),
+            },
         }
     }
+    
+    error!("Template folder creation failed, rolled back: {}; {}", copy_error, outcome.join(", "));
+    Err(format!("Failed to create project folder: {}. Rolled back: {}", copy_error, outcome.join(", ")))
 }
 
 /// Helper function to recursively rename template files.
@@ -2686,7 +6065,8 @@ fn visit_dirs(dir: &Path, old_pattern: &str, new_pattern: &str) -> Result<(), St
 /// - `Err(String)`: Error during folder copy or file rename operations
 /// 
 /// # Template Structure
-/// Source: `{PROJECT_FOLDER_PATH}/01 RFPs/_yy-cccnn Project Name/`
+/// Source: `{PROJECT_FOLDER_PATH}/01 RFPs/_yy-cccnn Project Name/`, or
+/// `{PROJECT_FOLDER_PATH}/01 RFPs/{template_override}/` when the project has one
 /// Destination: `{PROJECT_FOLDER_PATH}/01 RFPs/{project_number} {project_short_name}/`
 /// 
 /// # File Renaming
@@ -2695,10 +6075,9 @@ fn visit_dirs(dir: &Path, old_pattern: &str, new_pattern: &str) -> Result<(), St
 /// - `yy-cccnn-FP-01.indd` → `25-97105-FP-01.indd`
 /// - `yy-cccnn-var Default Values.json` → `25-97105-var Default Values.json`
 /// 
-/// # Cross-Platform Support
-/// - **macOS/Linux**: Uses `cp -R` command for recursive folder copying
-/// - **Windows**: Uses `xcopy` command with appropriate flags
-/// - Path separators are automatically normalized for each platform
+/// # Cancellation
+/// The copy is registered as a cancellable operation and checks for
+/// cancellation between files; a cancelled copy removes the partial folder.
 /// 
 /// # Frontend Usage
 /// ```typescript
@@ -2709,7 +6088,7 @@ fn visit_dirs(dir: &Path, old_pattern: &str, new_pattern: &str) -> Result<(), St
 /// console.log(result); // "Template copied successfully to: /path/to/project"
 /// ```
 #[tauri::command]
-pub async fn copy_project_template(project_number: String, project_short_name: String, app_handle: AppHandle) -> Result<String, String> {
+pub async fn copy_project_template(project_number: String, project_short_name: String, state: State<'_, AppState>, app_handle: AppHandle) -> Result<String, String> {
     info!("Copying project template for number: {}, short name: {}", project_number, project_short_name);
     
     // Get project folder path from settings
@@ -2718,27 +6097,46 @@ pub async fn copy_project_template(project_number: String, project_short_name: S
     let base_path = settings.project_folder_path
         .ok_or_else(|| "PROJECT_FOLDER_PATH not configured in settings".to_string())?;
     
-    // Normalize paths for cross-platform compatibility
-    let base_path_buf = PathBuf::from(&base_path);
-    let template_path = base_path_buf.join("01 RFPs").join("_yy-cccnn Project Name");
-    let dest_folder_name = format!("{} {}", project_number, project_short_name);
-    let dest_path = base_path_buf.join("01 RFPs").join(&dest_folder_name);
+    // Use the project's template override if it has one
+    let manager_clone = {
+        let manager = state.lock().map_err(|e| e.to_string())?;
+        manager.clone()
+    };
+    let template_override = match manager_clone.get_project_by_number(&project_number).await {
+        Ok(project) => project.and_then(|p| p.template_override),
+        Err(e) => {
+            warn!("Could not look up template override for {}, using default template: {}", project_number, e);
+            None
+        }
+    };
+    
+    let template_path = project_template_path(&base_path, template_override.as_deref())?;
+    let dest_path = rfp_project_folder(&base_path, &project_number, &project_short_name)?;
     
     info!("Template path: {:?}", template_path);
     info!("Destination path: {:?}", dest_path);
     
-    // Check if template folder exists
-    if !template_path.exists() {
+    // Check the template is complete before copying anything
+    let structure = template_structure(&template_path);
+    if !structure.template_found {
         return Err(format!("Template folder not found: {:?}", template_path));
     }
+    if !structure.valid {
+        return Err(format!("Template folder {:?} is incomplete, missing: {}", template_path, structure.missing.join(", ")));
+    }
     
     // Check if destination already exists
     if dest_path.exists() {
         return Err(format!("Destination folder already exists: {:?}", dest_path));
     }
     
-    // Copy template folder using cross-platform approach
-    copy_folder_recursive(&template_path, &dest_path)?;
+    // Copy the template file by file, removing the partial folder if the copy
+    // fails or is cancelled
+    let operation = manager_clone.start_operation(&format!("Copy template for {}", project_number));
+    if let Err(e) = copy_folder_recursive(&template_path, &dest_path, &operation.token) {
+        let _ = fs::remove_dir_all(&dest_path);
+        return Err(e);
+    }
     
     info!("Successfully copied template folder");
     
@@ -2825,6 +6223,9 @@ pub async fn copy_project_template(project_number: String, project_short_name: S
 pub async fn populate_project_data(fp_id: String, project_number: String, project_short_name: String, state: State<'_, AppState>, app_handle: AppHandle) -> Result<String, String> {
     info!("Populating project data for FP: {}, Project: {} {}", fp_id, project_number, project_short_name);
     
+    validate_folder_component(&project_number, "project number")?;
+    validate_folder_component(&project_short_name, "project short name")?;
+    
     // Fetch FP record data from database
     info!("Fetching FP record data for ID: {}", fp_id);
     let manager_clone = {
@@ -2836,52 +6237,11 @@ pub async fn populate_project_data(fp_id: String, project_number: String, projec
     let fps = manager_clone.get_fees().await
         .map_err(|e| format!("Failed to fetch FPs: {}", e))?;
     
-    // Find the specific FP
+    // The database stores "fee:⟨22_96601_1⟩" while the frontend may send
+    // "fee:22_96601_1" or the bare key
     info!("Looking for FP ID: {}", fp_id);
-    info!("Available FP IDs in database:");
-    for (i, f) in fps.iter().enumerate().take(5) {
-        if let Some(id) = &f.id {
-            let full_format = id.to_string();
-            let id_value = id.id.to_string();
-            let without_brackets = format!("{}:{}", id.tb, id_value);
-            info!("  FP {}: Full DB format = '{}'", i, full_format);
-            info!("  FP {}: Without brackets = '{}'", i, without_brackets);
-        }
-    }
-    
     let fp = fps.iter()
-        .find(|f| {
-            if let Some(id) = &f.id {
-                // The database stores: "fee:⟨22_96601_1⟩" (with angle brackets)
-                // The frontend sends: "fee:22_96601_1" (without angle brackets)
-                // We need to handle both formats
-                
-                let id_string = id.to_string(); // This gives us the full "fee:⟨22_96601_1⟩" format
-                let id_value = id.id.to_string(); // This gives us just the ID part
-                let formatted_without_brackets = format!("{}:{}", id.tb, id_value);
-                
-                info!("Comparing FP ID:");
-                info!("  Thing.to_string() = '{}'", id_string);
-                info!("  Thing.tb = '{}'", id.tb);
-                info!("  Thing.id = '{}'", id_value);
-                info!("  Formatted without brackets = '{}'", formatted_without_brackets);
-                info!("  Target from frontend = '{}'", fp_id);
-                
-                // Try both formats: with brackets (database format) and without brackets (frontend format)
-                let matches = id_string == fp_id || formatted_without_brackets == fp_id;
-                
-                if matches {
-                    info!("✓ Found matching FP!");
-                } else {
-                    info!("✗ No match");
-                }
-                
-                matches
-            } else {
-                info!("FP has no ID");
-                false
-            }
-        })
+        .find(|f| f.id.as_ref().is_some_and(|id| thing_matches(id, &fp_id)))
         .ok_or_else(|| {
             info!("No matching FP found for ID: {}", fp_id);
             format!("FP not found: {}", fp_id)
@@ -2900,6 +6260,90 @@ pub async fn populate_project_data(fp_id: String, project_number: String, projec
     Ok(format!("✅ Project data populated and file renamed: {} | {}", json_result, rename_result))
 }
 
+/// Reject folder name components that could escape the project base folder.
+/// 
+/// `project_number` and `project_short_name` come straight from the frontend
+/// and are joined into filesystem paths, so path separators and the `.` and
+/// `..` segments are not allowed. Dots inside a name (`Villa..Annex`) are fine.
+fn validate_folder_component(value: &str, label: &str) -> Result<(), String> {
+    if value.trim().is_empty() {
+        return Err(format!("Invalid {}: must not be empty", label));
+    }
+    if value.contains('/') || value.contains('\\') || value.contains('\0') || matches!(value.trim(), "." | "..") {
+        return Err(format!("Invalid {}: '{}' must not be '.' or '..' or contain path separators", label, value));
+    }
+    Ok(())
+}
+
+/// The `<number> <short name>` folder name for a project, after validating both parts.
+/// 
+/// SurrealDB brackets are stripped from the number (`⟨25-97105⟩` → `25-97105`)
+/// and whitespace in the short name is trimmed and collapsed, so the name
+/// matches what was stored on the project.
+fn project_folder_name(project_number: &str, project_short_name: &str) -> Result<String, String> {
+    let number = record_key(project_number, "projects");
+    let short_name = crate::db::utils::normalize_text(project_short_name);
+    
+    validate_folder_component(number, "project number")?;
+    validate_folder_component(&short_name, "project short name")?;
+    Ok(format!("{} {}", number, short_name))
+}
+
+/// Build `<base>/01 RFPs/<number> <short name>` using `project_folder_name`.
+/// 
+/// # Returns
+/// - `Ok(PathBuf)`: Project folder path, guaranteed to sit under `base_path`
+/// - `Err(String)`: Invalid project number or short name
+fn rfp_project_folder(base_path: &str, project_number: &str, project_short_name: &str) -> Result<PathBuf, String> {
+    safe_join(
+        Path::new(base_path),
+        &["01 RFPs", &project_folder_name(project_number, project_short_name)?],
+    )
+}
+
+/// Preview the folder name a new project will get.
+/// 
+/// Shown on the New Project form. Applies the same bracket stripping and
+/// whitespace normalization as the template copy commands, so the preview
+/// matches the folder that is actually created.
+/// 
+/// # Parameters
+/// - `number_id`: Project number, e.g. "25-97105"
+/// - `name_short`: Project short name
+/// 
+/// # Returns
+/// - `Ok(String)`: Folder name, e.g. "25-97105 Hotel ABC"
+/// - `Err(String)`: Empty number or short name, or path separators in either
+/// 
+/// # Frontend Usage
+/// ```typescript
+/// const folder = await invoke('preview_project_folder_name', { numberId: '25-97105', nameShort: 'Hotel ABC' });
+/// ```
+#[tauri::command]
+pub async fn preview_project_folder_name(number_id: String, name_short: String) -> Result<String, String> {
+    project_folder_name(&number_id, &name_short)
+}
+
+/// Resolve the template folder to copy for a project.
+/// 
+/// Uses `<base>/01 RFPs/<template_override>` when the project has an override
+/// and the default `_yy-cccnn Project Name` template otherwise.
+/// 
+/// # Returns
+/// - `Ok(PathBuf)`: Template folder path, guaranteed to sit under `base_path`
+/// - `Err(String)`: Invalid template override
+fn project_template_path(base_path: &str, template_override: Option<&str>) -> Result<PathBuf, String> {
+    let template = match template_override.map(str::trim).filter(|t| !t.is_empty()) {
+        Some(template) => {
+            validate_folder_component(template, "template override")?;
+            template
+        }
+        None => "_yy-cccnn Project Name",
+    };
+    
+    safe_join(Path::new(base_path), &["01 RFPs", template])
+}
+
 /// Update the project JSON file with real FP record data.
 /// 
 /// This function reads the JSON template file, replaces placeholder values with
@@ -2924,10 +6368,7 @@ async fn update_project_json_file(project_number: &str, project_short_name: &str
         .ok_or_else(|| "PROJECT_FOLDER_PATH not configured in settings".to_string())?;
     
     // Construct JSON file path
-    let base_path_buf = PathBuf::from(&base_path);
-    let json_file_path = base_path_buf
-        .join("01 RFPs")
-        .join(format!("{} {}", project_number, project_short_name))
+    let json_file_path = rfp_project_folder(&base_path, project_number, project_short_name)?
         .join("02 Proposal")
         .join(format!("{}-var Default Values.json", project_number));
     
@@ -2992,9 +6433,7 @@ pub async fn check_project_folder_exists(project_number: String, project_short_n
     let base_path = settings.project_folder_path
         .ok_or_else(|| "PROJECT_FOLDER_PATH not configured in settings".to_string())?;
     
-    let base_path_buf = PathBuf::from(&base_path);
-    let dest_folder_name = format!("{} {}", project_number, project_short_name);
-    let dest_path = base_path_buf.join("01 RFPs").join(&dest_folder_name);
+    let dest_path = rfp_project_folder(&base_path, &project_number, &project_short_name)?;
     
     let exists = dest_path.exists();
     info!("Project folder '{}' exists: {}", dest_path.display(), exists);
@@ -3013,10 +6452,7 @@ pub async fn check_var_json_exists(project_number: String, project_short_name: S
     let base_path = settings.project_folder_path
         .ok_or_else(|| "PROJECT_FOLDER_PATH not configured in settings".to_string())?;
     
-    let base_path_buf = PathBuf::from(&base_path);
-    let project_dir = base_path_buf
-        .join("01 RFPs")
-        .join(format!("{} {}", project_number, project_short_name))
+    let project_dir = rfp_project_folder(&base_path, &project_number, &project_short_name)?
         .join("02 Proposal");
     let json_file_path = project_dir.join(format!("{}-var.json", project_number));
     
@@ -3037,10 +6473,7 @@ pub async fn check_var_json_template_exists(project_number: String, project_shor
     let base_path = settings.project_folder_path
         .ok_or_else(|| "PROJECT_FOLDER_PATH not configured in settings".to_string())?;
     
-    let base_path_buf = PathBuf::from(&base_path);
-    let project_dir = base_path_buf
-        .join("01 RFPs")
-        .join(format!("{} {}", project_number, project_short_name))
+    let project_dir = rfp_project_folder(&base_path, &project_number, &project_short_name)?
         .join("02 Proposal");
     let json_template_path = project_dir.join(format!("{}-var Default Values.json", project_number));
     
@@ -3050,6 +6483,76 @@ pub async fn check_var_json_template_exists(project_number: String, project_shor
     Ok(exists)
 }
 
+/// Check that the project's `02 Proposal` folder can be written to.
+/// 
+/// Shares may leave this folder read-only, in which case `write_fee_to_json`
+/// fails late. This performs a temporary write so the UI can warn up front.
+/// The project folder is looked up in the status folder for `project_status`.
+#[tauri::command]
+pub async fn check_proposal_folder_writable(project_number: String, project_short_name: String, project_status: String, app_handle: AppHandle) -> Result<bool, String> {
+    info!("Checking if proposal folder is writable for: {} {} ({})", project_number, project_short_name, project_status);
+    
+    let settings = get_settings(app_handle).await.map_err(|e| format!("Failed to get settings: {}", e))?;
+    
+    let base_path = settings.project_folder_path
+        .ok_or_else(|| "PROJECT_FOLDER_PATH not configured in settings".to_string())?;
+    
+    let proposal_dir = proposal_folder(&base_path, &project_number, &project_short_name, &project_status)?;
+    
+    let writable = is_dir_writable(&proposal_dir)?;
+    info!("Proposal folder '{}' writable: {}", proposal_dir.display(), writable);
+    
+    Ok(writable)
+}
+
+/// Build `<base>/<status folder>/<number> <short name>/02 Proposal`.
+/// 
+/// # Returns
+/// - `Ok(PathBuf)`: Proposal folder path, guaranteed to sit under `base_path`
+/// - `Err(String)`: Unknown status, or invalid project number or short name
+fn proposal_folder(base_path: &str, project_number: &str, project_short_name: &str, status: &str) -> Result<PathBuf, String> {
+    safe_join(
+        Path::new(base_path),
+        &[
+            folder_management::get_folder_for_status(status)?,
+            &project_folder_name(project_number, project_short_name)?,
+            "02 Proposal",
+        ],
+    )
+}
+
+/// Attempt to create and remove a temporary file in `dir`.
+/// 
+/// Returns an error if the directory does not exist, and `Ok(false)` if it
+/// exists but the temporary file cannot be written.
+fn is_dir_writable(dir: &Path) -> Result<bool, String> {
+    use std::io::Write;
+    
+    if !dir.is_dir() {
+        return Err(format!("Folder not found: {}", dir.display()));
+    }
+    
+    let probe_path = dir.join(format!(".e-fees-write-test-{}", uuid::Uuid::new_v4()));
+    let result = fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&probe_path)
+        .and_then(|mut file| file.write_all(b"write test"));
+    
+    match result {
+        Ok(_) => {
+            if let Err(e) = fs::remove_file(&probe_path) {
+                warn!("Failed to remove write test file {:?}: {}", probe_path, e);
+            }
+            Ok(true)
+        }
+        Err(e) => {
+            warn!("Folder {:?} is not writable: {}", dir, e);
+            Ok(false)
+        }
+    }
+}
+
 /// Rename an existing folder with _old suffix
 #[tauri::command]
 pub async fn rename_folder_with_old_suffix(project_number: String, project_short_name: String, app_handle: AppHandle) -> Result<String, String> {
@@ -3059,20 +6562,28 @@ pub async fn rename_folder_with_old_suffix(project_number: String, project_short
     let base_path = settings.project_folder_path
         .ok_or_else(|| "PROJECT_FOLDER_PATH not configured in settings".to_string())?;
     
-    let base_path_buf = PathBuf::from(&base_path);
-    let original_folder_name = format!("{} {}", project_number, project_short_name);
-    let original_path = base_path_buf.join("01 RFPs").join(&original_folder_name);
+    rename_folder_with_old_suffix_in(&base_path, &project_number, &project_short_name)
+}
+
+/// Rename a project's RFP folder to `<folder name>_old` (or `_old2`, ...)
+/// under an explicit base path, returning the result message
+fn rename_folder_with_old_suffix_in(base_path: &str, project_number: &str, project_short_name: &str) -> Result<String, String> {
+    let original_path = rfp_project_folder(base_path, project_number, project_short_name)?;
     
     if !original_path.exists() {
         return Err("Original folder does not exist".to_string());
     }
+    let original_folder_name = original_path.file_name()
+        .ok_or("Could not get folder name")?
+        .to_string_lossy()
+        .to_string();
     
     // Create a unique _old folder name with timestamp if needed
     let mut old_suffix = "_old".to_string();
     let mut counter = 1;
     loop {
         let old_folder_name = format!("{}{}", original_folder_name, old_suffix);
-        let old_path = base_path_buf.join("01 RFPs").join(&old_folder_name);
+        let old_path = safe_join(Path::new(base_path), &["01 RFPs", &old_folder_name])?;
         
         if !old_path.exists() {
             // Rename the folder
@@ -3098,10 +6609,7 @@ pub async fn rename_var_json_with_old_suffix(project_number: String, project_sho
     let base_path = settings.project_folder_path
         .ok_or_else(|| "PROJECT_FOLDER_PATH not configured in settings".to_string())?;
     
-    let base_path_buf = PathBuf::from(&base_path);
-    let project_dir = base_path_buf
-        .join("01 RFPs")
-        .join(format!("{} {}", project_number, project_short_name))
+    let project_dir = rfp_project_folder(&base_path, &project_number, &project_short_name)?
         .join("02 Proposal");
     
     let original_json_name = format!("{}-var.json", project_number);
@@ -3155,10 +6663,7 @@ async fn rename_json_file(project_number: &str, project_short_name: &str, app_ha
         .ok_or_else(|| "PROJECT_FOLDER_PATH not configured in settings".to_string())?;
     
     // Construct file paths
-    let base_path_buf = PathBuf::from(&base_path);
-    let project_folder = base_path_buf
-        .join("01 RFPs")
-        .join(format!("{} {}", project_number, project_short_name))
+    let project_folder = rfp_project_folder(&base_path, project_number, project_short_name)?
         .join("02 Proposal");
     
     let old_file_path = project_folder.join(format!("{}-var Default Values.json", project_number));
@@ -3187,67 +6692,68 @@ async fn rename_json_file(project_number: &str, project_short_name: &str, app_ha
 /// 
 /// # Returns
 /// - `Ok(String)`: Formatted date (e.g., "01 Dec 2025")
-/// Cross-platform recursive folder copying function.
+/// Recursively copy a folder, checking `token` between entries.
 /// 
-/// This function provides robust folder copying that works on both Mac and Windows
-/// by using native system commands that handle permissions, symlinks, and special
-/// files correctly.
+/// Files are copied one at a time with `fs::copy`, so cancelling stops the
+/// copy at a file boundary. A failed or cancelled copy leaves the entries
+/// already copied in `dest`; callers remove the partial folder.
 /// 
 /// # Parameters
 /// - `src`: Source folder path
-/// - `dest`: Destination folder path
+/// - `dest`: Destination folder path, created if missing
+/// - `token`: Cancellation token of the operation doing the copy
 /// 
 /// # Returns
 /// - `Ok(())`: Folder copied successfully
-/// - `Err(String)`: Copy operation failed
-/// 
-/// # Platform Commands
-/// - **macOS/Linux**: `cp -R source dest`
-/// - **Windows**: `xcopy source dest /E /I /Q /Y`
-fn copy_folder_recursive(src: &Path, dest: &Path) -> Result<(), String> {
-    use std::process::Command;
-    
+/// - `Err(String)`: Copy failed or was cancelled
+fn copy_folder_recursive(src: &Path, dest: &Path, token: &CancellationToken) -> Result<(), String> {
     info!("Copying folder from {:?} to {:?}", src, dest);
     
-    let result = if cfg!(target_os = "windows") {
-        // Windows: Use xcopy for robust copying
-        Command::new("xcopy")
-            .args(&[
-                src.to_string_lossy().as_ref(),
-                dest.to_string_lossy().as_ref(),
-                "/E",  // Copy directories and subdirectories, including empty ones
-                "/I",  // If destination does not exist and copying more than one file, assume destination is a directory
-                "/Q",  // Don't display file names while copying
-                "/Y"   // Overwrite existing files without prompting
-            ])
-            .output()
-    } else {
-        // macOS/Linux: Use cp for recursive copying
-        Command::new("cp")
-            .args(&[
-                "-R",  // Recursive copy
-                src.to_string_lossy().as_ref(),
-                dest.to_string_lossy().as_ref()
-            ])
-            .output()
-    };
+    if !src.is_dir() {
+        return Err(format!("Source folder not found: {}", src.display()));
+    }
     
-    match result {
-        Ok(output) => {
-            if output.status.success() {
-                info!("Successfully copied folder using system command");
-                Ok(())
-            } else {
-                let stderr = String::from_utf8_lossy(&output.stderr);
-                error!("Folder copy command failed: {}", stderr);
-                Err(format!("Failed to copy folder: {}", stderr))
-            }
+    match copy_dir_recursively(src, dest, token) {
+        Ok(()) => {
+            info!("Successfully copied folder");
+            Ok(())
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {
+            info!("Folder copy to {:?} cancelled", dest);
+            Err(format!("Cancelled while copying {}", src.display()))
         }
         Err(e) => {
-            error!("Failed to execute copy command: {}", e);
-            Err(format!("Failed to execute copy command: {}", e))
+            error!("Folder copy failed: {}", e);
+            Err(format!("Failed to copy folder: {}", e))
+        }
+    }
+}
+
+/// Recursively copy a directory and its contents, checking for cancellation
+/// between entries
+fn copy_dir_recursively(src: &Path, dest: &Path, token: &CancellationToken) -> Result<(), std::io::Error> {
+    if !src.exists() {
+        return Ok(());
+    }
+    
+    fs::create_dir_all(dest)?;
+    
+    for entry in fs::read_dir(src)? {
+        if token.is_cancelled() {
+            return Err(std::io::Error::new(std::io::ErrorKind::Interrupted, "Operation cancelled"));
+        }
+        let entry = entry?;
+        let src_path = entry.path();
+        let dest_path = dest.join(entry.file_name());
+        
+        if src_path.is_dir() {
+            copy_dir_recursively(&src_path, &dest_path, token)?;
+        } else {
+            fs::copy(&src_path, &dest_path)?;
         }
     }
+    
+    Ok(())
 }
 
 /// Cross-platform file renaming function with improved error handling.
@@ -3348,6 +6854,183 @@ fn visit_dirs_cross_platform(dir: &Path, old_pattern: &str, new_pattern: &str) -
     Ok(())
 }
 
+/// Set or clear the template folder used for a project's template copies.
+/// 
+/// Some projects need a non-standard template (e.g. a different service
+/// line). The override names a folder in `01 RFPs` that
+/// `copy_project_template` and `create_project_with_template` copy instead of
+/// `_yy-cccnn Project Name`.
+/// 
+/// # Parameters
+/// - `project_id`: The string ID of the project (e.g. "25_97105")
+/// - `template_override`: Template folder name, or `null`/empty to use the default
+/// 
+/// # Returns
+/// - `Ok(Project)`: Updated project
+/// - `Err(String)`: Invalid folder name, project not found or database error
+/// 
+/// # Frontend Usage
+/// ```typescript
+/// await invoke('set_project_template_override', {
+///   projectId: '25_97105',
+///   templateOverride: '_yy-cccnn Project Name (Daylight)'
+/// });
+/// ```
+#[tauri::command]
+pub async fn set_project_template_override(project_id: String, template_override: Option<String>, state: State<'_, AppState>) -> Result<Project, String> {
+    let template_override = template_override
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty());
+    if let Some(template) = &template_override {
+        validate_folder_component(template, "template override")?;
+    }
+    
+    let project_name = format!("project '{}'", project_id);
+    execute_with_manager(
+        &state,
+        |manager| {
+            let id_clone = project_id.clone();
+            Box::pin(async move {
+                manager.set_project_template_override(&id_clone, template_override).await
+            })
+        },
+        "update",
+        &project_name
+    ).await
+}
+
+/// Get the template folder override of a project.
+/// 
+/// # Returns
+/// - `Ok(Some(String))`: Template folder name in `01 RFPs`
+/// - `Ok(None)`: Project uses the default template
+/// - `Err(String)`: Database error
+/// 
+/// # Frontend Usage
+/// ```typescript
+/// const template = await invoke('get_project_template_override', { projectId: '25_97105' });
+/// ```
+#[tauri::command]
+pub async fn get_project_template_override(project_id: String, state: State<'_, AppState>) -> Result<Option<String>, String> {
+    let project_name = format!("project '{}'", project_id);
+    execute_with_manager(
+        &state,
+        |manager| {
+            let id_clone = project_id.clone();
+            Box::pin(async move {
+                manager.get_project_template_override(&id_clone).await
+            })
+        },
+        "fetch",
+        &project_name
+    ).await
+}
+
+/// Count the template files and folders that would be renamed on copy.
+/// 
+/// Walks the RFP template folder (`01 RFPs/_yy-cccnn Project Name`) and counts
+/// every entry whose name contains the `yy-cccnn` placeholder, giving users a
+/// sanity check of the template before a bulk operation. The template folder
+/// itself is not counted.
+/// 
+/// # Returns
+/// - `Ok(u64)`: Number of files and folders containing the placeholder
+/// - `Err(String)`: Settings missing or template folder not readable
+/// 
+/// # Frontend Usage
+/// ```typescript
+/// const count = await invoke('count_template_placeholder_files');
+/// console.log(`${count} template files will be renamed`);
+/// ```
+#[tauri::command]
+pub async fn count_template_placeholder_files(app_handle: AppHandle) -> Result<u64, String> {
+    let settings = get_settings(app_handle).await.map_err(|e| format!("Failed to get settings: {}", e))?;
+    let base_path = settings.project_folder_path
+        .ok_or_else(|| "PROJECT_FOLDER_PATH not configured in settings".to_string())?;
+    
+    let template_path = PathBuf::from(&base_path).join("01 RFPs").join("_yy-cccnn Project Name");
+    if !template_path.is_dir() {
+        return Err(format!("Template folder not found: {:?}", template_path));
+    }
+    
+    let count = count_placeholder_entries(&template_path, "yy-cccnn")?;
+    info!("Template {:?} has {} entries containing the placeholder", template_path, count);
+    Ok(count)
+}
+
+/// Recursively count entries below `dir` whose name contains `pattern`.
+fn count_placeholder_entries(dir: &Path, pattern: &str) -> Result<u64, String> {
+    let entries = fs::read_dir(dir)
+        .map_err(|e| format!("Failed to read directory {:?}: {}", dir, e))?;
+    
+    let mut count = 0;
+    for entry in entries {
+        let entry = entry.map_err(|e| format!("Failed to read entry: {}", e))?;
+        let path = entry.path();
+        
+        if path.is_dir() {
+            count += count_placeholder_entries(&path, pattern)?;
+        }
+        
+        if entry.file_name().to_string_lossy().contains(pattern) {
+            count += 1;
+        }
+    }
+    
+    Ok(count)
+}
+
+/// Entries every project template must contain, relative to the template folder.
+const TEMPLATE_REQUIRED_ENTRIES: &[&str] = &[
+    "02 Proposal",
+    "02 Proposal/yy-cccnn-var Default Values.json",
+];
+
+/// Check the RFP template folder has the structure `copy_project_template` needs.
+/// 
+/// Looks for `01 RFPs/_yy-cccnn Project Name` with its `02 Proposal` folder
+/// and the `yy-cccnn-var Default Values.json` placeholder file, reporting
+/// each as present or missing rather than failing on the first problem.
+/// 
+/// # Returns
+/// - `Ok(TemplateStructureReport)`: Present and missing entries, relative to the template folder
+/// - `Err(String)`: Settings missing
+/// 
+/// # Frontend Usage
+/// ```typescript
+/// const report = await invoke('validate_template_structure');
+/// if (!report.valid) showWarning(`Template is missing: ${report.missing.join(', ')}`);
+/// ```
+#[tauri::command]
+pub async fn validate_template_structure(app_handle: AppHandle) -> Result<TemplateStructureReport, String> {
+    let settings = get_settings(app_handle).await.map_err(|e| format!("Failed to get settings: {}", e))?;
+    let base_path = settings.project_folder_path
+        .ok_or_else(|| "PROJECT_FOLDER_PATH not configured in settings".to_string())?;
+    
+    let report = template_structure(&project_template_path(&base_path, None)?);
+    if !report.valid {
+        warn!("Template {} is missing: {}", report.template_path, report.missing.join(", "));
+    }
+    Ok(report)
+}
+
+/// Report which `TEMPLATE_REQUIRED_ENTRIES` exist under `template_path`.
+fn template_structure(template_path: &Path) -> TemplateStructureReport {
+    let template_found = template_path.is_dir();
+    let (present, missing): (Vec<String>, Vec<String>) = TEMPLATE_REQUIRED_ENTRIES
+        .iter()
+        .map(|entry| entry.to_string())
+        .partition(|entry| template_found && template_path.join(entry).exists());
+    
+    TemplateStructureReport {
+        template_path: template_path.display().to_string(),
+        template_found,
+        valid: template_found && missing.is_empty(),
+        present,
+        missing,
+    }
+}
+
 // ============================================================================
 // LOCATION SUGGESTION COMMANDS
 // ============================================================================
@@ -3487,6 +7170,78 @@ pub async fn get_city_suggestions(country: String, state: State<'_, AppState>) -
     }
 }
 
+/// Get projects in a city, optionally narrowed to one area.
+/// 
+/// Backs the geographic breakdown in market analysis. All inputs are passed
+/// as bound query parameters.
+/// 
+/// # Parameters
+/// - `country`: Country name (e.g., "United Arab Emirates")
+/// - `city`: City name (e.g., "Dubai")
+/// - `area`: Optional area (e.g., "Marina"); omit for every area in the city
+/// 
+/// # Returns
+/// - `Ok(Vec<Project>)`: Matching projects ordered by area and number
+/// - `Err(String)`: Query error or database failure
+/// 
+/// # Frontend Usage
+/// ```typescript
+/// const projects = await invoke('get_projects_by_area', {
+///   country: 'United Arab Emirates', city: 'Dubai', area: 'Marina'
+/// });
+/// ```
+#[tauri::command]
+pub async fn get_projects_by_area(country: String, city: String, area: Option<String>, state: State<'_, AppState>) -> Result<Vec<Project>, String> {
+    execute_with_manager(
+        &state,
+        |manager| Box::pin(async move {
+            manager.get_projects_by_area(&country, &city, area.as_deref()).await
+        }),
+        "fetch",
+        "projects by area"
+    ).await
+}
+
+/// Get the distinct activities used across fee proposals.
+/// 
+/// # Returns
+/// - `Ok(Vec<String>)`: Unique non-empty activities, alphabetically
+/// - `Err(String)`: Query error or database failure
+/// 
+/// # Frontend Usage
+/// ```typescript
+/// const activities = await invoke('get_distinct_activities');
+/// // Returns: ["Design and Consultancy", "Supervision", ...]
+/// ```
+crud_command!(
+    get_distinct_activities,
+    Vec<String>,
+    get_distinct_activities,
+    "fetch",
+    "distinct activities"
+);
+
+/// Get the distinct packages used across fee proposals.
+/// 
+/// Packages are free text, so this complements the fixed activity list
+/// with the values actually in use.
+/// 
+/// # Returns
+/// - `Ok(Vec<String>)`: Unique non-empty packages, alphabetically
+/// - `Err(String)`: Query error or database failure
+/// 
+/// # Frontend Usage
+/// ```typescript
+/// const packages = await invoke('get_distinct_packages');
+/// ```
+crud_command!(
+    get_distinct_packages,
+    Vec<String>,
+    get_distinct_packages,
+    "fetch",
+    "distinct packages"
+);
+
 /// Frontend logging command for unified logging across frontend and backend.
 /// 
 /// This command allows the Svelte frontend to send log messages to the Rust
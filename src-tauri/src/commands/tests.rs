@@ -0,0 +1,1319 @@
+//! # Command Module Unit Tests
+//!
+//! Tests for the pure helper logic behind Tauri commands. Commands themselves
+//! need a live database and app handle, so these tests exercise the functions
+//! they delegate to.
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::*;
+    use crate::db::{CancellationToken, Company, Contact, ContactCreate, Fee, Project, ProjectNumber, Revision, StatsCache, StatsCounts, TimeStamps};
+    use surrealdb::sql::Thing;
+
+    // ============================================================================
+    // FEE COMPARISON TESTS
+    // ============================================================================
+
+    #[test]
+    fn test_diff_fees_identical() {
+        let fee = sample_fee();
+        assert!(diff_fees(&fee, &fee.clone()).is_empty());
+    }
+
+    #[test]
+    fn test_diff_fees_reports_changed_fields() {
+        let a = sample_fee();
+        let mut b = sample_fee();
+        b.rev = 2;
+        b.status = "Sent".to_string();
+        b.issue_date = "250215".to_string();
+        b.contact_id = Thing::from(("contacts", "jane_doe"));
+        b.time.updated_at = "2025-02-15T00:00:00Z".to_string(); // Ignored
+
+        let diffs = diff_fees(&a, &b);
+        let fields: Vec<&str> = diffs.iter().map(|d| d.field.as_str()).collect();
+        assert_eq!(fields, vec!["rev", "status", "issue_date", "contact_id"]);
+
+        let status = &diffs[1];
+        assert_eq!(status.a_value, serde_json::json!("Draft"));
+        assert_eq!(status.b_value, serde_json::json!("Sent"));
+        assert_eq!(diffs[3].b_value, serde_json::json!("contacts:jane_doe"));
+    }
+
+    #[test]
+    fn test_diff_fees_revisions() {
+        let a = sample_fee();
+        let mut b = sample_fee();
+        b.revisions.push(Revision {
+            revision_number: 2,
+            revision_date: "2025-02-15".to_string(),
+            author_email: "staff@example.com".to_string(),
+            author_name: "Staff".to_string(),
+            notes: "Reduced scope".to_string(),
+        });
+
+        let diffs = diff_fees(&a, &b);
+        assert_eq!(diffs.len(), 1);
+        assert_eq!(diffs[0].field, "revisions");
+    }
+
+    // ============================================================================
+    // FEE TREE TESTS
+    // ============================================================================
+
+    #[test]
+    fn test_build_fee_tree_groups_and_sorts() {
+        let projects = vec![sample_project("25-97106"), sample_project("25-97105")];
+        let fees = vec![
+            fee_for("25_97105", 2),
+            fee_for("25_97106", 1),
+            fee_for("25_97105", 1),
+            fee_for("25_97106", 3),
+            fee_for("25_97106", 2),
+        ];
+
+        let tree = build_fee_tree(projects, fees);
+        assert_eq!(tree.len(), 2);
+        assert_eq!(tree[0].project.number.id, "25-97105");
+        assert_eq!(tree[0].fees.iter().map(|f| f.rev).collect::<Vec<_>>(), vec![1, 2]);
+        assert_eq!(tree[1].project.number.id, "25-97106");
+        assert_eq!(tree[1].fees.iter().map(|f| f.rev).collect::<Vec<_>>(), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_build_fee_tree_project_without_fees() {
+        let tree = build_fee_tree(vec![sample_project("25-97105")], Vec::new());
+        assert_eq!(tree.len(), 1);
+        assert!(tree[0].fees.is_empty());
+    }
+
+    // ============================================================================
+    // LATEST FEE TESTS
+    // ============================================================================
+
+    #[test]
+    fn test_latest_fee_per_project_keeps_highest_rev() {
+        let mut other = fee_for("25_97106", 1);
+        other.number = "25-97106-FP-1".to_string();
+        let fees = vec![fee_for("25_97105", 2), other, fee_for("25_97105", 3), fee_for("25_97105", 1)];
+
+        let latest = latest_fees_per_project(fees);
+        assert_eq!(latest.len(), 2);
+        assert_eq!(latest[0].project_id.to_string(), "projects:25_97105");
+        assert_eq!(latest[0].rev, 3);
+        assert_eq!(latest[1].project_id.to_string(), "projects:25_97106");
+    }
+
+    #[test]
+    fn test_latest_fee_per_project_breaks_ties_by_issue_date() {
+        let mut older = fee_for("25_97105", 1);
+        older.issue_date = "250110".to_string();
+        let mut newer = fee_for("25_97105", 1);
+        newer.issue_date = "250301".to_string();
+
+        let latest = latest_fees_per_project(vec![newer, older]);
+        assert_eq!(latest.len(), 1);
+        assert_eq!(latest[0].issue_date, "250301");
+    }
+
+    #[test]
+    fn test_latest_fee_per_project_matches_escaped_and_bare_links() {
+        use surrealdb::sql::Id;
+
+        // projects:⟨2597105⟩ and projects:2597105 link the same project
+        let mut escaped = fee_for("2597105", 1);
+        escaped.project_id = Thing::from(("projects", Id::String("2597105".to_string())));
+        let mut bare = fee_for("2597105", 2);
+        bare.project_id = Thing::from(("projects", Id::Number(2597105)));
+
+        let latest = latest_fees_per_project(vec![escaped, bare]);
+        assert_eq!(latest.len(), 1);
+        assert_eq!(latest[0].rev, 2);
+    }
+
+    #[test]
+    fn test_latest_fee_amount_uses_newest_revision() {
+        let mut first = fee_for("25_97105", 1);
+        first.amount = Some(90_000.0);
+        let mut second = fee_for("25_97105", 2);
+        second.amount = Some(120_000.0);
+        assert_eq!(latest_fee_amount(vec![second.clone(), first.clone()]), Some(120_000.0));
+
+        second.amount = None;
+        assert_eq!(latest_fee_amount(vec![first, second]), None);
+        assert_eq!(latest_fee_amount(Vec::new()), None);
+    }
+
+    #[test]
+    fn test_fee_json_exports_amount() {
+        let mut fee = sample_fee();
+        let project = sample_project("25-97105");
+        let company = sample_company("CHE");
+        let contact = sample_contact("john_smith");
+        assert_eq!(build_fee_json(&fee, &project, &company, &contact)["31 Fee Amount"], "");
+
+        fee.amount = Some(125_000.5);
+        let json = build_fee_json(&fee, &project, &company, &contact);
+        assert_eq!(json["31 Fee Amount"], "125000.50");
+        assert_eq!(json["32 Fee Currency"], "AED");
+    }
+
+    // ============================================================================
+    // ISSUE DATE TESTS
+    // ============================================================================
+
+    #[test]
+    fn test_parse_issue_date() {
+        assert_eq!(parse_issue_date("250110"), chrono::NaiveDate::from_ymd_opt(2025, 1, 10));
+        assert_eq!(parse_issue_date("991231"), chrono::NaiveDate::from_ymd_opt(1999, 12, 31));
+        assert_eq!(parse_issue_date("251310"), None);
+        assert_eq!(parse_issue_date("2025-01-10"), None);
+    }
+
+    #[test]
+    fn test_fees_issued_after_today() {
+        let today = chrono::NaiveDate::from_ymd_opt(2025, 6, 1).unwrap();
+        let mut past = fee_for("25_97105", 1);
+        past.issue_date = "250110".to_string();
+        let mut future = fee_for("25_97106", 1);
+        future.issue_date = "251006".to_string(); // Transposed 250610
+        let mut invalid = fee_for("25_97107", 1);
+        invalid.issue_date = "TBC".to_string();
+
+        let flagged = fees_issued_after(vec![past, future, invalid], today);
+        assert_eq!(flagged.len(), 1);
+        assert_eq!(flagged[0].issue_date, "251006");
+    }
+
+    #[test]
+    fn test_monthly_report_respects_month_boundaries() {
+        let issued = |project: &str, date: &str, status: &str| {
+            let mut fee = fee_for(project, 1);
+            fee.issue_date = date.to_string();
+            fee.status = status.to_string();
+            fee
+        };
+        let fees = vec![
+            issued("25_97101", "250531", "Sent"),   // Last day of previous month
+            issued("25_97102", "250601", "Sent"),   // First day
+            issued("25_97103", "250615", "Draft"),
+            issued("25_97104", "250630", "Sent"),   // Last day
+            issued("25_97105", "250701", "Sent"),   // First day of next month
+            issued("24_97106", "240615", "Sent"),   // Same month, other year
+            issued("25_97107", "TBC", "Draft"),
+        ];
+
+        let report = monthly_report(fees, 25, 6);
+        assert_eq!(report["year"], 2025);
+        assert_eq!(report["month"], 6);
+        assert_eq!(report["total"], 3);
+        assert_eq!(report["byStatus"], serde_json::json!({ "Draft": 1, "Sent": 2 }));
+        let dates: Vec<&str> = report["fees"].as_array().unwrap()
+            .iter()
+            .map(|f| f["issue_date"].as_str().unwrap())
+            .collect();
+        assert_eq!(dates, vec!["250601", "250615", "250630"]);
+    }
+
+    #[test]
+    fn test_duplicate_fee_numbers_grouped() {
+        let mut original = fee_for("25_97105", 1);
+        original.number = "25-97105-FP-1".to_string();
+        original.name = "Original".to_string();
+        let mut reassigned = fee_for("25_97106", 1);
+        reassigned.number = "25-97105-FP-1 ".to_string();
+        reassigned.name = "Reassigned".to_string();
+        let mut unique = fee_for("25_97107", 1);
+        unique.number = "25-97107-FP-1".to_string();
+        let mut blank = fee_for("25_97108", 1);
+        blank.number = String::new();
+        let mut blank_too = fee_for("25_97109", 1);
+        blank_too.number = String::new();
+
+        let groups = duplicate_fee_numbers(vec![original, unique, reassigned, blank, blank_too]);
+        assert_eq!(groups.len(), 1);
+        let names: Vec<&str> = groups[0].iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, vec!["Original", "Reassigned"]);
+    }
+
+    #[test]
+    fn test_normalize_base_path_trims_trailing_separators() {
+        let base = normalize_base_path("E:\\Projects\\");
+        assert_eq!(base, "E:\\Projects");
+
+        let joined = std::path::Path::new(&base).join("01 RFPs");
+        assert!(!joined.to_string_lossy().contains("\\\\"));
+        #[cfg(windows)]
+        assert_eq!(joined, std::path::PathBuf::from("E:\\Projects\\01 RFPs"));
+        #[cfg(not(windows))]
+        assert_eq!(joined.to_string_lossy(), "E:\\Projects/01 RFPs");
+
+        assert_eq!(normalize_base_path(" /Volumes/base/Projects// "), "/Volumes/base/Projects");
+        assert_eq!(normalize_base_path("E:/Projects\\"), "E:/Projects");
+        assert_eq!(normalize_base_path("E:\\"), "E:\\");
+        assert_eq!(normalize_base_path("/"), "/");
+        assert_eq!(normalize_base_path("  "), "");
+
+        let settings = parse_settings_env("PROJECT_FOLDER_PATH=\"E:\\Projects\\\"\n");
+        assert_eq!(settings.project_folder_path.as_deref(), Some("E:\\Projects"));
+    }
+
+    // ============================================================================
+    // PROJECT NUMBER BATCH TESTS
+    // ============================================================================
+
+    #[test]
+    fn test_project_number_checks_mixed_batch() {
+        let numbers = ["25-97105", "25-97106", "25-971", "2597107", " 25-97107 ", "25-97107"]
+            .map(String::from)
+            .to_vec();
+        let checks = project_number_checks(numbers, &["25-97105".to_string()]);
+
+        let summary: Vec<(&str, bool, bool)> = checks.iter()
+            .map(|check| (check.number.as_str(), check.format_ok, check.available))
+            .collect();
+        assert_eq!(summary, [
+            ("25-97105", true, false),
+            ("25-97106", true, true),
+            ("25-971", false, false),
+            ("2597107", false, false),
+            (" 25-97107 ", true, true),
+            ("25-97107", true, false),
+        ]);
+    }
+
+    // ============================================================================
+    // RECORD LABEL TESTS
+    // ============================================================================
+
+    #[test]
+    fn test_labelled_record_splits_supported_tables() {
+        assert_eq!(labelled_record("projects:⟨25_97105⟩").unwrap(), ("projects", "25_97105".to_string()));
+        assert_eq!(labelled_record("company:CHE").unwrap(), ("company", "CHE".to_string()));
+        assert_eq!(labelled_record(" contacts:john_smith ").unwrap(), ("contacts", "john_smith".to_string()));
+        assert_eq!(labelled_record("fee:25_97105_1").unwrap(), ("fee", "25_97105_1".to_string()));
+        assert!(labelled_record("country:are").is_err());
+        assert!(labelled_record("25_97105").is_err());
+    }
+
+    #[test]
+    fn test_record_label_per_entity_type() {
+        let project = serde_json::json!({ "name": "Grand Hotel Lighting", "name_short": "Hotel" });
+        assert_eq!(record_label("projects", &project).as_deref(), Some("Grand Hotel Lighting"));
+
+        let company = serde_json::json!({ "name": "Chedi Hospitality", "name_short": "CHE" });
+        assert_eq!(record_label("company", &company).as_deref(), Some("Chedi Hospitality"));
+
+        let contact = serde_json::json!({ "full_name": "John Smith", "first_name": "John", "last_name": "Smith" });
+        assert_eq!(record_label("contacts", &contact).as_deref(), Some("John Smith"));
+        let contact = serde_json::json!({ "first_name": "John", "last_name": "Smith" });
+        assert_eq!(record_label("contacts", &contact).as_deref(), Some("John Smith"));
+        assert_eq!(record_label("contacts", &serde_json::json!({ "email": "j@example.com" })), None);
+
+        let fee = serde_json::json!({ "name": "Hotel Lighting Design", "number": "25-97105-FP-1" });
+        assert_eq!(record_label("fee", &fee).as_deref(), Some("25-97105-FP-1"));
+        assert_eq!(record_label("fee", &serde_json::json!({ "number": "  " })), None);
+    }
+
+    // ============================================================================
+    // CONTACT ID TESTS
+    // ============================================================================
+
+    #[test]
+    fn test_contact_key_accepts_prefixed_and_bare_ids() {
+        assert_eq!(contact_key("contacts:john_smith"), "john_smith");
+        assert_eq!(contact_key("john_smith"), "john_smith");
+        assert_eq!(contact_key(" contacts:⟨john smith⟩ "), "john smith");
+        assert_eq!(contact_key("⟨john smith⟩"), "john smith");
+    }
+
+    #[test]
+    fn test_validate_contact_create() {
+        let contact = ContactCreate {
+            first_name: "John".to_string(),
+            last_name: "Smith".to_string(),
+            email: "john.smith@hotel.com".to_string(),
+            phone: "+971 50 123 4567".to_string(),
+            position: "Project Manager".to_string(),
+            company: "CHE".to_string(),
+        };
+        assert_eq!(validate_contact_create(&contact), Ok(()));
+
+        let invalid = ContactCreate {
+            first_name: " ".to_string(),
+            email: "john.smith".to_string(),
+            phone: "050 123 4567".to_string(),
+            company: String::new(),
+            ..contact
+        };
+        let err = validate_contact_create(&invalid).unwrap_err();
+        assert!(err.contains("name"));
+        assert!(err.contains("Invalid email 'john.smith'"));
+        assert!(err.contains("Phone"));
+        assert!(err.contains("Company is required"));
+    }
+
+    #[test]
+    fn test_fee_project_mismatches_reports_differing_activity() {
+        let row = serde_json::json!({
+            "fee_activity": "Design and Consultancy",
+            "fee_package": "Lighting",
+            "project_activity": "Supervision",
+            "project_package": " lighting ",
+        });
+
+        assert_eq!(
+            fee_project_mismatches(&row),
+            vec![FeeProjectMismatch {
+                field: "activity".to_string(),
+                fee_value: "Design and Consultancy".to_string(),
+                project_value: "Supervision".to_string(),
+            }]
+        );
+
+        let without_project_values = serde_json::json!({ "fee_activity": "Design and Consultancy", "fee_package": "Lighting" });
+        assert!(fee_project_mismatches(&without_project_values).is_empty());
+    }
+
+    #[test]
+    fn test_proposal_index_orders_by_rev() {
+        let revision = |rev: i32, status: &str, issue_date: &str| Fee {
+            id: Some(Thing::from(("fee", format!("25_97105_{}", rev).as_str()))),
+            number: format!("25-97105-FP-{}", rev),
+            rev,
+            status: status.to_string(),
+            issue_date: issue_date.to_string(),
+            ..sample_fee()
+        };
+        let fees = vec![revision(3, "Sent", "250301"), revision(1, "Lost", "250110"), revision(2, "Cancelled", "250201")];
+
+        let index = proposal_index(fees);
+
+        let revs: Vec<i32> = index.iter().map(|entry| entry.rev).collect();
+        assert_eq!(revs, [1, 2, 3]);
+        assert_eq!(
+            index[2],
+            ProposalIndexEntry {
+                number: "25-97105-FP-3".to_string(),
+                rev: 3,
+                status: "Sent".to_string(),
+                issue_date: "250301".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_fees_csv_quotes_commas_and_keeps_unresolved_fees() {
+        let mut quoted = sample_fee();
+        quoted.number = "25-97105-FP-2".to_string();
+        quoted.name = "Hotel Lighting, Phase 2".to_string();
+        let mut orphan = sample_fee();
+        orphan.number = "25-97199-FP-1".to_string();
+        orphan.project_id = Thing::from(("projects", "25_97199"));
+        orphan.company_id = Thing::from(("company", "GONE"));
+        orphan.contact_id = Thing::from(("contacts", "gone"));
+
+        let projects = vec![sample_project("25-97105")];
+        let companies = vec![sample_company("CHE")];
+        let contacts = vec![sample_contact("john_smith")];
+
+        let mut out = Vec::new();
+        let rows = write_fees_csv(&mut out, vec![orphan, quoted, sample_fee()], &projects, &companies, &contacts).unwrap();
+        let csv = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+
+        assert_eq!(rows, 3);
+        assert_eq!(lines[0], "number,name,status,issue_date,project_name,company_name,contact_name,activity,package");
+        assert_eq!(
+            lines[1],
+            "25-97105-FP-1,Hotel Lighting Design,Draft,250110,Hotel Lighting,CHE Holdings,John Smith,Design and Consultancy,Lighting"
+        );
+        assert!(lines[2].starts_with("25-97105-FP-2,\"Hotel Lighting, Phase 2\",Draft,"));
+        assert_eq!(lines[3], "25-97199-FP-1,Hotel Lighting Design,Draft,250110,,,,Design and Consultancy,Lighting");
+    }
+
+    #[test]
+    fn test_exports_skip_fees_of_archived_projects() {
+        let mut archived = sample_project("25-97106");
+        archived.deleted_at = Some("2025-06-01T00:00:00Z".to_string());
+        let projects = vec![sample_project("25-97105"), archived];
+        let fees = vec![fee_for("25_97105", 1), fee_for("25_97106", 1), fee_for("25_97199", 1)];
+
+        let kept: Vec<Option<Thing>> = without_archived_project_fees(fees, &projects)
+            .into_iter()
+            .map(|fee| fee.id)
+            .collect();
+        assert_eq!(
+            kept,
+            vec![Some(Thing::from(("fee", "25_97105_1"))), Some(Thing::from(("fee", "25_97199_1")))]
+        );
+    }
+
+    #[test]
+    fn test_page_size_is_capped() {
+        assert_eq!(page_size(50), Ok(50));
+        assert_eq!(page_size(500), Ok(500));
+        assert_eq!(page_size(10_000), Ok(500));
+        assert!(page_size(0).is_err());
+    }
+
+    #[test]
+    fn test_suggested_email_domain_flags_outlier() {
+        let contact = |key: &str, email: &str| Contact {
+            email: Some(email.to_string()),
+            ..sample_contact(key)
+        };
+        let contacts = vec![
+            contact("a", "a@example.com"),
+            contact("b", "b@Example.com"),
+            contact("c", "c@example.com"),
+            contact("d", "d@exmaple.com"),
+            Contact { company: Some(Thing::from(("company", "OTHER"))), ..contact("e", "e@exmaple.com") },
+        ];
+
+        assert_eq!(suggested_email_domain(&contacts[3], &contacts), Some("example.com".to_string()));
+        assert_eq!(suggested_email_domain(&contacts[0], &contacts), None);
+        assert_eq!(suggested_email_domain(&contacts[4], &contacts), None);
+    }
+
+    #[test]
+    fn test_project_key_accepts_all_id_forms() {
+        assert_eq!(project_key("projects:25_97105"), "25_97105");
+        assert_eq!(project_key("projects:⟨25_97105⟩"), "25_97105");
+        assert_eq!(project_key("⟨25_97105⟩"), "25_97105");
+        assert_eq!(project_key(" 25_97105 "), "25_97105");
+        assert_eq!(project_key("25-97105"), "25_97105");
+    }
+
+    // ============================================================================
+    // CACHED STATISTICS TESTS
+    // ============================================================================
+
+    #[test]
+    fn test_stats_json_shape() {
+        let counts = StatsCounts { projects: 12, companies: 4, contacts: 9, fees: 20, active_fees: 15 };
+        assert_eq!(
+            stats_json(&counts),
+            serde_json::json!({
+                "totalProjects": 12,
+                "activeFees": 15,
+                "totalCompanies": 4,
+                "totalContacts": 9,
+                "totalFees": 20
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_cached_stats_reuses_value_within_ttl() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let cache = std::sync::Mutex::new(StatsCache::default());
+        let counter = AtomicUsize::new(0);
+        let calls = &counter;
+        let compute = move || async move {
+            let n = calls.fetch_add(1, Ordering::SeqCst) + 1;
+            serde_json::json!({ "totalProjects": n })
+        };
+
+        let first = cached_stats(&cache, 0, false, compute).await.unwrap();
+        let second = cached_stats(&cache, 0, false, compute).await.unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert_eq!(first, second);
+
+        // Forced refreshes and a new connection generation recompute
+        cached_stats(&cache, 0, true, compute).await.unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        cached_stats(&cache, 1, false, compute).await.unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_cached_stats_expires_after_ttl() {
+        let mut stats_cache = StatsCache::default();
+        stats_cache.ttl = std::time::Duration::ZERO;
+        let cache = std::sync::Mutex::new(stats_cache);
+
+        cached_stats(&cache, 0, false, || async { serde_json::json!(1) }).await.unwrap();
+        let second = cached_stats(&cache, 0, false, || async { serde_json::json!(2) }).await.unwrap();
+        assert_eq!(second, serde_json::json!(2));
+    }
+
+    // ============================================================================
+    // EXPORT VALIDATION TESTS
+    // ============================================================================
+
+    #[test]
+    fn test_missing_export_fields_complete() {
+        let fee = sample_fee();
+        let project = sample_project("25-97105");
+        let contact = sample_contact("john_smith");
+        assert!(missing_export_fields(&fee, Some(&project), Some(&contact)).is_empty());
+    }
+
+    #[test]
+    fn test_missing_export_fields_staff_email() {
+        let mut fee = sample_fee();
+        fee.staff_email = "  ".to_string();
+        let project = sample_project("25-97105");
+        let contact = sample_contact("john_smith");
+
+        assert_eq!(missing_export_fields(&fee, Some(&project), Some(&contact)), vec!["staff_email"]);
+        assert_eq!(
+            missing_export_fields(&fee, None, None),
+            vec!["staff_email", "project_name", "contact_name"]
+        );
+    }
+
+    #[test]
+    fn test_fee_json_mapping_covers_every_exported_key() {
+        let json = build_fee_json(
+            &sample_fee(),
+            &sample_project("25-97105"),
+            &sample_company("CHE"),
+            &sample_contact("john_smith"),
+        );
+        let exported: Vec<&String> = json.as_object().unwrap().keys().collect();
+        let mapping = fee_json_mapping();
+        let mapped: Vec<&String> = mapping.iter().map(|field| &field.key).collect();
+
+        for key in &exported {
+            assert!(mapped.contains(key), "no mapping entry for '{}'", key);
+        }
+        assert_eq!(exported.len(), mapped.len());
+        assert!(mapping.iter().all(|field| !field.source.is_empty()));
+    }
+
+    #[test]
+    fn test_export_active_fees_skips_cancelled_and_missing_folders() {
+        let base = temp_base("export");
+        let projects: Vec<Project> = ["25-97105", "25-97106", "25-97107", "25-97108"].iter().map(|n| sample_project(n)).collect();
+        let proposal = |number: &str| base.join("01 RFPs").join(format!("{} Hotel", number)).join("02 Proposal");
+        for number in ["25-97105", "25-97106", "25-97107"] {
+            std::fs::create_dir_all(proposal(number)).unwrap();
+        }
+
+        let mut cancelled = fee_for("25_97107", 1);
+        cancelled.status = "Cancelled".to_string();
+        let fees = vec![fee_for("25_97105", 1), fee_for("25_97106", 1), cancelled, fee_for("25_97108", 1)];
+
+        let batch = export_active_fees(
+            base.to_str().unwrap(),
+            &fees,
+            &projects,
+            &[sample_company("CHE")],
+            &[sample_contact("john_smith")],
+            false,
+            &CancellationToken::default(),
+        );
+
+        assert_eq!((batch.exported, batch.skipped, batch.failed), (2, 1, 0));
+        assert_eq!(batch.results.len(), 3);
+        assert!(batch.results.iter().all(|r| r.fee_id != "25_97107_1"));
+        assert!(batch.results[2].skipped);
+        assert!(batch.results[2].message.contains("Project folder not found"));
+
+        assert!(proposal("25-97105").join("25-97105-var.json").exists());
+        assert!(proposal("25-97106").join("25-97106-var.json").exists());
+        assert!(!proposal("25-97107").join("25-97107-var.json").exists());
+
+        // Existing exports are kept unless overwrite is requested
+        let rerun = |overwrite| export_active_fees(base.to_str().unwrap(), &fees[..1], &projects, &[sample_company("CHE")], &[sample_contact("john_smith")], overwrite, &CancellationToken::default());
+        assert_eq!((rerun(false).exported, rerun(false).skipped), (0, 1));
+        assert_eq!(rerun(true).exported, 1);
+
+        std::fs::remove_dir_all(&base).unwrap();
+    }
+
+    // ============================================================================
+    // PROJECT DOSSIER TESTS
+    // ============================================================================
+
+    #[test]
+    fn test_project_dossier_contains_project_and_fees() {
+        let project = sample_project("25-97105");
+        let fees = vec![fee_for("25_97105", 2), fee_for("25_97106", 1), fee_for("25_97105", 1)];
+        let companies = vec![sample_company("CHE"), sample_company("OTH")];
+        let contacts = vec![sample_contact("john_smith"), sample_contact("someone_else")];
+
+        let dossier = build_project_dossier(&project, &fees, &companies, &contacts, serde_json::Value::Null);
+
+        assert_eq!(dossier["project"]["number"]["id"], "25-97105");
+        let fee_ids: Vec<i64> = dossier["fees"].as_array().unwrap().iter().map(|f| f["rev"].as_i64().unwrap()).collect();
+        assert_eq!(fee_ids, vec![1, 2]);
+        assert_eq!(dossier["companies"].as_array().unwrap().len(), 1);
+        assert_eq!(dossier["companies"][0]["abbreviation"], "CHE");
+        assert_eq!(dossier["contacts"].as_array().unwrap().len(), 1);
+        assert!(dossier["exported_at"].is_string());
+    }
+
+    // ============================================================================
+    // SETTINGS SOURCE TESTS
+    // ============================================================================
+
+    #[test]
+    fn test_settings_sources_env_wins_over_file() {
+        let mut file = parse_settings_env("");
+        file.surrealdb_url = Some("ws://file:8000".to_string());
+        file.staff_name = Some("From File".to_string());
+        let env_lookup = |key: &str| match key {
+            "SURREALDB_URL" => Some("ws://env:8000".to_string()),
+            "STAFF_EMAIL" => Some(String::new()), // Empty values don't count
+            _ => None,
+        };
+
+        let sources = resolve_settings_sources(Some(&file), env_lookup);
+        assert_eq!(sources["SURREALDB_URL"], "env");
+        assert_eq!(sources["STAFF_NAME"], "file");
+        assert_eq!(sources["STAFF_EMAIL"], "default");
+        assert_eq!(sources["PROJECT_FOLDER_PATH"], "default");
+        assert_eq!(sources.len(), 13);
+    }
+
+    #[test]
+    fn test_settings_sources_without_file() {
+        let sources = resolve_settings_sources(None, |_| None);
+        assert!(sources.values().all(|source| source == "default"));
+    }
+
+    // ============================================================================
+    // SETTINGS IMPORT/EXPORT TESTS
+    // ============================================================================
+
+    #[test]
+    fn test_settings_export_import_roundtrip() {
+        let settings = sample_settings();
+        let exported = serde_json::to_string_pretty(&settings).unwrap();
+        let imported = parse_settings_import(&exported).unwrap();
+        assert_eq!(imported, settings);
+    }
+
+    #[test]
+    fn test_settings_import_invalid_file() {
+        assert!(parse_settings_import("SURREALDB_URL=ws://localhost:8000").is_err());
+        assert!(parse_settings_import("{}").is_err());
+
+        let mut settings = sample_settings();
+        settings.surrealdb_url = Some("localhost:8000".to_string());
+        let exported = serde_json::to_string(&settings).unwrap();
+        let err = parse_settings_import(&exported).unwrap_err();
+        assert!(err.contains("Invalid SurrealDB URL"));
+    }
+
+    #[test]
+    fn test_settings_migrated_from_legacy_env() {
+        let base = temp_base("settings");
+        std::fs::create_dir_all(&base).unwrap();
+        let json_path = base.join("settings.json");
+        let legacy = vec![base.join("missing.env"), base.join(".env")];
+        assert_eq!(load_settings_file(&json_path, &legacy).unwrap(), parse_settings_env(""));
+        assert!(!json_path.exists());
+
+        let env = "# SurrealDB Configuration\nSURREALDB_URL=\"ws://10.0.1.17:8000\"\nRUST_LOG=debug\nSTAFF_NAME=\"John Smith\"\n";
+        std::fs::write(base.join(".env"), env).unwrap();
+        let settings = load_settings_file(&json_path, &legacy).unwrap();
+        assert_eq!(settings, parse_settings_env(env));
+        assert!(json_path.exists());
+
+        // Once migrated the .env is no longer read
+        std::fs::write(base.join(".env"), "STAFF_NAME=\"Someone Else\"\n").unwrap();
+        assert_eq!(load_settings_file(&json_path, &legacy).unwrap(), settings);
+
+        std::fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn test_settings_file_roundtrip_drops_cleared_values() {
+        let base = temp_base("settings");
+        let json_path = base.join("nested").join("settings.json");
+
+        write_settings_file(&json_path, &sample_settings()).unwrap();
+        assert_eq!(load_settings_file(&json_path, &[]).unwrap(), sample_settings());
+
+        let mut settings = sample_settings();
+        settings.staff_phone = None;
+        write_settings_file(&json_path, &settings).unwrap();
+        assert_eq!(load_settings_file(&json_path, &[]).unwrap(), settings);
+
+        std::fs::write(&json_path, "STAFF_NAME=\"John Smith\"").unwrap();
+        assert!(load_settings_file(&json_path, &[]).unwrap_err().contains("Invalid settings file"));
+
+        std::fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn test_password_stashed_in_keychain() {
+        let stored = std::cell::RefCell::new(None);
+        let settings = stash_keychain_password(sample_settings(), None, |password| {
+            *stored.borrow_mut() = Some(password.to_string());
+            Ok(())
+        });
+        assert_eq!(settings.surrealdb_pass.as_deref(), Some(KEYCHAIN_PASSWORD_REF));
+        assert_eq!(stored.borrow().as_deref(), sample_settings().surrealdb_pass.as_deref());
+
+        let resolved = resolve_keychain_password(settings.clone(), || Ok(stored.borrow().clone().unwrap()));
+        assert_eq!(resolved, sample_settings());
+
+        // Already a reference: nothing to store
+        let unchanged = stash_keychain_password(settings, None, |_| panic!("reference stored again"));
+        assert_eq!(unchanged.surrealdb_pass.as_deref(), Some(KEYCHAIN_PASSWORD_REF));
+    }
+
+    #[test]
+    fn test_password_without_keychain() {
+        let settings = stash_keychain_password(sample_settings(), None, |_| Err("Keychain unavailable".to_string()));
+        assert_eq!(settings, sample_settings());
+
+        // Plaintext passwords are returned as-is
+        let resolved = resolve_keychain_password(sample_settings(), || panic!("keychain read for plaintext"));
+        assert_eq!(resolved, sample_settings());
+
+        let mut settings = sample_settings();
+        settings.surrealdb_pass = Some(KEYCHAIN_PASSWORD_REF.to_string());
+        let resolved = resolve_keychain_password(settings, || Err("Keychain unavailable".to_string()));
+        assert_eq!(resolved.surrealdb_pass, None);
+        assert_eq!(resolved.surrealdb_pass_error.as_deref(), Some("Keychain unavailable"));
+
+        // The reference is never used as a credential
+        let error = DatabaseConfig::from_settings(&resolved).unwrap_err();
+        assert!(error.contains("password unavailable: Keychain unavailable"));
+
+        // The error reaches the frontend but is never read back from a file
+        let json = serde_json::to_value(&resolved).unwrap();
+        assert_eq!(json["surrealdb_pass_error"], "Keychain unavailable");
+        let read_back: AppSettings = serde_json::from_value(json).unwrap();
+        assert_eq!(read_back.surrealdb_pass_error, None);
+    }
+
+    #[test]
+    fn test_unresolved_keychain_reference_survives_save() {
+        let base = temp_base("settings");
+        let json_path = base.join("settings.json");
+        let mut settings = sample_settings();
+        settings.surrealdb_pass = Some(KEYCHAIN_PASSWORD_REF.to_string());
+        write_settings_file(&json_path, &settings).unwrap();
+
+        // As get_settings then save_settings do when the keychain read fails
+        let loaded = resolve_keychain_password(load_settings_file(&json_path, &[]).unwrap(), || Err("Keychain unavailable".to_string()));
+        let existing = load_settings_file(&json_path, &[]).unwrap().surrealdb_pass;
+        let saved = stash_keychain_password(loaded, existing.as_deref(), |_| panic!("reference stored as a password"));
+        write_settings_file(&json_path, &saved).unwrap();
+        assert_eq!(load_settings_file(&json_path, &[]).unwrap(), settings);
+
+        // A form that sends no password leaves the reference alone
+        let mut cleared = sample_settings();
+        cleared.surrealdb_pass = None;
+        let saved = stash_keychain_password(cleared.clone(), existing.as_deref(), |_| panic!("nothing to store"));
+        assert_eq!(saved.surrealdb_pass.as_deref(), Some(KEYCHAIN_PASSWORD_REF));
+
+        // Without a reference on file, a cleared password stays cleared
+        assert_eq!(stash_keychain_password(cleared.clone(), Some("secret"), |_| panic!("nothing to store")), cleared);
+
+        std::fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn test_staff_info_from_settings() {
+        let staff = StaffInfo::from(&sample_settings());
+        assert_eq!(staff.name, "John Smith");
+        assert_eq!(staff.email, "john@example.com");
+        assert_eq!(staff.phone, "+971 50 000 0000");
+        assert_eq!(staff.position, "Director");
+
+        let mut settings = sample_settings();
+        settings.staff_phone = None;
+        assert_eq!(StaffInfo::from(&settings).phone, "");
+    }
+
+    #[test]
+    fn test_new_project_defaults_roundtrip_through_settings_file() {
+        let rendered = serde_json::to_string_pretty(&sample_settings()).unwrap();
+        assert!(rendered.contains("\"default_country\": \"United Arab Emirates\""));
+
+        let settings: AppSettings = serde_json::from_str(&rendered).unwrap();
+        assert_eq!(settings, sample_settings());
+        assert_eq!(
+            new_project_defaults(&settings),
+            serde_json::json!({ "country": "United Arab Emirates", "activity": "Design and Consultancy" })
+        );
+
+        let mut settings = sample_settings();
+        settings.default_activity = None;
+        assert_eq!(new_project_defaults(&settings)["activity"], "");
+    }
+
+    // ============================================================================
+    // COUNTRY IMPORT TESTS
+    // ============================================================================
+
+    #[test]
+    fn test_parse_country_import() {
+        let countries = parse_country_import(
+            r#"[{ "id": "country:are", "name": " United Arab Emirates ", "code": "AE", "dial_code": 971, "currency_code": "currency:AED" },
+                { "name": "Côte d'Ivoire", "dial_code": 225 }]"#,
+        )
+        .unwrap();
+        assert_eq!(countries.len(), 2);
+        assert_eq!(countries[0], serde_json::json!({
+            "id": "are",
+            "name_key": "united_arab_emirates",
+            "currency_code": "AED",
+            "record": { "name": "United Arab Emirates", "code": "AE", "dial_code": 971 },
+        }));
+        assert_eq!(countries[1]["id"], serde_json::Value::Null);
+        assert_eq!(countries[1]["name_key"], "côte_d_ivoire");
+        assert_eq!(countries[1]["currency_code"], serde_json::Value::Null);
+
+        assert!(parse_country_import("{}").unwrap_err().contains("Invalid countries file"));
+        assert!(parse_country_import(r#"[{ "dial_code": 971 }]"#).unwrap_err().contains("#1 has no name"));
+        assert!(parse_country_import(r#"[{ "name": "Oman", "dial_code": "968" }]"#).unwrap_err().contains("dial_code"));
+        assert!(parse_country_import(r#"[{ "name": "Oman", "dial_code": 968 }, { "name": "Oman", "dial_code": 968 }]"#)
+            .unwrap_err()
+            .contains("more than once"));
+        assert!(parse_country_import(r#"[{ "name": "Oman", "dial_code": 968, "currency_code": 512 }]"#)
+            .unwrap_err()
+            .contains("invalid currency ID"));
+    }
+
+    // ============================================================================
+    // STARTUP CHECK TESTS
+    // ============================================================================
+
+    #[test]
+    fn test_validate_settings_project_folder() {
+        let base = temp_base("settings");
+        let mut settings = sample_settings();
+        settings.project_folder_path = Some(base.to_string_lossy().to_string());
+
+        let err = validate_settings(&settings).unwrap_err();
+        assert!(err.contains("project folder"));
+        assert!(err.contains("'01 RFPs' subfolder"));
+
+        std::fs::create_dir_all(&base).unwrap();
+        let err = validate_settings(&settings).unwrap_err();
+        assert!(!err.contains("project folder '"));
+        assert!(err.contains("'01 RFPs' subfolder"));
+
+        std::fs::create_dir_all(base.join("01 RFPs")).unwrap();
+        assert_eq!(validate_settings(&settings), Ok(()));
+
+        settings.project_folder_path = None;
+        assert_eq!(validate_settings(&settings), Ok(()));
+
+        std::fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn test_awarded_template_folders_setting() {
+        // Settings files written before the setting existed get the defaults
+        let settings: AppSettings = serde_json::from_str(r#"{"staff_name": "John Smith"}"#).unwrap();
+        assert_eq!(settings.awarded_template_folders, default_awarded_template_folders());
+        assert_eq!(parse_settings_env("").awarded_template_folders, default_awarded_template_folders());
+
+        let folders = vec![" 03 Contract ".to_string(), "07 Drawings".to_string()];
+        assert_eq!(
+            clean_awarded_template_folders(&folders).unwrap(),
+            vec!["03 Contract".to_string(), "07 Drawings".to_string()]
+        );
+        assert_eq!(clean_awarded_template_folders(&[]).unwrap(), Vec::<String>::new());
+
+        let folders = vec!["03 Contract".to_string(), "../Secrets".to_string(), " ".to_string(), "03 Contract".to_string()];
+        let err = clean_awarded_template_folders(&folders).unwrap_err();
+        assert!(err.contains("'../Secrets' is not a single folder name"));
+        assert!(err.contains("empty folder name"));
+        assert!(err.contains("'03 Contract' is listed more than once"));
+    }
+
+    #[test]
+    fn test_startup_report_all_pass() {
+        let base = temp_base("startup");
+        std::fs::create_dir_all(base.join("01 RFPs").join("_yy-cccnn Project Name")).unwrap();
+        let mut settings = sample_settings();
+        settings.project_folder_path = Some(base.to_string_lossy().to_string());
+
+        let report = startup_report(true, &settings, Some(Ok(true)));
+        assert!(report.passed);
+        assert_eq!(report.checks.len(), 6);
+        assert!(report.checks.iter().all(|c| c.remediation.is_none()));
+
+        let report = startup_report(true, &settings, Some(Ok(false)));
+        let failed: Vec<&str> = report.checks.iter().filter(|c| !c.passed).map(|c| c.name.as_str()).collect();
+        assert_eq!(failed, ["Database write access"]);
+
+        std::fs::remove_dir_all(&base).unwrap();
+    }
+
+    #[test]
+    fn test_startup_report_missing_template() {
+        let base = temp_base("startup");
+        std::fs::create_dir_all(base.join("01 RFPs")).unwrap();
+        let mut settings = sample_settings();
+        settings.project_folder_path = Some(base.to_string_lossy().to_string());
+
+        let report = startup_report(true, &settings, Some(Ok(true)));
+        assert!(!report.passed);
+        let failed: Vec<&StartupCheck> = report.checks.iter().filter(|c| !c.passed).collect();
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].name, "Project template");
+        assert!(failed[0].remediation.as_ref().unwrap().contains("_yy-cccnn Project Name"));
+
+        std::fs::remove_dir_all(&base).unwrap();
+    }
+
+    // ============================================================================
+    // TEMPLATE PLACEHOLDER TESTS
+    // ============================================================================
+
+    #[test]
+    fn test_count_placeholder_entries() {
+        let template = temp_base("template").join("_yy-cccnn Project Name");
+        let proposal = template.join("02 Proposal");
+        let drawings = template.join("03 yy-cccnn Drawings");
+        std::fs::create_dir_all(&proposal).unwrap();
+        std::fs::create_dir_all(&drawings).unwrap();
+        std::fs::write(proposal.join("yy-cccnn-var Default Values.json"), "{}").unwrap();
+        std::fs::write(proposal.join("yy-cccnn-FP.docx"), "").unwrap();
+        std::fs::write(proposal.join("notes.txt"), "").unwrap();
+        std::fs::write(drawings.join("yy-cccnn-LD-001.pdf"), "").unwrap();
+
+        // Three files plus the drawings folder; the template root is not counted
+        assert_eq!(count_placeholder_entries(&template, "yy-cccnn"), Ok(4));
+
+        std::fs::remove_dir_all(template.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_template_structure_reports_missing_entries() {
+        let template = temp_base("template").join("_yy-cccnn Project Name");
+
+        let report = template_structure(&template);
+        assert!(!report.template_found);
+        assert!(!report.valid);
+        assert!(report.present.is_empty());
+        assert_eq!(report.missing.len(), 2);
+
+        std::fs::create_dir_all(template.join("02 Proposal")).unwrap();
+        let report = template_structure(&template);
+        assert!(report.template_found);
+        assert_eq!(report.present, vec!["02 Proposal"]);
+        assert_eq!(report.missing, vec!["02 Proposal/yy-cccnn-var Default Values.json"]);
+        assert!(!report.valid);
+
+        std::fs::write(template.join("02 Proposal").join("yy-cccnn-var Default Values.json"), "{}").unwrap();
+        let report = template_structure(&template);
+        assert!(report.valid);
+        assert!(report.missing.is_empty());
+
+        std::fs::remove_dir_all(template.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_project_template_path_uses_override() {
+        let base = std::env::temp_dir().join("Projects");
+        let base_str = base.to_str().unwrap();
+
+        assert_eq!(
+            project_template_path(base_str, Some("_yy-cccnn Daylight")).unwrap(),
+            base.join("01 RFPs").join("_yy-cccnn Daylight")
+        );
+        assert_eq!(
+            project_template_path(base_str, None).unwrap(),
+            base.join("01 RFPs").join("_yy-cccnn Project Name")
+        );
+        assert_eq!(
+            project_template_path(base_str, Some("  ")).unwrap(),
+            base.join("01 RFPs").join("_yy-cccnn Project Name")
+        );
+        assert!(project_template_path(base_str, Some("../Other")).is_err());
+        assert!(project_template_path(base_str, Some("..")).is_err());
+        assert_eq!(
+            project_template_path(base_str, Some("_yy-cccnn Villa..Annex")).unwrap(),
+            base.join("01 RFPs").join("_yy-cccnn Villa..Annex")
+        );
+    }
+
+    // ============================================================================
+    // APP PATHS TESTS
+    // ============================================================================
+
+    #[test]
+    fn test_app_paths_under_app_identifier() {
+        let config: serde_json::Value = serde_json::from_str(include_str!("../../tauri.conf.json")).unwrap();
+        let identifier = config["identifier"].as_str().unwrap();
+        let data_dir = std::env::temp_dir().join("data").join(identifier);
+        let log_dir = std::env::temp_dir().join("logs").join(identifier);
+
+        let paths = app_paths_from_dirs(&data_dir, &log_dir);
+        assert!(!paths.data_dir.is_empty());
+        assert!(!paths.log_dir.is_empty());
+        assert!(!paths.settings_path.is_empty());
+        assert!(paths.data_dir.contains(identifier));
+        assert!(paths.log_dir.contains(identifier));
+        assert!(std::path::Path::new(&paths.settings_path).starts_with(&data_dir));
+    }
+
+    #[test]
+    fn test_tail_log_lines_reads_across_rotation() {
+        let log_dir = temp_base("logs");
+        assert!(tail_log_lines(&log_dir, 10).unwrap().is_empty());
+
+        std::fs::create_dir_all(&log_dir).unwrap();
+        let rotated = log_dir.join("e-fees_2025-10-15_09-00-00.log");
+        let current = log_dir.join("e-fees.log");
+        std::fs::write(&rotated, "old 1\nold 2\nold 3\n").unwrap();
+        std::fs::write(&current, "new 1\nnew 2\n").unwrap();
+        std::fs::write(log_dir.join("notes.txt"), "not a log\n").unwrap();
+        let hour_ago = std::time::SystemTime::now() - std::time::Duration::from_secs(3600);
+        std::fs::File::options().write(true).open(&rotated).unwrap().set_modified(hour_ago).unwrap();
+
+        assert_eq!(tail_log_lines(&log_dir, 1).unwrap(), vec!["new 2"]);
+        assert_eq!(tail_log_lines(&log_dir, 3).unwrap(), vec!["old 3", "new 1", "new 2"]);
+        assert_eq!(tail_log_lines(&log_dir, 100).unwrap().len(), 5);
+
+        std::fs::remove_dir_all(&log_dir).unwrap();
+    }
+
+    // ============================================================================
+    // WINDOW PLACEMENT TESTS
+    // ============================================================================
+
+    #[test]
+    fn test_window_geometry_logical_and_physical() {
+        let mut placement = WindowPlacement { x: 1280.0, y: 0.0, width: 1280.0, height: 1399.6, use_logical: true };
+        match window_geometry(&placement).unwrap() {
+            (tauri::Position::Logical(position), tauri::Size::Logical(size)) => {
+                assert_eq!((position.x, position.y), (1280.0, 0.0));
+                assert_eq!((size.width, size.height), (1280.0, 1399.6));
+            }
+            other => panic!("expected logical geometry, got {:?}", other),
+        }
+
+        placement.use_logical = false;
+        match window_geometry(&placement).unwrap() {
+            (tauri::Position::Physical(position), tauri::Size::Physical(size)) => {
+                assert_eq!((position.x, position.y), (1280, 0));
+                assert_eq!((size.width, size.height), (1280, 1400));
+            }
+            other => panic!("expected physical geometry, got {:?}", other),
+        }
+
+        placement.height = 0.0;
+        assert!(window_geometry(&placement).is_err());
+    }
+
+    // ============================================================================
+    // FOLDER WRITE ACCESS TESTS
+    // ============================================================================
+
+    #[test]
+    fn test_proposal_folder_writable() {
+        let project_dir = temp_base("writable")
+            .join("01 RFPs")
+            .join("25-97105 Hotel");
+        let proposal_dir = project_dir.join("02 Proposal");
+        std::fs::create_dir_all(&proposal_dir).unwrap();
+
+        assert_eq!(is_dir_writable(&proposal_dir), Ok(true));
+        // The probe file is cleaned up afterwards
+        assert_eq!(std::fs::read_dir(&proposal_dir).unwrap().count(), 0);
+        assert!(is_dir_writable(&project_dir.join("03 Missing")).is_err());
+
+        std::fs::remove_dir_all(project_dir.parent().unwrap().parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_proposal_folder_follows_status() {
+        let rfp = proposal_folder("/Volumes/base/Projects", "25-97105", "Hotel", "RFP").unwrap();
+        assert_eq!(rfp, std::path::PathBuf::from("/Volumes/base/Projects/01 RFPs/25-97105 Hotel/02 Proposal"));
+
+        let active = proposal_folder("/Volumes/base/Projects", "25-97105", "Hotel", "Active").unwrap();
+        assert_eq!(active, std::path::PathBuf::from("/Volumes/base/Projects/11 Current/25-97105 Hotel/02 Proposal"));
+
+        assert!(proposal_folder("/Volumes/base/Projects", "25-97105", "Hotel", "Unknown").is_err());
+        assert!(proposal_folder("/Volumes/base/Projects", "25-97105", "../Hotel", "RFP").is_err());
+    }
+
+    // ============================================================================
+    // PROJECT FOLDER PATH VALIDATION TESTS
+    // ============================================================================
+
+    #[test]
+    fn test_rfp_project_folder_valid() {
+        let folder = rfp_project_folder("/Volumes/base/Projects", "25-97105", "Hotel ABC").unwrap();
+        assert_eq!(folder, std::path::PathBuf::from("/Volumes/base/Projects/01 RFPs/25-97105 Hotel ABC"));
+    }
+
+    #[test]
+    fn test_rfp_project_folder_rejects_traversal() {
+        let base = "/Volumes/base/Projects";
+        assert!(rfp_project_folder(base, "..", "Hotel").is_err());
+        assert!(rfp_project_folder(base, "25-97105", "../../etc").is_err());
+        assert!(rfp_project_folder(base, "25-97105", "Hotel/..").is_err());
+        assert!(rfp_project_folder(base, "25-97105", "..\\Windows").is_err());
+        assert!(rfp_project_folder(base, "25-97105", "/etc/passwd").is_err());
+        assert!(rfp_project_folder(base, "", "Hotel").is_err());
+        assert!(rfp_project_folder(base, "25-97105", ".").is_err());
+        assert!(validate_folder_component("Hotel ABC", "project short name").is_ok());
+        assert!(validate_folder_component("Villa..Annex", "project short name").is_ok());
+        assert_eq!(
+            rfp_project_folder(base, "25-97105", "Villa..Annex").unwrap(),
+            std::path::PathBuf::from("/Volumes/base/Projects/01 RFPs/25-97105 Villa..Annex")
+        );
+    }
+
+    #[test]
+    fn test_project_folder_name_preview() {
+        assert_eq!(project_folder_name("25-97105", "Hotel ABC"), Ok("25-97105 Hotel ABC".to_string()));
+        assert_eq!(project_folder_name("⟨25-97105⟩", "Hotel ABC"), Ok("25-97105 Hotel ABC".to_string()));
+        assert_eq!(project_folder_name(" 25-97105 ", "  Hotel   ABC  "), Ok("25-97105 Hotel ABC".to_string()));
+        assert!(project_folder_name("25-97105", "   ").is_err());
+        assert!(project_folder_name("⟨⟩", "Hotel").is_err());
+
+        let folder = rfp_project_folder("/Volumes/base/Projects", "⟨25-97105⟩", "Hotel ABC ").unwrap();
+        assert_eq!(folder, std::path::PathBuf::from("/Volumes/base/Projects/01 RFPs/25-97105 Hotel ABC"));
+    }
+
+    #[test]
+    fn test_rename_folder_with_old_suffix_uses_normalized_name() {
+        let base = temp_base("rename");
+        let rfps = base.join("01 RFPs");
+        std::fs::create_dir_all(rfps.join("25-97105 Hotel ABC")).unwrap();
+        let base_str = base.to_string_lossy().to_string();
+
+        let message = rename_folder_with_old_suffix_in(&base_str, "⟨25-97105⟩", "  Hotel   ABC ").unwrap();
+        assert!(message.ends_with("25-97105 Hotel ABC_old"));
+        assert!(rfps.join("25-97105 Hotel ABC_old").is_dir());
+        assert!(!rfps.join("25-97105 Hotel ABC").exists());
+
+        std::fs::create_dir_all(rfps.join("25-97105 Hotel ABC")).unwrap();
+        rename_folder_with_old_suffix_in(&base_str, "25-97105", "Hotel ABC").unwrap();
+        assert!(rfps.join("25-97105 Hotel ABC_old2").is_dir());
+
+        assert!(rename_folder_with_old_suffix_in(&base_str, "25-97105", "../Hotel").is_err());
+
+        std::fs::remove_dir_all(&base).unwrap();
+    }
+
+    // ============================================================================
+    // TEST FIXTURES
+    // ============================================================================
+
+    /// Fresh, uniquely named directory under the system temp dir.
+    fn temp_base(label: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("efees-{}-{}", label, uuid::Uuid::new_v4()))
+    }
+
+    fn sample_fee() -> Fee {
+        Fee {
+            id: Some(Thing::from(("fee", "25_97105_1"))),
+            name: "Hotel Lighting Design".to_string(),
+            number: "25-97105-FP-1".to_string(),
+            rev: 1,
+            status: "Draft".to_string(),
+            issue_date: "250110".to_string(),
+            activity: "Design and Consultancy".to_string(),
+            package: "Lighting".to_string(),
+            project_id: Thing::from(("projects", "25_97105")),
+            company_id: Thing::from(("company", "CHE")),
+            contact_id: Thing::from(("contacts", "john_smith")),
+            staff_name: "Staff".to_string(),
+            staff_email: "staff@example.com".to_string(),
+            staff_phone: "+971 50 000 0000".to_string(),
+            staff_position: "Director".to_string(),
+            strap_line: "sensory design studio".to_string(),
+            revisions: Vec::new(),
+            time: TimeStamps {
+                created_at: "2025-01-10T00:00:00Z".to_string(),
+                updated_at: "2025-01-10T00:00:00Z".to_string(),
+            },
+            currency: Some("AED".to_string()),
+            amount: None,
+        }
+    }
+
+    fn sample_settings() -> AppSettings {
+        AppSettings {
+            surrealdb_url: Some("ws://10.0.1.17:8000".to_string()),
+            surrealdb_ns: Some("emittiv".to_string()),
+            surrealdb_db: Some("projects".to_string()),
+            surrealdb_user: Some("app".to_string()),
+            surrealdb_pass: Some("secret".to_string()),
+            staff_name: Some("John Smith".to_string()),
+            staff_email: Some("john@example.com".to_string()),
+            staff_phone: Some("+971 50 000 0000".to_string()),
+            staff_position: Some("Director".to_string()),
+            project_folder_path: Some("/Volumes/base/Projects".to_string()),
+            default_country: Some("United Arab Emirates".to_string()),
+            default_activity: Some("Design and Consultancy".to_string()),
+            heartbeat_failure_threshold: Some("3".to_string()),
+            awarded_template_folders: default_awarded_template_folders(),
+            surrealdb_pass_error: None,
+        }
+    }
+
+    fn fee_for(project_key: &str, rev: i32) -> Fee {
+        let mut fee = sample_fee();
+        fee.id = Some(Thing::from(("fee", format!("{}_{}", project_key, rev).as_str())));
+        fee.project_id = Thing::from(("projects", project_key));
+        fee.rev = rev;
+        fee
+    }
+
+    fn sample_project(number: &str) -> Project {
+        Project {
+            id: Some(Thing::from(("projects", number.replace('-', "_").as_str()))),
+            name: "Hotel Lighting".to_string(),
+            name_short: "Hotel".to_string(),
+            status: "RFP".to_string(),
+            area: "Downtown".to_string(),
+            city: "Dubai".to_string(),
+            country: "United Arab Emirates".to_string(),
+            folder: format!("{} Hotel", number),
+            number: ProjectNumber {
+                year: number[..2].parse().unwrap(),
+                country: number[3..6].parse().unwrap(),
+                seq: number[6..].parse().unwrap(),
+                id: number.to_string(),
+            },
+            time: TimeStamps {
+                created_at: "2025-01-01T00:00:00Z".to_string(),
+                updated_at: "2025-01-01T00:00:00Z".to_string(),
+            },
+            activity: None,
+            package: None,
+            template_override: None,
+            deleted_at: None,
+        }
+    }
+
+    fn sample_company(abbreviation: &str) -> Company {
+        Company {
+            id: Some(Thing::from(("company", abbreviation))),
+            name: format!("{} Holdings", abbreviation),
+            name_short: abbreviation.to_string(),
+            abbreviation: abbreviation.to_string(),
+            city: "Dubai".to_string(),
+            country: "United Arab Emirates".to_string(),
+            reg_no: None,
+            tax_no: None,
+            time: TimeStamps {
+                created_at: "2025-01-01T00:00:00Z".to_string(),
+                updated_at: "2025-01-01T00:00:00Z".to_string(),
+            },
+        }
+    }
+
+    fn sample_contact(key: &str) -> Contact {
+        Contact {
+            id: Some(Thing::from(("contacts", key))),
+            first_name: Some("John".to_string()),
+            last_name: Some("Smith".to_string()),
+            full_name: Some("John Smith".to_string()),
+            email: Some("john@example.com".to_string()),
+            phone: Some("+971 50 000 0000".to_string()),
+            position: Some("Director".to_string()),
+            company: Some(Thing::from(("company", "CHE"))),
+            time: None,
+        }
+    }
+}
@@ -2,6 +2,7 @@ use crate::db::DatabaseManager;
 use crate::commands::AppState;
 use log::{error, info};
 use std::future::Future;
+use std::path::{Component, Path, PathBuf};
 use tauri::State;
 
 /// Generic helper for executing operations with database manager state management
@@ -44,6 +45,60 @@ where
     }
 }
 
+/// Join user-supplied path components onto a base folder without escaping it
+/// 
+/// Every filesystem command that builds paths from frontend input (project
+/// numbers, short names, folder names) goes through this function. Each
+/// component must be a single plain folder or file name: `.`, `..`, path
+/// separators, null bytes and absolute paths are rejected, while names that
+/// merely contain dots (`Villa..Annex`) are fine. If the base folder
+/// exists, the deepest existing part of the result is canonicalized and must
+/// still sit inside the canonical base, which also catches symlinks pointing
+/// elsewhere.
+/// 
+/// # Returns
+/// - `Ok(PathBuf)`: `base` joined with all components
+/// - `Err(String)`: A component is invalid or the result escapes `base`
+pub fn safe_join(base: &Path, components: &[&str]) -> Result<PathBuf, String> {
+    let mut path = base.to_path_buf();
+    
+    for component in components {
+        // `.`, `..`, empty and absolute components don't parse as one normal name
+        let is_plain_name = matches!(
+            Path::new(component).components().collect::<Vec<_>>().as_slice(),
+            [Component::Normal(_)]
+        );
+        
+        if !is_plain_name
+            || component.contains('/')
+            || component.contains('\\')
+            || component.contains('\0')
+        {
+            return Err(format!("Invalid path component: '{}'", component));
+        }
+        
+        path.push(component);
+    }
+    
+    if let Ok(canonical_base) = base.canonicalize() {
+        let mut existing = path.as_path();
+        while !existing.exists() {
+            match existing.parent() {
+                Some(parent) => existing = parent,
+                None => break,
+            }
+        }
+        
+        if let Ok(canonical) = existing.canonicalize() {
+            if !canonical.starts_with(&canonical_base) {
+                return Err(format!("Path {} is outside of {}", path.display(), base.display()));
+            }
+        }
+    }
+    
+    Ok(path)
+}
+
 /// Macro for generating standard CRUD command functions
 /// 
 /// This macro eliminates the boilerplate of creating similar command functions
@@ -171,6 +226,53 @@ mod tests {
         assert!(true);
     }
     
+    #[test]
+    fn test_safe_join_normal_components() {
+        let base = Path::new("/Volumes/base/Projects");
+        let path = safe_join(base, &["01 RFPs", "25-97105 Hotel ABC", "02 Proposal"]).unwrap();
+        assert_eq!(path, PathBuf::from("/Volumes/base/Projects/01 RFPs/25-97105 Hotel ABC/02 Proposal"));
+    }
+    
+    #[test]
+    fn test_safe_join_rejects_traversal() {
+        let base = Path::new("/Volumes/base/Projects");
+        assert!(safe_join(base, &["01 RFPs", ".."]).is_err());
+        assert!(safe_join(base, &["01 RFPs", "../../etc"]).is_err());
+        assert!(safe_join(base, &["01 RFPs", "..\\Windows"]).is_err());
+        assert!(safe_join(base, &["01 RFPs", "Hotel\0"]).is_err());
+        assert!(safe_join(base, &["."]).is_err());
+        assert!(safe_join(base, &[""]).is_err());
+    }
+    
+    #[test]
+    fn test_safe_join_allows_dots_inside_names() {
+        let base = Path::new("/Volumes/base/Projects");
+        let path = safe_join(base, &["01 RFPs", "25-97105 Villa..Annex"]).unwrap();
+        assert_eq!(path, PathBuf::from("/Volumes/base/Projects/01 RFPs/25-97105 Villa..Annex"));
+        assert!(safe_join(base, &["...", "Hotel."]).is_ok());
+    }
+    
+    #[test]
+    fn test_safe_join_rejects_absolute_paths() {
+        let base = Path::new("/Volumes/base/Projects");
+        assert!(safe_join(base, &["/etc/passwd"]).is_err());
+        assert!(safe_join(base, &["/"]).is_err());
+    }
+    
+    #[cfg(unix)]
+    #[test]
+    fn test_safe_join_rejects_symlink_escape() {
+        let root = std::env::temp_dir().join(format!("efees-safe-join-{}", uuid::Uuid::new_v4()));
+        let base = root.join("base");
+        std::fs::create_dir_all(base.join("01 RFPs")).unwrap();
+        std::os::unix::fs::symlink(&root, base.join("escape")).unwrap();
+        
+        assert!(safe_join(&base, &["01 RFPs", "25-97105 Hotel"]).is_ok());
+        assert!(safe_join(&base, &["escape", "secrets"]).is_err());
+        
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+    
     #[test]
     fn test_macro_expansion() {
         // Test that macros compile correctly
@@ -302,7 +302,7 @@ fn default_fee_stage() -> FeeStage {
 // Helper implementations
 impl ProjectNumber {
     pub fn new(year: u32, country: u32, seq: u32) -> Self {
-        let id = format!("{:02}-{}{:02}", year, country, seq);
+        let id = format!("{:02}-{:03}{:02}", year, country, seq);
         Self { year, country, seq, id }
     }
     
@@ -68,7 +68,8 @@ mod tests;
 
 use serde::{Deserialize, Serialize};
 use std::sync::{Arc, Mutex};
-use std::time::Duration;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::{Duration, Instant};
 use surrealdb::engine::remote::ws::{Client, Ws};
 use surrealdb::engine::remote::http::{Client as HttpClient, Http};
 use surrealdb::opt::auth::{Root, Namespace, Database};
@@ -79,11 +80,378 @@ use log::{error, info, warn};
 use chrono::{self, Datelike};
 use std::env;
 use crate::commands::CompanyUpdate;
+use utils::NormalizeText;
 // pub use crate::db::entities::{FeeUpdate}; // Temporarily disabled to test connection issue - now defined locally
 
 /// Interval for database connection health checks (30 seconds)
 const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(30);
 
+/// Consecutive failed health checks before the connection is reported lost
+const DEFAULT_HEARTBEAT_FAILURE_THRESHOLD: u32 = 2;
+
+/// Seconds to wait for the connection and sign-in before giving up
+const DEFAULT_CONNECTION_TIMEOUT_SECS: u64 = 10;
+
+/// Delay before the first automatic reconnect attempt, doubled on each retry
+const RECONNECT_BASE_DELAY: Duration = Duration::from_secs(1);
+
+/// Years a new project number may differ from the current year before
+/// `create_new_project` warns about a likely data-entry mistake
+const PROJECT_YEAR_TOLERANCE: i32 = 2;
+
+/// Companies sorted case-insensitively by name.
+/// SurrealDB only orders by selected fields, so the lowercase name is aliased;
+/// `?? ''` keeps records without a name from failing the whole query.
+pub(crate) const COMPANIES_ORDERED_QUERY: &str =
+    "SELECT *, string::lowercase(name ?? '') AS name_sort FROM company ORDER BY name_sort ASC";
+
+/// Projects sorted newest first. Records without a creation timestamp sort
+/// last and fall back to the project number (highest first). Archived
+/// projects are skipped unless `$include_deleted` is true.
+pub(crate) const PROJECTS_ORDERED_QUERY: &str =
+    "SELECT * FROM projects WHERE $include_deleted OR deleted_at IS NONE ORDER BY time.created_at DESC, number.id DESC";
+
+/// One page of companies sorted like `COMPANIES_ORDERED_QUERY`.
+/// Expects `$country` (NONE matches every country), `$limit` and `$offset`.
+pub(crate) const COMPANIES_PAGE_QUERY: &str = r#"SELECT *, string::lowercase(name ?? '') AS name_sort FROM company
+    WHERE (!$country OR string::lowercase(country ?? '') = string::lowercase($country))
+    ORDER BY name_sort ASC LIMIT $limit START $offset"#;
+
+/// Number of companies matching the `COMPANIES_PAGE_QUERY` filter.
+pub(crate) const COMPANIES_COUNT_QUERY: &str = r#"SELECT count() AS total FROM company
+    WHERE (!$country OR string::lowercase(country ?? '') = string::lowercase($country))
+    GROUP ALL"#;
+
+/// One page of projects sorted like `PROJECTS_ORDERED_QUERY`.
+/// Expects `$include_deleted`, `$limit` and `$offset`.
+pub(crate) const PROJECTS_PAGE_QUERY: &str = "SELECT * FROM projects WHERE $include_deleted OR deleted_at IS NONE \
+    ORDER BY time.created_at DESC, number.id DESC LIMIT $limit START $offset";
+
+/// Number of projects matching the `PROJECTS_PAGE_QUERY` filter.
+pub(crate) const PROJECTS_COUNT_QUERY: &str =
+    "SELECT count() AS total FROM projects WHERE $include_deleted OR deleted_at IS NONE GROUP ALL";
+
+/// One page of fee rows, newest first, read as raw values like `FEES_ALL_QUERY`.
+/// Expects `$limit` and `$offset`.
+pub(crate) const FEES_PAGE_QUERY: &str =
+    "SELECT * FROM fee ORDER BY time.created_at DESC, number DESC LIMIT $limit START $offset";
+
+/// Number of fee rows, including any that don't deserialize into `Fee`.
+pub(crate) const FEES_COUNT_QUERY: &str = "SELECT count() AS total FROM fee GROUP ALL";
+
+/// Dashboard counts: live (not archived) projects, companies, contacts, fees
+/// and active fees (not Lost or Cancelled), one statement each.
+pub(crate) const STATS_COUNTS_QUERY: &str = "SELECT count() AS total FROM projects WHERE deleted_at IS NONE GROUP ALL; \
+    SELECT count() AS total FROM company GROUP ALL; \
+    SELECT count() AS total FROM contacts GROUP ALL; \
+    SELECT count() AS total FROM fee GROUP ALL; \
+    SELECT count() AS total FROM fee WHERE status NOT IN ['Lost', 'Cancelled'] GROUP ALL;";
+
+/// Live project counts per country, largest first. Projects without a country
+/// are counted under `Unknown` so the counts add up to the project total.
+pub(crate) const PROJECT_COUNTS_BY_COUNTRY_QUERY: &str = "SELECT country ?? 'Unknown' AS country, count() AS count \
+    FROM projects WHERE deleted_at IS NONE GROUP BY country ORDER BY count DESC, country ASC";
+
+/// Live project counts per two-digit `number.year`, largest first; projects
+/// without a number have a `NONE` year.
+pub(crate) const PROJECT_COUNTS_BY_YEAR_QUERY: &str = "SELECT number.year AS year, count() AS count \
+    FROM projects WHERE deleted_at IS NONE GROUP BY year ORDER BY count DESC, year DESC";
+
+/// Projects whose `time.updated_at` is later than `$ts`, oldest change first.
+pub(crate) const PROJECTS_MODIFIED_SINCE_QUERY: &str =
+    "SELECT * FROM projects WHERE time.updated_at > <datetime>$ts ORDER BY time.updated_at ASC";
+
+/// Every fee row, read as raw values so rows can be converted one at a time.
+pub(crate) const FEES_ALL_QUERY: &str = "SELECT * FROM fee";
+
+/// Case-insensitive free-text search across fee text fields.
+/// Expects `$query` bound to the lowercased search term.
+pub(crate) const FEES_SEARCH_QUERY: &str = r#"SELECT * FROM fee WHERE
+    string::lowercase(name ?? '') CONTAINS $query OR
+    string::lowercase(number ?? '') CONTAINS $query OR
+    string::lowercase(activity ?? '') CONTAINS $query OR
+    string::lowercase(package ?? '') CONTAINS $query OR
+    string::lowercase(strap_line ?? '') CONTAINS $query
+    ORDER BY number ASC, rev ASC"#;
+
+/// Projects in a city, optionally narrowed to one area.
+/// Expects `$country`, `$city` and `$area` bound; an empty or missing `$area`
+/// matches every area.
+pub(crate) const PROJECTS_BY_AREA_QUERY: &str = r#"SELECT * FROM projects
+    WHERE country = $country AND city = $city AND (!$area OR area = $area)
+    ORDER BY area ASC, number.id ASC"#;
+
+/// Which of the formatted numbers in `$numbers` are already used by a project.
+pub(crate) const PROJECT_NUMBERS_TAKEN_QUERY: &str =
+    "SELECT VALUE number.id FROM projects WHERE number.id IN $numbers";
+
+/// The project `projects:$id`, selected directly by record ID.
+pub(crate) const PROJECT_BY_ID_QUERY: &str = "SELECT * FROM type::thing('projects', $id)";
+
+/// A single project by its formatted number (`YY-CCCNN`).
+pub(crate) const PROJECT_BY_NUMBER_QUERY: &str =
+    "SELECT * FROM projects WHERE number.id = $number LIMIT 1";
+
+/// Set or clear (`$template` = NONE) a project's template folder override.
+pub(crate) const PROJECT_TEMPLATE_OVERRIDE_QUERY: &str =
+    "UPDATE type::thing('projects', $id) SET template_override = $template RETURN AFTER";
+
+/// Archive (soft-delete) `projects:$id` by stamping `deleted_at`.
+pub(crate) const PROJECT_ARCHIVE_QUERY: &str =
+    "UPDATE type::thing('projects', $id) SET deleted_at = time::now(), time.updated_at = time::now() RETURN AFTER";
+
+/// Restore an archived `projects:$id` by clearing `deleted_at`.
+pub(crate) const PROJECT_RESTORE_QUERY: &str =
+    "UPDATE type::thing('projects', $id) SET deleted_at = NONE, time.updated_at = time::now() RETURN AFTER";
+
+/// A project's `deleted_at` (NONE when not archived); no rows when the project is missing.
+pub(crate) const PROJECT_DELETED_AT_QUERY: &str =
+    "SELECT VALUE deleted_at FROM type::thing('projects', $id)";
+
+/// A project's template folder override (NONE when unset).
+pub(crate) const PROJECT_TEMPLATE_OVERRIDE_GET_QUERY: &str =
+    "SELECT VALUE template_override FROM type::thing('projects', $id)";
+
+/// Any one project row, for schema inspection.
+pub(crate) const PROJECT_SAMPLE_QUERY: &str = "SELECT * FROM projects LIMIT 1";
+
+/// The project with the nearest lower sequence number in the same
+/// country and year. Expects `$year`, `$country` and `$seq` bound.
+pub(crate) const PROJECT_PREV_QUERY: &str = r#"SELECT * FROM projects
+    WHERE number.year = $year AND number.country = $country AND number.seq < $seq
+    ORDER BY number.seq DESC LIMIT 1"#;
+
+/// The project with the nearest higher sequence number in the same
+/// country and year. Expects `$year`, `$country` and `$seq` bound.
+pub(crate) const PROJECT_NEXT_QUERY: &str = r#"SELECT * FROM projects
+    WHERE number.year = $year AND number.country = $country AND number.seq > $seq
+    ORDER BY number.seq ASC LIMIT 1"#;
+
+/// Distinct non-empty fee activities, alphabetically.
+pub(crate) const FEE_ACTIVITIES_QUERY: &str =
+    "SELECT activity FROM fee WHERE activity IS NOT NONE AND string::trim(activity) != '' GROUP BY activity ORDER BY activity ASC";
+
+/// Distinct non-empty fee packages, alphabetically.
+pub(crate) const FEE_PACKAGES_QUERY: &str =
+    "SELECT package FROM fee WHERE package IS NOT NONE AND string::trim(package) != '' GROUP BY package ORDER BY package ASC";
+
+/// Rewrite legacy contacts whose `company` is a plain string ("CHE",
+/// "company:CHE" or "company:⟨CHE⟩") as a proper `company:` record link.
+/// Returns the IDs of the repaired contacts.
+pub(crate) const CONTACT_COMPANY_REPAIR_QUERY: &str = r#"UPDATE contacts SET company = type::thing('company',
+    string::replace(string::replace(string::replace(string::trim(company), 'company:', ''), '⟨', ''), '⟩', ''))
+    WHERE type::is::string(company) AND string::trim(company) != ''
+    RETURN id"#;
+
+/// Fix the common reasons a fee row fails to deserialize into `Fee`: a numeric
+/// `rev` stored as a string, and missing text fields or `revisions` (defaulted
+/// to '' and []). Returns the IDs of the repaired fees.
+pub(crate) const FEE_ROW_REPAIR_QUERY: &str = r#"UPDATE fee SET
+    rev = IF type::is::string(rev) AND string::is::numeric(rev) THEN <int> rev ELSE rev END,
+    revisions = revisions ?? [],
+    activity = activity ?? '',
+    package = package ?? '',
+    strap_line = strap_line ?? '',
+    staff_name = staff_name ?? '',
+    staff_email = staff_email ?? '',
+    staff_phone = staff_phone ?? '',
+    staff_position = staff_position ?? ''
+    WHERE (type::is::string(rev) AND string::is::numeric(rev))
+        OR revisions IS NONE OR revisions IS NULL
+        OR activity IS NONE OR activity IS NULL
+        OR package IS NONE OR package IS NULL
+        OR strap_line IS NONE OR strap_line IS NULL
+        OR staff_name IS NONE OR staff_name IS NULL
+        OR staff_email IS NONE OR staff_email IS NULL
+        OR staff_phone IS NONE OR staff_phone IS NULL
+        OR staff_position IS NONE OR staff_position IS NULL
+    RETURN id"#;
+
+/// Number of contacts other than `contacts:$id` whose email equals `$email`, ignoring case.
+pub(crate) const CONTACT_EMAIL_IN_USE_QUERY: &str = "SELECT count() AS total FROM contacts \
+    WHERE string::lowercase(string::trim(email ?? '')) = string::lowercase(string::trim($email)) \
+    AND record::id(id) != $id GROUP ALL";
+
+/// Append a revision to `fee:$id` and set `rev` to its number. The new number
+/// follows the higher of `rev` and the revision count, so a legacy fee with
+/// `rev = 3` and no tracked revisions continues at 4 instead of restarting at
+/// 1, and `rev` keeps matching `Fee::expected_rev`. The other fee fields are
+/// left untouched.
+pub(crate) const FEE_REVISION_APPEND_QUERY: &str = "UPDATE type::thing('fee', $id) SET \
+    revisions = array::append(revisions ?? [], { \
+        revision_number: math::max([rev ?? 0, array::len(revisions ?? [])]) + 1, \
+        revision_date: <string> time::now(), \
+        author_email: $author_email, \
+        author_name: $author_name, \
+        notes: $notes \
+    }), \
+    rev = array::last(revisions).revision_number, \
+    time.updated_at = time::now() \
+    RETURN AFTER";
+
+/// Fees of `projects:$pid`, newest first.
+pub(crate) const FEES_FOR_PROJECT_QUERY: &str =
+    "SELECT * FROM fee WHERE project_id = type::thing('projects', $pid) ORDER BY time.created_at DESC";
+
+/// Activity and package of `fee:$id` next to those of its project. Both are
+/// optional on projects, so the project values are missing when unset.
+pub(crate) const FEE_PROJECT_FIELDS_QUERY: &str = "SELECT \
+    activity AS fee_activity, package AS fee_package, \
+    project_id.activity AS project_activity, project_id.package AS project_package \
+    FROM type::thing('fee', $id)";
+
+/// Number of fees whose `contact_id` links to `contacts:$id`.
+pub(crate) const FEES_FOR_CONTACT_COUNT_QUERY: &str =
+    "SELECT count() AS total FROM fee WHERE contact_id = type::thing('contacts', $id) GROUP ALL";
+
+/// Country names and codes used to validate the free-text company country.
+pub(crate) const COUNTRY_LOOKUP_QUERY: &str =
+    "SELECT name, name_formal, name_official, code, code_alt FROM country";
+
+/// Create `projects:$id` from the bound `NewProject` fields.
+pub(crate) const PROJECT_CREATE_QUERY: &str = "CREATE type::thing('projects', $id) SET \
+    name = $name, name_short = $name_short, status = $status, area = $area, city = $city, \
+    country = $country, folder = $folder, number = $number, activity = $activity, package = $package, \
+    template_override = $template_override";
+
+/// IDs of companies whose record key is `$abbreviation`, ignoring case.
+pub(crate) const COMPANY_ABBREVIATION_QUERY: &str =
+    "SELECT VALUE id FROM company WHERE string::uppercase(<string> record::id(id)) = $abbreviation LIMIT 1";
+
+/// Create `company:$abbreviation` from the bound `CompanyCreate` fields.
+pub(crate) const COMPANY_CREATE_QUERY: &str = "CREATE type::thing('company', $abbreviation) SET \
+    name = $name, name_short = $name_short, abbreviation = $abbreviation, city = $city, \
+    country = $country, reg_no = $reg_no, tax_no = $tax_no, \
+    time = { created_at: time::now(), updated_at: time::now() }";
+
+/// Create a contact from the bound `ContactCreate` fields plus `$full_name`.
+pub(crate) const CONTACT_CREATE_QUERY: &str = "CREATE contacts SET \
+    first_name = $first_name, last_name = $last_name, full_name = $full_name, email = $email, \
+    phone = $phone, position = $position, company = type::thing('company', $company), \
+    time = { created_at: time::now(), updated_at: time::now() }";
+
+/// Create `fee:$id` from the bound `FeeCreate` fields.
+pub(crate) const FEE_CREATE_QUERY: &str = "CREATE type::thing('fee', $id) SET \
+    name = $name, number = $number, rev = $rev, \
+    project_id = type::thing('projects', $project_id), company_id = type::thing('company', $company_id), \
+    contact_id = type::thing('contacts', $contact_id), status = $status, issue_date = $issue_date, \
+    activity = $activity, package = $package, strap_line = $strap_line, staff_name = $staff_name, \
+    staff_email = $staff_email, staff_phone = $staff_phone, staff_position = $staff_position, \
+    currency = $currency, amount = $amount, revisions = [], time = { created_at: time::now(), updated_at: time::now() }";
+
+/// Overwrite `fee:$id` from the bound `FeeUpdate` fields; missing optional text becomes `''`.
+pub(crate) const FEE_UPDATE_QUERY: &str = "UPDATE type::thing('fee', $id) SET \
+    name = $name, number = $number, rev = $rev, \
+    project_id = type::thing('projects', $project_id), company_id = type::thing('company', $company_id), \
+    contact_id = type::thing('contacts', $contact_id), status = $status, issue_date = $issue_date, \
+    activity = $activity ?? '', package = $package ?? '', strap_line = $strap_line ?? '', \
+    staff_name = $staff_name ?? '', staff_email = $staff_email ?? '', staff_phone = $staff_phone ?? '', \
+    staff_position = $staff_position ?? '', \
+    time = { created_at: time.created_at OR time::now(), updated_at: time::now() } RETURN AFTER";
+
+/// Case-insensitive substring search of `$query` across the text fields of
+/// live (not archived) projects, newest first.
+pub(crate) const PROJECTS_SEARCH_QUERY: &str = r#"SELECT * FROM projects WHERE deleted_at IS NONE AND (
+    string::lowercase(name) CONTAINS string::lowercase($query) OR
+    string::lowercase(name_short) CONTAINS string::lowercase($query) OR
+    string::lowercase(number.id) CONTAINS string::lowercase($query) OR
+    string::lowercase(city) CONTAINS string::lowercase($query) OR
+    string::lowercase(area) CONTAINS string::lowercase($query) OR
+    string::lowercase(country) CONTAINS string::lowercase($query) OR
+    string::lowercase(folder) CONTAINS string::lowercase($query))
+    ORDER BY time.created_at DESC"#;
+
+/// Countries whose names, codes or dial code contain `$query`, case-insensitive.
+pub(crate) const COUNTRIES_SEARCH_QUERY: &str = "SELECT name, name_formal, name_official, code, code_alt, dial_code FROM country WHERE \
+    (name IS NOT NONE AND string::lowercase(name) CONTAINS string::lowercase($query)) OR \
+    (name_formal IS NOT NONE AND string::lowercase(name_formal) CONTAINS string::lowercase($query)) OR \
+    (name_official IS NOT NONE AND string::lowercase(name_official) CONTAINS string::lowercase($query)) OR \
+    (code IS NOT NONE AND string::lowercase(code) CONTAINS string::lowercase($query)) OR \
+    (code_alt IS NOT NONE AND string::lowercase(code_alt) CONTAINS string::lowercase($query)) OR \
+    (dial_code IS NOT NONE AND string::contains(<string>dial_code, $query)) \
+    ORDER BY name ASC LIMIT 15";
+
+/// Up to 20 distinct project areas in `$country`.
+pub(crate) const AREA_SUGGESTIONS_QUERY: &str =
+    "SELECT area FROM projects WHERE country = $country AND area IS NOT NONE GROUP BY area ORDER BY area ASC LIMIT 20";
+
+/// Up to 20 distinct project cities in `$country`.
+pub(crate) const CITY_SUGGESTIONS_QUERY: &str =
+    "SELECT city FROM projects WHERE country = $country AND city IS NOT NONE GROUP BY city ORDER BY city ASC LIMIT 20";
+
+/// Dial code of the country named `$name`.
+pub(crate) const COUNTRY_DIAL_CODE_QUERY: &str =
+    "SELECT dial_code FROM country WHERE name = $name LIMIT 1";
+
+/// Currency of the country named `$name`, following its `currency_code` link.
+pub(crate) const COUNTRY_CURRENCY_QUERY: &str = "SELECT currency_code.code AS code, currency_code.name AS name \
+    FROM country WHERE name = $name AND currency_code.code IS NOT NONE LIMIT 1";
+
+/// Names of all countries.
+pub(crate) const COUNTRY_NAMES_QUERY: &str = "SELECT VALUE name FROM country";
+
+/// Create or replace each country in `$countries` (as built by
+/// `parse_country_import`), matched by name.
+/// 
+/// The record keeps the `id` given in the file; without one it keeps the ID
+/// of the existing country with that name, or gets `country:<name_key>`, so
+/// references to countries survive a re-import. `currency_code` is stored as
+/// a `currency:` record link. Runs in a transaction, so a failing record
+/// imports nothing.
+pub(crate) const COUNTRY_IMPORT_QUERY: &str = "BEGIN TRANSACTION; \
+    FOR $country IN $countries { \
+        LET $id = IF $country.id THEN type::thing('country', $country.id) \
+            ELSE (SELECT VALUE id FROM country WHERE name = $country.record.name)[0] ?? type::thing('country', $country.name_key) END; \
+        DELETE country WHERE name = $country.record.name AND id != $id; \
+        UPSERT $id CONTENT $country.record; \
+        IF $country.currency_code { UPDATE $id SET currency_code = type::thing('currency', $country.currency_code); }; \
+    }; \
+    COMMIT TRANSACTION;";
+
+/// All currencies, ordered by code.
+pub(crate) const CURRENCIES_QUERY: &str = "SELECT * FROM currency ORDER BY code ASC";
+
+/// Currency code of the country of `projects:$project_id`.
+pub(crate) const PROJECT_CURRENCY_QUERY: &str = "SELECT VALUE currency_code.code FROM country \
+    WHERE name IN (SELECT VALUE country FROM type::thing('projects', $project_id)) LIMIT 1";
+
+/// Highest project sequence (1-99) used or reserved for `$year` and `$country`.
+pub(crate) const PROJECT_MAX_SEQ_QUERY: &str = "SELECT number.seq FROM projects, project_reservation \
+    WHERE number.year = $year AND number.country = $country AND number.seq >= 1 AND number.seq <= 99 \
+    ORDER BY number.seq DESC LIMIT 1";
+
+/// Country-years whose highest used or reserved sequence leaves fewer than
+/// `$threshold` of the 99 numbers, fullest first.
+pub(crate) const PROJECT_CAPACITY_QUERY: &str = "SELECT year, country, used, 99 - used AS remaining FROM ( \
+        SELECT year, country, math::max(seq) AS used FROM ( \
+            SELECT number.year AS year, number.country AS country, number.seq AS seq FROM projects, project_reservation \
+            WHERE number.seq >= 1 AND number.seq <= 99 \
+        ) GROUP BY year, country \
+    ) WHERE 99 - used < $threshold ORDER BY remaining ASC, year DESC, country ASC";
+
+/// Reserve every `ProjectNumber` in `$numbers` as `project_reservation:YY_CCCNN`.
+/// Runs in a transaction, so a clash with an existing reservation reserves nothing.
+pub(crate) const PROJECT_RESERVATION_BLOCK_QUERY: &str = "BEGIN TRANSACTION; \
+    FOR $number IN $numbers { \
+        CREATE type::thing('project_reservation', string::replace($number.id, '-', '_')) \
+        SET number = $number, reserved_at = time::now(); \
+    }; \
+    COMMIT TRANSACTION;";
+
+/// Delete reservations for `$year` and `$country` whose number has no project yet.
+/// Only touches `project_reservation`, never `projects`.
+pub(crate) const PROJECT_RESERVATION_CLEAR_QUERY: &str = "DELETE project_reservation \
+    WHERE number.year = $year AND number.country = $country \
+    AND number.id NOT IN (SELECT VALUE number.id FROM projects WHERE number.year = $year AND number.country = $country) \
+    RETURN BEFORE";
+
+/// The record `$table:$id`, whatever its table.
+pub(crate) const RECORD_BY_ID_QUERY: &str = "SELECT * FROM type::thing($table, $id)";
+
+/// Create and immediately delete a throwaway `write_probe:$id` record.
+pub(crate) const WRITE_PROBE_QUERY: &str = "CREATE type::thing('write_probe', $id) SET checked_at = time::now(); \
+    DELETE type::thing('write_probe', $id)";
+
 // ============================================================================
 // CONFIGURATION STRUCTURES
 // ============================================================================
@@ -126,6 +494,10 @@ pub struct DatabaseConfig {
     pub verify_certificates: bool,
     /// Whether to accept invalid hostnames in certificates (default: false for security)
     pub accept_invalid_hostnames: bool,
+    /// Consecutive failed heartbeats before the status flips to disconnected
+    pub heartbeat_failure_threshold: u32,
+    /// Seconds allowed for connecting and signing in (default: 10)
+    pub connection_timeout_secs: u64,
 }
 
 impl DatabaseConfig {
@@ -181,6 +553,8 @@ impl DatabaseConfig {
             .map_err(|_| "SURREALDB_USER environment variable is required but not set".to_string())?;
         let password = env::var("SURREALDB_PASS")
             .map_err(|_| "SURREALDB_PASS environment variable is required but not set".to_string())?;
+        let heartbeat_failure_threshold = Self::parse_failure_threshold(env::var("HEARTBEAT_FAILURE_THRESHOLD").ok().as_deref());
+        let connection_timeout_secs = Self::parse_connection_timeout(env::var("SURREALDB_TIMEOUT").ok().as_deref());
 
         Ok(DatabaseConfig {
             url,
@@ -190,6 +564,8 @@ impl DatabaseConfig {
             password,
             verify_certificates,
             accept_invalid_hostnames,
+            heartbeat_failure_threshold,
+            connection_timeout_secs,
         })
     }
 
@@ -232,6 +608,9 @@ impl DatabaseConfig {
             .ok_or("SurrealDB username not configured in settings".to_string())?
             .clone();
         
+        if let Some(error) = &settings.surrealdb_pass_error {
+            return Err(format!("SurrealDB password unavailable: {}", error));
+        }
         let password = settings.surrealdb_pass.as_ref()
             .ok_or("SurrealDB password not configured in settings".to_string())?
             .clone();
@@ -244,8 +623,28 @@ impl DatabaseConfig {
             password,
             verify_certificates: true,  // Default to true for security
             accept_invalid_hostnames: false,  // Default to false for security
+            heartbeat_failure_threshold: Self::parse_failure_threshold(settings.heartbeat_failure_threshold.as_deref()),
+            connection_timeout_secs: Self::parse_connection_timeout(env::var("SURREALDB_TIMEOUT").ok().as_deref()),
         })
     }
+
+    /// Parse a heartbeat failure threshold setting, falling back to the
+    /// default of 2 when it is missing, not a number or zero.
+    pub fn parse_failure_threshold(value: Option<&str>) -> u32 {
+        value
+            .and_then(|v| v.trim().parse::<u32>().ok())
+            .filter(|threshold| *threshold > 0)
+            .unwrap_or(DEFAULT_HEARTBEAT_FAILURE_THRESHOLD)
+    }
+
+    /// Parse a `SURREALDB_TIMEOUT` value in seconds, falling back to the
+    /// default of 10 when it is missing, not a number or zero.
+    pub fn parse_connection_timeout(value: Option<&str>) -> u64 {
+        value
+            .and_then(|v| v.trim().parse::<u64>().ok())
+            .filter(|secs| *secs > 0)
+            .unwrap_or(DEFAULT_CONNECTION_TIMEOUT_SECS)
+    }
 }
 
 /// Connection status tracking structure for real-time monitoring.
@@ -272,6 +671,9 @@ pub struct ConnectionStatus {
     pub last_check: Option<String>,
     /// Human-readable error message if connection failed
     pub error_message: Option<String>,
+    /// Automatic reconnect attempts since the connection was lost (0 when connected)
+    #[serde(default)]
+    pub reconnect_attempts: u32,
 }
 
 impl Default for ConnectionStatus {
@@ -280,6 +682,156 @@ impl Default for ConnectionStatus {
             is_connected: false,
             last_check: None,
             error_message: None,
+            reconnect_attempts: 0,
+        }
+    }
+}
+
+/// Structured summary of `INFO FOR DB` for the schema explorer.
+/// 
+/// SurrealDB returns each section as an object mapping names to their
+/// `DEFINE` statements; only the names are kept here, sorted alphabetically.
+/// Use `get_table_schema` for the definition of a single table.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct DatabaseInfo {
+    /// Table names defined in the current database
+    pub tables: Vec<String>,
+    /// Custom function names (`fn::...`)
+    pub functions: Vec<String>,
+    /// Global parameter names (`$...`)
+    pub params: Vec<String>,
+    /// Full-text analyzer names
+    pub analyzers: Vec<String>,
+    /// Database-level user names
+    pub users: Vec<String>,
+}
+
+impl DatabaseInfo {
+    /// Build from the raw `INFO FOR DB` result, ignoring missing sections.
+    pub fn from_info_value(info: &serde_json::Value) -> Self {
+        let names = |section: &str| -> Vec<String> {
+            let mut names: Vec<String> = info
+                .get(section)
+                .and_then(|value| value.as_object())
+                .map(|map| map.keys().cloned().collect())
+                .unwrap_or_default();
+            names.sort();
+            names
+        };
+        
+        Self {
+            tables: names("tables"),
+            functions: names("functions"),
+            params: names("params"),
+            analyzers: names("analyzers"),
+            users: names("users"),
+        }
+    }
+}
+
+/// Default time a computed statistics object is reused before re-querying.
+pub const DEFAULT_STATS_TTL: Duration = Duration::from_secs(30);
+
+/// Cached result of `get_stats`, shared by all clones of a `DatabaseManager`.
+/// 
+/// Entries are tagged with the manager generation so a reconfigured
+/// connection never serves statistics from the previous database.
+#[derive(Debug, Clone)]
+pub struct StatsCache {
+    pub ttl: Duration,
+    entry: Option<(u64, Instant, serde_json::Value)>,
+}
+
+impl Default for StatsCache {
+    fn default() -> Self {
+        Self { ttl: DEFAULT_STATS_TTL, entry: None }
+    }
+}
+
+impl StatsCache {
+    /// Cached statistics for `generation`, if still within the TTL.
+    pub fn get(&self, generation: u64) -> Option<serde_json::Value> {
+        match &self.entry {
+            Some((entry_generation, stored_at, value))
+                if *entry_generation == generation && stored_at.elapsed() < self.ttl =>
+            {
+                Some(value.clone())
+            }
+            _ => None,
+        }
+    }
+    
+    pub fn store(&mut self, generation: u64, value: serde_json::Value) {
+        self.entry = Some((generation, Instant::now(), value));
+    }
+}
+
+/// Cooperative cancellation flag for a long-running operation.
+/// 
+/// Loops check `is_cancelled` between files and stop at that boundary, so
+/// cancelling never interrupts a file mid-write.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+    
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+/// A registered long-running operation as reported by `list_operations`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct OperationInfo {
+    pub id: String,
+    pub label: String,
+    pub started_at: String,
+    pub cancelled: bool,
+}
+
+/// In-flight operations keyed by ID, shared by all clones of a `DatabaseManager`.
+#[derive(Debug, Default)]
+pub struct OperationRegistry {
+    operations: std::collections::HashMap<String, (OperationInfo, CancellationToken)>,
+}
+
+impl OperationRegistry {
+    /// Operations still running, oldest first.
+    pub fn list(&self) -> Vec<OperationInfo> {
+        let mut operations: Vec<OperationInfo> = self.operations
+            .values()
+            .map(|(info, token)| OperationInfo { cancelled: token.is_cancelled(), ..info.clone() })
+            .collect();
+        operations.sort_by(|a, b| a.started_at.cmp(&b.started_at));
+        operations
+    }
+    
+    /// Request cancellation; returns `false` if no such operation is running.
+    pub fn cancel(&self, id: &str) -> bool {
+        match self.operations.get(id) {
+            Some((_, token)) => {
+                token.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// Registration of a running operation, removed from the registry on drop.
+pub struct OperationHandle {
+    pub id: String,
+    pub token: CancellationToken,
+    registry: Arc<Mutex<OperationRegistry>>,
+}
+
+impl Drop for OperationHandle {
+    fn drop(&mut self) {
+        if let Ok(mut registry) = self.registry.lock() {
+            registry.operations.remove(&self.id);
         }
     }
 }
@@ -337,6 +889,46 @@ pub struct Project {
     pub number: ProjectNumber,
     /// Creation and modification timestamps
     pub time: TimeStamps,
+    /// Main activity the project is bid for (e.g. "Design and Consultancy"),
+    /// which its fees are expected to share (optional)
+    #[serde(default)]
+    pub activity: Option<String>,
+    /// Main package the project is bid for (e.g. "Lighting") (optional)
+    #[serde(default)]
+    pub package: Option<String>,
+    /// Template folder under `01 RFPs` to copy instead of the default
+    /// `_yy-cccnn Project Name` (optional)
+    #[serde(default)]
+    pub template_override: Option<String>,
+    /// When the project was archived (soft-deleted); `None` for live projects
+    #[serde(default)]
+    pub deleted_at: Option<String>,
+}
+
+/// Project statuses from which a project no longer progresses. Guards that
+/// depend on a project being closed out should go through `Project::is_terminal`.
+pub const TERMINAL_PROJECT_STATUSES: [&str; 2] = ["Completed", "Cancelled"];
+
+impl Project {
+    /// Whether `status` is a terminal project status (Completed or Cancelled).
+    pub fn is_terminal_status(status: &str) -> bool {
+        TERMINAL_PROJECT_STATUSES.contains(&status.trim())
+    }
+
+    /// Whether the project is in a terminal status.
+    pub fn is_terminal(&self) -> bool {
+        Self::is_terminal_status(&self.status)
+    }
+}
+
+/// A newly created project with the warnings raised while creating it.
+/// 
+/// Warnings don't block creation (e.g. a project number year far from the
+/// current year) but should be shown to the user.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectCreateResult {
+    pub project: Project,
+    pub warnings: Vec<String>,
 }
 
 /// CompanyCreate represents a new company being created (without auto-managed fields)
@@ -369,6 +961,12 @@ pub struct NewProject {
     pub country: String,
     pub folder: String,
     pub number: ProjectNumber,
+    #[serde(default)]
+    pub activity: Option<String>,
+    #[serde(default)]
+    pub package: Option<String>,
+    #[serde(default)]
+    pub template_override: Option<String>,
 }
 
 /// Project number structure implementing the YY-CCCNN numbering system.
@@ -407,6 +1005,70 @@ pub struct ProjectNumber {
     pub id: String, // The formatted number like "24-97101"
 }
 
+impl ProjectNumber {
+    /// Build a project number, deriving `id` from the components.
+    pub fn new(year: i32, country: i32, seq: i32) -> Self {
+        let id = format!("{:02}-{:03}{:02}", year, country, seq);
+        Self { year, country, seq, id }
+    }
+    
+    /// Parse a formatted `YY-CCCNN` number back into its components.
+    pub fn parse(id: &str) -> Option<Self> {
+        let (year, rest) = id.trim().split_once('-')?;
+        if year.len() != 2 || rest.len() != 5 || !year.chars().chain(rest.chars()).all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+        Some(Self::new(year.parse().ok()?, rest[..3].parse().ok()?, rest[3..].parse().ok()?))
+    }
+    
+    /// Whether the stored `id` matches the one derived from year/country/seq.
+    pub fn is_consistent(&self) -> bool {
+        ProjectNumber::new(self.year, self.country, self.seq).id == self.id
+    }
+    
+    /// Warning when the two-digit year is more than `PROJECT_YEAR_TOLERANCE`
+    /// years away from `current_year` (full year, e.g. 2025). Wraps across
+    /// centuries, so 99 is one year before 00.
+    pub fn year_warning(&self, current_year: i32) -> Option<String> {
+        let distance = (self.year - current_year.rem_euclid(100) + 150).rem_euclid(100) - 50;
+        if distance.abs() > PROJECT_YEAR_TOLERANCE {
+            Some(format!(
+                "Project number '{}' has year {:02}, {} years {} the current year {}",
+                self.id,
+                self.year,
+                distance.abs(),
+                if distance < 0 { "before" } else { "after" },
+                current_year
+            ))
+        } else {
+            None
+        }
+    }
+    
+    /// `count` consecutive numbers starting at `first_seq`, or an error when
+    /// the block would run past the 99 projects a country-year allows.
+    /// 
+    /// Only that 99-per-country-year sequence cap is enforced here; `country`
+    /// is used as given and not checked against the 3-digit (999) code range.
+    pub fn block(year: i32, country: i32, first_seq: i32, count: u32) -> Result<Vec<Self>, String> {
+        if count == 0 {
+            return Err("Block must contain at least one project number".to_string());
+        }
+        let last_seq = i32::try_from(count).ok().and_then(|count| first_seq.checked_add(count - 1));
+        let last_seq = match last_seq {
+            Some(last_seq) if first_seq >= 1 && last_seq <= 99 => last_seq,
+            _ => return Err(format!(
+                "Cannot allocate {} project numbers for year {:02} country {}: only {} of 99 remain",
+                count,
+                year,
+                country,
+                (100 - first_seq.clamp(1, 100)).clamp(0, 99)
+            )),
+        };
+        Ok((first_seq..=last_seq).map(|seq| Self::new(year, country, seq)).collect())
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TimeStamps {
     pub created_at: String,
@@ -479,6 +1141,13 @@ pub struct Contact {
     pub time: Option<TimeStamps>,
 }
 
+// Contact that get_contacts filters out, with the required fields it lacks
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct IncompleteContact {
+    pub id: String,
+    pub missing_fields: Vec<String>,
+}
+
 // Contact creation struct without auto-managed fields
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ContactCreate {
@@ -508,6 +1177,12 @@ pub struct FeeCreate {
     pub staff_position: String,
     pub strap_line: String,
     pub revisions: Vec<Revision>,
+    /// Currency code (e.g. "AED"); defaults to the currency of the project's country
+    #[serde(default)]
+    pub currency: Option<String>,
+    /// Proposed fee value in `currency`
+    #[serde(default)]
+    pub amount: Option<f64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -550,6 +1225,29 @@ pub struct Fee {
     pub strap_line: String,
     pub revisions: Vec<Revision>,
     pub time: TimeStamps,
+    /// Currency code for monetary values (e.g. "AED"); `None` on older fees
+    #[serde(default)]
+    pub currency: Option<String>,
+    /// Proposed fee value in `currency`; `None` until priced
+    #[serde(default)]
+    pub amount: Option<f64>,
+}
+
+impl Fee {
+    /// Revision number implied by the record itself.
+    /// 
+    /// `rev` should equal the number of the latest entry in `revisions`.
+    /// Fees created before revisions were tracked have an empty array, so the
+    /// numeric suffix of the record ID (`fee:25_97105_3` → 3) is used instead.
+    pub fn expected_rev(&self) -> Option<i32> {
+        if let Some(latest) = self.revisions.last() {
+            return Some(latest.revision_number);
+        }
+        
+        self.id.as_ref()
+            .map(utils::normalize_thing_id)
+            .and_then(|key| key.rsplit('_').next().and_then(|suffix| suffix.parse().ok()))
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -561,12 +1259,95 @@ pub struct Revision {
     pub notes: String,
 }
 
+// Fees that deserialized plus the number of fee rows that did not
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FeeLoad {
+    pub fees: Vec<Fee>,
+    pub skipped: usize,
+}
+
+// Projects numbered immediately before and after a given number
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdjacentProjects {
+    pub prev: Option<Project>,
+    pub next: Option<Project>,
+}
+
+// A country-year approaching the 99-project sequence cap
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CapacityWarning {
+    pub year: i32,
+    pub country: i32,
+    pub used: i32,
+    pub remaining: i32,
+}
+
+// Record counts shown on the dashboard
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+pub struct StatsCounts {
+    pub projects: u64,
+    pub companies: u64,
+    pub contacts: u64,
+    pub fees: u64,
+    pub active_fees: u64,
+}
+
+// Number of projects in one country
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CountryProjectCount {
+    pub country: String,
+    pub count: u64,
+}
+
+// Number of projects numbered in one year
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct YearProjectCount {
+    pub year: Option<i32>,
+    pub count: u64,
+}
+
+// Currency of a country, as `{ code, name }`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CountryCurrency {
+    pub code: String,
+    pub name: String,
+}
+
+// A row of the `currency` table
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Currency {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<Thing>,
+    pub code: String,
+    pub name: String,
+}
+
+// One page of a list plus the total number of matching records
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub total: u64,
+}
+
 // Database manager - using HTTP client as primary, WS as fallback
 #[derive(Clone)]
 pub struct DatabaseManager {
     pub client: Option<DatabaseClient>,
     pub status: Arc<Mutex<ConnectionStatus>>,
     pub config: DatabaseConfig,
+    /// Incremented on every reconfigure so in-flight heartbeat checks against
+    /// a previous connection can be discarded. Shared across clones, so a
+    /// reload on a clone is seen before it is swapped into state
+    pub generation: Arc<AtomicU64>,
+    /// Shared flag ensuring only one heartbeat loop runs per application
+    pub heartbeat_running: Arc<AtomicBool>,
+    /// Statistics cache shared across clones, see `get_cached_stats`
+    pub stats_cache: Arc<Mutex<StatsCache>>,
+    /// Last configuration that connected and passed a health check, used to
+    /// roll back a reload with bad settings
+    pub last_known_good: Option<DatabaseConfig>,
+    /// Cancellable long-running operations, see `list_operations`
+    pub operations: Arc<Mutex<OperationRegistry>>,
 }
 
 // Enum to handle different connection types
@@ -654,26 +1435,13 @@ impl DatabaseClient {
         // Convert project number to database-safe format (replace - with _)
         let project_id = project.number.id.replace("-", "_");
         
-        // Build SET clause with project fields
-        let set_clauses = vec![
-            format!("name = '{}'", project.name.replace("'", "''")),
-            format!("name_short = '{}'", project.name_short.replace("'", "''")),
-            format!("status = '{}'", project.status.replace("'", "''")),
-            format!("area = '{}'", project.area.replace("'", "''")),
-            format!("city = '{}'", project.city.replace("'", "''")),
-            format!("country = '{}'", project.country.replace("'", "''")),
-            format!("folder = '{}'", project.folder.replace("'", "''")),
-            format!("number = {{ year: {}, country: {}, seq: {}, id: '{}' }}", 
-                project.number.year, project.number.country, project.number.seq, project.number.id.replace("'", "''"))
-        ];
-        
-        let query = format!("CREATE projects:{} SET {}", project_id, set_clauses.join(", "));
-        
-        info!("Executing project creation query: {}", query);
+        info!("Executing project creation query for projects:{}", project_id);
         
         let mut response = match self {
-            DatabaseClient::Http(client) => client.query(&query).await?,
-            DatabaseClient::WebSocket(client) => client.query(&query).await?,
+            DatabaseClient::Http(client) => client.query(PROJECT_CREATE_QUERY)
+                .bind(("id", project_id)).bind(project).await?,
+            DatabaseClient::WebSocket(client) => client.query(PROJECT_CREATE_QUERY)
+                .bind(("id", project_id)).bind(project).await?,
         };
         
         let result: Result<Vec<Project>, _> = response.take(0);
@@ -684,22 +1452,10 @@ impl DatabaseClient {
     }
     
     pub async fn create_company(&self, company: CompanyCreate) -> Result<Option<Company>, Error> {
-        // Use the abbreviation as the company ID and escape single quotes
-        let query = format!(
-            "CREATE company:{} SET name = '{}', name_short = '{}', abbreviation = '{}', city = '{}', country = '{}', reg_no = {}, tax_no = {}, time = {{ created_at: time::now(), updated_at: time::now() }}",
-            company.abbreviation,
-            company.name.replace("'", "''"),
-            company.name_short.replace("'", "''"),
-            company.abbreviation.replace("'", "''"),
-            company.city.replace("'", "''"),
-            company.country.replace("'", "''"),
-            company.reg_no.map_or("NONE".to_string(), |v| format!("'{}'", v.replace("'", "''"))),
-            company.tax_no.map_or("NONE".to_string(), |v| format!("'{}'", v.replace("'", "''")))
-        );
-        
+        // Use the abbreviation as the company ID
         let mut response = match self {
-            DatabaseClient::Http(client) => client.query(&query).await?,
-            DatabaseClient::WebSocket(client) => client.query(&query).await?,
+            DatabaseClient::Http(client) => client.query(COMPANY_CREATE_QUERY).bind(company).await?,
+            DatabaseClient::WebSocket(client) => client.query(COMPANY_CREATE_QUERY).bind(company).await?,
         };
         
         let result: Result<Vec<Company>, _> = response.take(0);
@@ -729,22 +1485,11 @@ impl DatabaseClient {
         
         // Create the contact with ALL required fields explicitly, let database auto-generate ID
         let full_name = format!("{} {}", contact.first_name, contact.last_name);
-        let query = format!(
-            "CREATE contacts SET first_name = '{}', last_name = '{}', full_name = '{}', email = '{}', phone = '{}', position = '{}', company = company:{}, time = {{ created_at: time::now(), updated_at: time::now() }}",
-            contact.first_name.replace("'", "''"),
-            contact.last_name.replace("'", "''"),
-            full_name.replace("'", "''"),
-            contact.email.replace("'", "''"),
-            contact.phone.replace("'", "''"),
-            contact.position.replace("'", "''"),
-            contact.company
-        );
-        
-        info!("Executing contact creation query: {}", query);
-        
         let mut response = match self {
-            DatabaseClient::Http(client) => client.query(&query).await?,
-            DatabaseClient::WebSocket(client) => client.query(&query).await?,
+            DatabaseClient::Http(client) => client.query(CONTACT_CREATE_QUERY)
+                .bind(("full_name", full_name)).bind(contact).await?,
+            DatabaseClient::WebSocket(client) => client.query(CONTACT_CREATE_QUERY)
+                .bind(("full_name", full_name)).bind(contact).await?,
         };
         
         let result: Result<Vec<Contact>, _> = response.take(0);
@@ -759,46 +1504,42 @@ impl DatabaseClient {
         // Build SET clauses for only the fields that are provided
         let mut set_clauses = Vec::new();
         
-        if let Some(first_name) = &contact_update.first_name {
-            set_clauses.push(format!("first_name = '{}'", first_name.replace("'", "''")));
+        if contact_update.first_name.is_some() {
+            set_clauses.push("first_name = $first_name");
         }
-        if let Some(last_name) = &contact_update.last_name {
-            set_clauses.push(format!("last_name = '{}'", last_name.replace("'", "''")));
+        if contact_update.last_name.is_some() {
+            set_clauses.push("last_name = $last_name");
         }
-        if let Some(full_name) = &contact_update.full_name {
-            set_clauses.push(format!("full_name = '{}'", full_name.replace("'", "''")));
+        if contact_update.full_name.is_some() {
+            set_clauses.push("full_name = $full_name");
         }
-        if let Some(email) = &contact_update.email {
-            set_clauses.push(format!("email = '{}'", email.replace("'", "''")));
+        if contact_update.email.is_some() {
+            set_clauses.push("email = $email");
         }
-        if let Some(phone) = &contact_update.phone {
-            set_clauses.push(format!("phone = '{}'", phone.replace("'", "''")));
+        if contact_update.phone.is_some() {
+            set_clauses.push("phone = $phone");
         }
-        if let Some(position) = &contact_update.position {
-            set_clauses.push(format!("position = '{}'", position.replace("'", "''")));
+        if contact_update.position.is_some() {
+            set_clauses.push("position = $position");
         }
-        if let Some(company) = &contact_update.company {
-            set_clauses.push(format!("company = company:{}", company));
+        if contact_update.company.is_some() {
+            set_clauses.push("company = type::thing('company', $company)");
         }
         
         // Always update the updated_at timestamp
-        set_clauses.push("time.updated_at = time::now()".to_string());
-        
-        if set_clauses.is_empty() {
-            return Ok(None); // Nothing to update
-        }
+        set_clauses.push("time.updated_at = time::now()");
         
         let query = format!(
-            "UPDATE contacts:{} SET {} RETURN AFTER",
-            id,
+            "UPDATE type::thing('contacts', $id) SET {} RETURN AFTER",
             set_clauses.join(", ")
         );
         
-        info!("Executing contact update query: {}", query);
+        info!("Executing contact update query for contacts:{}: {}", id, query);
         
+        let id = id.to_string();
         let mut response = match self {
-            DatabaseClient::Http(client) => client.query(&query).await?,
-            DatabaseClient::WebSocket(client) => client.query(&query).await?,
+            DatabaseClient::Http(client) => client.query(&query).bind(("id", id)).bind(contact_update).await?,
+            DatabaseClient::WebSocket(client) => client.query(&query).bind(("id", id)).bind(contact_update).await?,
         };
         
         let result: Result<Vec<Contact>, _> = response.take(0);
@@ -830,31 +1571,11 @@ impl DatabaseClient {
         // Generate Fee ID in format: project_number_rev (e.g., "25_97107_1")
         let fee_id = format!("{}_{}", fee.project_id.replace("-", "_"), fee.rev);
         
-        let query = format!(
-            "CREATE fee:{} SET name = '{}', number = '{}', rev = {}, project_id = projects:{}, company_id = company:{}, contact_id = contacts:{}, status = '{}', issue_date = '{}', activity = '{}', package = '{}', strap_line = '{}', staff_name = '{}', staff_email = '{}', staff_phone = '{}', staff_position = '{}', revisions = [], time = {{ created_at: time::now(), updated_at: time::now() }}",
-            fee_id,
-            fee.name.replace("'", "''"),
-            fee.number.replace("'", "''"),
-            fee.rev,
-            fee.project_id.replace("'", "''"),
-            fee.company_id.replace("'", "''"), 
-            fee.contact_id.replace("'", "''"),
-            fee.status.replace("'", "''"),
-            fee.issue_date.replace("'", "''"),
-            fee.activity.replace("'", "''"),
-            fee.package.replace("'", "''"),
-            fee.strap_line.replace("'", "''"),
-            fee.staff_name.replace("'", "''"),
-            fee.staff_email.replace("'", "''"),
-            fee.staff_phone.replace("'", "''"),
-            fee.staff_position.replace("'", "''")
-        );
-        
-        info!("Executing Fee creation query: {}", query);
+        info!("Executing Fee creation query for fee:{}", fee_id);
         
         let mut response = match self {
-            DatabaseClient::Http(client) => client.query(&query).await?,
-            DatabaseClient::WebSocket(client) => client.query(&query).await?,
+            DatabaseClient::Http(client) => client.query(FEE_CREATE_QUERY).bind(("id", fee_id)).bind(fee).await?,
+            DatabaseClient::WebSocket(client) => client.query(FEE_CREATE_QUERY).bind(("id", fee_id)).bind(fee).await?,
         };
         
         let result: Result<Vec<Fee>, _> = response.take(0);
@@ -867,31 +1588,10 @@ impl DatabaseClient {
     pub async fn update_fee(&self, id: &str, fee: FeeUpdate) -> Result<Option<Fee>, Error> {
         info!("DatabaseClient::update_fee called with id: '{}' and fee: {:?}", id, fee);
         
-        let query = format!(
-            "UPDATE fee:{} SET name = '{}', number = '{}', rev = {}, project_id = projects:{}, company_id = company:{}, contact_id = contacts:{}, status = '{}', issue_date = '{}', activity = '{}', package = '{}', strap_line = '{}', staff_name = '{}', staff_email = '{}', staff_phone = '{}', staff_position = '{}', time = {{ created_at: time.created_at OR time::now(), updated_at: time::now() }} RETURN AFTER",
-            id,
-            fee.name.replace("'", "''"),
-            fee.number.replace("'", "''"),
-            fee.rev,
-            fee.project_id.replace("'", "''"),
-            fee.company_id.replace("'", "''"),
-            fee.contact_id.replace("'", "''"),
-            fee.status.replace("'", "''"),
-            fee.issue_date.replace("'", "''"),
-            fee.activity.as_ref().unwrap_or(&String::new()).replace("'", "''"),
-            fee.package.as_ref().unwrap_or(&String::new()).replace("'", "''"),
-            fee.strap_line.as_ref().unwrap_or(&String::new()).replace("'", "''"),
-            fee.staff_name.as_ref().unwrap_or(&String::new()).replace("'", "''"),
-            fee.staff_email.as_ref().unwrap_or(&String::new()).replace("'", "''"),
-            fee.staff_phone.as_ref().unwrap_or(&String::new()).replace("'", "''"),
-            fee.staff_position.as_ref().unwrap_or(&String::new()).replace("'", "''")
-        );
-        
-        info!("Executing update query: {}", query);
-        
+        let id = id.to_string();
         let mut response = match self {
-            DatabaseClient::Http(client) => client.query(&query).await?,
-            DatabaseClient::WebSocket(client) => client.query(&query).await?,
+            DatabaseClient::Http(client) => client.query(FEE_UPDATE_QUERY).bind(("id", id)).bind(fee).await?,
+            DatabaseClient::WebSocket(client) => client.query(FEE_UPDATE_QUERY).bind(("id", id)).bind(fee).await?,
         };
         
         let result: Result<Vec<Fee>, _> = response.take(0);
@@ -910,6 +1610,14 @@ impl DatabaseClient {
         }
     }
     
+    pub async fn set_fee_rev(&self, id: &str, rev: i32) -> Result<Option<Fee>, Error> {
+        let rev_update = serde_json::json!({ "rev": rev });
+        match self {
+            DatabaseClient::Http(client) => client.update(("fee", id)).merge(rev_update).await,
+            DatabaseClient::WebSocket(client) => client.update(("fee", id)).merge(rev_update).await,
+        }
+    }
+    
     pub async fn delete_fee(&self, id: &str) -> Result<Option<Fee>, Error> {
         match self {
             DatabaseClient::Http(client) => client.delete(("fee", id)).await,
@@ -932,6 +1640,78 @@ impl DatabaseClient {
     }
 }
 
+/// Normalize and check a project before it is created, returning the soft
+/// warnings to report alongside the created project.
+/// 
+/// A number that doesn't match its components is an error; a number year
+/// far from `current_year` (full year, e.g. 2025) is only a warning, since
+/// old projects are legitimately entered late.
+pub(crate) fn prepare_new_project(project: &mut NewProject, current_year: i32) -> Result<Vec<String>, Error> {
+    project.normalize_text();
+    if !project.number.is_consistent() {
+        let expected = ProjectNumber::new(project.number.year, project.number.country, project.number.seq);
+        return Err(surrealdb::Error::Api(surrealdb::error::Api::InvalidRequest(format!(
+            "Project number '{}' does not match its components (expected '{}')",
+            project.number.id, expected.id
+        ))));
+    }
+    
+    let warnings: Vec<String> = project.number.year_warning(current_year).into_iter().collect();
+    for warning in &warnings {
+        warn!("{}", warning);
+    }
+    Ok(warnings)
+}
+
+/// Format the generated project number `YY-CCCNN`, rejecting sequences past
+/// the 99-per-country-year business limit.
+/// 
+/// Goes through `ProjectNumber::new` so the country is zero-padded to three
+/// digits exactly as `ProjectNumber::parse` expects.
+pub(crate) fn next_project_number(year: u8, country_code: u16, next_seq: u8) -> Result<String, Error> {
+    if next_seq > 99 {
+        error!("Sequence number {} exceeds limit of 99 for year {} country {}", next_seq, year, country_code);
+        return Err(surrealdb::Error::Api(surrealdb::error::Api::InvalidRequest(
+            format!("Maximum of 99 projects per year per country reached for year {} country {}", year, country_code)
+        )));
+    }
+    Ok(ProjectNumber::new(year as i32, country_code as i32, next_seq as i32).id)
+}
+
+/// Connection state to report after a heartbeat check.
+/// 
+/// A passing check resets the failure count. A failing check only flips a
+/// connected status to disconnected once `threshold` checks in a row have
+/// failed, so brief network jitter doesn't make the status flap.
+pub(crate) fn heartbeat_status(was_connected: bool, check_passed: bool, consecutive_failures: &mut u32, threshold: u32) -> bool {
+    if check_passed {
+        *consecutive_failures = 0;
+        return true;
+    }
+    *consecutive_failures += 1;
+    was_connected && *consecutive_failures < threshold.max(1)
+}
+
+/// Delay before automatic reconnect attempt `attempt` (counted from 1).
+/// 
+/// Doubles from one second and is capped at the heartbeat interval.
+pub(crate) fn reconnect_backoff(attempt: u32) -> Duration {
+    let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+    RECONNECT_BASE_DELAY.saturating_mul(factor).min(HEARTBEAT_INTERVAL)
+}
+
+/// Interpret the `CREATE` result of the write probe.
+/// 
+/// A permission error, or an empty result where table `PERMISSIONS` filtered
+/// the record out, means the user cannot write. Other errors are passed on.
+pub(crate) fn write_permitted(created: Result<Vec<serde_json::Value>, Error>) -> Result<bool, Error> {
+    match created {
+        Ok(records) => Ok(!records.is_empty()),
+        Err(e) if e.to_string().to_lowercase().contains("permission") => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
 impl DatabaseManager {
     pub fn new() -> Result<Self, String> {
         let config = DatabaseConfig::from_env()?;
@@ -942,6 +1722,11 @@ impl DatabaseManager {
             client: None,
             status: Arc::new(Mutex::new(ConnectionStatus::default())),
             config,
+            generation: Arc::new(AtomicU64::new(0)),
+            heartbeat_running: Arc::new(AtomicBool::new(false)),
+            stats_cache: Arc::new(Mutex::new(StatsCache::default())),
+            last_known_good: None,
+            operations: Arc::new(Mutex::new(OperationRegistry::default())),
         })
     }
 
@@ -963,6 +1748,8 @@ impl DatabaseManager {
             password: String::new(),
             verify_certificates: true,
             accept_invalid_hostnames: false,
+            heartbeat_failure_threshold: DEFAULT_HEARTBEAT_FAILURE_THRESHOLD,
+            connection_timeout_secs: DEFAULT_CONNECTION_TIMEOUT_SECS,
         };
 
         let mut status = ConnectionStatus::default();
@@ -973,6 +1760,11 @@ impl DatabaseManager {
             client: None,
             status: Arc::new(Mutex::new(status)),
             config,
+            generation: Arc::new(AtomicU64::new(0)),
+            heartbeat_running: Arc::new(AtomicBool::new(false)),
+            stats_cache: Arc::new(Mutex::new(StatsCache::default())),
+            last_known_good: None,
+            operations: Arc::new(Mutex::new(OperationRegistry::default())),
         }
     }
 
@@ -996,6 +1788,11 @@ impl DatabaseManager {
             client: None,
             status: Arc::new(Mutex::new(ConnectionStatus::default())),
             config,
+            generation: Arc::new(AtomicU64::new(0)),
+            heartbeat_running: Arc::new(AtomicBool::new(false)),
+            stats_cache: Arc::new(Mutex::new(StatsCache::default())),
+            last_known_good: None,
+            operations: Arc::new(Mutex::new(OperationRegistry::default())),
         }
     }
 
@@ -1030,10 +1827,13 @@ impl DatabaseManager {
             password,
             verify_certificates: true,
             accept_invalid_hostnames: false,
+            heartbeat_failure_threshold: self.config.heartbeat_failure_threshold,
+            connection_timeout_secs: self.config.connection_timeout_secs,
         };
 
-        // Reset client connection
+        // Reset client connection and invalidate checks against the old one
         self.client = None;
+        self.generation.fetch_add(1, Ordering::SeqCst);
         
         // Update status to indicate configuration is ready but not connected
         self.update_status(false, Some("Database reconfigured. Connection will be attempted automatically.".to_string()));
@@ -1044,6 +1844,70 @@ impl DatabaseManager {
         Ok(())
     }
 
+    /// Register a cancellable operation for `list_operations`/`cancel_operation`.
+    /// 
+    /// The operation stays listed until the returned handle is dropped.
+    pub fn start_operation(&self, label: &str) -> OperationHandle {
+        let id = uuid::Uuid::new_v4().to_string();
+        let token = CancellationToken::default();
+        let info = OperationInfo {
+            id: id.clone(),
+            label: label.to_string(),
+            started_at: chrono::Utc::now().to_rfc3339(),
+            cancelled: false,
+        };
+        
+        if let Ok(mut registry) = self.operations.lock() {
+            registry.operations.insert(id.clone(), (info, token.clone()));
+        }
+        info!("Started operation {}: {}", id, label);
+        
+        OperationHandle { id, token, registry: self.operations.clone() }
+    }
+
+    /// Reconfigure and connect, falling back to the last known good configuration.
+    /// 
+    /// If the new settings fail to connect and a previous configuration is known
+    /// to work, the manager reverts to it and reconnects. Without a previous
+    /// working configuration the new one is kept and the failure is only
+    /// reflected in the connection status.
+    /// 
+    /// # Returns
+    /// 
+    /// Returns Ok(()) if the new configuration was kept, Err explaining the
+    /// rollback (or the validation error) otherwise.
+    pub async fn reconfigure_or_rollback(&mut self, url: String, namespace: String, database: String, username: String, password: String) -> Result<(), String> {
+        let previous = self.last_known_good.clone();
+        self.reconfigure(url, namespace, database, username, password)?;
+        
+        let reason = match self.initialize().await {
+            Ok(_) if self.get_status().is_connected => return Ok(()),
+            Ok(_) => self.get_status().error_message.unwrap_or_else(|| "Health check failed".to_string()),
+            Err(e) => e.to_string(),
+        };
+        
+        let previous = match previous {
+            Some(previous) => previous,
+            None => {
+                warn!("New database configuration failed and no previous working configuration is known: {}", reason);
+                return Ok(());
+            }
+        };
+        
+        warn!("New database configuration failed ({}), reverting to {}", reason, previous.url);
+        self.config = previous;
+        self.client = None;
+        self.generation.fetch_add(1, Ordering::SeqCst);
+        if let Err(e) = self.initialize().await {
+            error!("Reconnecting with the previous configuration failed: {}", e);
+        }
+        
+        Err(format!(
+            "Could not connect with the new database settings ({}). Reverted to the previous configuration ({}).",
+            reason, self.config.url
+        ))
+    }
+
     // Initialize database connection
     pub async fn initialize(&mut self) -> Result<(), Error> {
         info!("Initializing database connection to {}", self.config.url);
@@ -1059,6 +1923,7 @@ impl DatabaseManager {
                         Ok(_) => {
                             info!("Database health check passed");
                             self.update_status(true, None);
+                            self.last_known_good = Some(self.config.clone());
                         }
                         Err(e) => {
                             warn!("Database health check failed: {}", e);
@@ -1078,6 +1943,9 @@ impl DatabaseManager {
                     s if s.contains("No such host is known") => {
                         format!("Cannot resolve hostname '10.0.1.17'. Please check if the SurrealDB server is accessible and the IP address is correct.")
                     },
+                    s if s.contains("Connection timed out") => {
+                        format!("Connection timed out after {}s. Please check the SurrealDB address {} and that the server is reachable.", self.config.connection_timeout_secs, self.config.url)
+                    },
                     s if s.contains("Connection refused") => {
                         format!("Connection refused by SurrealDB server at {}. Please check if SurrealDB is running.", self.config.url)
                     },
@@ -1096,8 +1964,28 @@ impl DatabaseManager {
         }
     }
 
-    // Connect to SurrealDB
+    // Connect to SurrealDB, giving up after the configured timeout so an
+    // unreachable host can't block setup on the OS-level connect timeout
     async fn connect(&mut self) -> Result<(), Error> {
+        let timeout_secs = self.config.connection_timeout_secs;
+        match tokio::time::timeout(Duration::from_secs(timeout_secs), self.open_client()).await {
+            Ok(Ok(db)) => {
+                self.client = Some(db);
+                info!("SurrealDB connection fully established and ready");
+                Ok(())
+            }
+            Ok(Err(e)) => Err(e),
+            Err(_) => {
+                error!("Connecting to SurrealDB at {} timed out after {}s", self.config.url, timeout_secs);
+                Err(surrealdb::Error::Api(surrealdb::error::Api::InvalidRequest(format!(
+                    "Connection timed out after {}s", timeout_secs
+                ))))
+            }
+        }
+    }
+
+    // Open and sign in a client for the configured database
+    async fn open_client(&self) -> Result<DatabaseClient, Error> {
         info!("Attempting to connect to SurrealDB at {}", self.config.url);
 
         let db = if self.config.url.starts_with("ws://") || self.config.url.starts_with("wss://") {
@@ -1237,9 +2125,7 @@ impl DatabaseManager {
             Err(e) => error!("INFO FOR DB query failed: {}", e),
         }
         
-        self.client = Some(db);
-        info!("SurrealDB connection fully established and ready");
-        Ok(())
+        Ok(db)
     }
 
     // Check if database is connected and responsive
@@ -1267,6 +2153,9 @@ impl DatabaseManager {
             status.is_connected = is_connected;
             status.last_check = Some(chrono::Utc::now().to_rfc3339());
             status.error_message = error_message;
+            if is_connected {
+                status.reconnect_attempts = 0;
+            }
         }
     }
 
@@ -1275,9 +2164,32 @@ impl DatabaseManager {
         self.status.lock().unwrap().clone()
     }
 
+    // Current configuration generation, see `generation`
+    pub fn generation(&self) -> u64 {
+        self.generation.load(Ordering::SeqCst)
+    }
+
     // Start heartbeat monitoring
-    pub async fn start_heartbeat(status: Arc<Mutex<ConnectionStatus>>, manager: Arc<Mutex<DatabaseManager>>) {
+    //
+    // The loop reads the manager from shared state on every tick, so a manager
+    // swapped in by `reload_database_config` is picked up automatically. Calling
+    // this more than once is a no-op while a loop is already running.
+    pub async fn start_heartbeat(manager: Arc<Mutex<DatabaseManager>>) {
+        let running = match manager.lock() {
+            Ok(mgr) => mgr.heartbeat_running.clone(),
+            Err(_) => {
+                warn!("Failed to acquire database manager lock to start heartbeat");
+                return;
+            }
+        };
+        
+        if running.swap(true, Ordering::SeqCst) {
+            info!("Database heartbeat already running");
+            return;
+        }
+        
         let mut interval = interval(HEARTBEAT_INTERVAL);
+        let mut consecutive_failures = 0;
         
         tauri::async_runtime::spawn(async move {
             loop {
@@ -1291,14 +2203,24 @@ impl DatabaseManager {
                         continue;
                     }
                 };
+                let generation = manager_clone.generation();
                 
                 let is_connected = manager_clone.check_connection().await;
                 
-                // Update status
-                if let Ok(mut status_guard) = status.lock() {
-                    status_guard.is_connected = is_connected;
+                // Discard the result if the configuration was reloaded mid-check
+                if manager_clone.generation() != generation {
+                    info!("Database configuration changed during heartbeat, skipping status update");
+                    consecutive_failures = 0;
+                    continue;
+                }
+                
+                // Update status, tolerating failures below the threshold
+                let threshold = manager_clone.config.heartbeat_failure_threshold;
+                if let Ok(mut status_guard) = manager_clone.status.lock() {
+                    let reported = heartbeat_status(status_guard.is_connected, is_connected, &mut consecutive_failures, threshold);
+                    status_guard.is_connected = reported;
                     status_guard.last_check = Some(chrono::Utc::now().to_rfc3339());
-                    if !is_connected && status_guard.error_message.is_none() {
+                    if !reported && status_guard.error_message.is_none() {
                         status_guard.error_message = Some("Heartbeat check failed".to_string());
                     } else if is_connected {
                         status_guard.error_message = None;
@@ -1307,62 +2229,143 @@ impl DatabaseManager {
                 
                 if is_connected {
                     info!("Database heartbeat: Connected");
+                } else if consecutive_failures < threshold {
+                    warn!("Database heartbeat failed ({}/{} consecutive failures)", consecutive_failures, threshold);
                 } else {
                     warn!("Database heartbeat: Disconnected");
+                    DatabaseManager::reconnect_with_backoff(&manager).await;
+                    consecutive_failures = 0;
+                    interval.reset();
                 }
             }
         });
     }
 
-    // Get all projects
-    pub async fn get_projects(&self) -> Result<Vec<Project>, Error> {
+    // Re-initialize the shared manager's connection with its current configuration
+    //
+    // The connection is made on a clone and swapped into state only if it is
+    // healthy and the configuration wasn't reloaded in the meantime. The swap
+    // bumps the generation so heartbeat checks still in flight against the old
+    // client are discarded.
+    pub async fn reconnect(manager: &Arc<Mutex<DatabaseManager>>) -> Result<ConnectionStatus, String> {
+        let mut manager_clone = {
+            let mgr = manager.lock().map_err(|e| e.to_string())?;
+            mgr.clone()
+        };
+        let generation = manager_clone.generation();
+        info!("Reconnecting to database at {}", manager_clone.config.url);
+        
+        manager_clone.initialize().await.map_err(|e| e.to_string())?;
+        let status = manager_clone.get_status();
+        if !status.is_connected {
+            return Err(status.error_message.unwrap_or_else(|| "Health check failed".to_string()));
+        }
+        
+        let mut mgr = manager.lock().map_err(|e| e.to_string())?;
+        if mgr.generation() != generation {
+            return Err("Database configuration changed during reconnect".to_string());
+        }
+        manager_clone.generation.fetch_add(1, Ordering::SeqCst);
+        *mgr = manager_clone;
+        info!("Database reconnected");
+        Ok(mgr.get_status())
+    }
+
+    // Keep reconnecting after the heartbeat reports the connection lost
+    //
+    // Waits `reconnect_backoff` before each attempt and records the attempt
+    // number in the status. Stops once connected, including by a manual
+    // reconnect, or when the configuration is reloaded.
+    async fn reconnect_with_backoff(manager: &Arc<Mutex<DatabaseManager>>) {
+        let (status, generation) = match manager.lock() {
+            Ok(mgr) => (mgr.status.clone(), mgr.generation()),
+            Err(_) => return,
+        };
+        
+        for attempt in 1.. {
+            if let Ok(mut status_guard) = status.lock() {
+                status_guard.reconnect_attempts = attempt;
+            }
+            let delay = reconnect_backoff(attempt);
+            warn!("Reconnecting to database in {:?} (attempt {})", delay, attempt);
+            tokio::time::sleep(delay).await;
+            
+            match DatabaseManager::reconnect(manager).await {
+                Ok(_) => {
+                    info!("Database reconnected after {} attempt(s)", attempt);
+                    return;
+                }
+                Err(e) => warn!("Reconnect attempt {} failed: {}", attempt, e),
+            }
+            
+            let superseded = match manager.lock() {
+                Ok(mgr) => mgr.generation() != generation || mgr.get_status().is_connected,
+                Err(_) => true,
+            };
+            if superseded {
+                info!("Stopping automatic reconnect: connection was re-established or reconfigured");
+                return;
+            }
+        }
+    }
+
+    // Get all projects, optionally including archived ones
+    pub async fn get_projects(&self, include_deleted: bool) -> Result<Vec<Project>, Error> {
         if let Some(client) = &self.client {
-            info!("Attempting to query projects table");
+            info!("Attempting to query projects table (include_deleted: {})", include_deleted);
+            
+            let mut response = match client {
+                DatabaseClient::Http(client) => client.query(PROJECTS_ORDERED_QUERY)
+                    .bind(("include_deleted", include_deleted)).await?,
+                DatabaseClient::WebSocket(client) => client.query(PROJECTS_ORDERED_QUERY)
+                    .bind(("include_deleted", include_deleted)).await?,
+            };
             
-            // Try both select() and raw query to debug
-            let select_result: Result<Vec<Project>, Error> = client.select("projects").await;
+            let select_result: Result<Vec<Project>, Error> = response.take(0);
             match &select_result {
-                Ok(projects) => info!("select('projects') returned {} records", projects.len()),
-                Err(e) => error!("select('projects') failed: {}", e),
+                Ok(projects) => info!("Project query returned {} records", projects.len()),
+                Err(e) => error!("Project query failed: {}", e),
             }
             
-            
             select_result.or_else(|_| Ok(vec![]))
         } else {
             Err(surrealdb::Error::Api(surrealdb::error::Api::InvalidRequest("No database connection".to_string())))
         }
     }
 
+    // Get one page of projects, optionally including archived ones
+    pub async fn get_projects_paged(&self, offset: u64, limit: u64, include_deleted: bool) -> Result<Page<Project>, Error> {
+        info!("Getting projects page: offset {}, limit {}, include_deleted {}", offset, limit, include_deleted);
+        
+        if let Some(client) = &self.client {
+            let mut response = match client {
+                DatabaseClient::Http(client) => client.query(PROJECTS_PAGE_QUERY).query(PROJECTS_COUNT_QUERY)
+                    .bind(("include_deleted", include_deleted)).bind(("limit", limit)).bind(("offset", offset)).await?,
+                DatabaseClient::WebSocket(client) => client.query(PROJECTS_PAGE_QUERY).query(PROJECTS_COUNT_QUERY)
+                    .bind(("include_deleted", include_deleted)).bind(("limit", limit)).bind(("offset", offset)).await?,
+            };
+            
+            let items: Vec<Project> = response.take(0)?;
+            let total: Option<u64> = response.take((1, "total"))?;
+            Ok(Page { items, total: total.unwrap_or(0) })
+        } else {
+            Err(surrealdb::Error::Api(surrealdb::error::Api::InvalidRequest("No database connection".to_string())))
+        }
+    }
+
     // Search projects with fuzzy-like matching
     pub async fn search_projects(&self, query: &str) -> Result<Vec<Project>, Error> {
         if let Some(client) = &self.client {
             info!("Searching projects with query: {}", query);
             
-            // Escape the query to prevent SQL injection
-            let escaped_query = query.replace("'", "\\'");
-            
-            // Build a SurrealQL query that searches across multiple fields
-            let search_query = format!(
-                r#"SELECT * FROM projects WHERE 
-                   string::lowercase(name) CONTAINS string::lowercase('{}') OR
-                   string::lowercase(name_short) CONTAINS string::lowercase('{}') OR
-                   string::lowercase(number.id) CONTAINS string::lowercase('{}') OR
-                   string::lowercase(city) CONTAINS string::lowercase('{}') OR
-                   string::lowercase(area) CONTAINS string::lowercase('{}') OR
-                   string::lowercase(country) CONTAINS string::lowercase('{}') OR
-                   string::lowercase(folder) CONTAINS string::lowercase('{}')
-                   ORDER BY time.created_at DESC"#,
-                escaped_query, escaped_query, escaped_query, escaped_query, 
-                escaped_query, escaped_query, escaped_query
-            );
-            
+            let term = query.to_string();
             let result: Result<Vec<Project>, surrealdb::Error> = match client {
                 DatabaseClient::Http(http_client) => {
-                    let mut response = http_client.query(&search_query).await?;
+                    let mut response = http_client.query(PROJECTS_SEARCH_QUERY).bind(("query", term)).await?;
                     response.take(0)
                 },
                 DatabaseClient::WebSocket(ws_client) => {
-                    let mut response = ws_client.query(&search_query).await?;
+                    let mut response = ws_client.query(PROJECTS_SEARCH_QUERY).bind(("query", term)).await?;
                     response.take(0)
                 }
             };
@@ -1382,16 +2385,61 @@ impl DatabaseManager {
         }
     }
 
-    // Get all companies
-    pub async fn get_companies(&self) -> Result<Vec<Company>, Error> {
+    // Get projects updated after the given RFC 3339 timestamp
+    pub async fn get_projects_modified_since(&self, timestamp: &str) -> Result<Vec<Project>, Error> {
+        info!("Getting projects modified since: {}", timestamp);
+        
+        if let Some(client) = &self.client {
+            let timestamp = timestamp.to_string();
+            
+            let mut response = match client {
+                DatabaseClient::Http(client) => client.query(PROJECTS_MODIFIED_SINCE_QUERY)
+                    .bind(("ts", timestamp)).await?,
+                DatabaseClient::WebSocket(client) => client.query(PROJECTS_MODIFIED_SINCE_QUERY)
+                    .bind(("ts", timestamp)).await?,
+            };
+            
+            let projects: Vec<Project> = response.take(0)?;
+            info!("Found {} modified projects", projects.len());
+            Ok(projects)
+        } else {
+            Err(surrealdb::Error::Api(surrealdb::error::Api::InvalidRequest("No database connection".to_string())))
+        }
+    }
+
+    // Search fees by free text (bound parameter, case-insensitive)
+    pub async fn search_fees(&self, query: &str) -> Result<Vec<Fee>, Error> {
+        if let Some(client) = &self.client {
+            info!("Searching fees with query: {}", query);
+            
+            let term = query.trim().to_lowercase();
+            let mut response = match client {
+                DatabaseClient::Http(client) => client.query(FEES_SEARCH_QUERY).bind(("query", term)).await?,
+                DatabaseClient::WebSocket(client) => client.query(FEES_SEARCH_QUERY).bind(("query", term)).await?,
+            };
+            
+            let fees: Vec<Fee> = response.take(0)?;
+            info!("Fee search returned {} fees", fees.len());
+            Ok(fees)
+        } else {
+            Err(surrealdb::Error::Api(surrealdb::error::Api::InvalidRequest("No database connection".to_string())))
+        }
+    }
+
+    // Get all companies
+    pub async fn get_companies(&self) -> Result<Vec<Company>, Error> {
         if let Some(client) = &self.client {
             info!("Attempting to query company table");
             
-            // Try both select() and raw query to debug
-            let select_result: Result<Vec<Company>, Error> = client.select("company").await;
+            let mut response = match client {
+                DatabaseClient::Http(client) => client.query(COMPANIES_ORDERED_QUERY).await?,
+                DatabaseClient::WebSocket(client) => client.query(COMPANIES_ORDERED_QUERY).await?,
+            };
+            
+            let select_result: Result<Vec<Company>, Error> = response.take(0);
             match &select_result {
-                Ok(companies) => info!("select('company') returned {} records", companies.len()),
-                Err(e) => error!("select('company') failed: {}", e),
+                Ok(companies) => info!("Company query returned {} records", companies.len()),
+                Err(e) => error!("Company query failed: {}", e),
             }
             
             select_result.or_else(|_| Ok(vec![]))
@@ -1400,6 +2448,26 @@ impl DatabaseManager {
         }
     }
 
+    // Get one page of companies, optionally filtered by country
+    pub async fn get_companies_paged(&self, offset: u64, limit: u64, country: Option<String>) -> Result<Page<Company>, Error> {
+        info!("Getting companies page: offset {}, limit {}, country {:?}", offset, limit, country);
+        
+        if let Some(client) = &self.client {
+            let mut response = match client {
+                DatabaseClient::Http(client) => client.query(COMPANIES_PAGE_QUERY).query(COMPANIES_COUNT_QUERY)
+                    .bind(("country", country)).bind(("limit", limit)).bind(("offset", offset)).await?,
+                DatabaseClient::WebSocket(client) => client.query(COMPANIES_PAGE_QUERY).query(COMPANIES_COUNT_QUERY)
+                    .bind(("country", country)).bind(("limit", limit)).bind(("offset", offset)).await?,
+            };
+            
+            let items: Vec<Company> = response.take(0)?;
+            let total: Option<u64> = response.take((1, "total"))?;
+            Ok(Page { items, total: total.unwrap_or(0) })
+        } else {
+            Err(surrealdb::Error::Api(surrealdb::error::Api::InvalidRequest("No database connection".to_string())))
+        }
+    }
+
     // Get all contacts
     pub async fn get_contacts(&self) -> Result<Vec<Contact>, Error> {
         if let Some(client) = &self.client {
@@ -1411,79 +2479,207 @@ impl DatabaseManager {
             // Filter out incomplete contacts (those missing required fields)
             let valid_contacts: Vec<Contact> = all_contacts.into_iter()
                 .filter(|contact| {
-                    // Check that all required fields are present and non-empty
-                    let has_first_name = contact.first_name.as_ref().map_or(false, |s| !s.is_empty());
-                    let has_last_name = contact.last_name.as_ref().map_or(false, |s| !s.is_empty());
-                    let has_email = contact.email.as_ref().map_or(false, |s| !s.is_empty());
-                    let has_phone = contact.phone.as_ref().map_or(false, |s| !s.is_empty());
-                    let has_position = contact.position.as_ref().map_or(false, |s| !s.is_empty());
-                    let has_company = contact.company.is_some();
-                    
-                    if !has_first_name || !has_last_name || !has_email {
-                        info!("Filtering out incomplete contact with ID: {:?}", contact.id);
+                    let missing = utils::missing_contact_fields(contact);
+                    if !missing.is_empty() {
+                        info!("Filtering out incomplete contact with ID: {:?} (missing {:?})", contact.id, missing);
                     }
-                    
-                    has_first_name && has_last_name && has_email && has_phone && has_position && has_company
+                    missing.is_empty()
                 })
                 .collect();
             
             info!("Successfully fetched {} valid contacts (filtered from raw)", valid_contacts.len());
             
+            let mut valid_contacts = valid_contacts;
+            utils::sort_contacts_by_name(&mut valid_contacts);
+            
             Ok(valid_contacts)
         } else {
             Err(surrealdb::Error::Api(surrealdb::error::Api::InvalidRequest("No database connection".to_string())))
         }
     }
 
-    // Get all fees
+    // Get contacts that get_contacts filters out for missing required fields
+    pub async fn find_incomplete_contacts(&self) -> Result<Vec<IncompleteContact>, Error> {
+        if let Some(client) = &self.client {
+            info!("Checking contacts for missing required fields");
+            
+            let all_contacts: Vec<Contact> = client.select("contacts").await?;
+            let incomplete = utils::incomplete_contacts(&all_contacts);
+            
+            info!("Found {} incomplete contacts out of {}", incomplete.len(), all_contacts.len());
+            Ok(incomplete)
+        } else {
+            Err(surrealdb::Error::Api(surrealdb::error::Api::InvalidRequest("No database connection".to_string())))
+        }
+    }
+
+    // Compare a sample project row against both Project structs
+    pub async fn describe_project_schema_gaps(&self) -> Result<Vec<String>, Error> {
+        if let Some(client) = &self.client {
+            let mut response = match client {
+                DatabaseClient::Http(client) => client.query(PROJECT_SAMPLE_QUERY).await?,
+                DatabaseClient::WebSocket(client) => client.query(PROJECT_SAMPLE_QUERY).await?,
+            };
+            
+            let rows: Vec<serde_json::Value> = response.take(0)?;
+            let row = rows.into_iter().next().ok_or_else(|| {
+                surrealdb::Error::Api(surrealdb::error::Api::InvalidRequest("No project records to inspect".to_string()))
+            })?;
+            
+            let gaps = utils::project_schema_gaps(&row);
+            info!("Project schema check found {} gaps", gaps.len());
+            Ok(gaps)
+        } else {
+            Err(surrealdb::Error::Api(surrealdb::error::Api::InvalidRequest("No database connection".to_string())))
+        }
+    }
+
+    // Get all fees, skipping rows that don't match the Fee struct
     pub async fn get_fees(&self) -> Result<Vec<Fee>, Error> {
+        self.load_fees().await.map(|load| load.fees)
+    }
+
+    // Get all fees plus the number of rows that failed to deserialize
+    pub async fn load_fees(&self) -> Result<FeeLoad, Error> {
         if let Some(client) = &self.client {
             info!("Attempting to query fee table");
             
-            // Try the query and handle potential deserialization errors
-            let result: Result<Vec<Fee>, Error> = client.select("fee").await;
-            match result {
-                Ok(fees) => {
-                    info!("Successfully fetched {} fee records", fees.len());
-                    Ok(fees)
-                }
-                Err(e) => {
-                    error!("Failed to select from fee table: {}", e);
-                    info!("This likely means deserialization failed - the Fee struct doesn't match the database schema");
-                    // Return empty vec for now so the app doesn't crash
-                    Ok(Vec::new())
-                }
+            let mut response = match client {
+                DatabaseClient::Http(client) => client.query(FEES_ALL_QUERY).await?,
+                DatabaseClient::WebSocket(client) => client.query(FEES_ALL_QUERY).await?,
+            };
+            
+            // Convert row by row so one bad record doesn't hide all the others
+            let rows: Vec<serde_json::Value> = response.take(0)?;
+            let (fees, unparseable) = utils::partition_fee_rows(rows);
+            if unparseable.is_empty() {
+                info!("Successfully fetched {} fee records", fees.len());
+            } else {
+                error!("Fetched {} fee records, skipped {} that don't match the Fee struct", fees.len(), unparseable.len());
             }
+            Ok(FeeLoad { fees, skipped: unparseable.len() })
         } else {
             Err(surrealdb::Error::Api(surrealdb::error::Api::InvalidRequest("No database connection".to_string())))
         }
     }
 
-    // Create a new project
-    pub async fn create_project(&self, project: Project) -> Result<Project, Error> {
+    // Get one page of fees, newest first, skipping rows that don't match the Fee struct
+    pub async fn get_fees_paged(&self, offset: u64, limit: u64) -> Result<Page<Fee>, Error> {
+        info!("Getting fees page: offset {}, limit {}", offset, limit);
+        
+        if let Some(client) = &self.client {
+            let mut response = match client {
+                DatabaseClient::Http(client) => client.query(FEES_PAGE_QUERY).query(FEES_COUNT_QUERY)
+                    .bind(("limit", limit)).bind(("offset", offset)).await?,
+                DatabaseClient::WebSocket(client) => client.query(FEES_PAGE_QUERY).query(FEES_COUNT_QUERY)
+                    .bind(("limit", limit)).bind(("offset", offset)).await?,
+            };
+            
+            let rows: Vec<serde_json::Value> = response.take(0)?;
+            let (items, unparseable) = utils::partition_fee_rows(rows);
+            if !unparseable.is_empty() {
+                error!("Skipped {} fee records on this page that don't match the Fee struct", unparseable.len());
+            }
+            let total: Option<u64> = response.take((1, "total"))?;
+            Ok(Page { items, total: total.unwrap_or(0) })
+        } else {
+            Err(surrealdb::Error::Api(surrealdb::error::Api::InvalidRequest("No database connection".to_string())))
+        }
+    }
+
+    // Get the fees of one project, newest first
+    pub async fn get_fees_for_project(&self, project_id: &str) -> Result<Vec<Fee>, Error> {
+        if let Some(client) = &self.client {
+            info!("Getting fees for project: {}", project_id);
+            
+            let pid = project_id.to_string();
+            let mut response = match client {
+                DatabaseClient::Http(client) => client.query(FEES_FOR_PROJECT_QUERY).bind(("pid", pid)).await?,
+                DatabaseClient::WebSocket(client) => client.query(FEES_FOR_PROJECT_QUERY).bind(("pid", pid)).await?,
+            };
+            
+            let rows: Vec<serde_json::Value> = response.take(0)?;
+            let (fees, unparseable) = utils::partition_fee_rows(rows);
+            if !unparseable.is_empty() {
+                error!("Skipped {} fee records of project {} that don't match the Fee struct", unparseable.len(), project_id);
+            }
+            Ok(fees)
+        } else {
+            Err(surrealdb::Error::Api(surrealdb::error::Api::InvalidRequest("No database connection".to_string())))
+        }
+    }
+
+    // Get the raw rows of fees that don't deserialize into Fee
+    pub async fn find_unparseable_fees(&self) -> Result<Vec<serde_json::Value>, Error> {
+        if let Some(client) = &self.client {
+            let mut response = match client {
+                DatabaseClient::Http(client) => client.query(FEES_ALL_QUERY).await?,
+                DatabaseClient::WebSocket(client) => client.query(FEES_ALL_QUERY).await?,
+            };
+            
+            let rows: Vec<serde_json::Value> = response.take(0)?;
+            let (_, unparseable) = utils::partition_fee_rows(rows);
+            info!("Found {} unparseable fee rows", unparseable.len());
+            Ok(unparseable)
+        } else {
+            Err(surrealdb::Error::Api(surrealdb::error::Api::InvalidRequest("No database connection".to_string())))
+        }
+    }
+
+    // Repair common schema mismatches on fee rows
+    pub async fn repair_fee_rows(&self) -> Result<u64, Error> {
+        if let Some(client) = &self.client {
+            let mut response = match client {
+                DatabaseClient::Http(client) => client.query(FEE_ROW_REPAIR_QUERY).await?,
+                DatabaseClient::WebSocket(client) => client.query(FEE_ROW_REPAIR_QUERY).await?,
+            };
+            
+            let repaired: Vec<serde_json::Value> = response.take(0)?;
+            info!("Repaired {} fee rows", repaired.len());
+            Ok(repaired.len() as u64)
+        } else {
+            Err(surrealdb::Error::Api(surrealdb::error::Api::InvalidRequest("No database connection".to_string())))
+        }
+    }
+
+    // Create a new project, returning it with any project number warnings
+    pub async fn create_project(&self, mut project: Project) -> Result<ProjectCreateResult, Error> {
+        project.normalize_text();
+        let warnings: Vec<String> = project.number.year_warning(chrono::Utc::now().year()).into_iter().collect();
         if let Some(client) = &self.client {
             let created: Option<Project> = client.create_project(project).await?;
             
-            created.ok_or_else(|| surrealdb::Error::Api(surrealdb::error::Api::InvalidRequest("Failed to create project".to_string())))
+            let project = created.ok_or_else(|| surrealdb::Error::Api(surrealdb::error::Api::InvalidRequest("Failed to create project".to_string())))?;
+            Ok(ProjectCreateResult { project, warnings })
         } else {
             Err(surrealdb::Error::Api(surrealdb::error::Api::InvalidRequest("No database connection".to_string())))
         }
     }
 
-    // Create a new project from NewProject struct (time auto-managed by database)
-    pub async fn create_new_project(&self, project: NewProject) -> Result<Project, Error> {
+    // Create a new project from NewProject struct (time auto-managed by database),
+    // returning it with any project number warnings
+    pub async fn create_new_project(&self, mut project: NewProject) -> Result<ProjectCreateResult, Error> {
+        let warnings = prepare_new_project(&mut project, chrono::Utc::now().year())?;
         if let Some(client) = &self.client {
             let created: Option<Project> = client.create_new_project(project).await?;
             
-            created.ok_or_else(|| surrealdb::Error::Api(surrealdb::error::Api::InvalidRequest("Failed to create project".to_string())))
+            let project = created.ok_or_else(|| surrealdb::Error::Api(surrealdb::error::Api::InvalidRequest("Failed to create project".to_string())))?;
+            Ok(ProjectCreateResult { project, warnings })
         } else {
             Err(surrealdb::Error::Api(surrealdb::error::Api::InvalidRequest("No database connection".to_string())))
         }
     }
 
     // Create a new company
-    pub async fn create_company(&self, company: CompanyCreate) -> Result<Company, Error> {
+    pub async fn create_company(&self, mut company: CompanyCreate) -> Result<Company, Error> {
+        company.normalize_text();
         if let Some(client) = &self.client {
+            if self.abbreviation_exists(&company.abbreviation).await? {
+                return Err(surrealdb::Error::Api(surrealdb::error::Api::InvalidRequest(
+                    format!("Company abbreviation '{}' already in use", company.abbreviation)
+                )));
+            }
+            company.country = self.canonical_country(&company.country).await?;
             let created: Option<Company> = client.create_company(company).await?;
             
             created.ok_or_else(|| surrealdb::Error::Api(surrealdb::error::Api::InvalidRequest("Failed to create company".to_string())))
@@ -1493,9 +2689,29 @@ impl DatabaseManager {
     }
 
 
+    // Check whether a company already uses this abbreviation (its record ID), ignoring case
+    pub async fn abbreviation_exists(&self, abbr: &str) -> Result<bool, Error> {
+        if let Some(client) = &self.client {
+            let abbreviation = utils::normalize_abbreviation(abbr);
+            let mut response = match client {
+                DatabaseClient::Http(client) => client.query(COMPANY_ABBREVIATION_QUERY).bind(("abbreviation", abbreviation)).await?,
+                DatabaseClient::WebSocket(client) => client.query(COMPANY_ABBREVIATION_QUERY).bind(("abbreviation", abbreviation)).await?,
+            };
+            
+            let ids: Vec<Thing> = response.take(0)?;
+            Ok(!ids.is_empty())
+        } else {
+            Err(surrealdb::Error::Api(surrealdb::error::Api::InvalidRequest("No database connection".to_string())))
+        }
+    }
+
     // Update an existing company with partial data
-    pub async fn update_company_partial(&self, id: &str, company_update: CompanyUpdate) -> Result<Company, Error> {
+    pub async fn update_company_partial(&self, id: &str, mut company_update: CompanyUpdate) -> Result<Company, Error> {
+        company_update.normalize_text();
         if let Some(client) = &self.client {
+            if let Some(country) = company_update.country.take() {
+                company_update.country = Some(self.canonical_country(&country).await?);
+            }
             let updated: Option<Company> = client.update_company_partial(id, company_update).await?;
             
             updated.ok_or_else(|| surrealdb::Error::Api(surrealdb::error::Api::InvalidRequest("Failed to update company".to_string())))
@@ -1504,6 +2720,36 @@ impl DatabaseManager {
         }
     }
 
+    // Resolve a company country to its canonical name in the country table
+    async fn canonical_country(&self, country: &str) -> Result<String, Error> {
+        if let Some(client) = &self.client {
+            let mut response = match client {
+                DatabaseClient::Http(client) => client.query(COUNTRY_LOOKUP_QUERY).await?,
+                DatabaseClient::WebSocket(client) => client.query(COUNTRY_LOOKUP_QUERY).await?,
+            };
+            
+            let countries: Vec<serde_json::Value> = response.take(0)?;
+            if countries.is_empty() {
+                warn!("Country table is empty, skipping validation of '{}'", country);
+                return Ok(country.to_string());
+            }
+            
+            match utils::resolve_country_name(country, &countries) {
+                Some(name) => {
+                    if name != country {
+                        info!("Corrected company country '{}' to '{}'", country, name);
+                    }
+                    Ok(name)
+                }
+                None => Err(surrealdb::Error::Api(surrealdb::error::Api::InvalidRequest(
+                    format!("Unknown country '{}'", country)
+                ))),
+            }
+        } else {
+            Err(surrealdb::Error::Api(surrealdb::error::Api::InvalidRequest("No database connection".to_string())))
+        }
+    }
+
     // Delete a company
     pub async fn delete_company(&self, id: &str) -> Result<Company, Error> {
         if let Some(client) = &self.client {
@@ -1516,7 +2762,8 @@ impl DatabaseManager {
     }
 
     // Create a new contact
-    pub async fn create_contact(&self, contact: ContactCreate) -> Result<Contact, Error> {
+    pub async fn create_contact(&self, mut contact: ContactCreate) -> Result<Contact, Error> {
+        contact.normalize_text();
         if let Some(client) = &self.client {
             let created: Option<Contact> = client.create_contact(contact).await?;
             
@@ -1527,7 +2774,8 @@ impl DatabaseManager {
     }
 
     // Update an existing contact with partial data
-    pub async fn update_contact_partial(&self, id: &str, contact_update: crate::commands::ContactUpdate) -> Result<Contact, Error> {
+    pub async fn update_contact_partial(&self, id: &str, mut contact_update: crate::commands::ContactUpdate) -> Result<Contact, Error> {
+        contact_update.normalize_text();
         if let Some(client) = &self.client {
             let updated: Option<Contact> = client.update_contact_partial(id, contact_update).await?;
             
@@ -1537,6 +2785,116 @@ impl DatabaseManager {
         }
     }
     
+    // Check whether another contact already uses an email (case-insensitive)
+    pub async fn email_in_use(&self, email: &str, excluding_id: &str) -> Result<bool, Error> {
+        if email.trim().is_empty() {
+            return Ok(false);
+        }
+        
+        if let Some(client) = &self.client {
+            let email = email.to_string();
+            let excluding_id = excluding_id.to_string();
+            
+            let mut response = match client {
+                DatabaseClient::Http(client) => client.query(CONTACT_EMAIL_IN_USE_QUERY)
+                    .bind(("email", email)).bind(("id", excluding_id)).await?,
+                DatabaseClient::WebSocket(client) => client.query(CONTACT_EMAIL_IN_USE_QUERY)
+                    .bind(("email", email)).bind(("id", excluding_id)).await?,
+            };
+            
+            let total: Option<u64> = response.take((0, "total"))?;
+            Ok(total.unwrap_or(0) > 0)
+        } else {
+            Err(surrealdb::Error::Api(surrealdb::error::Api::InvalidRequest("No database connection".to_string())))
+        }
+    }
+    
+    // Count records for the dashboard without fetching them
+    pub async fn get_stats_counts(&self) -> Result<StatsCounts, Error> {
+        if let Some(client) = &self.client {
+            let mut response = match client {
+                DatabaseClient::Http(client) => client.query(STATS_COUNTS_QUERY).await?,
+                DatabaseClient::WebSocket(client) => client.query(STATS_COUNTS_QUERY).await?,
+            };
+            
+            let mut totals = [0u64; 5];
+            for (index, total) in totals.iter_mut().enumerate() {
+                let count: Option<u64> = response.take((index, "total"))?;
+                *total = count.unwrap_or(0);
+            }
+            let [projects, companies, contacts, fees, active_fees] = totals;
+            Ok(StatsCounts { projects, companies, contacts, fees, active_fees })
+        } else {
+            Err(surrealdb::Error::Api(surrealdb::error::Api::InvalidRequest("No database connection".to_string())))
+        }
+    }
+    
+    // Count projects per country
+    pub async fn get_project_counts_by_country(&self) -> Result<Vec<CountryProjectCount>, Error> {
+        if let Some(client) = &self.client {
+            let mut response = match client {
+                DatabaseClient::Http(client) => client.query(PROJECT_COUNTS_BY_COUNTRY_QUERY).await?,
+                DatabaseClient::WebSocket(client) => client.query(PROJECT_COUNTS_BY_COUNTRY_QUERY).await?,
+            };
+            
+            response.take(0)
+        } else {
+            Err(surrealdb::Error::Api(surrealdb::error::Api::InvalidRequest("No database connection".to_string())))
+        }
+    }
+    
+    // Count projects per project number year
+    pub async fn get_project_counts_by_year(&self) -> Result<Vec<YearProjectCount>, Error> {
+        if let Some(client) = &self.client {
+            let mut response = match client {
+                DatabaseClient::Http(client) => client.query(PROJECT_COUNTS_BY_YEAR_QUERY).await?,
+                DatabaseClient::WebSocket(client) => client.query(PROJECT_COUNTS_BY_YEAR_QUERY).await?,
+            };
+            
+            response.take(0)
+        } else {
+            Err(surrealdb::Error::Api(surrealdb::error::Api::InvalidRequest("No database connection".to_string())))
+        }
+    }
+    
+    // Count the fee proposals that reference a contact
+    pub async fn count_fees_for_contact(&self, contact_id: &str) -> Result<u64, Error> {
+        if let Some(client) = &self.client {
+            let contact_id = contact_id.to_string();
+            
+            let mut response = match client {
+                DatabaseClient::Http(client) => client.query(FEES_FOR_CONTACT_COUNT_QUERY)
+                    .bind(("id", contact_id)).await?,
+                DatabaseClient::WebSocket(client) => client.query(FEES_FOR_CONTACT_COUNT_QUERY)
+                    .bind(("id", contact_id)).await?,
+            };
+            
+            let total: Option<u64> = response.take((0, "total"))?;
+            Ok(total.unwrap_or(0))
+        } else {
+            Err(surrealdb::Error::Api(surrealdb::error::Api::InvalidRequest("No database connection".to_string())))
+        }
+    }
+
+    // Get a fee's activity and package alongside its project's, as raw values
+    pub async fn get_fee_project_fields(&self, fee_id: &str) -> Result<serde_json::Value, Error> {
+        if let Some(client) = &self.client {
+            let fee_id = fee_id.to_string();
+            
+            let mut response = match client {
+                DatabaseClient::Http(client) => client.query(FEE_PROJECT_FIELDS_QUERY)
+                    .bind(("id", fee_id)).await?,
+                DatabaseClient::WebSocket(client) => client.query(FEE_PROJECT_FIELDS_QUERY)
+                    .bind(("id", fee_id)).await?,
+            };
+            
+            let mut rows: Vec<serde_json::Value> = response.take(0)?;
+            rows.pop().ok_or_else(|| surrealdb::Error::Api(surrealdb::error::Api::InvalidRequest("Fee not found".to_string())))
+        } else {
+            Err(surrealdb::Error::Api(surrealdb::error::Api::InvalidRequest("No database connection".to_string())))
+        }
+    }
+
     // Delete a contact
     pub async fn delete_contact(&self, id: &str) -> Result<Contact, Error> {
         if let Some(client) = &self.client {
@@ -1551,8 +2909,13 @@ impl DatabaseManager {
 
 
     // Create a new fee
-    pub async fn create_fee(&self, fee: FeeCreate) -> Result<Fee, Error> {
+    pub async fn create_fee(&self, mut fee: FeeCreate) -> Result<Fee, Error> {
+        fee.normalize_text();
         if let Some(client) = &self.client {
+            if fee.currency.as_deref().map_or(true, |code| code.trim().is_empty()) {
+                fee.currency = self.project_currency(client, &fee.project_id).await?;
+            }
+            
             let created: Option<Fee> = client.create_fee(fee).await?;
             
             created.ok_or_else(|| surrealdb::Error::Api(surrealdb::error::Api::InvalidRequest("Failed to create fee".to_string())))
@@ -1561,8 +2924,53 @@ impl DatabaseManager {
         }
     }
 
+    // Currency code of a project's country, if the country has one
+    async fn project_currency(&self, client: &DatabaseClient, project_id: &str) -> Result<Option<String>, Error> {
+        let project_id = project_id.to_string();
+        let mut response = match client {
+            DatabaseClient::Http(client) => client.query(PROJECT_CURRENCY_QUERY).bind(("project_id", project_id)).await?,
+            DatabaseClient::WebSocket(client) => client.query(PROJECT_CURRENCY_QUERY).bind(("project_id", project_id)).await?,
+        };
+        
+        let codes: Vec<String> = response.take(0)?;
+        Ok(codes.into_iter().next())
+    }
+
+    // Get the currency of a country by name
+    pub async fn get_currency_for_country(&self, country_name: &str) -> Result<CountryCurrency, Error> {
+        if let Some(client) = &self.client {
+            let name = country_name.to_string();
+            let mut response = match client {
+                DatabaseClient::Http(client) => client.query(COUNTRY_CURRENCY_QUERY).bind(("name", name)).await?,
+                DatabaseClient::WebSocket(client) => client.query(COUNTRY_CURRENCY_QUERY).bind(("name", name)).await?,
+            };
+            
+            let currency: Option<CountryCurrency> = response.take(0)?;
+            currency.ok_or_else(|| surrealdb::Error::Api(surrealdb::error::Api::InvalidRequest(
+                format!("No currency found for country '{}'", country_name)
+            )))
+        } else {
+            Err(surrealdb::Error::Api(surrealdb::error::Api::InvalidRequest("No database connection".to_string())))
+        }
+    }
+
+    // Get all currencies ordered by code
+    pub async fn get_currencies(&self) -> Result<Vec<Currency>, Error> {
+        if let Some(client) = &self.client {
+            let mut response = match client {
+                DatabaseClient::Http(client) => client.query(CURRENCIES_QUERY).await?,
+                DatabaseClient::WebSocket(client) => client.query(CURRENCIES_QUERY).await?,
+            };
+            
+            response.take(0)
+        } else {
+            Err(surrealdb::Error::Api(surrealdb::error::Api::InvalidRequest("No database connection".to_string())))
+        }
+    }
+
     // Update an existing fee
-    pub async fn update_fee(&self, id: &str, fee: FeeUpdate) -> Result<Fee, Error> {
+    pub async fn update_fee(&self, id: &str, mut fee: FeeUpdate) -> Result<Fee, Error> {
+        fee.normalize_text();
         if let Some(client) = &self.client {
             let updated: Option<Fee> = client.update_fee(id, fee).await?;
             
@@ -1583,8 +2991,69 @@ impl DatabaseManager {
         }
     }
 
+    // Record a new revision on a fee and bump its rev to match
+    pub async fn create_fee_revision(&self, id: &str, notes: &str, author_name: &str, author_email: &str) -> Result<Fee, Error> {
+        info!("Creating revision for fee {} by {}", id, author_email);
+        
+        if let Some(client) = &self.client {
+            let (id, notes, author_name, author_email) = (id.to_string(), notes.to_string(), author_name.to_string(), author_email.to_string());
+            
+            let mut response = match client {
+                DatabaseClient::Http(client) => client.query(FEE_REVISION_APPEND_QUERY)
+                    .bind(("id", id)).bind(("notes", notes)).bind(("author_name", author_name)).bind(("author_email", author_email)).await?,
+                DatabaseClient::WebSocket(client) => client.query(FEE_REVISION_APPEND_QUERY)
+                    .bind(("id", id)).bind(("notes", notes)).bind(("author_name", author_name)).bind(("author_email", author_email)).await?,
+            };
+            
+            let mut fees: Vec<Fee> = response.take(0)?;
+            fees.pop().ok_or_else(|| surrealdb::Error::Api(surrealdb::error::Api::InvalidRequest("Fee not found".to_string())))
+        } else {
+            Err(surrealdb::Error::Api(surrealdb::error::Api::InvalidRequest("No database connection".to_string())))
+        }
+    }
+
+    // Recompute each fee's rev from its revisions and fix mismatches
+    pub async fn reconcile_fee_revisions(&self) -> Result<u64, Error> {
+        if let Some(client) = &self.client {
+            let fees = self.get_fees().await?;
+            let mut repaired = 0u64;
+            
+            for fee in &fees {
+                if let (Some(id), Some(expected)) = (&fee.id, fee.expected_rev()) {
+                    if fee.rev != expected {
+                        let key = utils::normalize_thing_id(id);
+                        info!("Repairing fee {}: rev {} -> {}", key, fee.rev, expected);
+                        client.set_fee_rev(&key, expected).await?;
+                        repaired += 1;
+                    }
+                }
+            }
+            
+            Ok(repaired)
+        } else {
+            Err(surrealdb::Error::Api(surrealdb::error::Api::InvalidRequest("No database connection".to_string())))
+        }
+    }
+
+    // Convert string company references on contacts into record links
+    pub async fn repair_contact_company_links(&self) -> Result<u64, Error> {
+        if let Some(client) = &self.client {
+            let mut response = match client {
+                DatabaseClient::Http(client) => client.query(CONTACT_COMPANY_REPAIR_QUERY).await?,
+                DatabaseClient::WebSocket(client) => client.query(CONTACT_COMPANY_REPAIR_QUERY).await?,
+            };
+            
+            let repaired: Vec<serde_json::Value> = response.take(0)?;
+            info!("Repaired company links on {} contacts", repaired.len());
+            Ok(repaired.len() as u64)
+        } else {
+            Err(surrealdb::Error::Api(surrealdb::error::Api::InvalidRequest("No database connection".to_string())))
+        }
+    }
+
     // Update an existing project
-    pub async fn update_project(&self, id: &str, project_update: crate::commands::ProjectUpdate) -> Result<Project, Error> {
+    pub async fn update_project(&self, id: &str, mut project_update: crate::commands::ProjectUpdate) -> Result<Project, Error> {
+        project_update.normalize_text();
         if let Some(client) = &self.client {
             let updated: Option<Project> = client.update_project(id, project_update).await?;
             
@@ -1594,9 +3063,150 @@ impl DatabaseManager {
         }
     }
 
-    // Delete a project
+    // Get a single project by record key (e.g. "25_97105")
+    pub async fn get_project(&self, id: &str) -> Result<Option<Project>, Error> {
+        if let Some(client) = &self.client {
+            let id = id.to_string();
+            
+            let mut response = match client {
+                DatabaseClient::Http(client) => client.query(PROJECT_BY_ID_QUERY).bind(("id", id)).await?,
+                DatabaseClient::WebSocket(client) => client.query(PROJECT_BY_ID_QUERY).bind(("id", id)).await?,
+            };
+            
+            let mut projects: Vec<Project> = response.take(0)?;
+            Ok(projects.pop())
+        } else {
+            Err(surrealdb::Error::Api(surrealdb::error::Api::InvalidRequest("No database connection".to_string())))
+        }
+    }
+
+    // Look up a project by its formatted number
+    pub async fn get_project_by_number(&self, number: &str) -> Result<Option<Project>, Error> {
+        if let Some(client) = &self.client {
+            let number = number.to_string();
+            
+            let mut response = match client {
+                DatabaseClient::Http(client) => client.query(PROJECT_BY_NUMBER_QUERY)
+                    .bind(("number", number)).await?,
+                DatabaseClient::WebSocket(client) => client.query(PROJECT_BY_NUMBER_QUERY)
+                    .bind(("number", number)).await?,
+            };
+            
+            let mut projects: Vec<Project> = response.take(0)?;
+            Ok(projects.pop())
+        } else {
+            Err(surrealdb::Error::Api(surrealdb::error::Api::InvalidRequest("No database connection".to_string())))
+        }
+    }
+
+    // Get the template folder override of a project
+    pub async fn get_project_template_override(&self, id: &str) -> Result<Option<String>, Error> {
+        if let Some(client) = &self.client {
+            let id = id.to_string();
+            
+            let mut response = match client {
+                DatabaseClient::Http(client) => client.query(PROJECT_TEMPLATE_OVERRIDE_GET_QUERY)
+                    .bind(("id", id)).await?,
+                DatabaseClient::WebSocket(client) => client.query(PROJECT_TEMPLATE_OVERRIDE_GET_QUERY)
+                    .bind(("id", id)).await?,
+            };
+            
+            let overrides: Vec<Option<String>> = response.take(0)?;
+            Ok(overrides.into_iter().next().flatten())
+        } else {
+            Err(surrealdb::Error::Api(surrealdb::error::Api::InvalidRequest("No database connection".to_string())))
+        }
+    }
+
+    // Get the projects numbered just before and after a project number
+    pub async fn get_adjacent_projects(&self, number_id: &str) -> Result<AdjacentProjects, Error> {
+        let number = ProjectNumber::parse(number_id).ok_or_else(|| {
+            surrealdb::Error::Api(surrealdb::error::Api::InvalidRequest(format!("Invalid project number '{}', expected YY-CCCNN", number_id)))
+        })?;
+        
+        if let Some(client) = &self.client {
+            let mut response = match client {
+                DatabaseClient::Http(client) => client.query(PROJECT_PREV_QUERY).query(PROJECT_NEXT_QUERY)
+                    .bind(("year", number.year)).bind(("country", number.country)).bind(("seq", number.seq)).await?,
+                DatabaseClient::WebSocket(client) => client.query(PROJECT_PREV_QUERY).query(PROJECT_NEXT_QUERY)
+                    .bind(("year", number.year)).bind(("country", number.country)).bind(("seq", number.seq)).await?,
+            };
+            
+            let mut prev: Vec<Project> = response.take(0)?;
+            let mut next: Vec<Project> = response.take(1)?;
+            Ok(AdjacentProjects { prev: prev.pop(), next: next.pop() })
+        } else {
+            Err(surrealdb::Error::Api(surrealdb::error::Api::InvalidRequest("No database connection".to_string())))
+        }
+    }
+
+    // Set or clear the template folder override of a project
+    pub async fn set_project_template_override(&self, id: &str, template_override: Option<String>) -> Result<Project, Error> {
+        info!("Setting template override for project {}: {:?}", id, template_override);
+        
+        if let Some(client) = &self.client {
+            let id = id.to_string();
+            
+            let mut response = match client {
+                DatabaseClient::Http(client) => client.query(PROJECT_TEMPLATE_OVERRIDE_QUERY)
+                    .bind(("id", id)).bind(("template", template_override)).await?,
+                DatabaseClient::WebSocket(client) => client.query(PROJECT_TEMPLATE_OVERRIDE_QUERY)
+                    .bind(("id", id)).bind(("template", template_override)).await?,
+            };
+            
+            let mut projects: Vec<Project> = response.take(0)?;
+            projects.pop().ok_or_else(|| surrealdb::Error::Api(surrealdb::error::Api::InvalidRequest("Project not found".to_string())))
+        } else {
+            Err(surrealdb::Error::Api(surrealdb::error::Api::InvalidRequest("No database connection".to_string())))
+        }
+    }
+
+    // Archive (soft-delete) or restore a project
+    pub async fn set_project_archived(&self, id: &str, archived: bool) -> Result<Project, Error> {
+        info!("Setting archived = {} for project {}", archived, id);
+        
+        if let Some(client) = &self.client {
+            let query = if archived { PROJECT_ARCHIVE_QUERY } else { PROJECT_RESTORE_QUERY };
+            let id = id.to_string();
+            
+            let mut response = match client {
+                DatabaseClient::Http(client) => client.query(query).bind(("id", id)).await?,
+                DatabaseClient::WebSocket(client) => client.query(query).bind(("id", id)).await?,
+            };
+            
+            let mut projects: Vec<Project> = response.take(0)?;
+            projects.pop().ok_or_else(|| surrealdb::Error::Api(surrealdb::error::Api::InvalidRequest("Project not found".to_string())))
+        } else {
+            Err(surrealdb::Error::Api(surrealdb::error::Api::InvalidRequest("No database connection".to_string())))
+        }
+    }
+
+    // Permanently delete a project; it must be archived first and in a terminal status
     pub async fn delete_project(&self, id: &str) -> Result<Project, Error> {
         if let Some(client) = &self.client {
+            let key = id.to_string();
+            let mut response = match client {
+                DatabaseClient::Http(client) => client.query(PROJECT_DELETED_AT_QUERY).bind(("id", key)).await?,
+                DatabaseClient::WebSocket(client) => client.query(PROJECT_DELETED_AT_QUERY).bind(("id", key)).await?,
+            };
+            let deleted_at: Vec<serde_json::Value> = response.take(0)?;
+            match deleted_at.first() {
+                None => return Err(surrealdb::Error::Api(surrealdb::error::Api::InvalidRequest("Project not found".to_string()))),
+                Some(value) if value.is_null() => return Err(surrealdb::Error::Api(surrealdb::error::Api::InvalidRequest(
+                    "Project must be archived before it can be deleted".to_string()
+                ))),
+                Some(_) => {}
+            }
+            
+            if let Some(project) = self.get_project(id).await? {
+                if !project.is_terminal() {
+                    return Err(surrealdb::Error::Api(surrealdb::error::Api::InvalidRequest(format!(
+                        "Project is '{}'; only Completed or Cancelled projects can be deleted",
+                        project.status
+                    ))));
+                }
+            }
+            
             let deleted: Option<Project> = client.delete_project(id).await?;
             
             deleted.ok_or_else(|| surrealdb::Error::Api(surrealdb::error::Api::InvalidRequest("Failed to delete project".to_string())))
@@ -1605,6 +3215,56 @@ impl DatabaseManager {
         }
     }
 
+    // Hard-delete a just-created project, skipping the archive-first rule of delete_project
+    pub async fn discard_project(&self, id: &str) -> Result<Project, Error> {
+        if let Some(client) = &self.client {
+            let deleted: Option<Project> = client.delete_project(id).await?;
+            
+            deleted.ok_or_else(|| surrealdb::Error::Api(surrealdb::error::Api::InvalidRequest("Failed to delete project".to_string())))
+        } else {
+            Err(surrealdb::Error::Api(surrealdb::error::Api::InvalidRequest("No database connection".to_string())))
+        }
+    }
+
+    // Get any record by table and key as raw JSON
+    pub async fn get_record(&self, table: &str, id: &str) -> Result<Option<serde_json::Value>, Error> {
+        if let Some(client) = &self.client {
+            let (table, id) = (table.to_string(), id.to_string());
+            let mut response = match client {
+                DatabaseClient::Http(client) => client.query(RECORD_BY_ID_QUERY).bind(("table", table)).bind(("id", id)).await?,
+                DatabaseClient::WebSocket(client) => client.query(RECORD_BY_ID_QUERY).bind(("table", table)).bind(("id", id)).await?,
+            };
+            
+            let records: Vec<serde_json::Value> = response.take(0)?;
+            Ok(records.into_iter().next())
+        } else {
+            Err(surrealdb::Error::Api(surrealdb::error::Api::InvalidRequest("No database connection".to_string())))
+        }
+    }
+
+    // Check the connected user can write, using a throwaway record
+    pub async fn check_write_permission(&self) -> Result<bool, Error> {
+        if let Some(client) = &self.client {
+            let id = uuid::Uuid::new_v4().simple().to_string();
+            let response = match client {
+                DatabaseClient::Http(client) => client.query(WRITE_PROBE_QUERY).bind(("id", id)).await,
+                DatabaseClient::WebSocket(client) => client.query(WRITE_PROBE_QUERY).bind(("id", id)).await,
+            };
+            
+            let created = response.and_then(|mut response| {
+                if let Err(e) = response.take::<Vec<serde_json::Value>>(1) {
+                    warn!("Write probe record could not be deleted: {}", e);
+                }
+                response.take::<Vec<serde_json::Value>>(0)
+            });
+            let permitted = write_permitted(created)?;
+            info!("Database write permission: {}", permitted);
+            Ok(permitted)
+        } else {
+            Err(surrealdb::Error::Api(surrealdb::error::Api::InvalidRequest("No database connection".to_string())))
+        }
+    }
+
     // Get table schema information
     pub async fn get_table_schema(&self, table_name: &str) -> Result<serde_json::Value, Error> {
         if let Some(client) = &self.client {
@@ -1622,6 +3282,55 @@ impl DatabaseManager {
         }
     }
 
+    // Get database-level information (tables, functions, params, ...)
+    pub async fn get_database_info(&self) -> Result<DatabaseInfo, Error> {
+        if let Some(client) = &self.client {
+            let mut result = match client {
+                DatabaseClient::Http(client) => client.query("INFO FOR DB;").await?,
+                DatabaseClient::WebSocket(client) => client.query("INFO FOR DB;").await?,
+            };
+            
+            let info: Option<serde_json::Value> = result.take(0)?;
+            Ok(info.map(|value| DatabaseInfo::from_info_value(&value)).unwrap_or_default())
+        } else {
+            Err(surrealdb::Error::Api(surrealdb::error::Api::InvalidRequest("No database connection".to_string())))
+        }
+    }
+
+    // Confirm the configured namespace and database are actually defined
+    pub async fn verify_namespace_database(&self) -> Result<bool, Error> {
+        if let Some(client) = &self.client {
+            info!("Verifying namespace '{}' and database '{}'", self.config.namespace, self.config.database);
+            
+            // Namespace and database users may not read root info; skip the namespace check then
+            let root_response = match client {
+                DatabaseClient::Http(client) => client.query("INFO FOR ROOT").await,
+                DatabaseClient::WebSocket(client) => client.query("INFO FOR ROOT").await,
+            };
+            let root_info: Option<serde_json::Value> = root_response.ok()
+                .and_then(|mut response| response.take(0).ok().flatten());
+            
+            let mut ns_response = match client {
+                DatabaseClient::Http(client) => client.query("INFO FOR NS").await?,
+                DatabaseClient::WebSocket(client) => client.query("INFO FOR NS").await?,
+            };
+            let ns_info: Option<serde_json::Value> = ns_response.take(0).map_err(|e| {
+                surrealdb::Error::Api(surrealdb::error::Api::InvalidRequest(format!(
+                    "Namespace '{}' does not exist or is not accessible: {}", self.config.namespace, e
+                )))
+            })?;
+            
+            utils::check_namespace_database(&self.config.namespace, &self.config.database, root_info.as_ref(), &ns_info.unwrap_or_default())
+                .map_err(|e| {
+                    warn!("{}", e);
+                    surrealdb::Error::Api(surrealdb::error::Api::InvalidRequest(e))
+                })?;
+            Ok(true)
+        } else {
+            Err(surrealdb::Error::Api(surrealdb::error::Api::InvalidRequest("No database connection".to_string())))
+        }
+    }
+
     // Investigate a specific database record
     pub async fn investigate_record(&self, record_id: &str) -> Result<serde_json::Value, Error> {
         if let Some(client) = &self.client {
@@ -1730,52 +3439,109 @@ impl DatabaseManager {
         }
     }
     
-    // Generate next project number for given country name and year
-    pub async fn generate_next_project_number(&self, country_name: &str, year: Option<u8>) -> Result<String, Error> {
-        info!("Generating next project number for country: {}, year: {:?}", country_name, year);
+    // Look up the dial code of a country by its name
+    async fn country_dial_code(&self, client: &DatabaseClient, country_name: &str) -> Result<u16, Error> {
+        info!("Looking up country code for: {}", country_name);
+        
+        let name = country_name.to_string();
+        let mut country_response = match client {
+            DatabaseClient::Http(client) => client.query(COUNTRY_DIAL_CODE_QUERY).bind(("name", name)).await?,
+            DatabaseClient::WebSocket(client) => client.query(COUNTRY_DIAL_CODE_QUERY).bind(("name", name)).await?,
+        };
+        
+        let records: Vec<serde_json::Value> = country_response.take(0)?;
+        let first = records.first().ok_or_else(|| surrealdb::Error::Api(surrealdb::error::Api::InvalidRequest(
+            format!("Country not found: {}", country_name)
+        )))?;
+        let dial_code_value = first.get("dial_code").ok_or_else(|| surrealdb::Error::Api(surrealdb::error::Api::InvalidRequest(
+            format!("No dial_code field found for country: {}", country_name)
+        )))?;
+        dial_code_value.as_u64().map(|dial_code| dial_code as u16).ok_or_else(|| surrealdb::Error::Api(surrealdb::error::Api::InvalidRequest(
+            format!("Dial code is not a number for country: {}", country_name)
+        )))
+    }
+    
+    // Reserve a block of consecutive project numbers for a country and year
+    pub async fn allocate_project_number_block(&self, country_name: &str, year: u8, count: u32) -> Result<Vec<String>, Error> {
+        info!("Allocating {} project numbers for country: {}, year: {}", count, country_name, year);
         
         if let Some(client) = &self.client {
-            // First, look up the dial code from the country name  
-            let country_lookup_query = format!(
-                "SELECT dial_code FROM country WHERE name = '{}' LIMIT 1",
-                country_name
-            );
+            let country_code = self.country_dial_code(client, country_name).await?;
             
-            info!("Looking up country code for: {}", country_name);
+            let mut response = match client {
+                DatabaseClient::Http(client) => client.query(PROJECT_MAX_SEQ_QUERY)
+                    .bind(("year", year)).bind(("country", country_code)).await?,
+                DatabaseClient::WebSocket(client) => client.query(PROJECT_MAX_SEQ_QUERY)
+                    .bind(("year", year)).bind(("country", country_code)).await?,
+            };
+            let records: Vec<serde_json::Value> = response.take(0)?;
+            let max_seq = records.first()
+                .and_then(|record| record["number"]["seq"].as_i64())
+                .unwrap_or(0) as i32;
+            
+            let numbers = ProjectNumber::block(year as i32, country_code as i32, max_seq + 1, count)
+                .map_err(|e| surrealdb::Error::Api(surrealdb::error::Api::InvalidRequest(e)))?;
+            let ids: Vec<String> = numbers.iter().map(|number| number.id.clone()).collect();
+            
+            let response = match client {
+                DatabaseClient::Http(client) => client.query(PROJECT_RESERVATION_BLOCK_QUERY)
+                    .bind(("numbers", numbers)).await?,
+                DatabaseClient::WebSocket(client) => client.query(PROJECT_RESERVATION_BLOCK_QUERY)
+                    .bind(("numbers", numbers)).await?,
+            };
+            response.check()?;
             
-            let mut country_response = match client {
-                DatabaseClient::Http(client) => client.query(&country_lookup_query).await?,
-                DatabaseClient::WebSocket(client) => client.query(&country_lookup_query).await?,
+            info!("Reserved project numbers {:?}", ids);
+            Ok(ids)
+        } else {
+            Err(surrealdb::Error::Api(surrealdb::error::Api::InvalidRequest("No database connection".to_string())))
+        }
+    }
+    
+    // Remove unused project number reservations for a country and year
+    pub async fn clear_project_number_reservations(&self, country_name: &str, year: u8) -> Result<u64, Error> {
+        info!("Clearing project number reservations for country: {}, year: {}", country_name, year);
+        
+        if let Some(client) = &self.client {
+            let country_code = self.country_dial_code(client, country_name).await?;
+            
+            let mut response = match client {
+                DatabaseClient::Http(client) => client.query(PROJECT_RESERVATION_CLEAR_QUERY)
+                    .bind(("year", year)).bind(("country", country_code)).await?,
+                DatabaseClient::WebSocket(client) => client.query(PROJECT_RESERVATION_CLEAR_QUERY)
+                    .bind(("year", year)).bind(("country", country_code)).await?,
             };
             
-            let country_result: Result<Vec<serde_json::Value>, _> = country_response.take(0);
-            let country_code = match country_result {
-                Ok(records) => {
-                    if let Some(first) = records.first() {
-                        if let Some(dial_code_value) = first.get("dial_code") {
-                            if let Some(dial_code) = dial_code_value.as_u64() {
-                                dial_code as u16
-                            } else {
-                                return Err(surrealdb::Error::Api(surrealdb::error::Api::InvalidRequest(
-                                    format!("Dial code is not a number for country: {}", country_name)
-                                )));
-                            }
-                        } else {
-                            return Err(surrealdb::Error::Api(surrealdb::error::Api::InvalidRequest(
-                                format!("No dial_code field found for country: {}", country_name)
-                            )));
-                        }
-                    } else {
-                        return Err(surrealdb::Error::Api(surrealdb::error::Api::InvalidRequest(
-                            format!("Country not found: {}", country_name)
-                        )));
-                    }
-                }
-                Err(e) => {
-                    return Err(e);
-                }
+            let cleared: Vec<serde_json::Value> = response.take(0)?;
+            info!("Cleared {} project number reservations", cleared.len());
+            Ok(cleared.len() as u64)
+        } else {
+            Err(surrealdb::Error::Api(surrealdb::error::Api::InvalidRequest("No database connection".to_string())))
+        }
+    }
+    
+    // Country-years with fewer than `threshold` project numbers left
+    pub async fn get_capacity_warnings(&self, threshold: u32) -> Result<Vec<CapacityWarning>, Error> {
+        if let Some(client) = &self.client {
+            let mut response = match client {
+                DatabaseClient::Http(client) => client.query(PROJECT_CAPACITY_QUERY).bind(("threshold", threshold)).await?,
+                DatabaseClient::WebSocket(client) => client.query(PROJECT_CAPACITY_QUERY).bind(("threshold", threshold)).await?,
             };
             
+            response.take(0)
+        } else {
+            Err(surrealdb::Error::Api(surrealdb::error::Api::InvalidRequest("No database connection".to_string())))
+        }
+    }
+    
+    // Generate next project number for given country name and year
+    pub async fn generate_next_project_number(&self, country_name: &str, year: Option<u8>) -> Result<String, Error> {
+        info!("Generating next project number for country: {}, year: {:?}", country_name, year);
+        
+        if let Some(client) = &self.client {
+            // First, look up the dial code from the country name  
+            let country_code = self.country_dial_code(client, country_name).await?;
+            
             info!("Found country code {} for country {}", country_code, country_name);
             
             // Get current year if not provided
@@ -1785,16 +3551,13 @@ impl DatabaseManager {
             });
             
             // Query to find the max sequence number for the given year and country
-            let query = format!(
-                "SELECT number.seq FROM projects WHERE number.year = {} AND number.country = {} AND number.seq >= 1 AND number.seq <= 99 ORDER BY number.seq DESC LIMIT 1",
-                year, country_code
-            );
-            
-            info!("Executing query: {}", query);
+            info!("Looking up highest sequence for year {} and country {}", year, country_code);
             
             let mut response = match client {
-                DatabaseClient::Http(client) => client.query(&query).await?,
-                DatabaseClient::WebSocket(client) => client.query(&query).await?,
+                DatabaseClient::Http(client) => client.query(PROJECT_MAX_SEQ_QUERY)
+                    .bind(("year", year)).bind(("country", country_code)).await?,
+                DatabaseClient::WebSocket(client) => client.query(PROJECT_MAX_SEQ_QUERY)
+                    .bind(("year", year)).bind(("country", country_code)).await?,
             };
             
             let result: Result<Vec<serde_json::Value>, _> = response.take(0);
@@ -1833,16 +3596,7 @@ impl DatabaseManager {
                 },
             };
             
-            // Check if sequence would exceed 99 (business rule limit)
-            if next_seq > 99 {
-                error!("Sequence number {} exceeds limit of 99 for year {} country {}", next_seq, year, country_code);
-                return Err(surrealdb::Error::Api(surrealdb::error::Api::InvalidRequest(
-                    format!("Maximum of 99 projects per year per country reached for year {} country {}", year, country_code)
-                )));
-            }
-            
-            // Format the project number: YY-CCCNN (sequence always 2 digits)
-            let project_number = format!("{:02}-{}{:02}", year, country_code, next_seq);
+            let project_number = next_project_number(year, country_code, next_seq)?;
             info!("Generated project number: {}", project_number);
             
             Ok(project_number)
@@ -1912,21 +3666,29 @@ impl DatabaseManager {
         }
     }
     
+    // Get which of the given project numbers already exist, in one query
+    pub async fn get_taken_project_numbers(&self, numbers: Vec<String>) -> Result<Vec<String>, Error> {
+        if let Some(client) = &self.client {
+            let mut response = match client {
+                DatabaseClient::Http(client) => client.query(PROJECT_NUMBERS_TAKEN_QUERY).bind(("numbers", numbers)).await?,
+                DatabaseClient::WebSocket(client) => client.query(PROJECT_NUMBERS_TAKEN_QUERY).bind(("numbers", numbers)).await?,
+            };
+            
+            response.take(0)
+        } else {
+            Err(surrealdb::Error::Api(surrealdb::error::Api::InvalidRequest("No database connection".to_string())))
+        }
+    }
+    
     // Search countries with fuzzy matching
     pub async fn search_countries(&self, query: &str) -> Result<Vec<serde_json::Value>, Error> {
         info!("Searching countries with query: {}", query);
         
         if let Some(client) = &self.client {
-            let search_query = format!(
-                "SELECT name, name_formal, name_official, code, code_alt, dial_code FROM country WHERE (name IS NOT NONE AND string::lowercase(name) CONTAINS string::lowercase('{}')) OR (name_formal IS NOT NONE AND string::lowercase(name_formal) CONTAINS string::lowercase('{}')) OR (name_official IS NOT NONE AND string::lowercase(name_official) CONTAINS string::lowercase('{}')) OR (code IS NOT NONE AND string::lowercase(code) CONTAINS string::lowercase('{}')) OR (code_alt IS NOT NONE AND string::lowercase(code_alt) CONTAINS string::lowercase('{}')) OR (dial_code IS NOT NONE AND string::contains(<string>dial_code, '{}')) ORDER BY name ASC LIMIT 15",
-                query, query, query, query, query, query
-            );
-            
-            info!("Executing country search query: {}", search_query);
-            
+            let term = query.to_string();
             let mut response = match client {
-                DatabaseClient::Http(client) => client.query(&search_query).await?,
-                DatabaseClient::WebSocket(client) => client.query(&search_query).await?,
+                DatabaseClient::Http(client) => client.query(COUNTRIES_SEARCH_QUERY).bind(("query", term)).await?,
+                DatabaseClient::WebSocket(client) => client.query(COUNTRIES_SEARCH_QUERY).bind(("query", term)).await?,
             };
             
             let result: Result<Vec<serde_json::Value>, _> = response.take(0);
@@ -1945,21 +3707,48 @@ impl DatabaseManager {
         }
     }
 
+    // Import countries, matched by name; existing countries are kept unless `replace`
+    pub async fn import_countries(&self, countries: Vec<serde_json::Value>, replace: bool) -> Result<u64, Error> {
+        if let Some(client) = &self.client {
+            let countries: Vec<serde_json::Value> = if replace {
+                countries
+            } else {
+                let mut response = match client {
+                    DatabaseClient::Http(client) => client.query(COUNTRY_NAMES_QUERY).await?,
+                    DatabaseClient::WebSocket(client) => client.query(COUNTRY_NAMES_QUERY).await?,
+                };
+                let existing: Vec<String> = response.take(0)?;
+                countries
+                    .into_iter()
+                    .filter(|country| !country["record"]["name"].as_str().is_some_and(|name| existing.iter().any(|e| e == name)))
+                    .collect()
+            };
+            
+            let count = countries.len() as u64;
+            if count > 0 {
+                let response = match client {
+                    DatabaseClient::Http(client) => client.query(COUNTRY_IMPORT_QUERY).bind(("countries", countries)).await?,
+                    DatabaseClient::WebSocket(client) => client.query(COUNTRY_IMPORT_QUERY).bind(("countries", countries)).await?,
+                };
+                response.check()?;
+            }
+            
+            info!("Imported {} countries", count);
+            Ok(count)
+        } else {
+            Err(surrealdb::Error::Api(surrealdb::error::Api::InvalidRequest("No database connection".to_string())))
+        }
+    }
+
     // Get area suggestions for a country
     pub async fn get_area_suggestions(&self, country: &str) -> Result<Vec<String>, Error> {
         info!("Getting area suggestions for country: {}", country);
         
         if let Some(client) = &self.client {
-            let query = format!(
-                "SELECT area FROM projects WHERE country = '{}' AND area IS NOT NONE GROUP BY area ORDER BY area ASC LIMIT 20",
-                country.replace("'", "''") // Escape single quotes
-            );
-            
-            info!("Executing area suggestions query: {}", query);
-            
+            let country_name = country.to_string();
             let mut response = match client {
-                DatabaseClient::Http(client) => client.query(&query).await?,
-                DatabaseClient::WebSocket(client) => client.query(&query).await?,
+                DatabaseClient::Http(client) => client.query(AREA_SUGGESTIONS_QUERY).bind(("country", country_name)).await?,
+                DatabaseClient::WebSocket(client) => client.query(AREA_SUGGESTIONS_QUERY).bind(("country", country_name)).await?,
             };
             
             let result: Result<Vec<serde_json::Value>, _> = response.take(0);
@@ -1982,21 +3771,39 @@ impl DatabaseManager {
         }
     }
 
+    // Get projects in a city, optionally filtered by area
+    pub async fn get_projects_by_area(&self, country: &str, city: &str, area: Option<&str>) -> Result<Vec<Project>, Error> {
+        info!("Getting projects by area: {} / {} / {:?}", country, city, area);
+        
+        if let Some(client) = &self.client {
+            let country = country.to_string();
+            let city = city.to_string();
+            let area = area.map(|a| a.to_string());
+            
+            let mut response = match client {
+                DatabaseClient::Http(client) => client.query(PROJECTS_BY_AREA_QUERY)
+                    .bind(("country", country)).bind(("city", city)).bind(("area", area)).await?,
+                DatabaseClient::WebSocket(client) => client.query(PROJECTS_BY_AREA_QUERY)
+                    .bind(("country", country)).bind(("city", city)).bind(("area", area)).await?,
+            };
+            
+            let projects: Vec<Project> = response.take(0)?;
+            info!("Found {} projects", projects.len());
+            Ok(projects)
+        } else {
+            Err(surrealdb::Error::Api(surrealdb::error::Api::InvalidRequest("No database connection".to_string())))
+        }
+    }
+
     // Get city suggestions for a country
     pub async fn get_city_suggestions(&self, country: &str) -> Result<Vec<String>, Error> {
         info!("Getting city suggestions for country: {}", country);
         
         if let Some(client) = &self.client {
-            let query = format!(
-                "SELECT city FROM projects WHERE country = '{}' AND city IS NOT NONE GROUP BY city ORDER BY city ASC LIMIT 20",
-                country.replace("'", "''") // Escape single quotes
-            );
-            
-            info!("Executing city suggestions query: {}", query);
-            
+            let country_name = country.to_string();
             let mut response = match client {
-                DatabaseClient::Http(client) => client.query(&query).await?,
-                DatabaseClient::WebSocket(client) => client.query(&query).await?,
+                DatabaseClient::Http(client) => client.query(CITY_SUGGESTIONS_QUERY).bind(("country", country_name)).await?,
+                DatabaseClient::WebSocket(client) => client.query(CITY_SUGGESTIONS_QUERY).bind(("country", country_name)).await?,
             };
             
             let result: Result<Vec<serde_json::Value>, _> = response.take(0);
@@ -2088,4 +3895,34 @@ impl DatabaseManager {
             Err(surrealdb::Error::Api(surrealdb::error::Api::InvalidRequest("No database connection".to_string())))
         }
     }
+
+    // Get the distinct activities used across fees
+    pub async fn get_distinct_activities(&self) -> Result<Vec<String>, Error> {
+        self.get_distinct_fee_values(FEE_ACTIVITIES_QUERY, "activity").await
+    }
+
+    // Get the distinct packages used across fees
+    pub async fn get_distinct_packages(&self) -> Result<Vec<String>, Error> {
+        self.get_distinct_fee_values(FEE_PACKAGES_QUERY, "package").await
+    }
+
+    async fn get_distinct_fee_values(&self, query: &str, field: &str) -> Result<Vec<String>, Error> {
+        if let Some(client) = &self.client {
+            let mut response = match client {
+                DatabaseClient::Http(client) => client.query(query).await?,
+                DatabaseClient::WebSocket(client) => client.query(query).await?,
+            };
+            
+            let rows: Vec<serde_json::Value> = response.take(0)?;
+            let values: Vec<String> = rows
+                .into_iter()
+                .filter_map(|row| row.get(field).and_then(|v| v.as_str()).map(|s| s.to_string()))
+                .collect();
+            
+            info!("Found {} distinct fee {} values", values.len(), field);
+            Ok(values)
+        } else {
+            Err(surrealdb::Error::Api(surrealdb::error::Api::InvalidRequest("No database connection".to_string())))
+        }
+    }
 }
\ No newline at end of file
@@ -9,7 +9,8 @@
 
 #[cfg(test)]
 mod tests {
-    use crate::db::DatabaseConfig;
+    use crate::db::{heartbeat_status, next_project_number, prepare_new_project, reconnect_backoff, write_permitted, AREA_SUGGESTIONS_QUERY, CITY_SUGGESTIONS_QUERY, COMPANIES_COUNT_QUERY, COMPANIES_ORDERED_QUERY, COMPANIES_PAGE_QUERY, COMPANY_ABBREVIATION_QUERY, COMPANY_CREATE_QUERY, CONTACT_COMPANY_REPAIR_QUERY, CONTACT_CREATE_QUERY, CONTACT_EMAIL_IN_USE_QUERY, COUNTRIES_SEARCH_QUERY, COUNTRY_CURRENCY_QUERY, COUNTRY_DIAL_CODE_QUERY, COUNTRY_IMPORT_QUERY, COUNTRY_LOOKUP_QUERY, COUNTRY_NAMES_QUERY, CURRENCIES_QUERY, FEES_ALL_QUERY, FEES_COUNT_QUERY, FEES_FOR_CONTACT_COUNT_QUERY, FEES_FOR_PROJECT_QUERY, FEES_PAGE_QUERY, FEES_SEARCH_QUERY, FEE_ACTIVITIES_QUERY, FEE_CREATE_QUERY, FEE_PACKAGES_QUERY, FEE_PROJECT_FIELDS_QUERY, FEE_REVISION_APPEND_QUERY, FEE_ROW_REPAIR_QUERY, FEE_UPDATE_QUERY, PROJECTS_BY_AREA_QUERY, PROJECTS_COUNT_QUERY, PROJECTS_MODIFIED_SINCE_QUERY, PROJECTS_ORDERED_QUERY, PROJECTS_PAGE_QUERY, PROJECTS_SEARCH_QUERY, PROJECT_ARCHIVE_QUERY, PROJECT_BY_ID_QUERY, PROJECT_BY_NUMBER_QUERY, PROJECT_CAPACITY_QUERY, PROJECT_COUNTS_BY_COUNTRY_QUERY, PROJECT_COUNTS_BY_YEAR_QUERY, PROJECT_CREATE_QUERY, PROJECT_CURRENCY_QUERY, PROJECT_DELETED_AT_QUERY, PROJECT_MAX_SEQ_QUERY, PROJECT_NEXT_QUERY, PROJECT_NUMBERS_TAKEN_QUERY, PROJECT_PREV_QUERY, PROJECT_RESERVATION_BLOCK_QUERY, PROJECT_RESERVATION_CLEAR_QUERY, PROJECT_RESTORE_QUERY, PROJECT_SAMPLE_QUERY, PROJECT_TEMPLATE_OVERRIDE_GET_QUERY, PROJECT_TEMPLATE_OVERRIDE_QUERY, RECORD_BY_ID_QUERY, STATS_COUNTS_QUERY, TERMINAL_PROJECT_STATUSES, WRITE_PROBE_QUERY, CapacityWarning, CompanyCreate, Contact, ContactCreate, CountryCurrency, CountryProjectCount, Currency, DatabaseConfig, DatabaseInfo, DatabaseManager, Fee, FeeCreate, FeeUpdate, NewProject, Project, ProjectNumber, Revision, StatsCounts, TimeStamps, YearProjectCount};
+    use crate::db::utils::{check_namespace_database, incomplete_contacts, normalize_abbreviation, normalize_text, normalize_thing_id, partition_fee_rows, project_schema_gaps, record_key, resolve_country_name, sort_contacts_by_name, thing_matches, PROJECT_FIELDS, NormalizeText};
     use std::env;
 
     // ============================================================================
@@ -142,6 +143,400 @@ mod tests {
         }
     }
 
+    /// Text that broke the old quote escaping: quotes, backslashes and Unicode.
+    const AWKWARD_TEXT: &str = "O'Brien \\' Tower\\ – Dubaï 東京 ''";
+
+    #[tokio::test]
+    async fn test_project_create_and_search_round_trip_awkward_text() {
+        let db = memory_db().await;
+        let project = NewProject {
+            name: AWKWARD_TEXT.to_string(),
+            name_short: "Brien's".to_string(),
+            status: "RFP".to_string(),
+            area: AWKWARD_TEXT.to_string(),
+            city: "Dubai".to_string(),
+            country: "Côte d'Ivoire".to_string(),
+            folder: format!("25-22505 {}", AWKWARD_TEXT),
+            number: ProjectNumber::new(25, 225, 5),
+            activity: None,
+            package: None,
+            template_override: Some(AWKWARD_TEXT.to_string()),
+        };
+        let mut response = db
+            .query(PROJECT_CREATE_QUERY)
+            .bind(("id", "25_22505".to_string()))
+            .bind(project)
+            .await
+            .unwrap();
+        let created: Vec<serde_json::Value> = response.take(0).unwrap();
+        assert_eq!(created[0]["name"], AWKWARD_TEXT);
+        assert_eq!(created[0]["template_override"], AWKWARD_TEXT);
+        assert_eq!(created[0]["number"]["id"], "25-22505");
+
+        async fn search(db: &surrealdb::Surreal<surrealdb::engine::local::Db>, term: &str) -> usize {
+            let mut response = db.query(PROJECTS_SEARCH_QUERY).bind(("query", term.to_string())).await.unwrap();
+            let projects: Vec<serde_json::Value> = response.take(0).unwrap();
+            projects.len()
+        }
+        assert_eq!(search(&db, "o'brien \\'").await, 1);
+        assert_eq!(search(&db, "東京 ''").await, 1);
+        assert_eq!(search(&db, "' OR 1=1--").await, 0);
+
+        async fn suggestions(db: &surrealdb::Surreal<surrealdb::engine::local::Db>, query: &str, field: &str) -> Vec<String> {
+            let mut response = db.query(query).bind(("country", "Côte d'Ivoire".to_string())).await.unwrap();
+            let rows: Vec<serde_json::Value> = response.take(0).unwrap();
+            rows.iter().filter_map(|row| row[field].as_str().map(str::to_string)).collect()
+        }
+        assert_eq!(suggestions(&db, AREA_SUGGESTIONS_QUERY, "area").await, vec![AWKWARD_TEXT.to_string()]);
+        assert_eq!(suggestions(&db, CITY_SUGGESTIONS_QUERY, "city").await, vec!["Dubai".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_company_abbreviation_in_use_ignoring_case() {
+        let db = memory_db().await;
+        let mut company = CompanyCreate {
+            name: "Chedi Hospitality".to_string(),
+            name_short: "Chedi".to_string(),
+            abbreviation: " che ".to_string(),
+            city: "Dubai".to_string(),
+            country: "United Arab Emirates".to_string(),
+            reg_no: None,
+            tax_no: None,
+        };
+        company.normalize_text();
+        assert_eq!(company.abbreviation, "CHE");
+        db.query(COMPANY_CREATE_QUERY).bind(company).await.unwrap();
+        db.query("CREATE company:dmcc SET name = 'Legacy lowercase key'").await.unwrap();
+
+        async fn in_use(db: &surrealdb::Surreal<surrealdb::engine::local::Db>, abbr: &str) -> bool {
+            let mut response = db
+                .query(COMPANY_ABBREVIATION_QUERY)
+                .bind(("abbreviation", normalize_abbreviation(abbr)))
+                .await
+                .unwrap();
+            let ids: Vec<surrealdb::sql::Thing> = response.take(0).unwrap();
+            !ids.is_empty()
+        }
+        assert!(in_use(&db, "CHE").await);
+        assert!(in_use(&db, "che ").await);
+        assert!(in_use(&db, "DMCC").await);
+        assert!(!in_use(&db, "EMT").await);
+    }
+
+    #[tokio::test]
+    async fn test_company_contact_fee_round_trip_awkward_text() {
+        let db = memory_db().await;
+        let company = CompanyCreate {
+            name: AWKWARD_TEXT.to_string(),
+            name_short: "O'B".to_string(),
+            abbreviation: "OBT".to_string(),
+            city: "Dubai".to_string(),
+            country: "Côte d'Ivoire".to_string(),
+            reg_no: Some("R\\1'".to_string()),
+            tax_no: None,
+        };
+        let mut response = db.query(COMPANY_CREATE_QUERY).bind(company).await.unwrap();
+        let companies: Vec<serde_json::Value> = response.take(0).unwrap();
+        assert_eq!(companies[0]["name"], AWKWARD_TEXT);
+        assert_eq!(companies[0]["reg_no"], "R\\1'");
+        assert!(companies[0]["tax_no"].is_null());
+
+        let contact = ContactCreate {
+            first_name: "Seán".to_string(),
+            last_name: "O'Brien".to_string(),
+            email: "sean.o'brien@example.com".to_string(),
+            phone: "+971 \\ 50".to_string(),
+            position: AWKWARD_TEXT.to_string(),
+            company: "OBT".to_string(),
+        };
+        let mut response = db
+            .query(CONTACT_CREATE_QUERY)
+            .bind(("full_name", "Seán O'Brien".to_string()))
+            .bind(contact)
+            .await
+            .unwrap();
+        let contacts: Vec<Contact> = response.take(0).unwrap();
+        assert_eq!(contacts[0].full_name.as_deref(), Some("Seán O'Brien"));
+        assert_eq!(contacts[0].position.as_deref(), Some(AWKWARD_TEXT));
+        assert_eq!(contacts[0].company.as_ref().map(|c| c.to_string()).as_deref(), Some("company:OBT"));
+        let contact_key = contacts[0].id.as_ref().unwrap().id.to_string().trim_start_matches('⟨').trim_end_matches('⟩').to_string();
+
+        let fee = FeeCreate {
+            name: AWKWARD_TEXT.to_string(),
+            number: "25-22505-FP".to_string(),
+            rev: 1,
+            status: "Draft".to_string(),
+            issue_date: "251016".to_string(),
+            activity: "Design & Build".to_string(),
+            package: "Lighting's".to_string(),
+            project_id: "25_22505".to_string(),
+            company_id: "OBT".to_string(),
+            contact_id: contact_key.clone(),
+            staff_name: "Zoë".to_string(),
+            staff_email: "zoe@example.com".to_string(),
+            staff_phone: "+971".to_string(),
+            staff_position: "Lead".to_string(),
+            strap_line: AWKWARD_TEXT.to_string(),
+            revisions: vec![],
+            currency: Some("AED".to_string()),
+            amount: Some(125_000.5),
+        };
+        let mut response = db
+            .query(FEE_CREATE_QUERY)
+            .bind(("id", "25_22505_1".to_string()))
+            .bind(fee)
+            .await
+            .unwrap();
+        let fees: Vec<serde_json::Value> = response.take(0).unwrap();
+        assert_eq!(fees[0]["strap_line"], AWKWARD_TEXT);
+        assert_eq!(fees[0]["currency"], "AED");
+        assert_eq!(fees[0]["amount"], 125_000.5);
+
+        let mut response = db
+            .query(FEES_FOR_CONTACT_COUNT_QUERY)
+            .bind(("id", contact_key.clone()))
+            .await
+            .unwrap();
+        let total: Option<u64> = response.take((0, "total")).unwrap();
+        assert_eq!(total, Some(1));
+
+        let update = FeeUpdate {
+            name: format!("{} (rev)", AWKWARD_TEXT),
+            number: "25-22505-FP".to_string(),
+            rev: 2,
+            status: "Sent".to_string(),
+            issue_date: "251017".to_string(),
+            activity: None,
+            package: Some("\\'".to_string()),
+            project_id: "25_22505".to_string(),
+            company_id: "OBT".to_string(),
+            contact_id: contact_key,
+            staff_name: None,
+            staff_email: None,
+            staff_phone: None,
+            staff_position: None,
+            strap_line: None,
+            revisions: vec![],
+        };
+        let mut response = db
+            .query(FEE_UPDATE_QUERY)
+            .bind(("id", "25_22505_1".to_string()))
+            .bind(update)
+            .await
+            .unwrap();
+        let fees: Vec<serde_json::Value> = response.take(0).unwrap();
+        assert_eq!(fees[0]["name"], format!("{} (rev)", AWKWARD_TEXT));
+        assert_eq!(fees[0]["package"], "\\'");
+        assert_eq!(fees[0]["activity"], "");
+        assert_eq!(fees[0]["rev"], 2);
+    }
+
+    #[tokio::test]
+    async fn test_country_queries_bind_names_with_quotes() {
+        let db = memory_db().await;
+        db.query(
+            "CREATE country:civ SET name = \"Côte d'Ivoire\", code = 'CI', dial_code = 225; \
+             CREATE projects:25_22503 SET number = { year: 25, country: 225, seq: 3, id: '25-22503' };",
+        )
+        .await
+        .unwrap();
+
+        let mut response = db
+            .query(COUNTRY_DIAL_CODE_QUERY)
+            .bind(("name", "Côte d'Ivoire".to_string()))
+            .await
+            .unwrap();
+        let rows: Vec<serde_json::Value> = response.take(0).unwrap();
+        assert_eq!(rows[0]["dial_code"], 225);
+
+        let mut response = db.query(COUNTRIES_SEARCH_QUERY).bind(("query", "d'ivo".to_string())).await.unwrap();
+        let rows: Vec<serde_json::Value> = response.take(0).unwrap();
+        assert_eq!(rows.len(), 1);
+
+        let mut response = db
+            .query(PROJECT_MAX_SEQ_QUERY)
+            .bind(("year", 25u8))
+            .bind(("country", 225u16))
+            .await
+            .unwrap();
+        let rows: Vec<serde_json::Value> = response.take(0).unwrap();
+        assert_eq!(rows[0]["number"]["seq"], 3);
+    }
+
+    #[tokio::test]
+    async fn test_taken_project_numbers_in_one_query() {
+        let db = memory_db().await;
+        db.query(
+            "CREATE projects:25_97105 SET number = { year: 25, country: 971, seq: 5, id: '25-97105' }; \
+             CREATE projects:25_96601 SET number = { year: 25, country: 966, seq: 1, id: '25-96601' };",
+        )
+        .await
+        .unwrap();
+
+        let mut response = db
+            .query(PROJECT_NUMBERS_TAKEN_QUERY)
+            .bind(("numbers", vec!["25-97105".to_string(), "25-97106".to_string()]))
+            .await
+            .unwrap();
+        let taken: Vec<String> = response.take(0).unwrap();
+        assert_eq!(taken, ["25-97105"]);
+    }
+
+    #[tokio::test]
+    async fn test_currency_follows_country_link() {
+        let db = memory_db().await;
+        db.query(
+            "CREATE currency:AED SET code = 'AED', name = 'UAE Dirham'; \
+             CREATE country:are SET name = 'United Arab Emirates', code = 'AE', dial_code = 971, currency_code = currency:AED; \
+             CREATE country:atf SET name = 'French Southern Territories', code = 'TF', dial_code = 262; \
+             CREATE projects:25_97105 SET country = 'United Arab Emirates';",
+        )
+        .await
+        .unwrap();
+
+        let mut response = db
+            .query(COUNTRY_CURRENCY_QUERY)
+            .bind(("name", "United Arab Emirates".to_string()))
+            .await
+            .unwrap();
+        let currency: Option<CountryCurrency> = response.take(0).unwrap();
+        assert_eq!(currency, Some(CountryCurrency { code: "AED".to_string(), name: "UAE Dirham".to_string() }));
+
+        let mut response = db
+            .query(COUNTRY_CURRENCY_QUERY)
+            .bind(("name", "French Southern Territories".to_string()))
+            .await
+            .unwrap();
+        let currency: Option<CountryCurrency> = response.take(0).unwrap();
+        assert_eq!(currency, None);
+
+        async fn project_currency(db: &surrealdb::Surreal<surrealdb::engine::local::Db>, project_id: &str) -> Vec<String> {
+            let mut response = db
+                .query(PROJECT_CURRENCY_QUERY)
+                .bind(("project_id", project_id.to_string()))
+                .await
+                .unwrap();
+            response.take(0).unwrap()
+        }
+        assert_eq!(project_currency(&db, "25_97105").await, ["AED"]);
+        assert!(project_currency(&db, "25_97199").await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_currencies_ordered_by_code() {
+        let db = memory_db().await;
+        db.query(
+            "CREATE currency:USD SET code = 'USD', name = 'US Dollar'; \
+             CREATE currency:AED SET code = 'AED', name = 'UAE Dirham'; \
+             CREATE currency:SAR SET code = 'SAR', name = 'Saudi Riyal'; \
+             CREATE country:sau SET name = 'Saudi Arabia', code = 'SA', dial_code = 966, currency_code = currency:SAR;",
+        )
+        .await
+        .unwrap();
+
+        let mut response = db.query(CURRENCIES_QUERY).await.unwrap();
+        let currencies: Vec<Currency> = response.take(0).unwrap();
+        let codes: Vec<&str> = currencies.iter().map(|c| c.code.as_str()).collect();
+        assert_eq!(codes, ["AED", "SAR", "USD"]);
+        assert_eq!(currencies[0].id.as_ref().map(|id| id.to_string()).as_deref(), Some("currency:AED"));
+
+        let mut response = db
+            .query(COUNTRY_CURRENCY_QUERY)
+            .bind(("name", "Saudi Arabia".to_string()))
+            .await
+            .unwrap();
+        let currency: Option<CountryCurrency> = response.take(0).unwrap();
+        assert_eq!(currency.map(|c| c.code).as_deref(), Some("SAR"));
+    }
+
+    /// A `COUNTRY_IMPORT_QUERY` entry as built by `parse_country_import`.
+    fn country_entry(id: Option<&str>, name: &str, dial_code: u16, currency_code: Option<&str>) -> serde_json::Value {
+        serde_json::json!({
+            "id": id,
+            "name_key": name.to_lowercase().replace(' ', "_"),
+            "currency_code": currency_code,
+            "record": { "name": name, "dial_code": dial_code },
+        })
+    }
+
+    async fn import_countries(db: &surrealdb::Surreal<surrealdb::engine::local::Db>, countries: Vec<serde_json::Value>) {
+        db.query(COUNTRY_IMPORT_QUERY).bind(("countries", countries)).await.unwrap().check().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_import_countries_into_empty_table() {
+        let db = memory_db().await;
+
+        import_countries(&db, vec![
+            country_entry(None, "United Arab Emirates", 971, None),
+            country_entry(None, "Saudi Arabia", 966, None),
+        ])
+        .await;
+
+        let mut response = db.query(COUNTRY_NAMES_QUERY).await.unwrap();
+        let mut names: Vec<String> = response.take(0).unwrap();
+        names.sort();
+        assert_eq!(names, ["Saudi Arabia", "United Arab Emirates"]);
+
+        let mut response = db
+            .query(COUNTRY_DIAL_CODE_QUERY)
+            .bind(("name", "Saudi Arabia".to_string()))
+            .await
+            .unwrap();
+        let rows: Vec<serde_json::Value> = response.take(0).unwrap();
+        assert_eq!(rows[0]["dial_code"], 966);
+
+        // Re-importing replaces by name instead of duplicating
+        import_countries(&db, vec![country_entry(None, "Saudi Arabia", 967, None)]).await;
+        let mut response = db.query(COUNTRY_NAMES_QUERY).await.unwrap();
+        let names: Vec<String> = response.take(0).unwrap();
+        assert_eq!(names.len(), 2);
+        let mut response = db
+            .query(COUNTRY_DIAL_CODE_QUERY)
+            .bind(("name", "Saudi Arabia".to_string()))
+            .await
+            .unwrap();
+        let rows: Vec<serde_json::Value> = response.take(0).unwrap();
+        assert_eq!(rows[0]["dial_code"], 967);
+    }
+
+    #[tokio::test]
+    async fn test_import_countries_keeps_ids_and_links_currency() {
+        let db = memory_db().await;
+        db.query(
+            "CREATE currency:AED SET code = 'AED', name = 'UAE Dirham'; \
+             CREATE country:sa SET name = 'Saudi Arabia', dial_code = 966;",
+        )
+        .await
+        .unwrap();
+
+        import_countries(&db, vec![
+            country_entry(Some("ae"), "United Arab Emirates", 971, Some("AED")),
+            country_entry(None, "Saudi Arabia", 966, None),
+            country_entry(None, "Oman", 968, None),
+        ])
+        .await;
+
+        let mut response = db.query("SELECT VALUE <string> id FROM country").await.unwrap();
+        let mut ids: Vec<String> = response.take(0).unwrap();
+        ids.sort();
+        assert_eq!(ids, ["country:ae", "country:oman", "country:sa"]);
+
+        let mut response = db
+            .query(COUNTRY_CURRENCY_QUERY)
+            .bind(("name", "United Arab Emirates".to_string()))
+            .await
+            .unwrap();
+        let currency: Option<CountryCurrency> = response.take(0).unwrap();
+        assert_eq!(currency.map(|c| c.code).as_deref(), Some("AED"));
+
+        // A re-import keeps the record the file names, so links to it still resolve
+        import_countries(&db, vec![country_entry(Some("ae"), "United Arab Emirates", 971, Some("AED"))]).await;
+        let mut response = db.query("SELECT VALUE name FROM country:ae").await.unwrap();
+        let names: Vec<String> = response.take(0).unwrap();
+        assert_eq!(names, ["United Arab Emirates"]);
+    }
+
     // ============================================================================
     // DATABASE CONFIGURATION TESTS
     // ============================================================================
@@ -213,6 +608,1655 @@ mod tests {
         env::remove_var("SURREALDB_ACCEPT_INVALID_HOSTNAMES");
     }
 
+    #[test]
+    fn test_reconfigure_bad_then_good_config() {
+        let mut manager = DatabaseManager::new_unconfigured();
+        let original = manager.config.clone();
+        let original_status = manager.get_status();
+
+        // Incomplete settings are rejected and leave the manager untouched
+        let result = manager.reconfigure(
+            "".to_string(),
+            "ns".to_string(),
+            "db".to_string(),
+            "user".to_string(),
+            "pass".to_string(),
+        );
+        assert!(result.is_err());
+        assert_eq!(manager.config.url, original.url);
+        assert_eq!(manager.config.namespace, original.namespace);
+        assert_eq!(manager.config.database, original.database);
+        assert_eq!(manager.config.username, original.username);
+        assert_eq!(manager.generation(), 0);
+        let status = manager.get_status();
+        assert_eq!(status.is_connected, original_status.is_connected);
+        assert_eq!(status.error_message, original_status.error_message);
+
+        // Valid settings replace the config and invalidate the old connection
+        let result = manager.reconfigure(
+            "ws://localhost:8000".to_string(),
+            "ns".to_string(),
+            "db".to_string(),
+            "user".to_string(),
+            "pass".to_string(),
+        );
+        assert!(result.is_ok());
+        assert_eq!(manager.config.url, "ws://localhost:8000");
+        assert_eq!(manager.generation(), 1);
+        assert!(manager.client.is_none());
+
+        let status = manager.get_status();
+        assert!(!status.is_connected);
+        assert!(status.error_message.unwrap().contains("reconfigured"));
+    }
+
+    #[test]
+    fn test_reconfigure_shares_status_with_clones() {
+        // The heartbeat holds clones of the manager, so a reload must update
+        // the same status and generation they report through
+        let mut manager = DatabaseManager::new_unconfigured();
+        let heartbeat_clone = manager.clone();
+        let in_flight_generation = heartbeat_clone.generation();
+
+        manager
+            .reconfigure(
+                "http://localhost:8000".to_string(),
+                "ns".to_string(),
+                "db".to_string(),
+                "user".to_string(),
+                "pass".to_string(),
+            )
+            .unwrap();
+
+        assert_ne!(heartbeat_clone.generation(), in_flight_generation);
+        assert_eq!(heartbeat_clone.generation(), manager.generation());
+        assert!(std::sync::Arc::ptr_eq(&heartbeat_clone.generation, &manager.generation));
+        assert!(std::sync::Arc::ptr_eq(&heartbeat_clone.status, &manager.status));
+        assert!(std::sync::Arc::ptr_eq(&heartbeat_clone.heartbeat_running, &manager.heartbeat_running));
+    }
+
+    #[test]
+    fn test_operation_registry_cancel_and_cleanup() {
+        let manager = DatabaseManager::new_unconfigured();
+        let clone = manager.clone();
+
+        let operation = manager.start_operation("Export var.json for all active fees");
+        let listed = clone.operations.lock().unwrap().list();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].id, operation.id);
+        assert!(!listed[0].cancelled);
+
+        assert!(clone.operations.lock().unwrap().cancel(&operation.id));
+        assert!(operation.token.is_cancelled());
+        assert!(manager.operations.lock().unwrap().list()[0].cancelled);
+        assert!(!clone.operations.lock().unwrap().cancel("unknown"));
+
+        drop(operation);
+        assert!(manager.operations.lock().unwrap().list().is_empty());
+    }
+
+    #[test]
+    fn test_heartbeat_tolerates_single_transient_failure() {
+        let mut failures = 0;
+
+        // One failure between passing checks keeps the connected status
+        assert!(heartbeat_status(true, false, &mut failures, 2));
+        assert!(heartbeat_status(true, true, &mut failures, 2));
+        assert_eq!(failures, 0);
+
+        // Two in a row flip it
+        assert!(heartbeat_status(true, false, &mut failures, 2));
+        assert!(!heartbeat_status(true, false, &mut failures, 2));
+        assert!(!heartbeat_status(false, false, &mut failures, 2));
+
+        // A disconnected status is not revived by a failure, and a threshold
+        // of 1 flips on the first failure
+        let mut failures = 0;
+        assert!(!heartbeat_status(false, false, &mut failures, 2));
+        let mut failures = 0;
+        assert!(!heartbeat_status(true, false, &mut failures, 1));
+    }
+
+    #[test]
+    fn test_reconnect_backoff_doubles_up_to_heartbeat_interval() {
+        let delays: Vec<u64> = (1..=7).map(|attempt| reconnect_backoff(attempt).as_secs()).collect();
+        assert_eq!(delays, [1, 2, 4, 8, 16, 30, 30]);
+        assert_eq!(reconnect_backoff(u32::MAX).as_secs(), 30);
+    }
+
+    #[test]
+    fn test_write_permitted_treats_permission_errors_as_read_only() {
+        let denied = surrealdb::Error::Api(surrealdb::error::Api::InvalidRequest(
+            "IAM error: Not enough permissions to perform this action".to_string(),
+        ));
+        assert!(!write_permitted(Err(denied)).unwrap());
+        assert!(!write_permitted(Ok(vec![])).unwrap());
+        assert!(write_permitted(Ok(vec![serde_json::json!({ "id": "write_probe:abc" })])).unwrap());
+
+        let other = surrealdb::Error::Api(surrealdb::error::Api::InvalidRequest("Connection refused".to_string()));
+        assert!(write_permitted(Err(other)).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_record_by_id_across_tables() {
+        let db = memory_db().await;
+        db.query(
+            "CREATE projects:25_97105 SET name = 'Grand Hotel Lighting'; \
+             CREATE company:CHE SET name = 'Chedi Hospitality'; \
+             CREATE contacts:john_smith SET full_name = 'John Smith'; \
+             CREATE fee:25_97105_1 SET number = '25-97105-FP-1';",
+        )
+        .await
+        .unwrap();
+
+        async fn record(db: &surrealdb::Surreal<surrealdb::engine::local::Db>, table: &str, id: &str) -> Vec<serde_json::Value> {
+            let mut response = db
+                .query(RECORD_BY_ID_QUERY)
+                .bind(("table", table.to_string()))
+                .bind(("id", id.to_string()))
+                .await
+                .unwrap();
+            response.take(0).unwrap()
+        }
+        assert_eq!(record(&db, "projects", "25_97105").await[0]["name"], "Grand Hotel Lighting");
+        assert_eq!(record(&db, "company", "CHE").await[0]["name"], "Chedi Hospitality");
+        assert_eq!(record(&db, "contacts", "john_smith").await[0]["full_name"], "John Smith");
+        assert_eq!(record(&db, "fee", "25_97105_1").await[0]["number"], "25-97105-FP-1");
+        assert!(record(&db, "fee", "25_97105_9").await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_write_probe_leaves_no_record() {
+        let db = memory_db().await;
+        let mut response = db.query(WRITE_PROBE_QUERY).bind(("id", "probe".to_string())).await.unwrap();
+        assert!(write_permitted(response.take(0)).unwrap());
+
+        let mut response = db.query("SELECT * FROM write_probe").await.unwrap();
+        let left: Vec<serde_json::Value> = response.take(0).unwrap();
+        assert!(left.is_empty());
+    }
+
+    #[test]
+    fn test_terminal_project_statuses() {
+        for status in ["Draft", "RFP", "Active", "On Hold"] {
+            assert!(!Project::is_terminal_status(status), "{} should not be terminal", status);
+        }
+        for status in ["Completed", "Cancelled"] {
+            assert!(Project::is_terminal_status(status), "{} should be terminal", status);
+        }
+        assert!(Project::is_terminal_status(" Completed "));
+        assert!(!Project::is_terminal_status("completed"));
+        assert!(!Project::is_terminal_status(""));
+        assert_eq!(TERMINAL_PROJECT_STATUSES.len(), 2);
+    }
+
+    #[test]
+    fn test_parse_heartbeat_failure_threshold() {
+        assert_eq!(DatabaseConfig::parse_failure_threshold(None), 2);
+        assert_eq!(DatabaseConfig::parse_failure_threshold(Some(" 4 ")), 4);
+        assert_eq!(DatabaseConfig::parse_failure_threshold(Some("0")), 2);
+        assert_eq!(DatabaseConfig::parse_failure_threshold(Some("often")), 2);
+    }
+
+    #[test]
+    fn test_parse_connection_timeout() {
+        assert_eq!(DatabaseConfig::parse_connection_timeout(None), 10);
+        assert_eq!(DatabaseConfig::parse_connection_timeout(Some(" 3 ")), 3);
+        assert_eq!(DatabaseConfig::parse_connection_timeout(Some("0")), 10);
+        assert_eq!(DatabaseConfig::parse_connection_timeout(Some("soon")), 10);
+    }
+
+    #[tokio::test]
+    async fn test_initialize_times_out_on_unresponsive_host() {
+        // Accepts the TCP connection but never answers the WebSocket handshake
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let config = DatabaseConfig {
+            url: format!("ws://{}", listener.local_addr().unwrap()),
+            namespace: "emittiv".to_string(),
+            database: "projects".to_string(),
+            username: "app".to_string(),
+            password: "secret".to_string(),
+            verify_certificates: true,
+            accept_invalid_hostnames: false,
+            heartbeat_failure_threshold: 2,
+            connection_timeout_secs: 1,
+        };
+        let mut manager = DatabaseManager::from_config(config);
+
+        let started = std::time::Instant::now();
+        let err = manager.initialize().await.unwrap_err();
+        assert!(err.to_string().contains("Connection timed out after 1s"), "{}", err);
+        assert!(started.elapsed() < std::time::Duration::from_secs(5));
+        assert!(manager.client.is_none());
+
+        let status = manager.get_status();
+        assert!(!status.is_connected);
+        assert!(status.error_message.unwrap().starts_with("Connection timed out after 1s"));
+    }
+
+    #[tokio::test]
+    async fn test_failed_reload_reverts_to_last_known_good_config() {
+        let good = DatabaseConfig {
+            url: "ws://127.0.0.1:1".to_string(),
+            namespace: "emittiv".to_string(),
+            database: "projects".to_string(),
+            username: "app".to_string(),
+            password: "secret".to_string(),
+            verify_certificates: true,
+            accept_invalid_hostnames: false,
+            heartbeat_failure_threshold: 2,
+            connection_timeout_secs: 10,
+        };
+        let mut manager = DatabaseManager::from_config(good.clone());
+        // As recorded by a successful initialize()
+        manager.last_known_good = Some(good.clone());
+
+        // Nothing listens on port 1, so the new settings cannot connect
+        let result = manager
+            .reconfigure_or_rollback(
+                "http://127.0.0.1:1".to_string(),
+                "wrong_ns".to_string(),
+                "wrong_db".to_string(),
+                "app".to_string(),
+                "secret".to_string(),
+            )
+            .await;
+
+        let err = result.unwrap_err();
+        assert!(err.contains("Reverted to the previous configuration"));
+        assert_eq!(manager.config.url, good.url);
+        assert_eq!(manager.config.namespace, good.namespace);
+        assert_eq!(manager.config.database, good.database);
+        assert_eq!(manager.config.username, good.username);
+        assert_eq!(manager.config.password, good.password);
+        assert_eq!(manager.generation(), 2);
+
+        // The previous settings don't connect either, and the status says so
+        let status = manager.get_status();
+        assert!(!status.is_connected);
+        assert!(status.error_message.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_failed_reload_without_previous_config_keeps_new_settings() {
+        let mut manager = DatabaseManager::new_unconfigured();
+
+        let result = manager
+            .reconfigure_or_rollback(
+                "http://127.0.0.1:1".to_string(),
+                "ns".to_string(),
+                "db".to_string(),
+                "user".to_string(),
+                "pass".to_string(),
+            )
+            .await;
+
+        assert!(result.is_ok());
+        assert_eq!(manager.config.url, "http://127.0.0.1:1");
+        assert!(!manager.get_status().is_connected);
+    }
+
+    // ============================================================================
+    // DATABASE INFO PARSING TESTS
+    // ============================================================================
+
+    #[test]
+    fn test_database_info_lists_known_tables() {
+        let raw = serde_json::json!({
+            "accesses": {},
+            "analyzers": {},
+            "functions": {},
+            "params": {},
+            "tables": {
+                "projects": "DEFINE TABLE projects TYPE ANY SCHEMALESS PERMISSIONS NONE",
+                "fee": "DEFINE TABLE fee TYPE ANY SCHEMALESS PERMISSIONS NONE",
+                "company": "DEFINE TABLE company TYPE ANY SCHEMALESS PERMISSIONS NONE",
+                "country": "DEFINE TABLE country TYPE ANY SCHEMALESS PERMISSIONS NONE",
+                "contacts": "DEFINE TABLE contacts TYPE ANY SCHEMALESS PERMISSIONS NONE"
+            },
+            "users": { "app": "DEFINE USER app ON DATABASE ROLES EDITOR" }
+        });
+
+        let info = DatabaseInfo::from_info_value(&raw);
+        assert_eq!(info.tables, vec!["company", "contacts", "country", "fee", "projects"]);
+        assert_eq!(info.users, vec!["app"]);
+        assert!(info.functions.is_empty());
+    }
+
+    #[test]
+    fn test_database_info_missing_sections() {
+        let info = DatabaseInfo::from_info_value(&serde_json::json!({}));
+        assert_eq!(info, DatabaseInfo::default());
+    }
+
+    // ============================================================================
+    // TEXT NORMALIZATION TESTS
+    // ============================================================================
+
+    #[test]
+    fn test_normalize_abbreviation() {
+        assert_eq!(normalize_abbreviation("che"), "CHE");
+        assert_eq!(normalize_abbreviation(" CHE "), "CHE");
+        assert_eq!(normalize_abbreviation("dm\u{00A0}cc"), "DMCC");
+    }
+
+    #[test]
+    fn test_normalize_text_whitespace() {
+        assert_eq!(normalize_text("Dubai "), "Dubai");
+        assert_eq!(normalize_text("  Hotel   ABC  "), "Hotel ABC");
+        assert_eq!(normalize_text("Palm\u{00A0}Jumeirah\t"), "Palm Jumeirah");
+        assert_eq!(normalize_text("   "), "");
+    }
+
+    #[test]
+    fn test_new_project_stored_trimmed_and_searchable() {
+        let mut project = NewProject {
+            name: "Grand Hotel ".to_string(),
+            name_short: " Hotel\u{00A0}ABC".to_string(),
+            status: "RFP".to_string(),
+            area: "Downtown  ".to_string(),
+            city: "Dubai ".to_string(),
+            country: "United  Arab Emirates".to_string(),
+            folder: "25-97106 Hotel ABC ".to_string(),
+            number: ProjectNumber { year: 25, country: 971, seq: 6, id: "25-97106".to_string() },
+            activity: None,
+            package: None,
+            template_override: None,
+        };
+        project.normalize_text();
+
+        assert_eq!(project.name, "Grand Hotel");
+        assert_eq!(project.name_short, "Hotel ABC");
+        assert_eq!(project.city, "Dubai");
+        assert_eq!(project.country, "United Arab Emirates");
+        assert_eq!(project.folder, "25-97106 Hotel ABC");
+        // Search terms typed by users now match the stored value exactly
+        assert!(project.city.to_lowercase() == "dubai");
+        assert!(project.name.to_lowercase().ends_with("hotel"));
+    }
+
+    #[tokio::test]
+    async fn test_normalized_projects_found_by_search_with_and_without_accents() {
+        use surrealdb::sql::Thing;
+
+        let db = memory_db().await;
+        let projects = [
+            ("25_97101", "Café  Marina ", "25-97101 Café\u{00A0}Marina ", ProjectNumber::new(25, 971, 1)),
+            ("25_97102", " Dubai Marina\t", "25-97102 Dubai  Marina", ProjectNumber::new(25, 971, 2)),
+        ];
+        for (id, name, folder, number) in projects {
+            let mut project = NewProject {
+                name: name.to_string(),
+                name_short: name.to_string(),
+                status: "RFP".to_string(),
+                area: "Marina ".to_string(),
+                city: " Dubai".to_string(),
+                country: "United Arab Emirates".to_string(),
+                folder: folder.to_string(),
+                number,
+                activity: None,
+                package: None,
+                template_override: None,
+            };
+            project.normalize_text();
+            db.query(PROJECT_CREATE_QUERY).bind(("id", id.to_string())).bind(project).await.unwrap().check().unwrap();
+        }
+
+        async fn search(db: &surrealdb::Surreal<surrealdb::engine::local::Db>, term: &str) -> Vec<String> {
+            let mut response = db.query(PROJECTS_SEARCH_QUERY).bind(("query", term.to_string())).await.unwrap();
+            let rows: Vec<serde_json::Value> = response.take(0).unwrap();
+            let mut names: Vec<String> = rows.iter().filter_map(|row| row["name"].as_str().map(str::to_string)).collect();
+            names.sort();
+            names
+        }
+        // Accented and plain names are stored with single spaces, so the
+        // terms users type match the name and the folder alike
+        assert_eq!(search(&db, "café marina").await, vec!["Café Marina"]);
+        assert_eq!(search(&db, "97101 café marina").await, vec!["Café Marina"]);
+        assert_eq!(search(&db, "dubai marina").await, vec!["Dubai Marina"]);
+        assert_eq!(search(&db, "97102 dubai marina").await, vec!["Dubai Marina"]);
+        assert_eq!(search(&db, "marina").await, vec!["Café Marina", "Dubai Marina"]);
+
+        let mut response = db.query("SELECT VALUE folder FROM projects:25_97101").await.unwrap();
+        let folders: Vec<String> = response.take(0).unwrap();
+        assert_eq!(folders, vec!["25-97101 Café Marina"]);
+
+        // Saving an edited project normalizes its folder too
+        let mut edited = Project {
+            id: Some(Thing::from(("projects", "25_97101"))),
+            name: "Café Marina".to_string(),
+            name_short: "Café Marina".to_string(),
+            status: "RFP".to_string(),
+            area: "Marina".to_string(),
+            city: "Dubai".to_string(),
+            country: "United Arab Emirates".to_string(),
+            folder: " 25-97101  Café\u{00A0}Marina ".to_string(),
+            number: ProjectNumber::new(25, 971, 1),
+            time: TimeStamps {
+                created_at: "2025-01-10T00:00:00Z".to_string(),
+                updated_at: "2025-01-10T00:00:00Z".to_string(),
+            },
+            activity: None,
+            package: None,
+            template_override: None,
+            deleted_at: None,
+        };
+        edited.normalize_text();
+        assert_eq!(edited.folder, "25-97101 Café Marina");
+    }
+
+    // ============================================================================
+    // CONTACT ORDERING TESTS
+    // ============================================================================
+
+    #[test]
+    fn test_contacts_sorted_by_last_then_first_name() {
+        let mut contacts = vec![
+            contact(Some("Zoe"), Some("adams")),
+            contact(Some("Omar"), None),
+            contact(Some("Ben"), Some("Adams")),
+            contact(Some("Carla"), Some("Baker")),
+            contact(None, Some("Adams")),
+        ];
+        sort_contacts_by_name(&mut contacts);
+
+        let names: Vec<(Option<&str>, Option<&str>)> = contacts
+            .iter()
+            .map(|c| (c.first_name.as_deref(), c.last_name.as_deref()))
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                (Some("Ben"), Some("Adams")),
+                (Some("Zoe"), Some("adams")),
+                (None, Some("Adams")),
+                (Some("Carla"), Some("Baker")),
+                (Some("Omar"), None),
+            ]
+        );
+    }
+
+    // ============================================================================
+    // PROJECT NUMBER TESTS
+    // ============================================================================
+
+    #[test]
+    fn test_project_number_new_formats_id() {
+        assert_eq!(ProjectNumber::new(25, 971, 5).id, "25-97105");
+        assert_eq!(ProjectNumber::new(5, 1, 12).id, "05-00112");
+    }
+
+    #[test]
+    fn test_project_number_consistency() {
+        let consistent = ProjectNumber { year: 25, country: 971, seq: 5, id: "25-97105".to_string() };
+        assert!(consistent.is_consistent());
+
+        let inconsistent = ProjectNumber { year: 25, country: 966, seq: 5, id: "25-97105".to_string() };
+        assert!(!inconsistent.is_consistent());
+    }
+
+    #[test]
+    fn test_project_number_year_warning() {
+        let ten_years_off = ProjectNumber::new(15, 971, 5);
+        let warning = ten_years_off.year_warning(2025).unwrap();
+        assert!(warning.contains("15-97105"));
+        assert!(warning.contains("10 years before"));
+
+        assert!(ProjectNumber::new(25, 971, 5).year_warning(2025).is_none());
+        assert!(ProjectNumber::new(23, 971, 5).year_warning(2025).is_none());
+        assert!(ProjectNumber::new(22, 971, 5).year_warning(2025).is_some());
+        assert!(ProjectNumber::new(1, 971, 5).year_warning(2099).is_none()); // Across the century
+    }
+
+    #[test]
+    fn test_project_creation_returns_year_warning() {
+        let new_project = |number: ProjectNumber| NewProject {
+            name: "Grand Hotel".to_string(),
+            name_short: "Hotel".to_string(),
+            status: "RFP".to_string(),
+            area: "Downtown".to_string(),
+            city: "Dubai".to_string(),
+            country: "United Arab Emirates".to_string(),
+            folder: format!("{} Hotel", number.id),
+            number,
+            activity: None,
+            package: None,
+            template_override: None,
+        };
+
+        let mut project = new_project(ProjectNumber::new(15, 971, 5));
+        let warnings = prepare_new_project(&mut project, 2025).unwrap();
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("10 years before"));
+
+        let mut current = new_project(ProjectNumber::new(25, 971, 5));
+        assert!(prepare_new_project(&mut current, 2025).unwrap().is_empty());
+
+        let mut mismatched = new_project(ProjectNumber { year: 25, country: 971, seq: 5, id: "25-97106".to_string() });
+        assert!(prepare_new_project(&mut mismatched, 2025).unwrap_err().to_string().contains("does not match"));
+    }
+
+    #[test]
+    fn test_project_number_parse() {
+        let number = ProjectNumber::parse("25-97105").unwrap();
+        assert_eq!((number.year, number.country, number.seq), (25, 971, 5));
+        assert_eq!(number.id, "25-97105");
+        assert!(ProjectNumber::parse("25-9715").is_none());
+        assert!(ProjectNumber::parse("2597105").is_none());
+        assert!(ProjectNumber::parse("25-97A05").is_none());
+    }
+
+    #[test]
+    fn test_generated_number_for_two_digit_dial_code_parses() {
+        let generated = next_project_number(25, 41, 3).unwrap();
+        assert_eq!(generated, "25-04103");
+
+        let parsed = ProjectNumber::parse(&generated).unwrap();
+        assert_eq!((parsed.year, parsed.country, parsed.seq), (25, 41, 3));
+        assert!(parsed.is_consistent());
+
+        assert!(next_project_number(25, 41, 100).is_err());
+    }
+
+    // ============================================================================
+    // QUERY ORDERING TESTS (in-memory SurrealDB)
+    // ============================================================================
+
+    async fn memory_db() -> surrealdb::Surreal<surrealdb::engine::local::Db> {
+        let db = surrealdb::Surreal::new::<surrealdb::engine::local::Mem>(()).await.unwrap();
+        db.use_ns("test").use_db("test").await.unwrap();
+        db
+    }
+
+    #[tokio::test]
+    async fn test_incomplete_contacts_reports_missing_fields() {
+        let db = memory_db().await;
+        db.query(
+            "CREATE contacts:jane_doe SET first_name = 'Jane', last_name = 'Doe', email = 'jane@example.com', \
+                phone = '+971 50 000 0000', position = 'Director', company = company:CHE; \
+             CREATE contacts:john_smith SET first_name = 'John', last_name = 'Smith', email = 'john@example.com', \
+                company = company:CHE;",
+        )
+        .await
+        .unwrap();
+
+        let contacts: Vec<Contact> = db.select("contacts").await.unwrap();
+        let incomplete = incomplete_contacts(&contacts);
+
+        assert_eq!(incomplete.len(), 1);
+        assert_eq!(incomplete[0].id, "contacts:john_smith");
+        assert_eq!(incomplete[0].missing_fields, vec!["phone", "position"]);
+    }
+
+    #[tokio::test]
+    async fn test_contact_company_repair_rewrites_string_links() {
+        let db = memory_db().await;
+        db.query(
+            "CREATE contacts:plain SET first_name = 'Jane', company = 'CHE'; \
+             CREATE contacts:prefixed SET first_name = 'Omar', company = 'company:ABC'; \
+             CREATE contacts:linked SET first_name = 'John', company = company:CHE;",
+        )
+        .await
+        .unwrap();
+
+        let mut response = db.query(CONTACT_COMPANY_REPAIR_QUERY).await.unwrap();
+        let repaired: Vec<serde_json::Value> = response.take(0).unwrap();
+        assert_eq!(repaired.len(), 2);
+
+        let contacts: Vec<Contact> = db.select("contacts").await.unwrap();
+        let company_of = |key: &str| {
+            contacts
+                .iter()
+                .find(|c| c.id.as_ref().unwrap().id.to_string() == key)
+                .and_then(|c| c.company.as_ref())
+                .map(|company| company.to_string())
+        };
+        assert_eq!(company_of("plain").as_deref(), Some("company:CHE"));
+        assert_eq!(company_of("prefixed").as_deref(), Some("company:ABC"));
+        assert_eq!(company_of("linked").as_deref(), Some("company:CHE"));
+
+        // Running again finds nothing left to repair
+        let mut response = db.query(CONTACT_COMPANY_REPAIR_QUERY).await.unwrap();
+        let repaired: Vec<serde_json::Value> = response.take(0).unwrap();
+        assert!(repaired.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_distinct_activities_and_packages() {
+        let db = memory_db().await;
+        db.query(
+            "CREATE fee:a SET activity = 'Supervision', package = 'Lighting'; \
+             CREATE fee:b SET activity = 'Design and Consultancy', package = 'AV'; \
+             CREATE fee:c SET activity = 'Supervision', package = 'Lighting'; \
+             CREATE fee:d SET activity = '', package = '  '; \
+             CREATE fee:e SET activity = 'Design and Consultancy';",
+        )
+        .await
+        .unwrap();
+
+        let distinct = |rows: Vec<serde_json::Value>, field: &str| -> Vec<String> {
+            rows.iter().map(|r| r[field].as_str().unwrap().to_string()).collect()
+        };
+
+        let mut response = db.query(FEE_ACTIVITIES_QUERY).await.unwrap();
+        let activities = distinct(response.take(0).unwrap(), "activity");
+        assert_eq!(activities, vec!["Design and Consultancy", "Supervision"]);
+
+        let mut response = db.query(FEE_PACKAGES_QUERY).await.unwrap();
+        let packages = distinct(response.take(0).unwrap(), "package");
+        assert_eq!(packages, vec!["AV", "Lighting"]);
+    }
+
+    #[tokio::test]
+    async fn test_projects_by_area_query() {
+        let db = memory_db().await;
+        db.query(
+            "CREATE projects:25_97105 SET name = 'Hotel', country = 'United Arab Emirates', city = 'Dubai', area = 'Marina', number = { id: '25-97105' }; \
+             CREATE projects:25_97106 SET name = 'Villa', country = 'United Arab Emirates', city = 'Dubai', area = 'Jumeirah', number = { id: '25-97106' }; \
+             CREATE projects:25_97107 SET name = 'Tower', country = 'United Arab Emirates', city = 'Dubai', area = 'Marina', number = { id: '25-97107' }; \
+             CREATE projects:25_97108 SET name = 'Mall', country = 'United Arab Emirates', city = 'Abu Dhabi', area = 'Marina', number = { id: '25-97108' };",
+        )
+        .await
+        .unwrap();
+
+        let db = &db;
+        let names = move |area: Option<&str>| {
+            let area = area.map(String::from);
+            async move {
+                let mut response = db
+                    .query(PROJECTS_BY_AREA_QUERY)
+                    .bind(("country", "United Arab Emirates".to_string()))
+                    .bind(("city", "Dubai".to_string()))
+                    .bind(("area", area))
+                    .await
+                    .unwrap();
+                let rows: Vec<serde_json::Value> = response.take(0).unwrap();
+                rows.iter().map(|r| r["name"].as_str().unwrap().to_string()).collect::<Vec<_>>()
+            }
+        };
+
+        assert_eq!(names(Some("Marina")).await, vec!["Hotel", "Tower"]);
+        assert_eq!(names(None).await, vec!["Villa", "Hotel", "Tower"]);
+    }
+
+    #[tokio::test]
+    async fn test_projects_modified_since_query() {
+        let db = memory_db().await;
+        db.query(
+            "CREATE projects:25_97105 SET name = 'Old', time = { created_at: d'2025-01-01T00:00:00Z', updated_at: d'2025-01-05T00:00:00Z' }; \
+             CREATE projects:25_97106 SET name = 'Newer', time = { created_at: d'2025-01-01T00:00:00Z', updated_at: d'2025-03-01T00:00:00Z' }; \
+             CREATE projects:25_97107 SET name = 'Newest', time = { created_at: d'2025-01-01T00:00:00Z', updated_at: d'2025-06-01T12:00:00Z' }; \
+             CREATE projects:25_97108 SET name = 'Boundary', time = { created_at: d'2025-01-01T00:00:00Z', updated_at: d'2025-02-01T00:00:00Z' };",
+        )
+        .await
+        .unwrap();
+
+        let mut response = db
+            .query(PROJECTS_MODIFIED_SINCE_QUERY)
+            .bind(("ts", "2025-02-01T00:00:00Z".to_string()))
+            .await
+            .unwrap();
+        let rows: Vec<serde_json::Value> = response.take(0).unwrap();
+        let names: Vec<&str> = rows.iter().map(|r| r["name"].as_str().unwrap()).collect();
+        assert_eq!(names, vec!["Newer", "Newest"]);
+    }
+
+    #[tokio::test]
+    async fn test_project_template_override_queries() {
+        let db = memory_db().await;
+        db.query("CREATE projects:25_97105 SET name = 'Hotel', number = { id: '25-97105' }")
+            .await
+            .unwrap();
+
+        async fn get_override(db: &surrealdb::Surreal<surrealdb::engine::local::Db>) -> Option<String> {
+            let mut response = db
+                .query(PROJECT_TEMPLATE_OVERRIDE_GET_QUERY)
+                .bind(("id", "25_97105".to_string()))
+                .await
+                .unwrap();
+            let overrides: Vec<Option<String>> = response.take(0).unwrap();
+            overrides.into_iter().next().flatten()
+        }
+        assert_eq!(get_override(&db).await, None);
+
+        db.query(PROJECT_TEMPLATE_OVERRIDE_QUERY)
+            .bind(("id", "25_97105".to_string()))
+            .bind(("template", Some("_yy-cccnn Daylight".to_string())))
+            .await
+            .unwrap();
+        assert_eq!(get_override(&db).await.as_deref(), Some("_yy-cccnn Daylight"));
+
+        let mut response = db
+            .query(PROJECT_BY_NUMBER_QUERY)
+            .bind(("number", "25-97105".to_string()))
+            .await
+            .unwrap();
+        let rows: Vec<serde_json::Value> = response.take(0).unwrap();
+        assert_eq!(rows[0]["template_override"], "_yy-cccnn Daylight");
+
+        db.query(PROJECT_TEMPLATE_OVERRIDE_QUERY)
+            .bind(("id", "25_97105".to_string()))
+            .bind(("template", None::<String>))
+            .await
+            .unwrap();
+        assert_eq!(get_override(&db).await, None);
+    }
+
+    #[tokio::test]
+    async fn test_project_by_id_query() {
+        let db = memory_db().await;
+        db.query("CREATE projects:25_97105 SET name = 'Hotel', number = { id: '25-97105' }, template_override = '_yy-cccnn Daylight'")
+            .await
+            .unwrap();
+
+        let mut response = db
+            .query(PROJECT_BY_ID_QUERY)
+            .bind(("id", "25_97105".to_string()))
+            .await
+            .unwrap();
+        let rows: Vec<serde_json::Value> = response.take(0).unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0]["name"], "Hotel");
+        assert_eq!(rows[0]["template_override"], "_yy-cccnn Daylight");
+
+        let mut response = db
+            .query(PROJECT_BY_ID_QUERY)
+            .bind(("id", "25_97199".to_string()))
+            .await
+            .unwrap();
+        let rows: Vec<serde_json::Value> = response.take(0).unwrap();
+        assert!(rows.is_empty());
+    }
+
+    #[test]
+    fn test_check_namespace_database_names_missing_database() {
+        let root_info = serde_json::json!({ "namespaces": { "emittiv": "DEFINE NAMESPACE emittiv" }, "users": {} });
+        let ns_info = serde_json::json!({ "databases": { "archive": "DEFINE DATABASE archive" }, "users": {} });
+
+        let err = check_namespace_database("emittiv", "projects", Some(&root_info), &ns_info).unwrap_err();
+        assert_eq!(err, "Database 'projects' does not exist in namespace 'emittiv'");
+
+        let err = check_namespace_database("other", "projects", Some(&root_info), &ns_info).unwrap_err();
+        assert_eq!(err, "Namespace 'other' does not exist");
+
+        assert!(check_namespace_database("emittiv", "archive", Some(&root_info), &ns_info).is_ok());
+        assert!(check_namespace_database("emittiv", "archive", None, &ns_info).is_ok());
+        assert!(check_namespace_database("emittiv", "archive", None, &serde_json::json!({})).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_project_schema_gaps_for_row_without_stage() {
+        let db = memory_db().await;
+        db.query(
+            "CREATE projects:25_97105 SET name = 'Grand Hotel', name_short = 'Hotel', status = 'RFP', \
+             area = 'Downtown', city = 'Dubai', country = 'United Arab Emirates', folder = '25-97105 Hotel', \
+             number = { year: 25, country: 971, seq: 5, id: '25-97105' }, package = 'Lighting', legacy_code = 'X1', \
+             time = { created_at: time::now(), updated_at: time::now() }",
+        )
+        .await
+        .unwrap();
+
+        let mut response = db.query(PROJECT_SAMPLE_QUERY).await.unwrap();
+        let rows: Vec<serde_json::Value> = response.take(0).unwrap();
+        let gaps = project_schema_gaps(&rows[0]);
+
+        assert!(gaps.contains(&"entities::Project: missing `stage` (optional)".to_string()));
+        assert!(gaps.contains(&"entities::Project: missing `activity` (required, rows fail to deserialize)".to_string()));
+        assert!(gaps.contains(&"db::Project: missing `template_override` (optional)".to_string()));
+        assert!(gaps.contains(&"db::Project: row field `legacy_code` is not in the struct".to_string()));
+        assert!(gaps.contains(&"entities::Project: row field `legacy_code` is not in the struct".to_string()));
+        assert!(!gaps.iter().any(|gap| gap.starts_with("db::Project") && gap.contains("required")));
+    }
+
+    #[test]
+    fn test_project_schema_gaps_for_archived_row() {
+        let row = serde_json::json!({
+            "id": "projects:25_97105",
+            "name": "Grand Hotel",
+            "name_short": "Hotel",
+            "status": "Cancelled",
+            "area": "Downtown",
+            "city": "Dubai",
+            "country": "United Arab Emirates",
+            "folder": "25-97105 Hotel",
+            "number": { "year": 25, "country": 971, "seq": 5, "id": "25-97105" },
+            "time": { "created_at": "2025-01-01T00:00:00Z", "updated_at": "2025-01-05T00:00:00Z" },
+            "deleted_at": "2025-02-01T00:00:00Z"
+        });
+        let gaps = project_schema_gaps(&row);
+
+        assert!(!gaps.contains(&"db::Project: row field `deleted_at` is not in the struct".to_string()));
+        assert!(gaps.contains(&"db::Project: missing `template_override` (optional)".to_string()));
+    }
+
+    #[test]
+    fn test_project_fields_match_project_struct() {
+        let project = Project {
+            id: None,
+            name: "Grand Hotel".to_string(),
+            name_short: "Hotel".to_string(),
+            status: "RFP".to_string(),
+            area: "Downtown".to_string(),
+            city: "Dubai".to_string(),
+            country: "United Arab Emirates".to_string(),
+            folder: "25-97105 Hotel".to_string(),
+            number: ProjectNumber { year: 25, country: 971, seq: 5, id: "25-97105".to_string() },
+            time: TimeStamps {
+                created_at: "2025-01-01T00:00:00Z".to_string(),
+                updated_at: "2025-01-01T00:00:00Z".to_string(),
+            },
+            activity: None,
+            package: None,
+            template_override: None,
+            deleted_at: None,
+        };
+        let row = serde_json::to_value(&project).unwrap();
+        let row = row.as_object().unwrap();
+
+        // Every serialized key is listed, and only the fields left as None are optional
+        let mut listed: Vec<&str> = PROJECT_FIELDS.iter().map(|(field, _)| *field).collect();
+        let mut serialized: Vec<&str> = row.keys().map(String::as_str).collect();
+        listed.sort();
+        serialized.sort();
+        assert_eq!(listed, serialized);
+        for (field, required) in PROJECT_FIELDS {
+            assert_eq!(*required, !row[*field].is_null(), "`{}` has the wrong required flag", field);
+        }
+    }
+
+    /// Create statement for a fee row matching the `Fee` struct; `rev` is
+    /// spliced in verbatim so tests can store a malformed value.
+    fn fee_row_query(key: &str, rev: &str) -> String {
+        format!(
+            "CREATE fee:{key} SET name = 'Lighting Design', number = '25-97105-FP-{key}', rev = {rev}, \
+             status = 'Draft', issue_date = '250115', activity = 'Design', package = 'Lighting', \
+             project_id = projects:25_97105, company_id = company:CHE, contact_id = contacts:john_smith, \
+             staff_name = '', staff_email = '', staff_phone = '', staff_position = '', strap_line = '', \
+             revisions = [], time = {{ created_at: '2025-01-15T00:00:00Z', updated_at: '2025-01-15T00:00:00Z' }};"
+        )
+    }
+
+    #[tokio::test]
+    async fn test_fee_rows_skip_malformed_row() {
+        let db = memory_db().await;
+        let seed = [fee_row_query("a", "1"), fee_row_query("b", "'two'"), fee_row_query("c", "3")].concat();
+        db.query(seed).await.unwrap();
+
+        let mut response = db.query(FEES_ALL_QUERY).await.unwrap();
+        let rows: Vec<serde_json::Value> = response.take(0).unwrap();
+        assert_eq!(rows.len(), 3);
+
+        let (fees, unparseable) = partition_fee_rows(rows);
+        let mut numbers: Vec<&str> = fees.iter().map(|f| f.number.as_str()).collect();
+        numbers.sort();
+        assert_eq!(numbers, vec!["25-97105-FP-a", "25-97105-FP-c"]);
+        assert_eq!(unparseable.len(), 1);
+        assert_eq!(unparseable[0]["rev"], "two");
+    }
+
+    #[tokio::test]
+    async fn test_string_rev_fee_reported_and_repaired() {
+        let db = memory_db().await;
+        let seed = [fee_row_query("a", "1"), fee_row_query("b", "'2'")].concat();
+        db.query(seed).await.unwrap();
+        db.query("UPDATE fee:a SET strap_line = NONE").await.unwrap();
+
+        let mut response = db.query(FEES_ALL_QUERY).await.unwrap();
+        let rows: Vec<serde_json::Value> = response.take(0).unwrap();
+        let (_, unparseable) = partition_fee_rows(rows);
+        assert_eq!(unparseable.len(), 2);
+        assert!(unparseable.iter().any(|row| row["rev"] == "2"));
+
+        let mut response = db.query(FEE_ROW_REPAIR_QUERY).await.unwrap();
+        let repaired: Vec<serde_json::Value> = response.take(0).unwrap();
+        assert_eq!(repaired.len(), 2);
+
+        let mut response = db.query(FEES_ALL_QUERY).await.unwrap();
+        let rows: Vec<serde_json::Value> = response.take(0).unwrap();
+        let (fees, unparseable) = partition_fee_rows(rows);
+        assert!(unparseable.is_empty());
+        assert!(fees.iter().any(|fee| fee.rev == 2));
+    }
+
+    #[tokio::test]
+    async fn test_fee_row_repair_fixes_rev_and_revisions() {
+        let db = memory_db().await;
+        let seed = [fee_row_query("25_97105_3", "'3'"), fee_row_query("25_97105_4", "4")].concat();
+        db.query(seed).await.unwrap();
+        db.query("UPDATE fee:25_97105_3 SET revisions = NONE, staff_name = NONE").await.unwrap();
+
+        let mut response = db.query(FEE_ROW_REPAIR_QUERY).await.unwrap();
+        let repaired: Vec<serde_json::Value> = response.take(0).unwrap();
+        assert_eq!(repaired.len(), 1);
+
+        // The string rev became a number and the missing fields got defaults
+        let mut response = db.query("SELECT * FROM fee:25_97105_3").await.unwrap();
+        let rows: Vec<serde_json::Value> = response.take(0).unwrap();
+        assert_eq!(rows[0]["rev"], 3);
+        assert_eq!(rows[0]["revisions"], serde_json::json!([]));
+        assert_eq!(rows[0]["staff_name"], "");
+
+        // The repaired row deserializes, and its rev agrees with the one
+        // reconcile_fee_revisions derives from the record ID
+        let (fees, unparseable) = partition_fee_rows(rows);
+        assert!(unparseable.is_empty());
+        assert_eq!(fees[0].rev, 3);
+        assert!(fees[0].revisions.is_empty());
+        assert_eq!(fees[0].expected_rev(), Some(3));
+
+        // Rows that already deserialize are left alone
+        let mut response = db.query(FEE_ROW_REPAIR_QUERY).await.unwrap();
+        let repaired: Vec<serde_json::Value> = response.take(0).unwrap();
+        assert!(repaired.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_adjacent_projects_across_gap() {
+        let db = memory_db().await;
+        db.query(
+            "CREATE projects:25_97103 SET name = 'Three', number = { year: 25, country: 971, seq: 3, id: '25-97103' }; \
+             CREATE projects:25_97105 SET name = 'Five', number = { year: 25, country: 971, seq: 5, id: '25-97105' }; \
+             CREATE projects:25_97109 SET name = 'Nine', number = { year: 25, country: 971, seq: 9, id: '25-97109' }; \
+             CREATE projects:25_96606 SET name = 'Saudi', number = { year: 25, country: 966, seq: 6, id: '25-96606' }; \
+             CREATE projects:24_97107 SET name = 'Last year', number = { year: 24, country: 971, seq: 7, id: '24-97107' };",
+        )
+        .await
+        .unwrap();
+
+        async fn neighbours(db: &surrealdb::Surreal<surrealdb::engine::local::Db>, id: &str) -> (Option<String>, Option<String>) {
+            let number = ProjectNumber::parse(id).unwrap();
+            let mut response = db
+                .query(PROJECT_PREV_QUERY)
+                .query(PROJECT_NEXT_QUERY)
+                .bind(("year", number.year))
+                .bind(("country", number.country))
+                .bind(("seq", number.seq))
+                .await
+                .unwrap();
+            let prev: Vec<serde_json::Value> = response.take(0).unwrap();
+            let next: Vec<serde_json::Value> = response.take(1).unwrap();
+            let name = |rows: &[serde_json::Value]| rows.first().map(|r| r["name"].as_str().unwrap().to_string());
+            (name(&prev), name(&next))
+        }
+
+        assert_eq!(neighbours(&db, "25-97105").await, (Some("Three".to_string()), Some("Nine".to_string())));
+        assert_eq!(neighbours(&db, "25-97107").await, (Some("Five".to_string()), Some("Nine".to_string())));
+        assert_eq!(neighbours(&db, "25-97103").await, (None, Some("Five".to_string())));
+        assert_eq!(neighbours(&db, "25-97109").await, (Some("Five".to_string()), None));
+    }
+
+    #[test]
+    fn test_project_number_block_respects_sequence_cap() {
+        let block = ProjectNumber::block(25, 971, 97, 3).unwrap();
+        let ids: Vec<&str> = block.iter().map(|n| n.id.as_str()).collect();
+        assert_eq!(ids, vec!["25-97197", "25-97198", "25-97199"]);
+
+        assert!(ProjectNumber::block(25, 971, 97, 4).is_err());
+        assert!(ProjectNumber::block(25, 971, 100, 1).is_err());
+        assert!(ProjectNumber::block(25, 971, 1, 0).is_err());
+        // Counts beyond i32 are rejected as too many, not wrapped to "at least one"
+        let err = ProjectNumber::block(25, 971, 1, u32::MAX).unwrap_err();
+        assert!(err.contains("only 99 of 99 remain"), "{}", err);
+        assert!(ProjectNumber::block(25, 971, 1, i32::MAX as u32).is_err());
+        assert!(ProjectNumber::block(25, 971, i32::MAX, 2).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_project_number_block_is_sequential_and_reserved() {
+        let db = memory_db().await;
+        db.query(
+            "CREATE projects:25_97101 SET number = { year: 25, country: 971, seq: 1, id: '25-97101' }; \
+             CREATE projects:25_97102 SET number = { year: 25, country: 971, seq: 2, id: '25-97102' }; \
+             CREATE projects:25_96601 SET number = { year: 25, country: 966, seq: 1, id: '25-96601' };",
+        )
+        .await
+        .unwrap();
+
+        async fn max_seq(db: &surrealdb::Surreal<surrealdb::engine::local::Db>) -> i64 {
+            let mut response = db
+                .query(PROJECT_MAX_SEQ_QUERY)
+                .bind(("year", 25u8))
+                .bind(("country", 971u16))
+                .await
+                .unwrap();
+            let rows: Vec<serde_json::Value> = response.take(0).unwrap();
+            rows[0]["number"]["seq"].as_i64().unwrap()
+        }
+        assert_eq!(max_seq(&db).await, 2);
+
+        let block = ProjectNumber::block(25, 971, 3, 5).unwrap();
+        let ids: Vec<String> = block.iter().map(|n| n.id.clone()).collect();
+        assert_eq!(ids, vec!["25-97103", "25-97104", "25-97105", "25-97106", "25-97107"]);
+        db.query(PROJECT_RESERVATION_BLOCK_QUERY)
+            .bind(("numbers", block.clone()))
+            .await
+            .unwrap()
+            .check()
+            .unwrap();
+
+        let mut response = db
+            .query("SELECT VALUE number.id FROM project_reservation ORDER BY number.seq")
+            .await
+            .unwrap();
+        let reserved: Vec<String> = response.take(0).unwrap();
+        assert_eq!(reserved, ids);
+        assert_eq!(max_seq(&db).await, 7);
+
+        // Reserving an overlapping block fails as a whole
+        let overlap = ProjectNumber::block(25, 971, 7, 2).unwrap();
+        assert!(db.query(PROJECT_RESERVATION_BLOCK_QUERY).bind(("numbers", overlap)).await.unwrap().check().is_err());
+        let mut response = db.query("SELECT VALUE number.id FROM project_reservation").await.unwrap();
+        let reserved: Vec<String> = response.take(0).unwrap();
+        assert_eq!(reserved.len(), 5);
+    }
+
+    #[tokio::test]
+    async fn test_contact_email_in_use_ignores_case_and_own_record() {
+        let db = memory_db().await;
+        db.query(
+            "CREATE contacts:john_smith SET email = 'John.Smith@Example.com'; \
+             CREATE contacts:jane_doe SET email = 'jane@example.com'; \
+             CREATE contacts:no_email SET first_name = 'Nobody';",
+        )
+        .await
+        .unwrap();
+
+        async fn in_use(db: &surrealdb::Surreal<surrealdb::engine::local::Db>, email: &str, excluding_id: &str) -> bool {
+            let mut response = db
+                .query(CONTACT_EMAIL_IN_USE_QUERY)
+                .bind(("email", email.to_string()))
+                .bind(("id", excluding_id.to_string()))
+                .await
+                .unwrap();
+            let total: Option<u64> = response.take((0, "total")).unwrap();
+            total.unwrap_or(0) > 0
+        }
+        assert!(in_use(&db, "john.smith@example.com", "jane_doe").await);
+        assert!(in_use(&db, " JOHN.SMITH@EXAMPLE.COM ", "").await);
+        assert!(!in_use(&db, "john.smith@example.com", "john_smith").await);
+        assert!(!in_use(&db, "new@example.com", "").await);
+    }
+
+    #[tokio::test]
+    async fn test_clearing_reservations_keeps_projects() {
+        let db = memory_db().await;
+        db.query(
+            "CREATE projects:25_97101 SET name = 'Hotel', number = { year: 25, country: 971, seq: 1, id: '25-97101' }; \
+             CREATE projects:25_97103 SET name = 'Villa', number = { year: 25, country: 971, seq: 3, id: '25-97103' };",
+        )
+        .await
+        .unwrap();
+        db.query(PROJECT_RESERVATION_BLOCK_QUERY)
+            .bind(("numbers", ProjectNumber::block(25, 971, 2, 3).unwrap()))
+            .await
+            .unwrap()
+            .check()
+            .unwrap();
+        db.query(PROJECT_RESERVATION_BLOCK_QUERY)
+            .bind(("numbers", ProjectNumber::block(25, 966, 1, 1).unwrap()))
+            .await
+            .unwrap()
+            .check()
+            .unwrap();
+
+        let mut response = db
+            .query(PROJECT_RESERVATION_CLEAR_QUERY)
+            .bind(("year", 25u8))
+            .bind(("country", 971u16))
+            .await
+            .unwrap();
+        let cleared: Vec<serde_json::Value> = response.take(0).unwrap();
+        let mut cleared: Vec<&str> = cleared.iter().filter_map(|r| r["number"]["id"].as_str()).collect();
+        cleared.sort();
+        assert_eq!(cleared, vec!["25-97102", "25-97104"]);
+
+        let mut response = db.query("SELECT VALUE number.id FROM projects ORDER BY number.seq").await.unwrap();
+        let projects: Vec<String> = response.take(0).unwrap();
+        assert_eq!(projects, vec!["25-97101", "25-97103"]);
+
+        // The used reservation and the other country's reservation remain
+        let mut response = db.query("SELECT VALUE number.id FROM project_reservation ORDER BY number.id").await.unwrap();
+        let reserved: Vec<String> = response.take(0).unwrap();
+        assert_eq!(reserved, vec!["25-96601", "25-97103"]);
+    }
+
+    #[tokio::test]
+    async fn test_stats_counts_computed_in_database() {
+        let db = memory_db().await;
+
+        async fn counts(db: &surrealdb::Surreal<surrealdb::engine::local::Db>) -> StatsCounts {
+            let mut response = db.query(STATS_COUNTS_QUERY).await.unwrap();
+            let mut totals = [0u64; 5];
+            for (index, total) in totals.iter_mut().enumerate() {
+                let count: Option<u64> = response.take((index, "total")).unwrap();
+                *total = count.unwrap_or(0);
+            }
+            let [projects, companies, contacts, fees, active_fees] = totals;
+            StatsCounts { projects, companies, contacts, fees, active_fees }
+        }
+        assert_eq!(counts(&db).await, StatsCounts::default());
+
+        db.query(
+            "CREATE projects:25_97101 SET name = 'Hotel'; \
+             CREATE projects:25_97102 SET name = 'Villa'; \
+             CREATE company:CHE SET name = 'Conrad'; \
+             CREATE fee:25_97101_1 SET status = 'Sent'; \
+             CREATE fee:25_97101_2 SET status = 'Lost'; \
+             CREATE fee:25_97102_1 SET status = 'Cancelled'; \
+             CREATE fee:25_97102_2 SET status = 'Awarded';",
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            counts(&db).await,
+            StatsCounts { projects: 2, companies: 1, contacts: 0, fees: 4, active_fees: 2 }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_project_counts_by_country_and_year_reconcile() {
+        let db = memory_db().await;
+
+        let mut response = db.query(PROJECT_COUNTS_BY_COUNTRY_QUERY).await.unwrap();
+        let by_country: Vec<CountryProjectCount> = response.take(0).unwrap();
+        assert!(by_country.is_empty());
+
+        db.query(
+            "CREATE projects:25_97101 SET country = 'United Arab Emirates', number = { year: 25, country: 971, seq: 1, id: '25-97101' }; \
+             CREATE projects:25_97102 SET country = 'United Arab Emirates', number = { year: 25, country: 971, seq: 2, id: '25-97102' }; \
+             CREATE projects:24_97101 SET country = 'United Arab Emirates', number = { year: 24, country: 971, seq: 1, id: '24-97101' }; \
+             CREATE projects:25_96601 SET country = 'Saudi Arabia', number = { year: 25, country: 966, seq: 1, id: '25-96601' }; \
+             CREATE projects:legacy SET name = 'Legacy';",
+        )
+        .await
+        .unwrap();
+
+        let mut response = db.query(PROJECT_COUNTS_BY_COUNTRY_QUERY).await.unwrap();
+        let by_country: Vec<CountryProjectCount> = response.take(0).unwrap();
+        assert_eq!(
+            by_country,
+            vec![
+                CountryProjectCount { country: "United Arab Emirates".to_string(), count: 3 },
+                CountryProjectCount { country: "Saudi Arabia".to_string(), count: 1 },
+                CountryProjectCount { country: "Unknown".to_string(), count: 1 },
+            ]
+        );
+
+        let mut response = db.query(PROJECT_COUNTS_BY_YEAR_QUERY).await.unwrap();
+        let by_year: Vec<YearProjectCount> = response.take(0).unwrap();
+        assert_eq!(by_year[0], YearProjectCount { year: Some(25), count: 3 });
+        assert_eq!(by_year.len(), 3);
+
+        let mut response = db.query(STATS_COUNTS_QUERY).await.unwrap();
+        let total: Option<u64> = response.take((0, "total")).unwrap();
+        assert_eq!(by_country.iter().map(|c| c.count).sum::<u64>(), total.unwrap());
+        assert_eq!(by_year.iter().map(|y| y.count).sum::<u64>(), total.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_capacity_warnings_report_near_full_country_years() {
+        let db = memory_db().await;
+        db.query(
+            "CREATE projects:25_97101 SET name = 'Hotel', number = { year: 25, country: 971, seq: 1, id: '25-97101' }; \
+             CREATE projects:25_97195 SET name = 'Tower', number = { year: 25, country: 971, seq: 95, id: '25-97195' }; \
+             CREATE projects:25_96610 SET name = 'Villa', number = { year: 25, country: 966, seq: 10, id: '25-96610' };",
+        )
+        .await
+        .unwrap();
+        db.query(PROJECT_RESERVATION_BLOCK_QUERY)
+            .bind(("numbers", ProjectNumber::block(24, 966, 97, 1).unwrap()))
+            .await
+            .unwrap()
+            .check()
+            .unwrap();
+
+        let mut response = db.query(PROJECT_CAPACITY_QUERY).bind(("threshold", 5u32)).await.unwrap();
+        let warnings: Vec<CapacityWarning> = response.take(0).unwrap();
+        assert_eq!(
+            warnings,
+            vec![
+                CapacityWarning { year: 24, country: 966, used: 97, remaining: 2 },
+                CapacityWarning { year: 25, country: 971, used: 95, remaining: 4 },
+            ]
+        );
+
+        let mut response = db.query(PROJECT_CAPACITY_QUERY).bind(("threshold", 4u32)).await.unwrap();
+        let warnings: Vec<CapacityWarning> = response.take(0).unwrap();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].country, 966);
+    }
+
+    #[tokio::test]
+    async fn test_archived_projects_hidden_until_restored() {
+        let db = memory_db().await;
+        db.query(
+            "CREATE projects:25_97101 SET name = 'Hotel', number = { id: '25-97101' }; \
+             CREATE projects:25_97102 SET name = 'Villa', number = { id: '25-97102' };",
+        )
+        .await
+        .unwrap();
+
+        async fn names(db: &surrealdb::Surreal<surrealdb::engine::local::Db>, include_deleted: bool) -> Vec<String> {
+            let mut response = db.query(PROJECTS_ORDERED_QUERY).bind(("include_deleted", include_deleted)).await.unwrap();
+            let rows: Vec<serde_json::Value> = response.take(0).unwrap();
+            rows.iter().filter_map(|row| row["name"].as_str().map(str::to_string)).collect()
+        }
+        async fn deleted_at(db: &surrealdb::Surreal<surrealdb::engine::local::Db>, id: &str) -> Vec<serde_json::Value> {
+            let mut response = db.query(PROJECT_DELETED_AT_QUERY).bind(("id", id.to_string())).await.unwrap();
+            response.take(0).unwrap()
+        }
+        assert!(deleted_at(&db, "25_97101").await[0].is_null());
+        assert!(deleted_at(&db, "25_99999").await.is_empty());
+
+        let mut response = db.query(PROJECT_ARCHIVE_QUERY).bind(("id", "25_97101".to_string())).await.unwrap();
+        let archived: Vec<serde_json::Value> = response.take(0).unwrap();
+        assert!(!archived[0]["deleted_at"].is_null());
+        assert!(!deleted_at(&db, "25_97101").await[0].is_null());
+        assert_eq!(names(&db, false).await, vec!["Villa"]);
+        assert_eq!(names(&db, true).await.len(), 2);
+
+        db.query(PROJECT_RESTORE_QUERY).bind(("id", "25_97101".to_string())).await.unwrap();
+        assert!(deleted_at(&db, "25_97101").await[0].is_null());
+        assert_eq!(names(&db, false).await.len(), 2);
+
+        // Archiving a missing project doesn't create it
+        let mut response = db.query(PROJECT_ARCHIVE_QUERY).bind(("id", "25_99999".to_string())).await.unwrap();
+        let missing: Vec<serde_json::Value> = response.take(0).unwrap();
+        assert!(missing.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_archived_projects_excluded_from_counts_and_search() {
+        let db = memory_db().await;
+        db.query(
+            "CREATE projects:25_97101 SET name = 'Hotel Marina', country = 'United Arab Emirates', number = { year: 25, country: 971, seq: 1, id: '25-97101' }; \
+             CREATE projects:25_97102 SET name = 'Villa Marina', country = 'United Arab Emirates', number = { year: 25, country: 971, seq: 2, id: '25-97102' };",
+        )
+        .await
+        .unwrap();
+        db.query(PROJECT_ARCHIVE_QUERY).bind(("id", "25_97102".to_string())).await.unwrap();
+
+        let mut response = db.query(STATS_COUNTS_QUERY).await.unwrap();
+        let total: Option<u64> = response.take((0, "total")).unwrap();
+        assert_eq!(total, Some(1));
+
+        let mut response = db.query(PROJECT_COUNTS_BY_COUNTRY_QUERY).await.unwrap();
+        let by_country: Vec<CountryProjectCount> = response.take(0).unwrap();
+        assert_eq!(by_country, vec![CountryProjectCount { country: "United Arab Emirates".to_string(), count: 1 }]);
+
+        let mut response = db.query(PROJECT_COUNTS_BY_YEAR_QUERY).await.unwrap();
+        let by_year: Vec<YearProjectCount> = response.take(0).unwrap();
+        assert_eq!(by_year, vec![YearProjectCount { year: Some(25), count: 1 }]);
+
+        let mut response = db.query(PROJECTS_SEARCH_QUERY).bind(("query", "marina".to_string())).await.unwrap();
+        let found: Vec<serde_json::Value> = response.take(0).unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0]["name"], "Hotel Marina");
+    }
+
+    #[tokio::test]
+    async fn test_fees_for_project_newest_first() {
+        let db = memory_db().await;
+        db.query(
+            "CREATE fee:25_97105_1 SET project_id = projects:25_97105, time = { created_at: d'2025-01-01T00:00:00Z' }; \
+             CREATE fee:25_97105_2 SET project_id = projects:25_97105, time = { created_at: d'2025-02-01T00:00:00Z' }; \
+             CREATE fee:25_97106_1 SET project_id = projects:25_97106, time = { created_at: d'2025-03-01T00:00:00Z' };",
+        )
+        .await
+        .unwrap();
+
+        let mut response = db
+            .query(FEES_FOR_PROJECT_QUERY)
+            .bind(("pid", "25_97105".to_string()))
+            .await
+            .unwrap();
+        let rows: Vec<serde_json::Value> = response.take(0).unwrap();
+        let ids: Vec<String> = rows.iter().map(|row| row["id"].to_string()).collect();
+        assert_eq!(ids.len(), 2);
+        assert!(ids[0].contains("25_97105_2"));
+        assert!(ids[1].contains("25_97105_1"));
+    }
+
+    #[tokio::test]
+    async fn test_fee_project_fields_follow_project_link() {
+        let db = memory_db().await;
+        let project = NewProject {
+            name: "Grand Hotel".to_string(),
+            name_short: "Hotel".to_string(),
+            status: "RFP".to_string(),
+            area: "Downtown".to_string(),
+            city: "Dubai".to_string(),
+            country: "United Arab Emirates".to_string(),
+            folder: "25-97105 Hotel".to_string(),
+            number: ProjectNumber::new(25, 971, 5),
+            activity: Some("Supervision".to_string()),
+            package: Some("Lighting".to_string()),
+            template_override: None,
+        };
+        db.query(PROJECT_CREATE_QUERY).bind(("id", "25_97105".to_string())).bind(project).await.unwrap();
+        db.query("CREATE fee:25_97105_1 SET project_id = projects:25_97105, activity = 'Design and Consultancy', package = 'Lighting'")
+            .await
+            .unwrap();
+
+        let mut response = db
+            .query(FEE_PROJECT_FIELDS_QUERY)
+            .bind(("id", "25_97105_1".to_string()))
+            .await
+            .unwrap();
+        let rows: Vec<serde_json::Value> = response.take(0).unwrap();
+
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0]["fee_activity"], "Design and Consultancy");
+        assert_eq!(rows[0]["project_activity"], "Supervision");
+        assert_eq!(rows[0]["fee_package"], "Lighting");
+        assert_eq!(rows[0]["project_package"], "Lighting");
+    }
+
+    #[tokio::test]
+    async fn test_fee_revision_append_bumps_rev() {
+        let db = memory_db().await;
+        db.query("CREATE fee:25_97105_1 SET name = 'Lighting', number = '25-97105-FP', rev = 1, revisions = []")
+            .await
+            .unwrap();
+
+        async fn append(db: &surrealdb::Surreal<surrealdb::engine::local::Db>, notes: &str) -> serde_json::Value {
+            let mut response = db
+                .query(FEE_REVISION_APPEND_QUERY)
+                .bind(("id", "25_97105_1".to_string()))
+                .bind(("notes", notes.to_string()))
+                .bind(("author_name", "Jane Doe".to_string()))
+                .bind(("author_email", "jane@example.com".to_string()))
+                .await
+                .unwrap();
+            let mut rows: Vec<serde_json::Value> = response.take(0).unwrap();
+            rows.pop().unwrap()
+        }
+        append(&db, "Initial issue").await;
+        let fee = append(&db, "Reduced scope").await;
+
+        // The seeded rev 1 is kept, so the appended revisions are 2 and 3
+        assert_eq!(fee["rev"], 3);
+        assert_eq!(fee["name"], "Lighting");
+        assert_eq!(fee["number"], "25-97105-FP");
+        let revisions = fee["revisions"].as_array().unwrap();
+        assert_eq!(revisions.len(), 2);
+        assert_eq!(revisions[0]["revision_number"], 2);
+        assert_eq!(revisions[1]["revision_number"], 3);
+        assert_eq!(revisions[1]["notes"], "Reduced scope");
+        assert_eq!(revisions[1]["author_email"], "jane@example.com");
+        assert!(revisions[1]["revision_date"].as_str().is_some_and(|date| !date.is_empty()));
+    }
+
+    #[tokio::test]
+    async fn test_fee_revision_append_continues_legacy_rev() {
+        let db = memory_db().await;
+        db.query("CREATE fee:25_97105_3 SET name = 'Lighting', number = '25-97105-FP', rev = 3, revisions = []")
+            .await
+            .unwrap();
+
+        let mut response = db
+            .query(FEE_REVISION_APPEND_QUERY)
+            .bind(("id", "25_97105_3".to_string()))
+            .bind(("notes", "Revised programme".to_string()))
+            .bind(("author_name", "Jane Doe".to_string()))
+            .bind(("author_email", "jane@example.com".to_string()))
+            .await
+            .unwrap();
+        let mut rows: Vec<serde_json::Value> = response.take(0).unwrap();
+        let fee = rows.pop().unwrap();
+
+        assert_eq!(fee["rev"], 4);
+        let revisions = fee["revisions"].as_array().unwrap();
+        assert_eq!(revisions.len(), 1);
+        assert_eq!(revisions[0]["revision_number"], 4);
+    }
+
+    #[tokio::test]
+    async fn test_fees_for_contact_count() {
+        let db = memory_db().await;
+        db.query(
+            "CREATE fee:a SET contact_id = contacts:john_smith; \
+             CREATE fee:b SET contact_id = contacts:john_smith; \
+             CREATE fee:c SET contact_id = contacts:jane_doe;",
+        )
+        .await
+        .unwrap();
+
+        async fn count(db: &surrealdb::Surreal<surrealdb::engine::local::Db>, id: &str) -> u64 {
+            let mut response = db.query(FEES_FOR_CONTACT_COUNT_QUERY).bind(("id", id.to_string())).await.unwrap();
+            let total: Option<u64> = response.take((0, "total")).unwrap();
+            total.unwrap_or(0)
+        }
+        assert_eq!(count(&db, "john_smith").await, 2);
+        assert_eq!(count(&db, "jane_doe").await, 1);
+        assert_eq!(count(&db, "nobody").await, 0);
+    }
+
+    #[tokio::test]
+    async fn test_company_country_resolves_to_canonical_name() {
+        let db = memory_db().await;
+        db.query(
+            "CREATE country:AE SET name = 'United Arab Emirates', code = 'AE', code_alt = 'ARE', dial_code = 971; \
+             CREATE country:SA SET name = 'Saudi Arabia', name_official = 'Kingdom of Saudi Arabia', code = 'SA', code_alt = 'SAU', dial_code = 966;",
+        )
+        .await
+        .unwrap();
+
+        let mut response = db.query(COUNTRY_LOOKUP_QUERY).await.unwrap();
+        let countries: Vec<serde_json::Value> = response.take(0).unwrap();
+
+        assert_eq!(resolve_country_name("United Arab Emirates", &countries).as_deref(), Some("United Arab Emirates"));
+        assert_eq!(resolve_country_name(" united arab emirates ", &countries).as_deref(), Some("United Arab Emirates"));
+        assert_eq!(resolve_country_name("UAE", &countries).as_deref(), Some("United Arab Emirates"));
+        assert_eq!(resolve_country_name("ARE", &countries).as_deref(), Some("United Arab Emirates"));
+        assert_eq!(resolve_country_name("KSA", &countries).as_deref(), Some("Saudi Arabia"));
+        assert_eq!(resolve_country_name("Kingdom of Saudi Arabia", &countries).as_deref(), Some("Saudi Arabia"));
+        assert_eq!(resolve_country_name("Atlantis", &countries), None);
+        assert_eq!(resolve_country_name("", &countries), None);
+    }
+
+    #[tokio::test]
+    async fn test_companies_query_orders_case_insensitively() {
+        let db = memory_db().await;
+        db.query(
+            "CREATE company:ZED SET name = 'zenith Hotels'; \
+             CREATE company:ACM SET name = 'Acme Developments'; \
+             CREATE company:BET SET name = 'beta Group'; \
+             CREATE company:ALP SET name = 'alpha Holdings';",
+        )
+        .await
+        .unwrap();
+
+        let mut response = db.query(COMPANIES_ORDERED_QUERY).await.unwrap();
+        let rows: Vec<serde_json::Value> = response.take(0).unwrap();
+        let names: Vec<&str> = rows.iter().map(|r| r["name"].as_str().unwrap()).collect();
+        assert_eq!(names, vec!["Acme Developments", "alpha Holdings", "beta Group", "zenith Hotels"]);
+    }
+
+    #[tokio::test]
+    async fn test_companies_page_queries() {
+        let db = memory_db().await;
+        db.query(
+            "CREATE company:ZED SET name = 'zenith Hotels', country = 'Saudi Arabia'; \
+             CREATE company:ACM SET name = 'Acme Developments', country = 'United Arab Emirates'; \
+             CREATE company:BET SET name = 'beta Group', country = 'United Arab Emirates'; \
+             CREATE company:ALP SET name = 'alpha Holdings', country = 'United Arab Emirates';",
+        )
+        .await
+        .unwrap();
+
+        async fn page(
+            db: &surrealdb::Surreal<surrealdb::engine::local::Db>,
+            offset: u64,
+            limit: u64,
+            country: Option<&str>,
+        ) -> (Vec<String>, Option<u64>) {
+            let mut response = db
+                .query(COMPANIES_PAGE_QUERY)
+                .query(COMPANIES_COUNT_QUERY)
+                .bind(("country", country.map(str::to_string)))
+                .bind(("limit", limit))
+                .bind(("offset", offset))
+                .await
+                .unwrap();
+            let rows: Vec<serde_json::Value> = response.take(0).unwrap();
+            let total: Option<u64> = response.take((1, "total")).unwrap();
+            let names = rows.iter().map(|r| r["name"].as_str().unwrap().to_string()).collect();
+            (names, total)
+        }
+
+        assert_eq!(page(&db, 0, 2, None).await, (vec!["Acme Developments".to_string(), "alpha Holdings".to_string()], Some(4)));
+        assert_eq!(page(&db, 2, 2, None).await, (vec!["beta Group".to_string(), "zenith Hotels".to_string()], Some(4)));
+        assert_eq!(page(&db, 4, 2, None).await, (vec![], Some(4)));
+
+        let (names, total) = page(&db, 1, 10, Some("united arab emirates")).await;
+        assert_eq!(names, vec!["alpha Holdings", "beta Group"]);
+        assert_eq!(total, Some(3));
+
+        let (names, total) = page(&db, 0, 10, Some("Qatar")).await;
+        assert!(names.is_empty());
+        assert_eq!(total, None);
+    }
+
+    #[tokio::test]
+    async fn test_projects_and_fees_page_queries() {
+        let db = memory_db().await;
+        db.query(
+            "CREATE projects:25_97101 SET number = { id: '25-97101' }, time = { created_at: d'2025-01-05T09:00:00Z' }; \
+             CREATE projects:25_97102 SET number = { id: '25-97102' }, time = { created_at: d'2025-02-01T09:00:00Z' }; \
+             CREATE projects:25_97103 SET number = { id: '25-97103' }, time = { created_at: d'2025-03-01T09:00:00Z' }, deleted_at = d'2025-04-01T09:00:00Z'; \
+             CREATE fee:a SET number = '25-97101-FP', time = { created_at: d'2025-01-06T09:00:00Z' }; \
+             CREATE fee:b SET number = '25-97102-FP', time = { created_at: d'2025-02-02T09:00:00Z' }; \
+             CREATE fee:c SET number = '25-97103-FP', time = { created_at: d'2025-03-02T09:00:00Z' };",
+        )
+        .await
+        .unwrap();
+
+        async fn page(
+            db: &surrealdb::Surreal<surrealdb::engine::local::Db>,
+            page_query: &str,
+            count_query: &str,
+            offset: u64,
+            limit: u64,
+            include_deleted: bool,
+        ) -> (Vec<String>, Option<u64>) {
+            let mut response = db
+                .query(page_query)
+                .query(count_query)
+                .bind(("include_deleted", include_deleted))
+                .bind(("limit", limit))
+                .bind(("offset", offset))
+                .await
+                .unwrap();
+            let rows: Vec<serde_json::Value> = response.take(0).unwrap();
+            let total: Option<u64> = response.take((1, "total")).unwrap();
+            let numbers = rows
+                .iter()
+                .map(|r| r["number"]["id"].as_str().or(r["number"].as_str()).unwrap().to_string())
+                .collect();
+            (numbers, total)
+        }
+
+        assert_eq!(
+            page(&db, PROJECTS_PAGE_QUERY, PROJECTS_COUNT_QUERY, 0, 1, false).await,
+            (vec!["25-97102".to_string()], Some(2))
+        );
+        assert_eq!(
+            page(&db, PROJECTS_PAGE_QUERY, PROJECTS_COUNT_QUERY, 1, 1, false).await,
+            (vec!["25-97101".to_string()], Some(2))
+        );
+        assert_eq!(
+            page(&db, PROJECTS_PAGE_QUERY, PROJECTS_COUNT_QUERY, 0, 2, true).await,
+            (vec!["25-97103".to_string(), "25-97102".to_string()], Some(3))
+        );
+        assert_eq!(
+            page(&db, FEES_PAGE_QUERY, FEES_COUNT_QUERY, 1, 5, false).await,
+            (vec!["25-97102-FP".to_string(), "25-97101-FP".to_string()], Some(3))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_projects_query_orders_newest_first() {
+        let db = memory_db().await;
+        db.query(
+            "CREATE projects:25_97101 SET number = { id: '25-97101' }, time = { created_at: d'2025-01-05T09:00:00Z' }; \
+             CREATE projects:25_97103 SET number = { id: '25-97103' }, time = { created_at: d'2025-03-01T09:00:00Z' }; \
+             CREATE projects:24_97150 SET number = { id: '24-97150' }; \
+             CREATE projects:24_97151 SET number = { id: '24-97151' }; \
+             CREATE projects:25_97102 SET number = { id: '25-97102' }, time = { created_at: d'2025-02-10T09:00:00Z' };",
+        )
+        .await
+        .unwrap();
+
+        let mut response = db.query(PROJECTS_ORDERED_QUERY).await.unwrap();
+        let rows: Vec<serde_json::Value> = response.take(0).unwrap();
+        let numbers: Vec<&str> = rows.iter().map(|r| r["number"]["id"].as_str().unwrap()).collect();
+        assert_eq!(numbers, vec!["25-97103", "25-97102", "25-97101", "24-97151", "24-97150"]);
+    }
+
+    async fn search_fee_numbers(db: &surrealdb::Surreal<surrealdb::engine::local::Db>, query: &str) -> Vec<String> {
+        let mut response = db
+            .query(FEES_SEARCH_QUERY)
+            .bind(("query", query.to_lowercase()))
+            .await
+            .unwrap();
+        let rows: Vec<serde_json::Value> = response.take(0).unwrap();
+        rows.iter().map(|r| r["number"].as_str().unwrap().to_string()).collect()
+    }
+
+    #[tokio::test]
+    async fn test_fee_search_by_number_and_activity() {
+        let db = memory_db().await;
+        db.query(
+            "CREATE fee:25_97105_1 SET name = 'Hotel Lighting', number = '25-97105-FP-1', rev = 1, activity = 'Design and Consultancy', package = 'Lighting'; \
+             CREATE fee:25_96601_1 SET name = 'Villa Audio', number = '25-96601-FP-1', rev = 1, activity = 'Supervision', package = 'AV'; \
+             CREATE fee:25_97106_1 SET name = 'Mall', number = '25-97106-FP-1', rev = 1, activity = 'Design Review', strap_line = 'O''Brien design';",
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(search_fee_numbers(&db, "97105").await, vec!["25-97105-FP-1"]);
+        assert_eq!(search_fee_numbers(&db, "SUPERVISION").await, vec!["25-96601-FP-1"]);
+        assert_eq!(search_fee_numbers(&db, "design").await, vec!["25-97105-FP-1", "25-97106-FP-1"]);
+        // Quotes are bound, not interpolated
+        assert_eq!(search_fee_numbers(&db, "o'brien").await, vec!["25-97106-FP-1"]);
+    }
+
+    // ============================================================================
+    // FEE REVISION RECONCILIATION TESTS
+    // ============================================================================
+
+    #[test]
+    fn test_expected_rev_from_id_when_revisions_empty() {
+        // rev=5 was supplied by the caller but the record is revision 1
+        let mut fee = sample_fee("25_97105_1");
+        fee.rev = 5;
+        assert_eq!(fee.expected_rev(), Some(1));
+        assert_ne!(fee.rev, fee.expected_rev().unwrap());
+    }
+
+    #[test]
+    fn test_expected_rev_from_revisions() {
+        let mut fee = sample_fee("25_97105_1");
+        for n in 1..=3 {
+            fee.revisions.push(Revision {
+                revision_number: n,
+                revision_date: "2025-01-10".to_string(),
+                author_email: "staff@example.com".to_string(),
+                author_name: "Staff".to_string(),
+                notes: String::new(),
+            });
+        }
+        assert_eq!(fee.expected_rev(), Some(3));
+    }
+
+    #[test]
+    fn test_expected_rev_unparseable_id() {
+        let fee = sample_fee("legacy");
+        assert_eq!(fee.expected_rev(), None);
+    }
+
     // ============================================================================
     // PROJECT NUMBER GENERATION TESTS
     // ============================================================================
@@ -260,6 +2304,36 @@ mod tests {
         assert_eq!(extract_id_string(""), None);
     }
 
+    #[test]
+    fn test_thing_matches_bracketed_and_bare_ids() {
+        use surrealdb::sql::Thing;
+
+        // Keys starting with a digit are displayed as fee:⟨22_96601_1⟩
+        let fee = Thing::from(("fee", "22_96601_1"));
+        assert_eq!(normalize_thing_id(&fee), "22_96601_1");
+        assert!(thing_matches(&fee, "fee:⟨22_96601_1⟩"));
+        assert!(thing_matches(&fee, "fee:22_96601_1"));
+        assert!(thing_matches(&fee, "22_96601_1"));
+        assert!(thing_matches(&fee, &fee.to_string()));
+
+        assert!(!thing_matches(&fee, "fee:22_96601_2"));
+        assert!(!thing_matches(&fee, "projects:22_96601_1"));
+        assert_eq!(normalize_thing_id(&Thing::from(("company", "CHE"))), "CHE");
+    }
+
+    #[test]
+    fn test_record_key_matches_normalized_thing_id() {
+        use surrealdb::sql::Thing;
+
+        let fee = Thing::from(("fee", "22_96601_1"));
+        for input in ["fee:⟨22_96601_1⟩", "fee:22_96601_1", " 22_96601_1 ", "⟨22_96601_1⟩"] {
+            assert_eq!(record_key(input, "fee"), normalize_thing_id(&fee));
+        }
+        // Only the prefix of the given table is stripped
+        assert_eq!(record_key("fees:22_96601_1", "fee"), "fees:22_96601_1");
+        assert_eq!(record_key("projects:⟨25-97105⟩", "projects"), "25-97105");
+    }
+
     // ============================================================================
     // VALIDATION HELPER FUNCTIONS
     // ============================================================================
@@ -398,4 +2472,48 @@ mod tests {
         country_code: u16,
         sequence: u8,
     }
+
+    fn sample_fee(key: &str) -> Fee {
+        use surrealdb::sql::Thing;
+
+        Fee {
+            id: Some(Thing::from(("fee", key))),
+            name: "Hotel Lighting Design".to_string(),
+            number: "25-97105-FP-1".to_string(),
+            rev: 1,
+            status: "Draft".to_string(),
+            issue_date: "250110".to_string(),
+            activity: "Design and Consultancy".to_string(),
+            package: "Lighting".to_string(),
+            project_id: Thing::from(("projects", "25_97105")),
+            company_id: Thing::from(("company", "CHE")),
+            contact_id: Thing::from(("contacts", "john_smith")),
+            staff_name: "Staff".to_string(),
+            staff_email: "staff@example.com".to_string(),
+            staff_phone: "+971 50 000 0000".to_string(),
+            staff_position: "Director".to_string(),
+            strap_line: "sensory design studio".to_string(),
+            revisions: Vec::new(),
+            time: TimeStamps {
+                created_at: "2025-01-10T00:00:00Z".to_string(),
+                updated_at: "2025-01-10T00:00:00Z".to_string(),
+            },
+            currency: Some("AED".to_string()),
+            amount: None,
+        }
+    }
+
+    fn contact(first_name: Option<&str>, last_name: Option<&str>) -> Contact {
+        Contact {
+            id: None,
+            first_name: first_name.map(String::from),
+            last_name: last_name.map(String::from),
+            full_name: None,
+            email: Some("contact@example.com".to_string()),
+            phone: None,
+            position: None,
+            company: None,
+            time: None,
+        }
+    }
 }
@@ -1,3 +1,372 @@
-// This file is reserved for future database utility functions.
-// Currently, all database operations are implemented directly in the DatabaseManager
-// and use the execute_with_manager utility function from commands/utils.rs
\ No newline at end of file
+//! # Database Utility Functions
+//!
+//! Helpers applied by the `DatabaseManager` when data is written or read back,
+//! so every create, update and fetch path gets the same treatment regardless
+//! of which command triggered it.
+
+use super::{CompanyCreate, Contact, ContactCreate, Fee, FeeCreate, FeeUpdate, IncompleteContact, NewProject, Project};
+use log::warn;
+use std::cmp::Ordering;
+use surrealdb::sql::Thing;
+use crate::commands::{CompanyUpdate, ContactUpdate, ProjectUpdate};
+
+/// Trim and collapse whitespace in a text value.
+///
+/// Copy-pasted data often carries trailing spaces, doubled spaces or
+/// non-breaking spaces, which break folder names and searches
+/// (`"Dubai "` vs `"Dubai"`). All Unicode whitespace runs are collapsed to a
+/// single ASCII space and leading/trailing whitespace is removed.
+///
+/// # Examples
+/// - `"  Dubai "` → `"Dubai"`
+/// - `"Palm\u{00A0}Jumeirah"` → `"Palm Jumeirah"`
+/// - `"Hotel   ABC"` → `"Hotel ABC"`
+pub fn normalize_text(value: &str) -> String {
+    value.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Record key of a `Thing` without SurrealDB's `⟨...⟩` escaping.
+///
+/// `fee:⟨22_96601_1⟩` and `fee:22_96601_1` both give `22_96601_1`, so keys
+/// can be compared with each other and with IDs sent by the frontend.
+pub fn normalize_thing_id(thing: &Thing) -> String {
+    thing.id.to_string().trim_start_matches('⟨').trim_end_matches('⟩').to_string()
+}
+
+/// Record key of an ID sent by the frontend for a record in `table`.
+///
+/// Accepts the escaped `fee:⟨22_96601_1⟩`, unescaped `fee:22_96601_1` and
+/// bare `22_96601_1` or `⟨22_96601_1⟩` forms, and gives the same key as
+/// `normalize_thing_id` does for the stored record.
+pub fn record_key<'a>(input: &'a str, table: &str) -> &'a str {
+    let input = input.trim();
+    let key = input
+        .strip_prefix(table)
+        .and_then(|rest| rest.strip_prefix(':'))
+        .unwrap_or(input);
+    key.trim_start_matches('⟨').trim_end_matches('⟩')
+}
+
+/// Whether an ID sent by the frontend refers to `thing`.
+///
+/// Accepts the same forms as `record_key`. A prefix naming another table
+/// never matches.
+pub fn thing_matches(thing: &Thing, input: &str) -> bool {
+    if let Some((table, _)) = input.trim().split_once(':') {
+        if !table.starts_with('⟨') && table != thing.tb {
+            return false;
+        }
+    }
+    record_key(input, &thing.tb) == normalize_thing_id(thing)
+}
+
+/// Canonical form of a company abbreviation, which doubles as its record ID.
+///
+/// Removes all whitespace and uppercases, so `"che"`, `"CHE "` and `"C HE"`
+/// all become `"CHE"` instead of three companies that only look the same.
+pub fn normalize_abbreviation(value: &str) -> String {
+    value.split_whitespace().collect::<String>().to_uppercase()
+}
+
+fn normalize_option(value: &mut Option<String>) {
+    if let Some(text) = value {
+        *text = normalize_text(text);
+    }
+}
+
+/// Normalize the name and location fields of a record before it is stored.
+pub trait NormalizeText {
+    fn normalize_text(&mut self);
+}
+
+impl NormalizeText for Project {
+    fn normalize_text(&mut self) {
+        self.name = normalize_text(&self.name);
+        self.name_short = normalize_text(&self.name_short);
+        self.area = normalize_text(&self.area);
+        self.city = normalize_text(&self.city);
+        self.country = normalize_text(&self.country);
+        self.folder = normalize_text(&self.folder);
+    }
+}
+
+impl NormalizeText for NewProject {
+    fn normalize_text(&mut self) {
+        self.name = normalize_text(&self.name);
+        self.name_short = normalize_text(&self.name_short);
+        self.area = normalize_text(&self.area);
+        self.city = normalize_text(&self.city);
+        self.country = normalize_text(&self.country);
+        self.folder = normalize_text(&self.folder);
+    }
+}
+
+impl NormalizeText for ProjectUpdate {
+    fn normalize_text(&mut self) {
+        normalize_option(&mut self.name);
+        normalize_option(&mut self.name_short);
+        normalize_option(&mut self.area);
+        normalize_option(&mut self.city);
+        normalize_option(&mut self.country);
+        normalize_option(&mut self.folder);
+    }
+}
+
+impl NormalizeText for CompanyCreate {
+    fn normalize_text(&mut self) {
+        self.name = normalize_text(&self.name);
+        self.name_short = normalize_text(&self.name_short);
+        self.abbreviation = normalize_abbreviation(&self.abbreviation);
+        self.city = normalize_text(&self.city);
+        self.country = normalize_text(&self.country);
+    }
+}
+
+impl NormalizeText for CompanyUpdate {
+    fn normalize_text(&mut self) {
+        normalize_option(&mut self.name);
+        normalize_option(&mut self.name_short);
+        normalize_option(&mut self.city);
+        normalize_option(&mut self.country);
+    }
+}
+
+impl NormalizeText for ContactCreate {
+    fn normalize_text(&mut self) {
+        self.first_name = normalize_text(&self.first_name);
+        self.last_name = normalize_text(&self.last_name);
+        self.position = normalize_text(&self.position);
+    }
+}
+
+impl NormalizeText for ContactUpdate {
+    fn normalize_text(&mut self) {
+        normalize_option(&mut self.first_name);
+        normalize_option(&mut self.last_name);
+        normalize_option(&mut self.full_name);
+        normalize_option(&mut self.position);
+    }
+}
+
+impl NormalizeText for FeeCreate {
+    fn normalize_text(&mut self) {
+        self.name = normalize_text(&self.name);
+    }
+}
+
+impl NormalizeText for FeeUpdate {
+    fn normalize_text(&mut self) {
+        self.name = normalize_text(&self.name);
+    }
+}
+
+/// Sort contacts by last name, then first name, case-insensitively.
+///
+/// Contacts with a missing or empty name part sort after those that have one.
+pub fn sort_contacts_by_name(contacts: &mut [Contact]) {
+    fn name_key(name: &Option<String>) -> Option<String> {
+        name.as_ref()
+            .map(|n| n.trim().to_lowercase())
+            .filter(|n| !n.is_empty())
+    }
+
+    fn compare(a: &Option<String>, b: &Option<String>) -> Ordering {
+        match (name_key(a), name_key(b)) {
+            (Some(a), Some(b)) => a.cmp(&b),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        }
+    }
+
+    contacts.sort_by(|a, b| {
+        compare(&a.last_name, &b.last_name).then_with(|| compare(&a.first_name, &b.first_name))
+    });
+}
+
+/// Names of the required contact fields that are missing or empty.
+///
+/// A contact needs a first and last name, email, phone, position and company
+/// to be usable on a fee proposal; `get_contacts` drops any that don't.
+pub fn missing_contact_fields(contact: &Contact) -> Vec<String> {
+    let is_blank = |value: &Option<String>| value.as_ref().map_or(true, |s| s.is_empty());
+
+    let mut missing = Vec::new();
+    if is_blank(&contact.first_name) {
+        missing.push("first_name".to_string());
+    }
+    if is_blank(&contact.last_name) {
+        missing.push("last_name".to_string());
+    }
+    if is_blank(&contact.email) {
+        missing.push("email".to_string());
+    }
+    if is_blank(&contact.phone) {
+        missing.push("phone".to_string());
+    }
+    if is_blank(&contact.position) {
+        missing.push("position".to_string());
+    }
+    if contact.company.is_none() {
+        missing.push("company".to_string());
+    }
+    missing
+}
+
+/// Report every contact with at least one missing required field.
+pub fn incomplete_contacts(contacts: &[Contact]) -> Vec<IncompleteContact> {
+    contacts
+        .iter()
+        .filter_map(|contact| {
+            let missing_fields = missing_contact_fields(contact);
+            if missing_fields.is_empty() {
+                return None;
+            }
+            let id = contact
+                .id
+                .as_ref()
+                .map(|id| format!("{}:{}", id.tb, normalize_thing_id(id)))
+                .unwrap_or_default();
+            Some(IncompleteContact { id, missing_fields })
+        })
+        .collect()
+}
+
+/// Common abbreviations that aren't stored as a code in the `country` table.
+const COUNTRY_ALIASES: &[(&str, &str)] = &[
+    ("uae", "United Arab Emirates"),
+    ("ksa", "Saudi Arabia"),
+    ("uk", "United Kingdom"),
+    ("usa", "United States"),
+];
+
+/// Resolve a free-text country to its canonical `name` in the `country` table.
+///
+/// Matches case-insensitively against the name, formal and official names and
+/// both codes, after expanding common aliases such as "UAE".
+pub fn resolve_country_name(input: &str, countries: &[serde_json::Value]) -> Option<String> {
+    let needle = input.trim().to_lowercase();
+    if needle.is_empty() {
+        return None;
+    }
+    let needle = COUNTRY_ALIASES
+        .iter()
+        .find(|(alias, _)| *alias == needle)
+        .map(|(_, name)| name.to_lowercase())
+        .unwrap_or(needle);
+
+    countries
+        .iter()
+        .find(|country| {
+            ["name", "name_formal", "name_official", "code", "code_alt"]
+                .iter()
+                .filter_map(|field| country[*field].as_str())
+                .any(|value| value.trim().to_lowercase() == needle)
+        })
+        .and_then(|country| country["name"].as_str().map(String::from))
+}
+
+/// Fields of `db::Project` and whether deserialization requires them.
+pub(crate) const PROJECT_FIELDS: &[(&str, bool)] = &[
+    ("id", false),
+    ("name", true),
+    ("name_short", true),
+    ("status", true),
+    ("area", true),
+    ("city", true),
+    ("country", true),
+    ("folder", true),
+    ("number", true),
+    ("time", true),
+    ("activity", false),
+    ("package", false),
+    ("template_override", false),
+    ("deleted_at", false),
+];
+
+/// Fields of `entities::Project` (not compiled yet) and whether deserialization
+/// requires them.
+const ENTITIES_PROJECT_FIELDS: &[(&str, bool)] = &[
+    ("id", false),
+    ("name", true),
+    ("name_short", true),
+    ("activity", true),
+    ("package", true),
+    ("status", true),
+    ("stage", false),
+    ("area", true),
+    ("city", true),
+    ("country", true),
+    ("folder", true),
+    ("number", true),
+    ("time", false),
+];
+
+/// Compare a raw `projects` row against both `Project` structs.
+///
+/// Reports struct fields the row lacks (or holds as null), marking the ones
+/// that make deserialization fail, and row fields the struct would ignore.
+/// Used while consolidating `db::Project` and `entities::Project`.
+pub fn project_schema_gaps(row: &serde_json::Value) -> Vec<String> {
+    let mut gaps = Vec::new();
+    for (struct_name, fields) in [("db::Project", PROJECT_FIELDS), ("entities::Project", ENTITIES_PROJECT_FIELDS)] {
+        for (field, required) in fields {
+            if row.get(field).filter(|value| !value.is_null()).is_none() {
+                let impact = if *required { "required, rows fail to deserialize" } else { "optional" };
+                gaps.push(format!("{}: missing `{}` ({})", struct_name, field, impact));
+            }
+        }
+        if let Some(object) = row.as_object() {
+            for key in object.keys().filter(|key| !fields.iter().any(|(field, _)| field == key)) {
+                gaps.push(format!("{}: row field `{}` is not in the struct", struct_name, key));
+            }
+        }
+    }
+    gaps
+}
+
+/// Split raw fee rows into those that deserialize into `Fee` and those that
+/// don't.
+///
+/// Reading the table as `Vec<Fee>` fails outright on a single bad row, hiding
+/// every fee from the user. Converting row by row keeps the good ones; the
+/// bad rows are logged and returned untouched so they can be inspected.
+pub fn partition_fee_rows(rows: Vec<serde_json::Value>) -> (Vec<Fee>, Vec<serde_json::Value>) {
+    let mut fees = Vec::with_capacity(rows.len());
+    let mut unparseable = Vec::new();
+    for row in rows {
+        match serde_json::from_value::<Fee>(row.clone()) {
+            Ok(fee) => fees.push(fee),
+            Err(e) => {
+                warn!("Skipping fee row {} that does not match the Fee struct: {}", row["id"], e);
+                unparseable.push(row);
+            }
+        }
+    }
+    (fees, unparseable)
+}
+
+/// Check the configured namespace and database against `INFO FOR ROOT` and
+/// `INFO FOR NS` results.
+///
+/// Root-level authentication succeeds even when the namespace or database
+/// was never defined, and queries then just return nothing. `root_info` is
+/// `None` when the credentials can't read root info, in which case only the
+/// database is checked. The error names whichever one is missing.
+pub fn check_namespace_database(
+    namespace: &str,
+    database: &str,
+    root_info: Option<&serde_json::Value>,
+    ns_info: &serde_json::Value,
+) -> Result<(), String> {
+    let defines = |info: &serde_json::Value, section: &str, name: &str| {
+        info.get(section).and_then(|entries| entries.as_object()).is_some_and(|entries| entries.contains_key(name))
+    };
+    if root_info.is_some_and(|root| !defines(root, "namespaces", namespace)) {
+        return Err(format!("Namespace '{}' does not exist", namespace));
+    }
+    if !defines(ns_info, "databases", database) {
+        return Err(format!("Database '{}' does not exist in namespace '{}'", database, namespace));
+    }
+    Ok(())
+}
@@ -10,46 +10,124 @@ use commands::{
     check_db_connection,
     get_connection_status,
     get_projects,
+    get_projects_paginated,
     search_projects,
+    get_projects_modified_since,
+    get_project,
+    is_project_terminal,
     get_companies,
+    get_companies_paged,
     get_contacts,
     get_fees,
+    get_fees_paginated,
+    get_fees_checked,
+    find_unparseable_fees,
+    repair_fee_rows,
+    search_fees,
+    get_fees_for_project,
+    get_project_proposal_index,
+    get_project_fee_value,
     create_project,
     update_project,
     delete_project,
+    archive_project,
+    restore_project,
+    export_project_dossier,
     create_company,
     update_company,
     delete_company,
     create_contact,
+    bulk_create_contacts,
     update_contact,
     delete_contact,
+    find_incomplete_contacts,
+    repair_contact_company_links,
+    check_contact_email_domain,
     create_fee,
     update_fee,
     delete_fee,
+    compare_fees,
+    get_fee_tree,
+    get_latest_fee_per_project,
+    find_fees_with_future_issue_date,
+    get_monthly_report,
+    find_duplicate_fee_numbers,
+    reconcile_fee_revisions,
+    create_fee_revision,
+    validate_fee_for_export,
+    check_fee_project_consistency,
     health_check,
     get_stats,
+    get_project_stats_by_country,
+    get_project_stats_by_year,
+    get_cached_stats,
     get_db_info,
     get_table_schema,
+    describe_project_schema_gaps,
+    get_database_info,
+    verify_namespace_database,
+    check_write_permission,
     position_window_4k,
+    position_window,
+    get_monitor_info,
     get_settings,
     save_settings,
+    migrate_plaintext_password,
+    normalize_project_folder_path,
+    verify_settings_roundtrip,
     reload_database_config,
+    reconnect_database,
+    run_startup_checks,
+    set_heartbeat_failure_threshold,
+    get_default_staff,
+    get_new_project_defaults,
+    get_settings_sources,
+    export_settings,
+    import_settings,
     select_folder,
     open_folder_in_explorer,
+    get_app_paths,
+    open_app_path,
+    get_recent_logs,
     investigate_record,
+    resolve_record_label,
     search_countries,
+    get_currency_for_country,
+    get_currencies,
+    import_countries,
     generate_next_project_number,
+    allocate_project_number_block,
+    clear_project_number_reservations,
+    get_capacity_warnings,
     validate_project_number,
+    validate_project_numbers,
+    validate_project_number_consistency,
+    get_adjacent_projects,
+    get_project_number_warnings,
     create_project_with_template,
     copy_project_template,
+    set_project_template_override,
+    get_project_template_override,
+    count_template_placeholder_files,
+    validate_template_structure,
+    preview_project_folder_name,
     get_area_suggestions,
     get_all_cities,
     get_city_suggestions,
+    get_projects_by_area,
+    get_distinct_activities,
+    get_distinct_packages,
     write_fee_to_json,
     write_fee_to_json_safe,
+    get_fee_json_mapping,
+    export_all_active_fees,
+    export_fees_csv,
+    list_operations,
+    cancel_operation,
     check_project_folder_exists,
     check_var_json_exists,
     check_var_json_template_exists,
+    check_proposal_folder_writable,
     rename_folder_with_old_suffix,
     rename_var_json_with_old_suffix,
     populate_project_data,
@@ -57,8 +135,22 @@ use commands::{
     move_project_folder,
     move_project_from_rfp,
     move_project_to_archive,
+    archive_project_folder,
+    bulk_move_projects,
+    repair_project_folder_name,
+    find_misformatted_project_folders,
+    repair_misformatted_project_folders,
     list_projects_in_folder,
     validate_project_base_path,
+    find_projects_without_folders,
+    find_project_folder_collisions,
+    find_folder_name_encoding_issues,
+    find_name_short_mismatches,
+    ensure_awarded_templates,
+    validate_awarded_template_folders,
+    find_stale_var_jsons,
+    get_fee_file_status,
+    create_missing_project_folders,
     log_message,
 };
 
@@ -146,17 +238,6 @@ pub fn run() {
             
             // Clone state for heartbeat monitoring
             let heartbeat_state = app_state.clone();
-            let status = {
-                if let Ok(manager) = app_state.lock() {
-                    manager.status.clone()
-                } else {
-                    error!("Failed to get status from database manager");
-                    return Err(Box::new(std::io::Error::new(
-                        std::io::ErrorKind::Other,
-                        "Failed to initialize database manager"
-                    )));
-                }
-            };
             
             // Set up the application state
             app.manage(app_state.clone());
@@ -229,7 +310,7 @@ pub fn run() {
                 if initialized {
                     // Start heartbeat monitoring
                     info!("Starting database heartbeat monitoring");
-                    DatabaseManager::start_heartbeat(status, heartbeat_state).await;
+                    DatabaseManager::start_heartbeat(heartbeat_state).await;
                 }
             });
             
@@ -243,46 +324,124 @@ pub fn run() {
             check_db_connection,
             get_connection_status,
             get_projects,
+            get_projects_paginated,
             search_projects,
+            get_projects_modified_since,
+            get_project,
+            is_project_terminal,
             get_companies,
+            get_companies_paged,
             get_contacts,
             get_fees,
+            get_fees_paginated,
+            get_fees_checked,
+            find_unparseable_fees,
+            repair_fee_rows,
+            search_fees,
+            get_fees_for_project,
+            get_project_proposal_index,
+            get_project_fee_value,
             create_project,
             update_project,
             delete_project,
+            archive_project,
+            restore_project,
+            export_project_dossier,
             create_company,
             update_company,
             delete_company,
             create_contact,
+            bulk_create_contacts,
             update_contact,
             delete_contact,
+            find_incomplete_contacts,
+            repair_contact_company_links,
+            check_contact_email_domain,
             create_fee,
             update_fee,
             delete_fee,
+            compare_fees,
+            get_fee_tree,
+            get_latest_fee_per_project,
+            find_fees_with_future_issue_date,
+            get_monthly_report,
+            find_duplicate_fee_numbers,
+            reconcile_fee_revisions,
+            create_fee_revision,
+            validate_fee_for_export,
+            check_fee_project_consistency,
             health_check,
             get_stats,
+            get_project_stats_by_country,
+            get_project_stats_by_year,
+            get_cached_stats,
             get_db_info,
             get_table_schema,
+            describe_project_schema_gaps,
+            get_database_info,
+            verify_namespace_database,
+            check_write_permission,
             position_window_4k,
+            position_window,
+            get_monitor_info,
             get_settings,
             save_settings,
+            migrate_plaintext_password,
+            normalize_project_folder_path,
+            verify_settings_roundtrip,
             reload_database_config,
+            reconnect_database,
+            run_startup_checks,
+            set_heartbeat_failure_threshold,
+            get_default_staff,
+            get_new_project_defaults,
+            get_settings_sources,
+            export_settings,
+            import_settings,
             select_folder,
             open_folder_in_explorer,
+            get_app_paths,
+            open_app_path,
+            get_recent_logs,
             investigate_record,
+            resolve_record_label,
             search_countries,
+            get_currency_for_country,
+            get_currencies,
+            import_countries,
             generate_next_project_number,
+            allocate_project_number_block,
+            clear_project_number_reservations,
+            get_capacity_warnings,
             validate_project_number,
+            validate_project_numbers,
+            validate_project_number_consistency,
+            get_adjacent_projects,
+            get_project_number_warnings,
             create_project_with_template,
             copy_project_template,
+            set_project_template_override,
+            get_project_template_override,
+            count_template_placeholder_files,
+            validate_template_structure,
+            preview_project_folder_name,
                     get_area_suggestions,
             get_all_cities,
             get_city_suggestions,
+            get_projects_by_area,
+            get_distinct_activities,
+            get_distinct_packages,
             write_fee_to_json,
             write_fee_to_json_safe,
+            get_fee_json_mapping,
+            export_all_active_fees,
+            export_fees_csv,
+            list_operations,
+            cancel_operation,
             check_project_folder_exists,
             check_var_json_exists,
             check_var_json_template_exists,
+            check_proposal_folder_writable,
             rename_folder_with_old_suffix,
             rename_var_json_with_old_suffix,
             populate_project_data,
@@ -290,8 +449,22 @@ pub fn run() {
             move_project_folder,
             move_project_from_rfp,
             move_project_to_archive,
+            archive_project_folder,
+            bulk_move_projects,
+            repair_project_folder_name,
+            find_misformatted_project_folders,
+            repair_misformatted_project_folders,
             list_projects_in_folder,
             validate_project_base_path,
+            find_projects_without_folders,
+            find_project_folder_collisions,
+            find_folder_name_encoding_issues,
+            find_name_short_mismatches,
+            ensure_awarded_templates,
+            validate_awarded_template_folders,
+            find_stale_var_jsons,
+            get_fee_file_status,
+            create_missing_project_folders,
             log_message
         ])
         .run(tauri::generate_context!())
@@ -172,7 +172,7 @@ describe('API Compatibility Test - Original Tests vs Refactored Implementation',
 
       it('should create project successfully', async () => {
         const createdProject = { ...mockProject, ...newProject };
-        mockInvoke.mockResolvedValueOnce(createdProject);
+        mockInvoke.mockResolvedValueOnce({ project: createdProject, warnings: [] });
 
         const result = await ApiClient.createProject(newProject);
 
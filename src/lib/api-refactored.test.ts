@@ -230,7 +230,7 @@ describe('ApiClient Refactored - Backward Compatibility', () => {
 
       it('should create project successfully', async () => {
         const createdProject = { ...mockProject, ...newProject };
-        mockInvoke.mockResolvedValueOnce(createdProject);
+        mockInvoke.mockResolvedValueOnce({ project: createdProject, warnings: [] });
 
         const result = await ApiClient.createProject(newProject);
 
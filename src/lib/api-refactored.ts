@@ -37,6 +37,7 @@ import type {
   TableSchema,
   CountrySearchResult,
   ProjectCreationResult,
+  ProjectCreateResult,
   FileOperationResult
 } from '../types';
 
@@ -60,6 +61,7 @@ export type {
   TableSchema,
   CountrySearchResult,
   ProjectCreationResult,
+  ProjectCreateResult,
   FileOperationResult
 } from '../types';
 
@@ -246,8 +248,8 @@ class ConnectionApi extends BaseApiClient {
  * Implements all project CRUD operations with the consolidated patterns
  */
 class ProjectsApi extends BaseApiClient {
-  static async getProjects(): Promise<Project[]> {
-    return this.invoke<Project[]>('get_projects');
+  static async getProjects(includeDeleted = false): Promise<Project[]> {
+    return this.invoke<Project[]>('get_projects', { includeDeleted });
   }
 
   static async searchProjects(query: string): Promise<Project[]> {
@@ -264,13 +266,23 @@ class ProjectsApi extends BaseApiClient {
       }
     };
     
-    return this.invokeSafe<Project>('create_project', { project: projectData });
+    const created = await this.invokeSafe<ProjectCreateResult>('create_project', { project: projectData });
+    created?.warnings.forEach(warning => console.warn(warning));
+    return created ? created.project : null;
   }
 
   static async updateProject(id: string, projectData: ProjectUpdate): Promise<Project> {
     return this.invoke<Project>('update_project', { id, projectUpdate: projectData });
   }
 
+  static async archiveProject(id: string): Promise<Project> {
+    return this.invoke<Project>('archive_project', { id });
+  }
+
+  static async restoreProject(id: string): Promise<Project> {
+    return this.invoke<Project>('restore_project', { id });
+  }
+
   static async deleteProject(id: string): Promise<Project> {
     return this.invoke<Project>('delete_project', { id });
   }
@@ -444,8 +456,8 @@ class SystemApi extends BaseApiClient {
     return this.invoke<string>('save_settings', { settings });
   }
 
-  static async reloadDatabaseConfig(): Promise<string> {
-    return this.invoke<string>('reload_database_config');
+  static async reloadDatabaseConfig(): Promise<ConnectionStatus> {
+    return this.invoke<ConnectionStatus>('reload_database_config');
   }
 }
 
@@ -505,7 +517,9 @@ class ProjectWorkflowApi extends BaseApiClient {
   }
 
   static async createProjectWithTemplate(project: Partial<Project>): Promise<Project> {
-    return this.invoke<Project>('create_project_with_template', { project });
+    const created = await this.invoke<ProjectCreateResult>('create_project_with_template', { project });
+    created.warnings.forEach(warning => console.warn(warning));
+    return created.project;
   }
 
   static async copyProjectTemplate(projectNumber: string, projectShortName: string): Promise<string> {
@@ -559,8 +573,8 @@ export class ApiClient {
   // ============================================================================
   // PROJECT MANAGEMENT METHODS - Delegated to ProjectsApi
   // ============================================================================
-  static async getProjects(): Promise<Project[]> {
-    return ProjectsApi.getProjects();
+  static async getProjects(includeDeleted = false): Promise<Project[]> {
+    return ProjectsApi.getProjects(includeDeleted);
   }
 
   static async searchProjects(query: string): Promise<Project[]> {
@@ -575,6 +589,14 @@ export class ApiClient {
     return ProjectsApi.updateProject(id, projectData);
   }
 
+  static async archiveProject(id: string): Promise<Project> {
+    return ProjectsApi.archiveProject(id);
+  }
+
+  static async restoreProject(id: string): Promise<Project> {
+    return ProjectsApi.restoreProject(id);
+  }
+
   static async deleteProject(id: string): Promise<Project> {
     return ProjectsApi.deleteProject(id);
   }
@@ -706,7 +728,7 @@ export class ApiClient {
     return SystemApi.saveSettings(settings);
   }
 
-  static async reloadDatabaseConfig(): Promise<string> {
+  static async reloadDatabaseConfig(): Promise<ConnectionStatus> {
     return SystemApi.reloadDatabaseConfig();
   }
 
@@ -767,6 +789,8 @@ export const {
   searchProjects,
   createProject,
   updateProject,
+  archiveProject,
+  restoreProject,
   deleteProject,
   
   // Company operations
@@ -238,7 +238,7 @@ describe('ApiClient', () => {
 
       it('should create project successfully', async () => {
         const createdProject = { ...mockProject, ...newProject };
-        mockInvoke.mockResolvedValueOnce(createdProject);
+        mockInvoke.mockResolvedValueOnce({ project: createdProject, warnings: [] });
 
         const result = await ApiClient.createProject(newProject);
 
@@ -48,6 +48,7 @@ import type {
   TableSchema,
   CountrySearchResult,
   ProjectCreationResult,
+  ProjectCreateResult,
   FileOperationResult
 } from '../types';
 
@@ -71,6 +72,7 @@ export type {
   TableSchema,
   CountrySearchResult,
   ProjectCreationResult,
+  ProjectCreateResult,
   FileOperationResult
 } from '../types';
 
@@ -183,6 +185,7 @@ export class ApiClient {
    * returned with their full project numbers, status information, and
    * location details.
    * 
+   * @param includeDeleted - Also return archived projects (default false)
    * @returns Promise<Project[]> - Array of all project records
    * 
    * @example
@@ -207,12 +210,13 @@ export class ApiClient {
    * - `area`, `city`, `country`: Location information
    * - `folder`: File system path for project files
    * - `time`: Creation and modification timestamps
+   * - `deleted_at`: Set when the project is archived
    * 
    * @throws Error - Re-throws database connection or query errors
    */
-  static async getProjects(): Promise<Project[]> {
+  static async getProjects(includeDeleted = false): Promise<Project[]> {
     try {
-      const projects = await invoke<Project[]>('get_projects');
+      const projects = await invoke<Project[]>('get_projects', { includeDeleted });
       return projects;
     } catch (error) {
       console.error('Failed to fetch projects from database:', error);
@@ -316,8 +320,9 @@ export class ApiClient {
         }
       };
       
-      const created = await invoke<Project>('create_project', { project: newProject });
-      return created;
+      const created = await invoke<ProjectCreateResult>('create_project', { project: newProject });
+      created.warnings.forEach(warning => console.warn(warning));
+      return created.project;
     } catch (error) {
       console.error('Failed to create project:', error);
       return null;
@@ -1297,22 +1302,22 @@ export class ApiClient {
    * and reinitialize the database connection with the new parameters. This allows
    * for real-time configuration changes without requiring an application restart.
    * 
-   * @returns Promise<string> Success or failure message from the backend
-   * @throws Error if the reload operation fails
+   * @returns Promise<ConnectionStatus> Connection status after re-initialization
+   * @throws Error if the settings are incomplete or the reload operation fails
    * 
    * @example
    * ```typescript
    * try {
-   *   const result = await ApiClient.reloadDatabaseConfig();
-   *   console.log('Database reloaded:', result);
+   *   const status = await ApiClient.reloadDatabaseConfig();
+   *   console.log('Database reloaded, connected:', status.is_connected);
    * } catch (error) {
    *   console.error('Failed to reload database:', error);
    * }
    * ```
    */
-  static async reloadDatabaseConfig(): Promise<string> {
+  static async reloadDatabaseConfig(): Promise<ConnectionStatus> {
     try {
-      return await invoke<string>('reload_database_config');
+      return await invoke<ConnectionStatus>('reload_database_config');
     } catch (error) {
       console.error('Failed to reload database configuration:', error);
       throw error;
@@ -1539,8 +1544,9 @@ export class ApiClient {
    */
   static async createProjectWithTemplate(project: Partial<Project>): Promise<Project> {
     try {
-      const created = await invoke<Project>('create_project_with_template', { project });
-      return created;
+      const created = await invoke<ProjectCreateResult>('create_project_with_template', { project });
+      created.warnings.forEach(warning => console.warn(warning));
+      return created.project;
     } catch (error) {
       console.error('Failed to create project with template:', error);
       throw error;
@@ -1626,6 +1632,32 @@ export class ApiClient {
     }
   }
 
+  /**
+   * Archives (soft-deletes) a project by setting `deleted_at`.
+   * 
+   * Archived projects are hidden from `getProjects()` unless `includeDeleted`
+   * is set, and only archived projects can be deleted permanently.
+   */
+  static async archiveProject(id: string): Promise<Project> {
+    try {
+      const archived = await invoke<Project>('archive_project', { id });
+      return archived;
+    } catch (error) {
+      console.error('Failed to archive project:', error);
+      throw error;
+    }
+  }
+
+  static async restoreProject(id: string): Promise<Project> {
+    try {
+      const restored = await invoke<Project>('restore_project', { id });
+      return restored;
+    } catch (error) {
+      console.error('Failed to restore project:', error);
+      throw error;
+    }
+  }
+
   static async deleteProject(id: string): Promise<Project> {
     try {
       const deleted = await invoke<Project>('delete_project', { id });
@@ -1856,6 +1888,8 @@ export const {
   searchProjects,
   createProject,
   updateProject,
+  archiveProject,
+  restoreProject,
   deleteProject,
   
   // Company operations
@@ -41,6 +41,8 @@ vi.mock('./api', () => ({
   getStats: vi.fn(),
   createProjectWithTemplate: vi.fn(),
   updateProject: vi.fn(),
+  archiveProject: vi.fn(),
+  restoreProject: vi.fn(),
   deleteProject: vi.fn(),
   createCompany: vi.fn(),
   updateCompany: vi.fn(),
@@ -340,6 +342,38 @@ describe('Store Management', () => {
         expect(api.deleteProject).toHaveBeenCalledWith('projects:test_project');
         expect(get(projectsStore)).toEqual([]);
       });
+
+      it('should archive project and drop it from the list', async () => {
+        vi.mocked(api.getProjects).mockResolvedValueOnce([mockProject]);
+        await projectsActions.load();
+
+        const archived = { ...mockProject, deleted_at: '2025-06-01T00:00:00Z' };
+        vi.mocked(api.archiveProject).mockResolvedValueOnce(archived);
+        vi.mocked(api.getProjects).mockResolvedValueOnce([]);
+
+        await projectsActions.archive('projects:test_project');
+
+        expect(api.archiveProject).toHaveBeenCalledWith('projects:test_project');
+        expect(api.getProjects).toHaveBeenLastCalledWith(false);
+        expect(get(projectsStore)).toEqual([]);
+      });
+
+      it('should load archived projects when shown and restore them', async () => {
+        const archived = { ...mockProject, deleted_at: '2025-06-01T00:00:00Z' };
+        vi.mocked(api.getProjects).mockResolvedValueOnce([archived]);
+        await projectsActions.setShowArchived(true);
+        expect(api.getProjects).toHaveBeenLastCalledWith(true);
+        expect(get(projectsStore)).toEqual([archived]);
+
+        vi.mocked(api.restoreProject).mockResolvedValueOnce(mockProject);
+        vi.mocked(api.getProjects).mockResolvedValueOnce([mockProject]);
+        await projectsActions.restore('projects:test_project');
+        expect(api.restoreProject).toHaveBeenCalledWith('projects:test_project');
+        expect(get(projectsStore)).toEqual([mockProject]);
+
+        vi.mocked(api.getProjects).mockResolvedValueOnce([mockProject]);
+        await projectsActions.setShowArchived(false);
+      });
     });
   });
 
@@ -202,10 +202,27 @@ export const projectsActions = {
     return await projectsActionsInternal.update(id, projectData);
   },
 
+  async archive(id: string) {
+    const archived = await projectsApi.archive(id);
+    await projectsActionsInternal.refresh();
+    return archived;
+  },
+
+  async restore(id: string) {
+    const restored = await projectsApi.restore(id);
+    await projectsActionsInternal.refresh();
+    return restored;
+  },
+
   async delete(id: string) {
     return await projectsActionsInternal.delete(id);
   },
 
+  async setShowArchived(show: boolean) {
+    projectsApi.includeArchived = show;
+    return await projectsActionsInternal.refresh();
+  },
+
   async refresh() {
     return await projectsActionsInternal.refresh();
   }
@@ -15,6 +15,8 @@ import {
   getFees,
   createProjectWithTemplate,
   updateProject,
+  archiveProject,
+  restoreProject,
   deleteProject,
   createCompany,
   updateCompany,
@@ -31,8 +33,11 @@ import {
  * Projects API adapter implementing CrudApi interface
  */
 export class ProjectsApiAdapter implements CrudApi<Project> {
+  /** Also load archived projects (those with `deleted_at` set) */
+  includeArchived = false;
+
   async getAll(): Promise<Project[]> {
-    return await getProjects();
+    return await getProjects(this.includeArchived);
   }
 
   async create(data: Omit<Project, 'id'>): Promise<Project> {
@@ -43,6 +48,14 @@ export class ProjectsApiAdapter implements CrudApi<Project> {
     return await updateProject(id, data);
   }
 
+  async archive(id: string): Promise<Project> {
+    return await archiveProject(id);
+  }
+
+  async restore(id: string): Promise<Project> {
+    return await restoreProject(id);
+  }
+
   async delete(id: string): Promise<Project> {
     return await deleteProject(id);
   }
@@ -12,6 +12,11 @@ export interface AppSettings {
   staff_phone?: string;
   staff_position?: string;
   project_folder_path?: string;
+  default_country?: string;
+  default_activity?: string;
+  heartbeat_failure_threshold?: string;
+  awarded_template_folders?: string[];
+  surrealdb_pass_error?: string; // set by the backend when the keychain password can't be read
 }
 
 // Settings store
@@ -40,7 +40,7 @@ describe('Tauri Commands Integration', () => {
 
       const result = await ApiClient.getProjects();
 
-      expect(invoke).toHaveBeenCalledWith('get_projects');
+      expect(invoke).toHaveBeenCalledWith('get_projects', { includeDeleted: false });
       expect(result).toEqual(mockProjects);
     });
 
@@ -63,7 +63,7 @@ describe('Tauri Commands Integration', () => {
         }
       };
 
-      vi.mocked(invoke).mockResolvedValueOnce(mockProject);
+      vi.mocked(invoke).mockResolvedValueOnce({ project: mockProject, warnings: [] });
 
       const result = await ApiClient.createProject(projectData);
 
@@ -85,6 +85,16 @@ describe('Tauri Commands Integration', () => {
       expect(result).toEqual(updatedProject);
     });
 
+    it('should archive and restore project', async () => {
+      vi.mocked(invoke).mockResolvedValueOnce(mockProject);
+      await ApiClient.archiveProject('projects:25-97101');
+      expect(invoke).toHaveBeenCalledWith('archive_project', { id: 'projects:25-97101' });
+
+      vi.mocked(invoke).mockResolvedValueOnce(mockProject);
+      await ApiClient.restoreProject('projects:25-97101');
+      expect(invoke).toHaveBeenCalledWith('restore_project', { id: 'projects:25-97101' });
+    });
+
     it('should delete project', async () => {
       vi.mocked(invoke).mockResolvedValueOnce(true);
 
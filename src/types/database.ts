@@ -76,7 +76,10 @@ export interface Project {
   country: string;
   folder: string;
   number: ProjectNumber;
+  activity?: string; // fees are checked against it
+  package?: string; // fees are checked against it
   time?: TimeInfo;
+  deleted_at?: string; // set while archived
 }
 
 export interface Fee {
@@ -143,7 +146,7 @@ export interface Currency {
 }
 
 // Create/Update types (without computed fields)
-export type ProjectCreate = Omit<Project, 'id' | 'time'>;
+export type ProjectCreate = Omit<Project, 'id' | 'time' | 'deleted_at'>;
 export type ProjectUpdate = Partial<ProjectCreate>;
 
 export type FeeCreate = Omit<Fee, 'id' | 'time' | 'rev'>;
@@ -180,7 +183,7 @@ export const ValidationRules = {
 // Helper functions
 export function generateProjectId(year: number, country: number, seq: number): string {
   const yy = year.toString().padStart(2, '0');
-  const ccc = country.toString();
+  const ccc = country.toString().padStart(3, '0');
   const nn = seq.toString().padStart(2, '0');
   return `${yy}-${ccc}${nn}`;
 }
@@ -57,10 +57,11 @@ export interface Project {
     created_at: string;
     updated_at: string;
   };
+  deleted_at?: string; // set while archived
 }
 
 // Create types for API operations
-export type ProjectCreate = Omit<Project, 'id' | 'time' | 'number'>;
+export type ProjectCreate = Omit<Project, 'id' | 'time' | 'number' | 'deleted_at'>;
 export type ProjectUpdate = Partial<ProjectCreate>;
 
 export interface Company {
@@ -123,6 +124,8 @@ export interface Fee {
   staff_phone?: string;
   staff_position?: string;
   strap_line?: string;
+  currency?: string;
+  amount?: number;
   revisions: Revision[];
   time?: {
     created_at: string;
@@ -256,6 +259,68 @@ export interface CountrySearchResult {
   dial_code: number;
 }
 
+/**
+ * Currency record.
+ * 
+ * Returned by `get_currencies` for the fee currency picker.
+ */
+export interface Currency {
+  /** Record ID, e.g. currency:AED */
+  id?: string | SurrealThing;
+  /** ISO 4217 code */
+  code: string;
+  /** Display name */
+  name: string;
+}
+
+/**
+ * Country-year running out of project numbers.
+ * 
+ * Returned by `get_capacity_warnings`.
+ */
+export interface CapacityWarning {
+  /** Two-digit year */
+  year: number;
+  /** Country dial code */
+  country: number;
+  /** Highest sequence used or reserved */
+  used: number;
+  /** Sequences left before 99 */
+  remaining: number;
+}
+
+/**
+ * Project count for one country, from get_project_stats_by_country.
+ */
+export interface CountryProjectCount {
+  /** Country name, or "Unknown" when unset */
+  country: string;
+  count: number;
+}
+
+/**
+ * Project count for one project number year, from get_project_stats_by_year.
+ */
+export interface YearProjectCount {
+  /** Two-digit year, or null for projects without a number */
+  year: number | null;
+  count: number;
+}
+
+/**
+ * Bulk contact creation summary.
+ * 
+ * Returned by `bulk_create_contacts`; failed rows don't stop the batch.
+ */
+export interface BulkContactResult {
+  /** Number of contacts created */
+  created: number;
+  /** Number of rows that failed */
+  failed: number;
+  /** Result per input row, by index */
+  details: { index: number; result: { Ok: Contact } | { Err: string } }[];
+}
+
 /**
  * Project creation result structure.
  * 
@@ -272,6 +337,16 @@ export interface ProjectCreationResult {
   warnings?: string[];
 }
 
+/**
+ * Project returned by `create_project` and `create_project_with_template`.
+ */
+export interface ProjectCreateResult {
+  /** The created project */
+  project: Project;
+  /** Warnings that did not block creation, e.g. an unusual number year */
+  warnings: string[];
+}
+
 /**
  * File operation result structure.
  * 